@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE users
+    ADD COLUMN last_login_at TIMESTAMPTZ,
+    ADD COLUMN last_login_ip TEXT;
@@ -0,0 +1,42 @@
+-- login attempts are tracked per client IP and per username with separate budgets
+CREATE TABLE login_rate_limits (
+    rate_key TEXT PRIMARY KEY,
+    attempt_count INT NOT NULL DEFAULT 1,
+    window_start timestamptz NOT NULL,
+    last_attempt_at timestamptz NOT NULL
+);
+
+-- check and update the login rate limit for a single key
+CREATE OR REPLACE FUNCTION check_login_rate_limit(
+    p_key TEXT,
+    p_max_attempts INT,
+    p_window_secs INT
+) RETURNS BOOLEAN AS $$
+DECLARE
+    v_count INT;
+    v_window_start timestamptz;
+BEGIN
+    SELECT attempt_count, window_start INTO v_count, v_window_start
+    FROM login_rate_limits
+    WHERE rate_key = p_key;
+
+    IF NOT FOUND OR v_window_start < NOW() - (p_window_secs || ' seconds')::INTERVAL THEN
+        -- new window or key
+        INSERT INTO login_rate_limits (rate_key, attempt_count, window_start, last_attempt_at)
+        VALUES (p_key, 1, NOW(), NOW())
+        ON CONFLICT (rate_key) DO UPDATE
+        SET attempt_count = 1,
+            window_start = NOW(),
+            last_attempt_at = NOW();
+        RETURN TRUE;
+    ELSIF v_count >= p_max_attempts THEN
+        RETURN FALSE;
+    ELSE
+        UPDATE login_rate_limits
+        SET attempt_count = attempt_count + 1,
+            last_attempt_at = NOW()
+        WHERE rate_key = p_key;
+        RETURN TRUE;
+    END IF;
+END
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,12 @@
+-- one row per login, so users can see where they're signed in and revoke devices they don't recognise
+CREATE TABLE user_sessions (
+    session_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    user_agent TEXT,
+    ip_address TEXT,
+    geo TEXT,
+    created_at timestamptz NOT NULL DEFAULT NOW(),
+    revoked_at timestamptz
+);
+
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id) WHERE revoked_at IS NULL;
@@ -0,0 +1,4 @@
+-- hash of the request payload that claimed the key
+-- NULL for rows written before fingerprinting, those are replayed without a check
+ALTER TABLE idempotency
+    ADD COLUMN request_fingerprint TEXT;
@@ -0,0 +1,68 @@
+-- anonymous page views reported by the frontend
+-- the client session id is only ever stored hashed
+CREATE TABLE page_visits (
+    visit_id uuid PRIMARY KEY,
+    path TEXT NOT NULL,
+    referrer TEXT,
+    session_hash TEXT,
+    duration_ms INT,
+    user_agent TEXT,
+    created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_page_visits_created_at ON page_visits (created_at);
+CREATE INDEX idx_page_visits_path ON page_visits (path, created_at);
+
+-- web vitals reported by the frontend, one row per measurement
+CREATE TABLE performance_metrics (
+    metric_id uuid PRIMARY KEY,
+    path TEXT NOT NULL,
+    metric_name TEXT NOT NULL,
+    value DOUBLE PRECISION NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_performance_metrics_created_at ON performance_metrics (created_at);
+CREATE INDEX idx_performance_metrics_path_name ON performance_metrics (path, metric_name, created_at);
+
+-- ingestion is anonymous, so it gets its own per-IP budget separate from logins
+CREATE TABLE metrics_rate_limits (
+    rate_key TEXT PRIMARY KEY,
+    request_count INT NOT NULL DEFAULT 1,
+    window_start timestamptz NOT NULL,
+    last_request_at timestamptz NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION check_metrics_rate_limit(
+    p_key TEXT,
+    p_max_requests INT,
+    p_window_secs INT
+) RETURNS BOOLEAN AS $$
+DECLARE
+    v_count INT;
+    v_window_start timestamptz;
+BEGIN
+    SELECT request_count, window_start INTO v_count, v_window_start
+    FROM metrics_rate_limits
+    WHERE rate_key = p_key;
+
+    IF NOT FOUND OR v_window_start < NOW() - (p_window_secs || ' seconds')::INTERVAL THEN
+        -- new window or key
+        INSERT INTO metrics_rate_limits (rate_key, request_count, window_start, last_request_at)
+        VALUES (p_key, 1, NOW(), NOW())
+        ON CONFLICT (rate_key) DO UPDATE
+        SET request_count = 1,
+            window_start = NOW(),
+            last_request_at = NOW();
+        RETURN TRUE;
+    ELSIF v_count >= p_max_requests THEN
+        RETURN FALSE;
+    ELSE
+        UPDATE metrics_rate_limits
+        SET request_count = request_count + 1,
+            last_request_at = NOW()
+        WHERE rate_key = p_key;
+        RETURN TRUE;
+    END IF;
+END
+$$ LANGUAGE plpgsql;
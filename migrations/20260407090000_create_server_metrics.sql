@@ -0,0 +1,12 @@
+-- one row per handled request, written in batches by the metrics middleware
+CREATE TABLE server_metrics (
+    metric_id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    endpoint TEXT NOT NULL,
+    method TEXT NOT NULL,
+    status_code INT NOT NULL,
+    response_time_ms DOUBLE PRECISION NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_server_metrics_created_at ON server_metrics (created_at);
+CREATE INDEX idx_server_metrics_endpoint ON server_metrics (endpoint, created_at);
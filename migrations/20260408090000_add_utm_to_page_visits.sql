@@ -0,0 +1,8 @@
+-- campaign attribution from the landing url's utm_* query parameters
+ALTER TABLE page_visits
+    ADD COLUMN utm_source TEXT,
+    ADD COLUMN utm_medium TEXT,
+    ADD COLUMN utm_campaign TEXT;
+
+CREATE INDEX idx_page_visits_utm ON page_visits (utm_source, utm_medium, utm_campaign, created_at)
+    WHERE utm_source IS NOT NULL OR utm_medium IS NOT NULL OR utm_campaign IS NOT NULL;
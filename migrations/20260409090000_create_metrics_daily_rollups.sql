@@ -0,0 +1,26 @@
+-- one row per UTC day, so long-range reports don't have to scan the raw tables
+CREATE TABLE page_visit_daily (
+    day DATE NOT NULL,
+    path TEXT NOT NULL,
+    visits BIGINT NOT NULL,
+    sessions BIGINT NOT NULL,
+    PRIMARY KEY (day, path)
+);
+
+-- percentiles don't add up, reports weight each day's by its sample count
+CREATE TABLE performance_metric_daily (
+    day DATE NOT NULL,
+    path TEXT NOT NULL,
+    metric_name TEXT NOT NULL,
+    samples BIGINT NOT NULL,
+    p50 DOUBLE PRECISION NOT NULL,
+    p75 DOUBLE PRECISION NOT NULL,
+    p95 DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (day, path, metric_name)
+);
+
+-- days the rollup job has finished, so it can catch up after downtime
+CREATE TABLE metrics_rollup_days (
+    day DATE PRIMARY KEY,
+    completed_at timestamptz NOT NULL DEFAULT NOW()
+);
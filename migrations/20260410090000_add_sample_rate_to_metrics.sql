@@ -0,0 +1,8 @@
+-- fraction of events that were kept when the row was written, reports weight
+-- each row by 1 / sample_rate to scale counts back up
+ALTER TABLE page_visits
+    ADD COLUMN sample_rate DOUBLE PRECISION NOT NULL DEFAULT 1.0 CHECK (sample_rate > 0 AND sample_rate <= 1);
+ALTER TABLE performance_metrics
+    ADD COLUMN sample_rate DOUBLE PRECISION NOT NULL DEFAULT 1.0 CHECK (sample_rate > 0 AND sample_rate <= 1);
+ALTER TABLE server_metrics
+    ADD COLUMN sample_rate DOUBLE PRECISION NOT NULL DEFAULT 1.0 CHECK (sample_rate > 0 AND sample_rate <= 1);
@@ -0,0 +1,12 @@
+-- requests over the configured threshold, kept whatever the server_metrics sample rate
+-- path is the raw request path so the exact slow page can be found
+CREATE TABLE slow_requests (
+    slow_request_id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    path TEXT NOT NULL,
+    method TEXT NOT NULL,
+    status_code INT NOT NULL,
+    duration_ms DOUBLE PRECISION NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_slow_requests_created_at ON slow_requests (created_at);
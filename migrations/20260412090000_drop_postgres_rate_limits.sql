@@ -0,0 +1,8 @@
+-- request limits are enforced by the rate limit middleware against redis now
+DROP FUNCTION check_email_rate_limit(TEXT, INT, INT);
+DROP FUNCTION check_login_rate_limit(TEXT, INT, INT);
+DROP FUNCTION check_metrics_rate_limit(TEXT, INT, INT);
+
+DROP TABLE message_rate_limits;
+DROP TABLE login_rate_limits;
+DROP TABLE metrics_rate_limits;
@@ -0,0 +1,4 @@
+-- the login before the current one, last_login_* always describes the caller's own session
+ALTER TABLE users
+    ADD COLUMN previous_login_at TIMESTAMPTZ,
+    ADD COLUMN previous_login_ip TEXT;
@@ -0,0 +1,16 @@
+-- durable background work (mail, webhooks, exports), claimed by workers with FOR UPDATE SKIP LOCKED
+-- a job that keeps failing is retried with backoff until max_attempts, then left as 'dead' for inspection
+CREATE TABLE jobs (
+    job_id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'succeeded', 'dead')),
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 5,
+    run_at timestamptz NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at timestamptz NOT NULL DEFAULT NOW(),
+    finished_at timestamptz
+);
+
+CREATE INDEX idx_jobs_ready ON jobs (run_at) WHERE status = 'queued';
@@ -0,0 +1,18 @@
+-- mail is written here (in the same transaction as whatever sends it) and delivered by a worker,
+-- failed deliveries retry with backoff until max_attempts, then stay as 'failed' for inspection
+CREATE TABLE email_outbox (
+    email_id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    recipients TEXT[] NOT NULL,
+    subject TEXT NOT NULL,
+    text_body TEXT NOT NULL,
+    html_body TEXT,
+    status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'sent', 'failed')),
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 5,
+    next_attempt_at timestamptz NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at timestamptz NOT NULL DEFAULT NOW(),
+    sent_at timestamptz
+);
+
+CREATE INDEX idx_email_outbox_ready ON email_outbox (next_attempt_at) WHERE status = 'queued';
@@ -0,0 +1,10 @@
+-- the virus scan of each upload that came through the api, objects without a row predate
+-- scanning (or were put in the bucket directly) and are served as before
+CREATE TABLE media_scans (
+    object_key TEXT PRIMARY KEY,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'clean', 'infected')),
+    -- what clamd matched, for infected uploads
+    signature TEXT,
+    created_at timestamptz NOT NULL DEFAULT NOW(),
+    scanned_at timestamptz
+);
@@ -0,0 +1,18 @@
+CREATE TABLE projects (
+    project_id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL,
+    -- blog-style sections, see types::article::ArticleSection
+    content JSONB NOT NULL DEFAULT '[]'::jsonb,
+    tech_tags TEXT[] NOT NULL DEFAULT '{}',
+    repo_url TEXT,
+    demo_url TEXT,
+    images JSONB NOT NULL DEFAULT '[]'::jsonb,
+    published BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_projects_published ON projects(published);
+CREATE INDEX idx_projects_created_at ON projects(created_at DESC);
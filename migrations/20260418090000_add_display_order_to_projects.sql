@@ -0,0 +1,12 @@
+ALTER TABLE projects ADD COLUMN display_order INTEGER NOT NULL DEFAULT 0;
+
+-- keep the newest-first order the grid had before
+UPDATE projects
+SET display_order = ordered.position
+FROM (
+    SELECT project_id, ROW_NUMBER() OVER (ORDER BY created_at DESC) - 1 AS position
+    FROM projects
+) AS ordered
+WHERE projects.project_id = ordered.project_id;
+
+CREATE INDEX idx_projects_display_order ON projects(display_order);
@@ -0,0 +1,21 @@
+CREATE TABLE skills (
+    skill_id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    -- 1 (getting started) to 5 (expert)
+    proficiency SMALLINT NOT NULL CHECK (proficiency BETWEEN 1 AND 5),
+    years SMALLINT NOT NULL DEFAULT 0 CHECK (years >= 0),
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- "Rust" and "rust" are the same skill
+CREATE UNIQUE INDEX idx_skills_lower_name ON skills(lower(name));
+
+CREATE TABLE project_skills (
+    project_id UUID NOT NULL REFERENCES projects(project_id) ON DELETE CASCADE,
+    skill_id UUID NOT NULL REFERENCES skills(skill_id) ON DELETE CASCADE,
+    PRIMARY KEY (project_id, skill_id)
+);
+
+CREATE INDEX idx_project_skills_skill_id ON project_skills(skill_id);
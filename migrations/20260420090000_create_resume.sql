@@ -0,0 +1,14 @@
+-- a single row, the resume is one document rather than a collection
+CREATE TABLE resume (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    name TEXT NOT NULL,
+    headline TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    -- see types::resume::{ExperienceEntry, EducationEntry}
+    experience JSONB NOT NULL DEFAULT '[]'::jsonb,
+    education JSONB NOT NULL DEFAULT '[]'::jsonb,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+INSERT INTO resume (name, headline, summary, updated_at)
+VALUES ('', '', '', NOW());
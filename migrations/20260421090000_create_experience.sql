@@ -0,0 +1,31 @@
+CREATE TABLE experience (
+    experience_id UUID PRIMARY KEY,
+    company TEXT NOT NULL,
+    role TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    -- NULL while it's the current job
+    end_date DATE CHECK (end_date >= start_date),
+    highlights TEXT[] NOT NULL DEFAULT '{}',
+    tech TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_experience_start_date ON experience(start_date DESC);
+
+-- entries kept on the resume until now move into the table
+INSERT INTO experience (
+    experience_id, company, role, start_date, end_date, highlights, created_at, updated_at
+)
+SELECT
+    gen_random_uuid(),
+    entry->>'company',
+    entry->>'role',
+    (entry->>'start_date')::date,
+    (entry->>'end_date')::date,
+    ARRAY(SELECT jsonb_array_elements_text(COALESCE(entry->'highlights', '[]'::jsonb))),
+    resume.updated_at,
+    resume.updated_at
+FROM resume, jsonb_array_elements(resume.experience) AS entry;
+
+ALTER TABLE resume DROP COLUMN experience;
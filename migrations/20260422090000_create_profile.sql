@@ -0,0 +1,15 @@
+-- a single row, edited in place like the resume
+CREATE TABLE profile (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    display_name TEXT NOT NULL,
+    bio TEXT NOT NULL,
+    avatar_url TEXT,
+    -- see types::profile::SocialLink
+    social_links JSONB NOT NULL DEFAULT '[]'::jsonb,
+    -- whether the contact form is advertised as open
+    contact_available BOOLEAN NOT NULL DEFAULT true,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+INSERT INTO profile (display_name, bio, updated_at)
+VALUES ('', '', NOW());
@@ -0,0 +1,8 @@
+-- the last successful fetch from github, a single row overwritten by the github_activity job
+-- so the public endpoint never waits on (or spends the rate limit of) the github api
+CREATE TABLE github_activity (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    -- see github::GithubActivity
+    activity JSONB NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL
+);
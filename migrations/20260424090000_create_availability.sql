@@ -0,0 +1,18 @@
+-- what the hire-me banner says, every change is a new row so the history is kept
+-- and the newest row is the current availability
+CREATE TYPE availability_status AS ENUM ('open_to_work', 'booked', 'unavailable');
+
+CREATE TABLE availability (
+    availability_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    status availability_status NOT NULL,
+    -- "booked until March", only meaningful while booked
+    booked_until DATE CHECK (booked_until IS NULL OR status = 'booked'),
+    note TEXT,
+    set_by UUID REFERENCES users (user_id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX availability_created_at_idx ON availability (created_at DESC);
+
+-- matches the profile's seeded contact_available
+INSERT INTO availability (status) VALUES ('open_to_work');
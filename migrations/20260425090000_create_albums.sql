@@ -0,0 +1,16 @@
+-- photography sets for the gallery, photos come from the media library
+CREATE TABLE albums (
+    album_id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+    -- see types::article::CarouselImage, shown in array order with the first as the cover
+    photos JSONB NOT NULL DEFAULT '[]'::jsonb,
+    published BOOLEAN NOT NULL DEFAULT false,
+    -- lowest first in the gallery, like projects
+    display_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_albums_display_order ON albums(display_order);
@@ -0,0 +1,14 @@
+-- tidy links for sharing, `/l/{code}` redirects to target_url
+-- every click is also a page_visits row, with the link's utm tags, so it shows up in the campaign reports
+CREATE TABLE short_links (
+    link_id UUID PRIMARY KEY,
+    code TEXT NOT NULL UNIQUE,
+    target_url TEXT NOT NULL,
+    utm_source TEXT,
+    utm_medium TEXT,
+    utm_campaign TEXT,
+    click_count BIGINT NOT NULL DEFAULT 0,
+    last_clicked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,15 @@
+-- short dated entries for the /now page and the site changelog, kept out of the blog
+-- so neither has to live as a never-published article
+CREATE TYPE now_entry_kind AS ENUM ('now', 'changelog');
+
+CREATE TABLE now_entries (
+    entry_id UUID PRIMARY KEY,
+    kind now_entry_kind NOT NULL,
+    entry_date DATE NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX now_entries_kind_entry_date_idx ON now_entries (kind, entry_date DESC);
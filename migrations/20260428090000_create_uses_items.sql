@@ -0,0 +1,13 @@
+-- the hardware, software and tools on the /uses page
+CREATE TABLE uses_items (
+    item_id UUID PRIMARY KEY,
+    category TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    url TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- the same thing listed twice in one category is a mistake, across categories it isn't
+CREATE UNIQUE INDEX idx_uses_items_category_name ON uses_items(lower(category), lower(name));
@@ -0,0 +1,3 @@
+-- new messages store the sender trimmed and lowercased, bring the old ones in line
+-- so the duplicate check and the inbox see one sender however it was typed
+UPDATE messages SET email = LOWER(TRIM(email)) WHERE email <> LOWER(TRIM(email));
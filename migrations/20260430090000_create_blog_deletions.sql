@@ -0,0 +1,9 @@
+-- a single row, when a post was last deleted, so the blog list's Last-Modified
+-- moves when a post disappears and not only when one is edited
+CREATE TABLE blog_deletions (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    last_deleted_at TIMESTAMPTZ
+);
+
+INSERT INTO blog_deletions (last_deleted_at)
+VALUES (NULL);
@@ -0,0 +1,266 @@
+use anyhow::Context;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::configuration::AntivirusSettings;
+use crate::storage::Storage;
+
+pub const SCAN_UPLOAD_JOB: &str = "scan_upload";
+
+// infected uploads are moved under this prefix rather than deleted, out of `/media`'s reach
+pub const QUARANTINE_PREFIX: &str = "quarantine";
+
+// clamd's default StreamMaxLength is 25MB, chunks only bound what's written at once
+const CHUNK_BYTES: usize = 64 * 1024;
+
+// whether uploads are scanned, `storage.antivirus.clamd_address` is set
+#[derive(Clone, Copy, Debug)]
+pub struct ScanUploads(pub bool);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanStatus {
+    // uploaded, not served until the scan says otherwise
+    Pending,
+    Clean,
+    Infected,
+}
+
+impl ScanStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Clean => "clean",
+            Self::Infected => "infected",
+        }
+    }
+
+    fn parse(status: &str) -> Option<Self> {
+        match status {
+            "pending" => Some(Self::Pending),
+            "clean" => Some(Self::Clean),
+            "infected" => Some(Self::Infected),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Clean,
+    // the signature clamd matched, e.g. `Eicar-Test-Signature`
+    Infected(String),
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ScanUpload {
+    pub key: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Clamd {
+    address: String,
+    timeout: Duration,
+}
+
+impl Clamd {
+    // `None` when no clamd is configured, uploads are then served as soon as they land
+    #[must_use]
+    pub fn from_settings(settings: &AntivirusSettings) -> Option<Self> {
+        settings.clamd_address.as_ref().map(|address| Self {
+            address: address.clone(),
+            timeout: Duration::from_secs(settings.timeout_secs),
+        })
+    }
+
+    /// # Errors
+    /// returns an error if clamd can't be reached, times out or reports an error of its own
+    pub async fn scan(&self, bytes: &[u8]) -> Result<Verdict, anyhow::Error> {
+        tokio::time::timeout(self.timeout, self.instream(bytes))
+            .await
+            .context("clamd didn't answer in time")?
+    }
+
+    async fn instream(&self, bytes: &[u8]) -> Result<Verdict, anyhow::Error> {
+        if self.address.starts_with('/') {
+            return self.instream_unix(bytes).await;
+        }
+        let socket = tokio::net::TcpStream::connect(&self.address)
+            .await
+            .with_context(|| format!("Failed to connect to clamd at {}", self.address))?;
+        instream(socket, bytes).await
+    }
+
+    #[cfg(unix)]
+    async fn instream_unix(&self, bytes: &[u8]) -> Result<Verdict, anyhow::Error> {
+        let socket = tokio::net::UnixStream::connect(&self.address)
+            .await
+            .with_context(|| format!("Failed to connect to clamd at {}", self.address))?;
+        instream(socket, bytes).await
+    }
+
+    #[cfg(not(unix))]
+    async fn instream_unix(&self, _bytes: &[u8]) -> Result<Verdict, anyhow::Error> {
+        anyhow::bail!("clamd's unix socket isn't reachable on this platform, use host:port")
+    }
+}
+
+// clamd's INSTREAM: length-prefixed chunks, a zero length to finish, one reply line back
+async fn instream(
+    mut socket: impl AsyncRead + AsyncWrite + Unpin,
+    bytes: &[u8],
+) -> Result<Verdict, anyhow::Error> {
+    socket.write_all(b"zINSTREAM\0").await?;
+    for chunk in bytes.chunks(CHUNK_BYTES) {
+        let length = u32::try_from(chunk.len()).context("chunk too large")?;
+        socket.write_all(&length.to_be_bytes()).await?;
+        socket.write_all(chunk).await?;
+    }
+    socket.write_all(&0u32.to_be_bytes()).await?;
+    socket.flush().await?;
+
+    let mut reply = Vec::new();
+    socket.read_to_end(&mut reply).await?;
+    parse_reply(&String::from_utf8_lossy(&reply))
+}
+
+// `stream: OK`, `stream: <signature> FOUND` or `<reason> ERROR`
+fn parse_reply(reply: &str) -> Result<Verdict, anyhow::Error> {
+    let reply = reply.trim_end_matches(['\0', '\n']);
+    let result = reply.strip_prefix("stream: ").unwrap_or(reply);
+    if result == "OK" {
+        return Ok(Verdict::Clean);
+    }
+    match result.strip_suffix(" FOUND") {
+        Some(signature) => Ok(Verdict::Infected(signature.to_string())),
+        None => anyhow::bail!("clamd couldn't scan the upload: {result}"),
+    }
+}
+
+// before the object is written, so there's no moment it's stored and servable unscanned
+/// # Errors
+/// returns a `sqlx` error if the row can't be written
+#[tracing::instrument(name = "Mark upload as pending scan", skip(pool))]
+pub async fn mark_pending(pool: &PgPool, key: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO media_scans (object_key)
+        VALUES ($1)
+        ON CONFLICT (object_key) DO UPDATE
+        SET status = 'pending', signature = NULL, scanned_at = NULL
+        "#,
+        key
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// `None` for objects that never went through the scan, they predate it or bypassed the api,
+// `/media` answers those with a 404 so an unknown key isn't reported as still scanning
+/// # Errors
+/// returns a `sqlx` error if the row can't be read
+pub async fn scan_status(pool: &PgPool, key: &str) -> Result<Option<ScanStatus>, sqlx::Error> {
+    let status = sqlx::query_scalar!(
+        r#"SELECT status FROM media_scans WHERE object_key = $1"#,
+        key
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(status.as_deref().and_then(ScanStatus::parse))
+}
+
+// the `scan_upload` job, a clamd that's down fails the attempt so the queue retries it,
+// the upload stays pending (and unserved) until then
+/// # Errors
+/// returns an error if the upload can't be read, scanned, quarantined or its status recorded
+#[tracing::instrument(name = "Scan upload", skip_all, fields(key))]
+pub async fn scan_upload(
+    payload: serde_json::Value,
+    clamd: &Clamd,
+    storage: &Storage,
+    pool: &PgPool,
+) -> Result<(), anyhow::Error> {
+    let ScanUpload { key } =
+        serde_json::from_value(payload).context("Invalid scan_upload payload")?;
+    tracing::Span::current().record("key", tracing::field::display(&key));
+
+    // replaced or removed since, whatever is there now was queued separately
+    let Some(bytes) = storage.get(&key).await? else {
+        tracing::warn!("Upload is gone, nothing to scan");
+        return Ok(());
+    };
+
+    let (status, signature) = match clamd.scan(&bytes).await? {
+        Verdict::Clean => (ScanStatus::Clean, None),
+        Verdict::Infected(signature) => {
+            tracing::warn!(signature = %signature, "Upload is infected, quarantining it");
+            storage
+                .put(&format!("{QUARANTINE_PREFIX}/{key}"), bytes)
+                .await?;
+            storage.delete(&key).await?;
+            (ScanStatus::Infected, Some(signature))
+        }
+    };
+
+    sqlx::query!(
+        r#"
+        UPDATE media_scans
+        SET status = $2, signature = $3, scanned_at = NOW()
+        WHERE object_key = $1
+        "#,
+        key,
+        status.as_str(),
+        signature
+    )
+    .execute(pool)
+    .await
+    .context("Failed to record the scan result")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn replies_are_parsed() {
+        assert_eq!(parse_reply("stream: OK\0").unwrap(), Verdict::Clean);
+        assert_eq!(
+            parse_reply("stream: Eicar-Test-Signature FOUND\0").unwrap(),
+            Verdict::Infected("Eicar-Test-Signature".to_string())
+        );
+        assert!(parse_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+
+    #[tokio::test]
+    async fn uploads_are_streamed_in_length_prefixed_chunks() {
+        let (client, mut server) = tokio::io::duplex(1024 * 1024);
+        let upload = vec![7u8; CHUNK_BYTES + 10];
+
+        let clamd = tokio::spawn(async move {
+            let mut command = [0u8; 10];
+            server.read_exact(&mut command).await.unwrap();
+            let mut lengths = Vec::new();
+            loop {
+                let length = server.read_u32().await.unwrap();
+                if length == 0 {
+                    break;
+                }
+                lengths.push(length);
+                let mut chunk = vec![0u8; length as usize];
+                server.read_exact(&mut chunk).await.unwrap();
+            }
+            server.write_all(b"stream: OK\0").await.unwrap();
+            (command, lengths)
+        });
+
+        let verdict = instream(client, &upload).await.unwrap();
+        let (command, lengths) = clamd.await.unwrap();
+
+        assert_eq!(verdict, Verdict::Clean);
+        assert_eq!(&command, b"zINSTREAM\0");
+        assert_eq!(lengths, [u32::try_from(CHUNK_BYTES).unwrap(), 10]);
+    }
+}
@@ -4,13 +4,18 @@ use actix_web::{
     cookie::{Cookie, SameSite},
     dev::{Payload, ServiceRequest, ServiceResponse},
     error::InternalError,
-    http::Method,
+    http::{Method, StatusCode},
     middleware::Next,
+    web,
 };
+use sqlx::PgPool;
 use std::future::{Ready, ready};
+use std::net::SocketAddr;
 use std::ops::Deref;
 use uuid::Uuid;
 
+use crate::authentication::is_session_revoked;
+use crate::errors::ApiProblem;
 use crate::session_state::TypedSession;
 use crate::types::user::UserRole;
 use crate::utils::{e500, unauthorized};
@@ -69,6 +74,16 @@ pub async fn reject_anonymous_users(
     // on get_user_id is acceptable. This is in effect, equivalent to the session
     // middleware not being configured.
     if let Some(user_id) = session.get_user_id().map_err(e500)? {
+        // sessions revoked from another device are logged out on their next request
+        if let Some(session_id) = session.get_session_id().map_err(e500)?
+            && let Some(pool) = req.app_data::<web::Data<PgPool>>()
+            && is_session_revoked(session_id, pool).await.map_err(e500)?
+        {
+            session.log_out();
+            let e = anyhow::anyhow!("The session has been revoked");
+            return Err(InternalError::from_response(e, unauthorized()).into());
+        }
+
         req.extensions_mut().insert(UserId(user_id));
         next.call(req).await
     } else {
@@ -80,6 +95,11 @@ pub async fn reject_anonymous_users(
 
 const XSRF_COOKIE_NAME: &str = "XSRF-TOKEN";
 const XSRF_HEADER_NAME: &str = "X-XSRF-TOKEN";
+// anonymous ingestion sent with `navigator.sendBeacon`, which can't set headers
+// there's no session-backed action behind it for a forged request to ride on
+const XSRF_EXEMPT_PATHS: [&str; 1] = ["/v1/metrics/visits/batch"];
+// presigned storage urls, the signature is the credential and the session plays no part
+const XSRF_EXEMPT_PREFIXES: [&str; 1] = ["/v1/storage/"];
 
 #[allow(clippy::future_not_send)]
 pub async fn cross_site_request_forgery_protection(
@@ -89,7 +109,10 @@ pub async fn cross_site_request_forgery_protection(
     let is_safe = matches!(
         request.method(),
         &Method::GET | &Method::HEAD | &Method::OPTIONS
-    );
+    ) || XSRF_EXEMPT_PATHS.contains(&request.path())
+        || XSRF_EXEMPT_PREFIXES
+            .iter()
+            .any(|prefix| request.path().starts_with(prefix));
 
     if !is_safe {
         let cookie_val = request
@@ -103,7 +126,11 @@ pub async fn cross_site_request_forgery_protection(
 
         match (cookie_val, header_val) {
             (Some(c), Some(h)) if !c.is_empty() && c == h => {}
-            _ => return Err(actix_web::error::ErrorForbidden("Invalid CSRF token")),
+            _ => {
+                let problem =
+                    ApiProblem::new(StatusCode::FORBIDDEN).with_detail("Invalid CSRF token");
+                return Err(problem.into());
+            }
         }
     }
 
@@ -150,3 +177,39 @@ pub async fn reject_non_admin(
     let e = anyhow::anyhow!("The user is not an admin");
     Err(InternalError::from_response(e, response).into())
 }
+
+// the address of the private listener when `application.admin_port` is set
+#[derive(Copy, Clone, Debug)]
+pub struct AdminListener(pub Option<SocketAddr>);
+
+const ADMIN_SCOPE: &str = "/v1/admin";
+const ADMIN_ONLY_PATHS: [&str; 1] = ["/metrics"];
+
+// the admin listener serves everything, login included, the public one hides the admin
+// surface behind a plain 404 so nothing there is even acknowledged to exist
+#[allow(clippy::future_not_send)]
+pub async fn restrict_admin_surface(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let admin_address = request
+        .app_data::<web::Data<AdminListener>>()
+        .and_then(|listener| listener.0);
+
+    if let Some(admin_address) = admin_address
+        && request.app_config().local_addr() != admin_address
+        // the decoded path, the one the router matches, so `/v1/%61dmin` can't slip past
+        && is_admin_surface(request.match_info().as_str())
+    {
+        return Err(ApiProblem::new(StatusCode::NOT_FOUND).into());
+    }
+
+    next.call(request).await
+}
+
+fn is_admin_surface(path: &str) -> bool {
+    ADMIN_ONLY_PATHS.contains(&path)
+        || path
+            .strip_prefix(ADMIN_SCOPE)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
@@ -1,10 +1,15 @@
 mod middleware;
 mod password;
+mod sessions;
 
 pub use middleware::{
-    UserId, cross_site_request_forgery_protection, reject_anonymous_users, reject_non_admin,
+    AdminListener, UserId, cross_site_request_forgery_protection, reject_anonymous_users,
+    reject_non_admin, restrict_admin_surface,
 };
 pub use password::{
     Credentials, change_password, compute_password_hash, update_user_password,
     validate_credentials, validate_credentials_with_verifier,
 };
+pub use sessions::{
+    DeviceSession, LoginMetadata, is_session_revoked, list_sessions, record_login, revoke_session,
+};
@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{http::StatusCode, web};
 use anyhow::Context;
 use argon2::{
     Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
@@ -6,9 +6,12 @@ use argon2::{
 };
 use secrecy::{ExposeSecret, SecretString};
 use sqlx::PgPool;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::configuration::PasswordHashingSettings;
 use crate::telemetry::spawn_blocking_with_tracing;
+use crate::types::api_response::ApiResponse;
 use crate::types::user::UserRole;
 use crate::{authentication::UserId, errors::AuthError, routes::get_username_by_id};
 
@@ -51,18 +54,20 @@ async fn get_stored_credentials(
 
 pub async fn validate_credentials(
     credentials: Credentials,
+    hashing: &PasswordHashingSettings,
     pool: &PgPool,
 ) -> Result<(uuid::Uuid, bool, bool, UserRole), AuthError> {
-    validate_credentials_with_verifier(credentials, pool, verify_password_hash).await
+    validate_credentials_with_verifier(credentials, hashing, pool, verify_password_hash).await
 }
 
 #[doc(hidden)]
-#[tracing::instrument("Validate credentials", skip(credentials, pool, verify_fn))]
+#[tracing::instrument("Validate credentials", skip(credentials, hashing, pool, verify_fn))]
 /// # Errors
 /// shoots off an `AuthError::InvalidCredentials` if the hash for the provided `credentials` cannot be verified
 /// or an `anyhow` error if the `username` doesn't exist in the database
 pub async fn validate_credentials_with_verifier<F>(
     credentials: Credentials,
+    hashing: &PasswordHashingSettings,
     pool: &PgPool,
     verify_fn: F,
 ) -> Result<(uuid::Uuid, bool, bool, UserRole), AuthError>
@@ -88,26 +93,106 @@ where
         user_role = stored_user_role;
         stored_password_hash
     } else {
-        // this is a made-up hash to prevent timing attacks
-        SecretString::new(
-            "$argon2id$v=19$m=19456,t=2,p=1$\
-                gZiV/M1gPc22ElAH/Jh1Hw$\
-                CWOrkoo7oJBQ/iyh7uJ0LO2aLEfrHwTWllSAxT0zRno"
-                .into(),
-        )
+        // a made-up hash to prevent timing attacks, it has to cost as much as a real one
+        match &hashing.dummy_hash {
+            Some(dummy_hash) => dummy_hash.clone(),
+            None => {
+                let hashing = hashing.clone();
+                spawn_blocking_with_tracing(move || dummy_password_hash(&hashing))
+                    .await
+                    .context("Failed to spawn blocking task.")??
+            }
+        }
     };
 
-    spawn_blocking_with_tracing(move || verify_fn(&expected_password_hash, &credentials.password))
-        .await
-        .context("Failed to spawn blocking task.")??;
+    let (expected_password_hash, password) = spawn_blocking_with_tracing(move || {
+        verify_fn(&expected_password_hash, &credentials.password)
+            .map(|()| (expected_password_hash, credentials.password))
+    })
+    .await
+    .context("Failed to spawn blocking task.")??;
 
     // only set to Some if we find stored credentials
     // so even if the default password ends up matching (somehow)
     // we never authenticate a non-existent user.
-    user_id
+    let user_id = user_id
         .ok_or_else(|| anyhow::anyhow!("Unknown username"))
-        .map_err(AuthError::InvalidCredentials)
-        .map(|id| (id, totp_enabled, must_change_password, user_role))
+        .map_err(AuthError::InvalidCredentials)?;
+
+    if needs_rehash(&expected_password_hash, hashing) {
+        spawn_rehash(
+            user_id,
+            password,
+            expected_password_hash,
+            hashing.clone(),
+            pool.clone(),
+        );
+    }
+
+    Ok((user_id, totp_enabled, must_change_password, user_role))
+}
+
+// true if the stored hash was produced with anything weaker than the configured params
+// (hashes that can't be parsed are left alone, verification would have failed anyway)
+fn needs_rehash(stored_password_hash: &SecretString, hashing: &PasswordHashingSettings) -> bool {
+    let Ok(target) = hashing.params() else {
+        return false;
+    };
+    let Ok(stored) = PasswordHash::new(stored_password_hash.expose_secret()) else {
+        return false;
+    };
+    let Ok(stored_params) = Params::try_from(&stored) else {
+        return false;
+    };
+
+    stored.algorithm != Algorithm::Argon2id.ident()
+        || stored.version != Some(Version::V0x13.into())
+        || stored_params.m_cost() < target.m_cost()
+        || stored_params.t_cost() < target.t_cost()
+        || stored_params.p_cost() < target.p_cost()
+}
+
+// upgrade the stored hash off the request path, login shouldn't wait on a second argon2 run
+// the update is conditional on the old hash so a concurrent password change always wins
+fn spawn_rehash(
+    user_id: Uuid,
+    password: SecretString,
+    old_password_hash: SecretString,
+    hashing: PasswordHashingSettings,
+    pool: PgPool,
+) {
+    let span = tracing::info_span!("Rehash password", %user_id);
+    let rehash = async move {
+        let password_hash =
+            spawn_blocking_with_tracing(move || compute_password_hash(&password, &hashing))
+                .await
+                .context("Failed to spawn blocking task.")??;
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET password_hash = $1
+            WHERE user_id = $2 AND password_hash = $3
+            "#,
+            password_hash.expose_secret(),
+            user_id,
+            old_password_hash.expose_secret(),
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to store the rehashed password.")?;
+        Ok::<(), anyhow::Error>(())
+    };
+
+    tokio::spawn(
+        async move {
+            match rehash.await {
+                Ok(()) => tracing::info!("Password hash upgraded to the configured parameters"),
+                Err(e) => tracing::warn!(error.cause_chain = ?e, "Failed to rehash password"),
+            }
+        }
+        .instrument(span),
+    );
 }
 
 #[tracing::instrument(
@@ -130,17 +215,20 @@ fn verify_password_hash(
         .map_err(AuthError::InvalidCredentials)
 }
 
-#[tracing::instrument(name = "Change password", skip(password, pool))]
+#[tracing::instrument(name = "Change password", skip(password, hashing, pool))]
 /// # Errors
 /// errors from anywhere in this function are handled by `anyhow` and passed up the pipeline
 pub async fn change_password(
     user_id: Uuid,
     password: SecretString,
+    hashing: &PasswordHashingSettings,
     pool: &PgPool,
 ) -> Result<(), anyhow::Error> {
-    let password_hash = spawn_blocking_with_tracing(move || compute_password_hash(&password))
-        .await?
-        .context("Failed to compute password hash")?;
+    let hashing = hashing.clone();
+    let password_hash =
+        spawn_blocking_with_tracing(move || compute_password_hash(&password, &hashing))
+            .await?
+            .context("Failed to compute password hash")?;
 
     sqlx::query!(
         r#"
@@ -165,9 +253,10 @@ pub struct ChangePasswordBody {
 
 pub async fn update_user_password(
     pool: web::Data<PgPool>,
+    hashing: web::Data<PasswordHashingSettings>,
     body: web::Json<ChangePasswordBody>,
     user_id: web::ReqData<UserId>,
-) -> Result<HttpResponse, AuthError> {
+) -> Result<ApiResponse, AuthError> {
     let body = body.into_inner();
     let user_id = **user_id;
 
@@ -181,29 +270,43 @@ pub async fn update_user_password(
         password: body.current_password.clone(),
     };
 
-    validate_credentials(credentials, &pool).await?;
+    validate_credentials(credentials, &hashing, &pool).await?;
 
     // If validation succeeds, we can proceed to change the password
-    change_password(user_id, body.new_password, pool.get_ref())
+    change_password(user_id, body.new_password, &hashing, pool.get_ref())
         .await
         .context("Failed to change password.")
         .map_err(AuthError::UnexpectedError)?;
 
-    Ok(HttpResponse::Accepted().finish())
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
+
+impl PasswordHashingSettings {
+    // unknown usernames are verified against this, so it's hashed with the same
+    // params as real passwords; otherwise raising the cost makes them measurably faster
+    /// # Errors
+    /// returns an `anyhow` error if the configured argon2 params are invalid
+    pub fn with_dummy_hash(mut self) -> Result<Self, anyhow::Error> {
+        self.dummy_hash = Some(dummy_password_hash(&self)?);
+        Ok(self)
+    }
+}
+
+fn dummy_password_hash(hashing: &PasswordHashingSettings) -> Result<SecretString, anyhow::Error> {
+    compute_password_hash(&SecretString::from(Uuid::new_v4().to_string()), hashing)
 }
 
-pub fn compute_password_hash(password: &SecretString) -> Result<SecretString, anyhow::Error> {
+pub fn compute_password_hash(
+    password: &SecretString,
+    hashing: &PasswordHashingSettings,
+) -> Result<SecretString, anyhow::Error> {
     let salt = SaltString::generate(&mut OsRng);
-    // expect is acceptable here because password hashing should never fail
-    // if Argon2 is configured and working properly, and we aren't testing Argon2
-    // so there's no reason to propogate this error
-    let password_hash = Argon2::new(
-        Algorithm::Argon2id,
-        Version::V0x13,
-        Params::new(19456, 2, 1, None).unwrap(),
-    )
-    .hash_password(password.expose_secret().as_bytes(), &salt)?
-    .to_string();
+    let params = hashing
+        .params()
+        .context("Invalid argon2 parameters in the password hashing settings")?;
+    let password_hash = Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password(password.expose_secret().as_bytes(), &salt)?
+        .to_string();
     Ok(SecretString::new(Box::from(password_hash)))
 }
 
@@ -225,4 +328,40 @@ mod test {
                 .contains("Failed to parse hash in PHC string format.")
         );
     }
+
+    #[test]
+    fn weaker_hashes_need_rehash() {
+        let hashing = PasswordHashingSettings::default();
+        let password = SecretString::new("password".into());
+
+        let current = compute_password_hash(&password, &hashing).unwrap();
+        assert!(!needs_rehash(&current, &hashing));
+
+        let weaker = PasswordHashingSettings {
+            memory_kib: 15000,
+            ..PasswordHashingSettings::default()
+        };
+        let outdated = compute_password_hash(&password, &weaker).unwrap();
+        assert!(needs_rehash(&outdated, &hashing));
+        // lowering the configured cost never downgrades existing hashes
+        assert!(!needs_rehash(&current, &weaker));
+
+        let garbage = SecretString::new("not-a-phc-string".into());
+        assert!(!needs_rehash(&garbage, &hashing));
+    }
+
+    #[test]
+    fn dummy_hash_uses_the_configured_params() {
+        let hashing = PasswordHashingSettings {
+            memory_kib: 32768,
+            iterations: 3,
+            ..PasswordHashingSettings::default()
+        }
+        .with_dummy_hash()
+        .unwrap();
+
+        let dummy_hash = hashing.dummy_hash.as_ref().unwrap();
+        assert!(!needs_rehash(dummy_hash, &hashing));
+        assert!(dummy_hash.expose_secret().contains("m=32768,t=3,p=1"));
+    }
 }
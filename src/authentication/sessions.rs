@@ -0,0 +1,177 @@
+use actix_web::{FromRequest, HttpRequest, dev::Payload, http::header::USER_AGENT};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::future::{Ready, ready};
+use uuid::Uuid;
+
+use crate::client_ip::{client_ip, from_trusted_proxy};
+
+// set by the CDN in production, absent when running locally
+// only read from trusted proxies, like `CF-Connecting-IP`, or a client could pick its location
+const GEO_HEADER: &str = "CF-IPCountry";
+
+// what we know about the device a login came from
+#[derive(Debug, Clone)]
+pub struct LoginMetadata {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub geo: Option<String>,
+}
+
+impl FromRequest for LoginMetadata {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let header = |name| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+
+        ready(Ok(Self {
+            user_agent: header(USER_AGENT.as_str()),
+            ip_address: client_ip(req).map(|ip| ip.to_string()),
+            geo: from_trusted_proxy(req)
+                .then(|| header(GEO_HEADER))
+                .flatten(),
+        }))
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct DeviceSession {
+    pub session_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub geo: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+#[tracing::instrument(name = "Record successful login", skip(metadata, pool))]
+/// # Errors
+/// returns an `anyhow` error if the login metadata can't be written to the database
+pub async fn record_login(
+    user_id: Uuid,
+    metadata: &LoginMetadata,
+    pool: &PgPool,
+) -> Result<Uuid, anyhow::Error> {
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")?;
+
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET previous_login_at = last_login_at,
+            previous_login_ip = last_login_ip,
+            last_login_at = NOW(),
+            last_login_ip = $2
+        WHERE user_id = $1
+        "#,
+        user_id,
+        metadata.ip_address,
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to record the user's last login.")?;
+
+    let session_id = Uuid::new_v4();
+    sqlx::query!(
+        r#"
+        INSERT INTO user_sessions (session_id, user_id, user_agent, ip_address, geo)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        session_id,
+        user_id,
+        metadata.user_agent,
+        metadata.ip_address,
+        metadata.geo,
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to record the login session.")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit the login session.")?;
+    Ok(session_id)
+}
+
+#[tracing::instrument(name = "List device sessions", skip(pool))]
+/// # Errors
+/// returns an `anyhow` error if the sessions can't be read from the database
+pub async fn list_sessions(
+    user_id: Uuid,
+    current_session_id: Option<Uuid>,
+    pool: &PgPool,
+) -> Result<Vec<DeviceSession>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT session_id, user_agent, ip_address, geo, created_at
+        FROM user_sessions
+        WHERE user_id = $1 AND revoked_at IS NULL
+        ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch the user's sessions.")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DeviceSession {
+            current: Some(row.session_id) == current_session_id,
+            session_id: row.session_id,
+            user_agent: row.user_agent,
+            ip_address: row.ip_address,
+            geo: row.geo,
+            created_at: row.created_at,
+        })
+        .collect())
+}
+
+#[tracing::instrument(name = "Revoke device session", skip(pool))]
+/// # Errors
+/// returns an `anyhow` error if the session can't be updated,
+/// `Ok(false)` means the session doesn't exist or belongs to someone else
+pub async fn revoke_session(
+    user_id: Uuid,
+    session_id: Uuid,
+    pool: &PgPool,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE user_sessions
+        SET revoked_at = COALESCE(revoked_at, NOW())
+        WHERE session_id = $1 AND user_id = $2
+        "#,
+        session_id,
+        user_id,
+    )
+    .execute(pool)
+    .await
+    .context("Failed to revoke the session.")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// # Errors
+/// returns an `anyhow` error if the session can't be looked up
+pub async fn is_session_revoked(session_id: Uuid, pool: &PgPool) -> Result<bool, anyhow::Error> {
+    let revoked = sqlx::query_scalar!(
+        r#"SELECT revoked_at IS NOT NULL AS "revoked!" FROM user_sessions WHERE session_id = $1"#,
+        session_id,
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to look up the session.")?;
+
+    Ok(revoked.unwrap_or(false))
+}
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use moka::sync::Cache;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::configuration::BlogCacheSettings;
+use crate::types::article::ArticleRecord;
+
+// one published page of `GET /v1/blog`, as the handler asked for it
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BlogPageKey {
+    pub slug: Option<String>,
+    pub page: i64,
+    pub page_size: i64,
+}
+
+pub struct BlogPage {
+    pub articles: Vec<ArticleRecord>,
+    pub total: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+// published pages only, drafts are never cached so a signed-in preview is always fresh
+// the least recently read page goes first once `max_entries` is reached
+pub struct BlogCache {
+    pages: Option<Cache<BlogPageKey, Arc<BlogPage>>>,
+    // bumped by every invalidation, a page read before one isn't stored after it
+    generation: AtomicU64,
+}
+
+impl BlogCache {
+    #[must_use]
+    pub fn new(settings: &BlogCacheSettings) -> Self {
+        let pages = (settings.ttl_secs > 0 && settings.max_entries > 0).then(|| {
+            Cache::builder()
+                .max_capacity(settings.max_entries)
+                .time_to_live(Duration::from_secs(settings.ttl_secs))
+                .build()
+        });
+        Self {
+            pages,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    // off when `ttl_secs` or `max_entries` is 0
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.pages.is_some()
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &BlogPageKey) -> Option<Arc<BlogPage>> {
+        self.pages.as_ref()?.get(key)
+    }
+
+    // taken before the page is read from postgres and handed back to `insert`
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub fn insert(&self, key: BlogPageKey, page: Arc<BlogPage>, generation: u64) {
+        if let Some(pages) = &self.pages
+            && self.generation() == generation
+        {
+            pages.insert(key, page);
+        }
+    }
+
+    // called once a publish, edit or delete has committed
+    pub fn invalidate_all(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        if let Some(pages) = &self.pages {
+            pages.invalidate_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn settings(ttl_secs: u64) -> BlogCacheSettings {
+        BlogCacheSettings {
+            max_entries: 10,
+            ttl_secs,
+        }
+    }
+
+    fn key(page: i64) -> BlogPageKey {
+        BlogPageKey {
+            slug: None,
+            page,
+            page_size: 20,
+        }
+    }
+
+    fn page() -> Arc<BlogPage> {
+        Arc::new(BlogPage {
+            articles: Vec::new(),
+            total: 0,
+            last_modified: None,
+        })
+    }
+
+    #[test]
+    fn pages_are_reused_until_invalidated() {
+        let cache = BlogCache::new(&settings(60));
+        cache.insert(key(1), page(), cache.generation());
+
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(2)).is_none());
+
+        cache.invalidate_all();
+        assert!(cache.get(&key(1)).is_none());
+    }
+
+    #[test]
+    fn a_page_read_before_an_invalidation_is_not_stored() {
+        let cache = BlogCache::new(&settings(60));
+        let generation = cache.generation();
+
+        cache.invalidate_all();
+        cache.insert(key(1), page(), generation);
+
+        assert!(cache.get(&key(1)).is_none());
+    }
+
+    #[test]
+    fn a_zero_ttl_turns_caching_off() {
+        let cache = BlogCache::new(&settings(0));
+        cache.insert(key(1), page(), cache.generation());
+
+        assert!(!cache.is_enabled());
+        assert!(BlogCache::new(&settings(60)).is_enabled());
+        assert!(cache.get(&key(1)).is_none());
+    }
+}
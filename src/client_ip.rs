@@ -0,0 +1,154 @@
+use actix_web::dev::Payload;
+use actix_web::{FromRequest, HttpRequest, web};
+use ipnet::IpNet;
+use std::future::{Ready, ready};
+use std::net::IpAddr;
+
+const CF_CONNECTING_IP: &str = "cf-connecting-ip";
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+// proxies in front of the server, from `application.trusted_proxies`
+// only requests arriving from one of these get their forwarded headers read
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies(pub Vec<IpNet>);
+
+impl TrustedProxies {
+    fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|net| net.contains(ip))
+    }
+
+    // whether the request came straight from one of our proxies, so headers it sets can be believed
+    #[must_use]
+    pub fn is_trusted(&self, request: &HttpRequest) -> bool {
+        request
+            .peer_addr()
+            .is_some_and(|peer| self.contains(&peer.ip()))
+    }
+
+    // the socket peer, unless that's a trusted proxy, then whoever the proxy says it forwarded for
+    // cloudflare's header wins when present, otherwise x-forwarded-for is walked from the right,
+    // skipping our own proxies, so a client can't pick its address by prepending entries
+    #[must_use]
+    pub fn client_ip(&self, request: &HttpRequest) -> Option<IpAddr> {
+        let peer = request.peer_addr()?.ip();
+        if !self.contains(&peer) {
+            return Some(peer);
+        }
+
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+
+        if let Some(ip) = header(CF_CONNECTING_IP).and_then(|value| value.trim().parse().ok()) {
+            return Some(ip);
+        }
+
+        let mut client = peer;
+        for hop in header(X_FORWARDED_FOR)
+            .into_iter()
+            .flat_map(|value| value.rsplit(','))
+        {
+            let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = hop;
+            if !self.contains(&hop) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+// the resolved address of whoever sent the request, see `TrustedProxies::client_ip`
+#[must_use]
+pub fn client_ip(request: &HttpRequest) -> Option<IpAddr> {
+    request.app_data::<web::Data<TrustedProxies>>().map_or_else(
+        || request.peer_addr().map(|peer| peer.ip()),
+        |proxies| proxies.client_ip(request),
+    )
+}
+
+// see `TrustedProxies::is_trusted`, nothing is trusted without `application.trusted_proxies`
+#[must_use]
+pub fn from_trusted_proxy(request: &HttpRequest) -> bool {
+    request
+        .app_data::<web::Data<TrustedProxies>>()
+        .is_some_and(|proxies| proxies.is_trusted(request))
+}
+
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl FromRequest for ClientIp {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(Self(client_ip(req))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn proxies() -> TrustedProxies {
+        TrustedProxies(vec!["10.0.0.0/8".parse().unwrap()])
+    }
+
+    fn request_from(peer: &str, headers: &[(&'static str, &str)]) -> HttpRequest {
+        let mut request = TestRequest::default().peer_addr(format!("{peer}:443").parse().unwrap());
+        for &(name, value) in headers {
+            request = request.insert_header((name, value));
+        }
+        request.to_http_request()
+    }
+
+    #[test]
+    fn untrusted_peers_are_taken_at_their_word() {
+        let request = request_from("203.0.113.7", &[(X_FORWARDED_FOR, "198.51.100.1")]);
+
+        assert_eq!(proxies().client_ip(&request), "203.0.113.7".parse().ok());
+    }
+
+    #[test]
+    fn trusted_peers_forward_the_nearest_untrusted_hop() {
+        // the leftmost entry is whatever the client sent, only the hops our proxies added count
+        let request = request_from(
+            "10.0.0.2",
+            &[(X_FORWARDED_FOR, "192.0.2.66, 198.51.100.1, 10.0.0.3")],
+        );
+
+        assert_eq!(proxies().client_ip(&request), "198.51.100.1".parse().ok());
+    }
+
+    #[test]
+    fn cloudflare_header_wins_from_trusted_peers() {
+        let request = request_from(
+            "10.0.0.2",
+            &[
+                (CF_CONNECTING_IP, "198.51.100.9"),
+                (X_FORWARDED_FOR, "198.51.100.1"),
+            ],
+        );
+
+        assert_eq!(proxies().client_ip(&request), "198.51.100.9".parse().ok());
+    }
+
+    #[test]
+    fn only_proxy_peers_are_trusted() {
+        assert!(proxies().is_trusted(&request_from("10.0.0.2", &[])));
+        assert!(!proxies().is_trusted(&request_from("203.0.113.7", &[])));
+    }
+
+    #[test]
+    fn trusted_peers_without_forwarded_headers_are_the_client() {
+        let request = request_from("10.0.0.2", &[(X_FORWARDED_FOR, "not-an-ip")]);
+
+        assert_eq!(proxies().client_ip(&request), "10.0.0.2".parse().ok());
+    }
+}
@@ -0,0 +1,118 @@
+use actix_cors::Cors;
+use actix_web::http;
+use regex::Regex;
+use std::str::FromStr;
+
+use crate::idempotency::{IDEMPOTENT_PROCESSED_AT_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use crate::metrics::INGEST_SIGNATURE_HEADER;
+use crate::reload::ReloadableSettings;
+use crate::types::api_response::RESPONSE_ENVELOPE_HEADER;
+
+// an allowed origin with `*` standing in for a single dns label,
+// e.g. `https://*.preview.devogel.dev` lets every preview deploy through
+#[derive(Clone, Debug)]
+pub struct OriginPattern(Regex);
+
+impl OriginPattern {
+    #[must_use]
+    pub fn matches(&self, origin: &str) -> bool {
+        self.0.is_match(&origin.to_ascii_lowercase())
+    }
+}
+
+impl FromStr for OriginPattern {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        let host = pattern.split_once("://").map_or("", |(_, host)| host);
+        if host.is_empty() || host.contains('/') {
+            return Err(format!(
+                "{pattern} is not an origin pattern, expected scheme://host[:port]"
+            ));
+        }
+        let label = "[a-z0-9](?:[a-z0-9-]*[a-z0-9])?";
+        let expression = pattern
+            .split('*')
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(label);
+
+        Regex::new(&format!("^{expression}$"))
+            .map(Self)
+            .map_err(|e| format!("invalid origin pattern {pattern}: {e}"))
+    }
+}
+
+// both /v1 and /v1/admin check origins the same way, exact matches first, then the patterns
+// both lists are re-read on every preflight, so a reload applies to the next one
+pub fn cors(reloadable: &ReloadableSettings, methods: Vec<&'static str>, max_age: usize) -> Cors {
+    let origins = reloadable.cors_allowed_origins.clone();
+    let patterns = reloadable.cors_allowed_origin_patterns.clone();
+
+    Cors::default()
+        .allowed_origin_fn(move |origin, _| {
+            let Ok(origin) = origin.to_str() else {
+                return false;
+            };
+            origins.get().iter().any(|allowed| origin == allowed)
+                || patterns.get().iter().any(|pattern| pattern.matches(origin))
+        })
+        .allowed_methods(methods)
+        .allowed_headers(vec![
+            http::header::AUTHORIZATION,
+            http::header::ACCEPT,
+            http::header::CONTENT_TYPE,
+            http::header::HeaderName::from_static("idempotency-key"),
+            http::header::HeaderName::from_static("x-xsrf-token"),
+            RESPONSE_ENVELOPE_HEADER,
+            INGEST_SIGNATURE_HEADER,
+        ])
+        .expose_headers(vec![
+            IDEMPOTENT_REPLAYED_HEADER,
+            IDEMPOTENT_PROCESSED_AT_HEADER,
+        ])
+        .supports_credentials()
+        .max_age(max_age)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pattern(s: &str) -> OriginPattern {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn wildcards_match_a_single_label() {
+        let preview = pattern("https://*.preview.example.com");
+
+        assert!(preview.matches("https://pr-123.preview.example.com"));
+        assert!(preview.matches("https://PR-7.preview.example.com"));
+        assert!(!preview.matches("https://a.b.preview.example.com"));
+        assert!(!preview.matches("https://preview.example.com"));
+        assert!(!preview.matches("http://pr-123.preview.example.com"));
+        assert!(!preview.matches("https://pr-123.preview.example.com.evil.com"));
+    }
+
+    #[test]
+    fn dots_and_ports_are_literal() {
+        let local = pattern("http://*.localhost:4200");
+
+        assert!(local.matches("http://app.localhost:4200"));
+        assert!(!local.matches("http://app.localhost:4201"));
+        assert!(!pattern("https://a.example.com").matches("https://aXexample.com"));
+    }
+
+    #[test]
+    fn paths_are_rejected() {
+        assert!("example.com".parse::<OriginPattern>().is_err());
+        assert!("https://*.example.com/".parse::<OriginPattern>().is_err());
+        assert!(
+            "https://*.example.com/app"
+                .parse::<OriginPattern>()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use secrecy::{ExposeSecret, SecretString};
+use std::time::Duration;
+
+use crate::configuration::{EmailProvider, EmailSettings};
+use crate::email::Email;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EmailError {
+    #[error("Postmark rejected the message")]
+    Postmark(#[from] reqwest::Error),
+    #[error("The smtp relay rejected the message")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error("Invalid address")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("Failed to build the message")]
+    Message(#[from] lettre::error::Error),
+}
+
+#[derive(Clone)]
+pub struct EmailClient {
+    sender: String,
+    transport: EmailTransport,
+}
+
+#[derive(Clone)]
+enum EmailTransport {
+    Postmark {
+        http_client: reqwest::Client,
+        base_url: String,
+        api_token: SecretString,
+    },
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SendEmailRequest<'a> {
+    from: &'a str,
+    to: &'a str,
+    subject: &'a str,
+    text_body: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    html_body: Option<&'a str>,
+}
+
+impl EmailClient {
+    // `None` while the sender or the provider's settings are missing, mail is simply not sent then
+    #[must_use]
+    pub fn from_settings(settings: &EmailSettings) -> Option<Self> {
+        if !settings.is_configured() {
+            tracing::info!("Email not configured, nothing will be mailed");
+            return None;
+        }
+        let sender = settings.sender.clone()?;
+        let timeout = Duration::from_secs(settings.timeout_secs);
+
+        let transport = match settings.provider {
+            EmailProvider::Postmark => EmailTransport::Postmark {
+                http_client: reqwest::Client::builder().timeout(timeout).build().ok()?,
+                base_url: settings.api_base_url.trim_end_matches('/').to_string(),
+                api_token: settings.api_token.clone()?,
+            },
+            EmailProvider::Smtp | EmailProvider::Ses => {
+                let host = settings.smtp_host()?;
+                let builder = if settings.smtp.starttls {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+                        .map_err(|e| {
+                            tracing::error!(error.cause_chain = ?e, "Invalid smtp relay");
+                        })
+                        .ok()?
+                } else {
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+                };
+                let builder = builder.port(settings.smtp.port).timeout(Some(timeout));
+                let builder = match (&settings.smtp.username, &settings.smtp.password) {
+                    (Some(username), Some(password)) => builder.credentials(Credentials::new(
+                        username.clone(),
+                        password.expose_secret().to_string(),
+                    )),
+                    _ => builder,
+                };
+                EmailTransport::Smtp(builder.build())
+            }
+        };
+
+        Some(Self { sender, transport })
+    }
+
+    /// # Errors
+    /// returns an `EmailError` if the provider can't be reached or rejects the message
+    pub async fn send(&self, recipients: &[String], email: &Email) -> Result<(), EmailError> {
+        match &self.transport {
+            EmailTransport::Postmark {
+                http_client,
+                base_url,
+                api_token,
+            } => {
+                let body = SendEmailRequest {
+                    from: &self.sender,
+                    to: &recipients.join(","),
+                    subject: &email.subject,
+                    text_body: &email.text_body,
+                    html_body: email.html_body.as_deref(),
+                };
+                http_client
+                    .post(format!("{base_url}/email"))
+                    .header("X-Postmark-Server-Token", api_token.expose_secret())
+                    .json(&body)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            EmailTransport::Smtp(transport) => {
+                transport.send(self.message(recipients, email)?).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    /// see `send`
+    pub async fn send_email(
+        &self,
+        recipients: &[String],
+        subject: &str,
+        text_body: &str,
+    ) -> Result<(), EmailError> {
+        let email = Email {
+            subject: subject.to_string(),
+            text_body: text_body.to_string(),
+            html_body: None,
+        };
+        self.send(recipients, &email).await
+    }
+
+    fn message(&self, recipients: &[String], email: &Email) -> Result<Message, EmailError> {
+        let mut builder = Message::builder()
+            .from(self.sender.parse::<Mailbox>()?)
+            .subject(&email.subject);
+        for recipient in recipients {
+            builder = builder.to(recipient.parse::<Mailbox>()?);
+        }
+
+        let message = match &email.html_body {
+            Some(html_body) => builder.multipart(MultiPart::alternative_plain_html(
+                email.text_body.clone(),
+                html_body.clone(),
+            ))?,
+            None => builder.singlepart(SinglePart::plain(email.text_body.clone()))?,
+        };
+        Ok(message)
+    }
+}
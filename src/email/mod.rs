@@ -0,0 +1,15 @@
+mod client;
+mod outbox;
+mod templates;
+
+pub use client::{EmailClient, EmailError};
+pub use outbox::{queue_email, run_email_delivery_worker_until_stopped, try_deliver_email};
+pub use templates::render_email;
+
+// a rendered message, ready to queue or send
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Email {
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: Option<String>,
+}
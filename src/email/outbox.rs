@@ -0,0 +1,183 @@
+use anyhow::Context;
+use sqlx::{PgExecutor, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::configuration::{JobSettings, Settings};
+use crate::email::{Email, EmailClient};
+use crate::jobs::ExecutionOutcome;
+use crate::startup::get_connection_pool;
+
+struct ClaimedEmail {
+    email_id: Uuid,
+    recipients: Vec<String>,
+    subject: String,
+    text_body: String,
+    html_body: Option<String>,
+    attempts: i32,
+    max_attempts: i32,
+}
+
+// takes an executor so the email only goes out if the change that sends it commits
+/// # Errors
+/// returns an error if the insert fails
+#[tracing::instrument(name = "Queue email", skip(executor, email), fields(subject = %email.subject))]
+pub async fn queue_email(
+    executor: impl PgExecutor<'_>,
+    recipients: &[String],
+    email: &Email,
+) -> Result<Uuid, anyhow::Error> {
+    let email_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO email_outbox (recipients, subject, text_body, html_body)
+        VALUES ($1, $2, $3, $4)
+        RETURNING email_id
+        "#,
+        recipients,
+        email.subject,
+        email.text_body,
+        email.html_body
+    )
+    .fetch_one(executor)
+    .await
+    .context("Failed to queue email")?;
+
+    Ok(email_id)
+}
+
+/// # Errors
+/// only returns if the worker can't keep running, failed deliveries are retried instead
+pub async fn run_email_delivery_worker_until_stopped(
+    configuration: Settings,
+    email_client: EmailClient,
+) -> Result<(), anyhow::Error> {
+    let pool = get_connection_pool(&configuration.database);
+    // retries back off the same way as the job queue
+    let settings = configuration.jobs;
+    let poll_interval = Duration::from_millis(settings.poll_interval_ms);
+
+    loop {
+        match try_deliver_email(&pool, &email_client, &settings).await {
+            Ok(ExecutionOutcome::TaskCompleted) => {}
+            Ok(ExecutionOutcome::EmptyQueue) => tokio::time::sleep(poll_interval).await,
+            Err(e) => {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    "Failed to drain the email outbox"
+                );
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+// claims one due email and sends it inside the claiming transaction
+/// # Errors
+/// returns an error if the outbox can't be read or the outcome can't be recorded,
+/// a failed delivery is recorded as a retry or as failed rather than returned
+#[tracing::instrument(name = "Deliver email", skip_all, fields(email_id))]
+pub async fn try_deliver_email(
+    pool: &PgPool,
+    email_client: &EmailClient,
+    settings: &JobSettings,
+) -> Result<ExecutionOutcome, anyhow::Error> {
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to start a transaction")?;
+    let claimed = sqlx::query_as!(
+        ClaimedEmail,
+        r#"
+        SELECT email_id, recipients, subject, text_body, html_body, attempts, max_attempts
+        FROM email_outbox
+        WHERE status = 'queued' AND next_attempt_at <= NOW()
+        ORDER BY next_attempt_at
+        FOR UPDATE SKIP LOCKED
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(transaction.as_mut())
+    .await
+    .context("Failed to claim an email")?;
+    let Some(claimed) = claimed else {
+        return Ok(ExecutionOutcome::EmptyQueue);
+    };
+    tracing::Span::current().record("email_id", tracing::field::display(claimed.email_id));
+
+    let email = Email {
+        subject: claimed.subject,
+        text_body: claimed.text_body,
+        html_body: claimed.html_body,
+    };
+    let outcome = email_client.send(&claimed.recipients, &email).await;
+    let attempts = claimed.attempts + 1;
+
+    match outcome {
+        Ok(()) => {
+            sqlx::query!(
+                r#"
+                UPDATE email_outbox
+                SET status = 'sent', attempts = $2, last_error = NULL, sent_at = NOW()
+                WHERE email_id = $1
+                "#,
+                claimed.email_id,
+                attempts
+            )
+            .execute(transaction.as_mut())
+            .await
+            .context("Failed to mark the email as sent")?;
+        }
+        Err(e) if attempts >= claimed.max_attempts => {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                attempts,
+                "Email failed for the last time, giving up"
+            );
+            sqlx::query!(
+                r#"
+                UPDATE email_outbox
+                SET status = 'failed', attempts = $2, last_error = $3
+                WHERE email_id = $1
+                "#,
+                claimed.email_id,
+                attempts,
+                format!("{:#}", anyhow::Error::from(e))
+            )
+            .execute(transaction.as_mut())
+            .await
+            .context("Failed to mark the email as failed")?;
+        }
+        Err(e) => {
+            let retry_in = settings.retry_backoff(attempts);
+            tracing::warn!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                attempts,
+                retry_in_secs = retry_in.as_secs(),
+                "Email delivery failed, retrying later"
+            );
+            sqlx::query!(
+                r#"
+                UPDATE email_outbox
+                SET attempts = $2, last_error = $3, next_attempt_at = NOW() + make_interval(secs => $4)
+                WHERE email_id = $1
+                "#,
+                claimed.email_id,
+                attempts,
+                format!("{:#}", anyhow::Error::from(e)),
+                retry_in.as_secs_f64()
+            )
+            .execute(transaction.as_mut())
+            .await
+            .context("Failed to reschedule the email")?;
+        }
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit the delivery outcome")?;
+    Ok(ExecutionOutcome::TaskCompleted)
+}
@@ -0,0 +1,77 @@
+use minijinja::{Environment, UndefinedBehavior};
+use std::sync::LazyLock;
+
+use crate::email::Email;
+
+// each email is a `{name}.subject` and `{name}.txt`, with an optional `{name}.html` alternative,
+// html templates escape their values, the others are sent as rendered
+const TEMPLATES: &[(&str, &str)] = &[
+    ("alert.subject", include_str!("templates/alert.subject")),
+    ("alert.txt", include_str!("templates/alert.txt")),
+];
+
+static ENVIRONMENT: LazyLock<Environment<'static>> = LazyLock::new(|| {
+    let mut environment = Environment::new();
+    // a typo'd variable fails the render instead of mailing a blank
+    environment.set_undefined_behavior(UndefinedBehavior::Strict);
+    for (name, source) in TEMPLATES {
+        environment
+            .add_template(name, source)
+            .expect("Email templates are checked at build time by the tests");
+    }
+    environment
+});
+
+/// # Errors
+/// returns an error if the template doesn't exist or the context is missing a value it uses
+pub fn render_email(name: &str, context: impl serde::Serialize) -> Result<Email, minijinja::Error> {
+    let context = minijinja::Value::from_serialize(context);
+    let render = |part: &str| {
+        ENVIRONMENT
+            .get_template(&format!("{name}.{part}"))
+            .and_then(|template| template.render(&context))
+    };
+
+    let html_body = match render("html") {
+        Ok(html_body) => Some(html_body),
+        Err(e) if e.kind() == minijinja::ErrorKind::TemplateNotFound => None,
+        Err(e) => return Err(e),
+    };
+    Ok(Email {
+        subject: render("subject")?.trim().to_string(),
+        text_body: render("txt")?,
+        html_body,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_template_parses() {
+        for (name, _) in TEMPLATES {
+            assert!(ENVIRONMENT.get_template(name).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn alerts_render_without_an_html_part() {
+        let email = render_email(
+            "alert",
+            minijinja::context! { kind => "error rate", message => "12% of requests failed" },
+        )
+        .unwrap();
+
+        assert_eq!(email.subject, "Alert: error rate");
+        assert_eq!(email.text_body, "12% of requests failed");
+        assert_eq!(email.html_body, None);
+    }
+
+    #[test]
+    fn missing_values_fail_the_render() {
+        let e = render_email("alert", minijinja::context! { kind => "error rate" }).unwrap_err();
+
+        assert_eq!(e.kind(), minijinja::ErrorKind::UndefinedError);
+    }
+}
@@ -0,0 +1,58 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AlbumError {
+    #[error("Album not found")]
+    AlbumNotFound,
+    // the slug comes from the title, so two albums can't share one
+    #[error("An album with this title already exists")]
+    DuplicateAlbum,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("The order must list every album exactly once")]
+    InvalidOrder,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for AlbumError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NothingToUpdate | Self::InvalidOrder | Self::ValidationError(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::AlbumNotFound => StatusCode::NOT_FOUND,
+            Self::DuplicateAlbum => StatusCode::CONFLICT,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = AlbumError::AlbumNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = AlbumError::DuplicateAlbum;
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        let e = AlbumError::NothingToUpdate;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = AlbumError::InvalidOrder;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = AlbumError::ValidationError("Invalid title".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = AlbumError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,9 +1,9 @@
-use actix_web::{ResponseError, http::StatusCode};
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
 
 #[derive(thiserror::Error, Debug)]
 pub enum AuthError {
-    #[error("Too many login requests")]
-    RateLimitExceeded,
     #[error("Invalid credentials")]
     InvalidCredentials(#[source] anyhow::Error),
     #[error(transparent)]
@@ -13,11 +13,14 @@ pub enum AuthError {
 impl ResponseError for AuthError {
     fn status_code(&self) -> StatusCode {
         match self {
-            Self::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
             Self::InvalidCredentials(_) => StatusCode::UNAUTHORIZED,
             Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
 }
 
 #[cfg(test)]
@@ -26,8 +29,6 @@ mod test {
 
     #[test]
     fn correct_status_code() {
-        let e = AuthError::RateLimitExceeded;
-        assert_eq!(e.status_code(), StatusCode::TOO_MANY_REQUESTS);
         let e = AuthError::InvalidCredentials(anyhow::anyhow!("e"));
         assert_eq!(e.status_code(), StatusCode::UNAUTHORIZED);
         let e = AuthError::UnexpectedError(anyhow::anyhow!("e"));
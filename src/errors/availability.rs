@@ -0,0 +1,37 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AvailabilityError {
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for AvailabilityError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = AvailabilityError::ValidationError("Invalid note".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = AvailabilityError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,4 +1,6 @@
-use actix_web::{ResponseError, http::StatusCode};
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
 
 #[derive(thiserror::Error, Debug)]
 pub enum BlogError {
@@ -31,6 +33,10 @@ impl ResponseError for BlogError {
             Self::QueryFailed | Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,64 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExperienceError {
+    #[error("Experience not found")]
+    ExperienceNotFound,
+    #[error("An entry can't end before it starts")]
+    EndsBeforeStart,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for ExperienceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::EndsBeforeStart | Self::NothingToUpdate | Self::ValidationError(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::ExperienceNotFound => StatusCode::NOT_FOUND,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+// 23514 is a check violation, an edit that moved only one of the dates past the other
+impl From<sqlx::Error> for ExperienceError {
+    fn from(e: sqlx::Error) -> Self {
+        match e
+            .as_database_error()
+            .and_then(|db_err| db_err.code())
+            .as_deref()
+        {
+            Some("23514") => Self::EndsBeforeStart,
+            _ => Self::UnexpectedError(anyhow::anyhow!("{e:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = ExperienceError::ExperienceNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = ExperienceError::EndsBeforeStart;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ExperienceError::ValidationError("Invalid role".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ExperienceError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
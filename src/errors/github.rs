@@ -0,0 +1,37 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GithubError {
+    #[error("No GitHub activity has been fetched yet")]
+    NotFetchedYet,
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for GithubError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotFetchedYet => StatusCode::NOT_FOUND,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = GithubError::NotFetchedYet;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = GithubError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,4 +1,15 @@
-use actix_web::{ResponseError, http::StatusCode};
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{
+        StatusCode,
+        header::{HeaderValue, RETRY_AFTER},
+    },
+};
+
+use crate::errors::ApiProblem;
+
+// how long a duplicate of an in-flight request should wait before retrying
+const IN_FLIGHT_RETRY_AFTER_SECS: u64 = 1;
 
 #[derive(thiserror::Error, Debug)]
 pub enum IdempotencyError {
@@ -8,6 +19,8 @@ pub enum IdempotencyError {
     InvalidKeyFormat,
     #[error("Request with this idempotency key is already being processed")]
     RequestInFlight,
+    #[error("Idempotency key was already used for a different request")]
+    PayloadMismatch,
     #[error(transparent)]
     DatabaseError(#[from] sqlx::Error),
     #[error(transparent)]
@@ -19,9 +32,21 @@ impl ResponseError for IdempotencyError {
         match self {
             Self::MissingIdempotencyKey | Self::InvalidKeyFormat => StatusCode::BAD_REQUEST,
             Self::RequestInFlight => StatusCode::CONFLICT,
+            Self::PayloadMismatch => StatusCode::UNPROCESSABLE_ENTITY,
             Self::DatabaseError(_) | Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = ApiProblem::from_error(self).error_response();
+        // the first request is still running, tell the client when it's worth asking again
+        if matches!(self, Self::RequestInFlight) {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(IN_FLIGHT_RETRY_AFTER_SECS));
+        }
+        response
+    }
 }
 
 #[cfg(test)]
@@ -36,9 +61,21 @@ mod test {
         assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
         let e = IdempotencyError::RequestInFlight;
         assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        let e = IdempotencyError::PayloadMismatch;
+        assert_eq!(e.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
         let e = IdempotencyError::DatabaseError(sqlx::Error::RowNotFound);
         assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
         let e = IdempotencyError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
         assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
     }
+
+    #[test]
+    fn in_flight_requests_are_told_to_retry() {
+        let response = IdempotencyError::RequestInFlight.error_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        let response = IdempotencyError::PayloadMismatch.error_response();
+        assert!(response.headers().get(RETRY_AFTER).is_none());
+    }
 }
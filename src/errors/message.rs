@@ -1,16 +1,6 @@
 use actix_web::{HttpResponse, ResponseError, http::StatusCode};
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct ErrorMessage {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    message: Option<String>,
-}
-
-impl ErrorMessage {
-    const fn new(message: Option<String>) -> Self {
-        Self { message }
-    }
-}
+use crate::errors::ApiProblem;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ContactSubmissionError {
@@ -20,8 +10,6 @@ pub enum ContactSubmissionError {
     MessageLength,
     #[error("Name length must be 2-100 characters")]
     NameLength,
-    #[error("Rate limit exceeded")]
-    RateLimitExceeded,
     #[error("Duplicate message detected")]
     DuplicateMessage,
     #[error(transparent)]
@@ -29,16 +17,13 @@ pub enum ContactSubmissionError {
 }
 
 impl ContactSubmissionError {
-    fn to_message_error(&self) -> Option<ErrorMessage> {
+    // what the contact form shows, duplicates get no hint on what counts as one
+    const fn detail(&self) -> Option<&'static str> {
         match self {
-            Self::InvalidEmail => Some(ErrorMessage::new(Some("Invalid email".to_string()))),
-            Self::MessageLength => Some(ErrorMessage::new(Some(
-                "Message must be between 10 and 5000 characters".to_string(),
-            ))),
-            Self::NameLength => Some(ErrorMessage::new(Some(
-                "Name must be between 2 and 100 characters.".to_string(),
-            ))),
-            Self::RateLimitExceeded | Self::DuplicateMessage | Self::UnexpectedError(_) => None,
+            Self::InvalidEmail => Some("Invalid email"),
+            Self::MessageLength => Some("Message must be between 10 and 5000 characters"),
+            Self::NameLength => Some("Name must be between 2 and 100 characters."),
+            Self::DuplicateMessage | Self::UnexpectedError(_) => None,
         }
     }
 }
@@ -47,16 +32,18 @@ impl ResponseError for ContactSubmissionError {
     fn status_code(&self) -> StatusCode {
         match self {
             Self::InvalidEmail | Self::MessageLength | Self::NameLength => StatusCode::BAD_REQUEST,
-            Self::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
             Self::DuplicateMessage => StatusCode::CONFLICT,
             Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        let status = self.status_code();
-        let error = self.to_message_error();
-        HttpResponse::build(status).json(error)
+        let problem = ApiProblem::new(self.status_code());
+        match self.detail() {
+            Some(detail) => problem.with_detail(detail),
+            None => problem,
+        }
+        .error_response()
     }
 }
 
@@ -72,6 +59,10 @@ impl ResponseError for MessageGetError {
             Self::TotalCount => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -89,6 +80,10 @@ impl ResponseError for MessagePatchError {
             Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
 }
 
 #[cfg(test)]
@@ -103,8 +98,6 @@ mod test {
         assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
         let e = ContactSubmissionError::NameLength;
         assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
-        let e = ContactSubmissionError::RateLimitExceeded;
-        assert_eq!(e.status_code(), StatusCode::TOO_MANY_REQUESTS);
         let e = ContactSubmissionError::DuplicateMessage;
         assert_eq!(e.status_code(), StatusCode::CONFLICT);
         let e = ContactSubmissionError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
@@ -122,26 +115,21 @@ mod test {
     #[test]
     fn correct_error_message() {
         let e = ContactSubmissionError::MessageLength;
-        let error_message = e.to_message_error().unwrap();
         assert_eq!(
-            error_message.message,
-            Some("Message must be between 10 and 5000 characters".to_string())
+            e.detail(),
+            Some("Message must be between 10 and 5000 characters")
         );
 
         let e = ContactSubmissionError::NameLength;
-        let error_message = e.to_message_error().unwrap();
         assert_eq!(
-            error_message.message,
-            Some("Name must be between 2 and 100 characters.".to_string())
+            e.detail(),
+            Some("Name must be between 2 and 100 characters.")
         );
 
-        let e = ContactSubmissionError::RateLimitExceeded;
-        assert!(e.to_message_error().is_none());
-
         let e = ContactSubmissionError::DuplicateMessage;
-        assert!(e.to_message_error().is_none());
+        assert!(e.detail().is_none());
 
         let e = ContactSubmissionError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
-        assert!(e.to_message_error().is_none());
+        assert!(e.detail().is_none());
     }
 }
@@ -0,0 +1,67 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MetricsIngestError {
+    #[error("Path must be a site-relative path of at most 2048 characters")]
+    InvalidPath,
+    #[error("Referrer must be at most 2048 characters")]
+    InvalidReferrer,
+    #[error("Duration must be between 0 and 86400000 milliseconds")]
+    InvalidDuration,
+    #[error("UTM parameters must be at most 200 characters")]
+    InvalidUtm,
+    #[error("Metric value must be a non-negative number")]
+    InvalidValue,
+    #[error("Batch must contain between 1 and {0} visits")]
+    InvalidBatchSize(usize),
+    #[error("Batch must be a JSON object with a list of visits")]
+    InvalidBatchBody,
+    #[error("Missing, stale or invalid metrics signature")]
+    InvalidSignature,
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for MetricsIngestError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidPath
+            | Self::InvalidReferrer
+            | Self::InvalidDuration
+            | Self::InvalidUtm
+            | Self::InvalidValue
+            | Self::InvalidBatchSize(_)
+            | Self::InvalidBatchBody => StatusCode::BAD_REQUEST,
+            Self::InvalidSignature => StatusCode::UNAUTHORIZED,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // validation failures tell the frontend which field was wrong in the detail
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = MetricsIngestError::InvalidPath;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = MetricsIngestError::InvalidValue;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = MetricsIngestError::InvalidBatchSize(50);
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = MetricsIngestError::InvalidBatchBody;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = MetricsIngestError::InvalidSignature;
+        assert_eq!(e.status_code(), StatusCode::UNAUTHORIZED);
+        let e = MetricsIngestError::UnexpectedError(anyhow::anyhow!("e"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
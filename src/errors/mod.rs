@@ -1,9 +1,41 @@
+mod album;
 mod authentication;
+mod availability;
 mod blog;
+mod experience;
+mod github;
 mod idempotency;
 mod message;
+mod metrics;
+mod now_entry;
+mod panic;
+mod problem;
+mod profile;
+mod project;
+mod resume;
+mod search;
+mod short_link;
+mod skill;
+mod storage;
+mod uses;
 
+pub use album::*;
 pub use authentication::*;
+pub use availability::*;
 pub use blog::*;
+pub use experience::*;
+pub use github::*;
 pub use idempotency::*;
 pub use message::*;
+pub use metrics::*;
+pub use now_entry::*;
+pub use panic::*;
+pub use problem::*;
+pub use profile::*;
+pub use project::*;
+pub use resume::*;
+pub use search::*;
+pub use short_link::*;
+pub use skill::*;
+pub use storage::*;
+pub use uses::*;
@@ -0,0 +1,46 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum NowEntryError {
+    #[error("Entry not found")]
+    EntryNotFound,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for NowEntryError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NothingToUpdate | Self::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Self::EntryNotFound => StatusCode::NOT_FOUND,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = NowEntryError::EntryNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = NowEntryError::NothingToUpdate;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = NowEntryError::ValidationError("Invalid title".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = NowEntryError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
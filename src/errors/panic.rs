@@ -0,0 +1,104 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    middleware::Next,
+    web,
+};
+use futures_util::FutureExt;
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+
+use crate::errors::ApiProblem;
+use crate::metrics::AppMetrics;
+
+// a panicking handler would otherwise drop the connection without a response,
+// this turns it into a plain 500 that `problem_details` dresses up with the request id
+// the panic message only goes to the logs, like every other server error's cause
+#[allow(clippy::future_not_send)]
+pub async fn catch_panics(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let metrics = request.app_data::<web::Data<AppMetrics>>().cloned();
+    let path = request
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    match AssertUnwindSafe(next.call(request)).catch_unwind().await {
+        Ok(result) => result,
+        Err(panic) => {
+            tracing::error!(
+                panic.message = %panic_message(panic.as_ref()),
+                path = %path,
+                "Request handler panicked"
+            );
+            if let Some(metrics) = metrics {
+                metrics
+                    .http_panics_total
+                    .with_label_values(&[path.as_str()])
+                    .inc();
+            }
+            Err(ApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR).into())
+        }
+    }
+}
+
+// `panic!` with a literal carries a `&str`, with format arguments a `String`
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::test::{TestRequest, init_service, try_call_service};
+    use actix_web::{App, HttpResponse, middleware::from_fn};
+
+    #[allow(clippy::unused_async)]
+    async fn explode() -> HttpResponse {
+        panic!("the handler exploded")
+    }
+
+    #[actix_web::test]
+    async fn panics_become_server_errors() {
+        let metrics = AppMetrics::new().unwrap();
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(catch_panics))
+                .app_data(web::Data::new(metrics.clone()))
+                .route("/explode", web::get().to(explode)),
+        )
+        .await;
+
+        let error = try_call_service(&app, TestRequest::get().uri("/explode").to_request())
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            metrics
+                .http_panics_total
+                .with_label_values(&["/explode"])
+                .get(),
+            1
+        );
+    }
+
+    #[test]
+    fn panic_messages_are_read_from_either_payload() {
+        let literal: Box<dyn Any + Send> = Box::new("literal");
+        let formatted: Box<dyn Any + Send> = Box::new(format!("formatted {}", 1));
+
+        assert_eq!(panic_message(literal.as_ref()), "literal");
+        assert_eq!(panic_message(formatted.as_ref()), "formatted 1");
+    }
+}
@@ -0,0 +1,206 @@
+use actix_web::{
+    HttpMessage, HttpResponse, ResponseError,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::{
+        StatusCode,
+        header::{CONTENT_TYPE, HeaderValue},
+    },
+    middleware::Next,
+};
+use tracing_actix_web::RequestId;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+// bodies of error responses that aren't problems yet, e.g. actix's own extractor errors,
+// are only reused as the detail when they're at most this long
+const MAX_DETAIL_LEN: usize = 1024;
+
+// an RFC 7807 error body, every 4xx and 5xx the API sends is one of these
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiProblem {
+    // `about:blank` means the status code says it all
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    // filled in by `problem_details` on the way out, ties the response to its logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiProblem {
+    #[must_use]
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: None,
+            request_id: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    // client errors explain themselves with the error's message,
+    // server errors never do, their cause chain only goes to the logs
+    #[must_use]
+    pub fn from_error(error: &(impl ResponseError + ?Sized)) -> Self {
+        let status = error.status_code();
+        let problem = Self::new(status);
+        if status.is_client_error() {
+            problem.with_detail(error.to_string())
+        } else {
+            problem
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl std::fmt::Display for ApiProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {detail}", self.title),
+            None => write!(f, "{}", self.title),
+        }
+    }
+}
+
+impl ResponseError for ApiProblem {
+    fn status_code(&self) -> StatusCode {
+        self.status()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status())
+            .content_type(PROBLEM_JSON)
+            .json(self)
+    }
+}
+
+// turns every error response into a problem with the request id, whoever produced it,
+// so extractor rejections, bare `finish()`es and middleware errors all look the same
+#[allow(clippy::future_not_send)]
+pub async fn problem_details(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(ToString::to_string);
+
+    match next.call(request).await {
+        Ok(response) => {
+            let (request, response) = response.map_into_boxed_body().into_parts();
+            let response = into_problem(response, request_id).await;
+            Ok(ServiceResponse::new(request, response))
+        }
+        // still an error, so it's logged with its cause
+        Err(e) => {
+            let response = into_problem(e.error_response(), request_id).await;
+            Err(InternalError::from_response(e, response).into())
+        }
+    }
+}
+
+async fn into_problem(response: HttpResponse, request_id: Option<String>) -> HttpResponse {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    let is_problem = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(PROBLEM_JSON.as_bytes()));
+    let (mut response, body) = response.into_parts();
+    let body = actix_web::body::to_bytes(body).await.unwrap_or_default();
+
+    let mut problem = if is_problem {
+        serde_json::from_slice(&body).unwrap_or_else(|_| ApiProblem::new(status))
+    } else {
+        let problem = ApiProblem::new(status);
+        match existing_detail(&body) {
+            Some(detail) if status.is_client_error() => problem.with_detail(detail),
+            _ => problem,
+        }
+    };
+    problem.request_id = request_id;
+
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    let body = serde_json::to_vec(&problem).unwrap_or_default();
+    response.set_body(BoxBody::new(body))
+}
+
+// what an error body that predates problems was trying to say,
+// either plain text or the old `{"message": ...}` json
+fn existing_detail(body: &[u8]) -> Option<String> {
+    if body.is_empty() || body.len() > MAX_DETAIL_LEN {
+        return None;
+    }
+    if let Ok(json) = serde_json::from_slice::<serde_json::Value>(body) {
+        return json["message"].as_str().map(str::to_string);
+    }
+    std::str::from_utf8(body)
+        .ok()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn problems_are_titled_after_their_status() {
+        let problem = ApiProblem::new(StatusCode::NOT_FOUND);
+
+        assert_eq!(
+            serde_json::to_value(&problem).unwrap(),
+            serde_json::json!({ "type": "about:blank", "title": "Not Found", "status": 404 })
+        );
+    }
+
+    #[test]
+    fn server_errors_keep_their_cause_to_themselves() {
+        let e = actix_web::error::ErrorInternalServerError("password column missing");
+        assert_eq!(ApiProblem::from_error(e.as_response_error()).detail, None);
+
+        let e = actix_web::error::ErrorBadRequest("Name is too long");
+        assert_eq!(
+            ApiProblem::from_error(e.as_response_error())
+                .detail
+                .as_deref(),
+            Some("Name is too long")
+        );
+    }
+
+    #[test]
+    fn old_bodies_become_the_detail() {
+        assert_eq!(
+            existing_detail(br#"{"message":"Invalid email"}"#).as_deref(),
+            Some("Invalid email")
+        );
+        assert_eq!(
+            existing_detail(b"Invalid CSRF token").as_deref(),
+            Some("Invalid CSRF token")
+        );
+        assert_eq!(existing_detail(b"[1, 2]"), None);
+        assert_eq!(existing_detail(b""), None);
+    }
+}
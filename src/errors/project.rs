@@ -0,0 +1,58 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ProjectError {
+    #[error("Project not found")]
+    ProjectNotFound,
+    // the slug comes from the title, so two projects can't share one
+    #[error("A project with this title already exists")]
+    DuplicateProject,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("The order must list every project exactly once")]
+    InvalidOrder,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for ProjectError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NothingToUpdate | Self::InvalidOrder | Self::ValidationError(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::ProjectNotFound => StatusCode::NOT_FOUND,
+            Self::DuplicateProject => StatusCode::CONFLICT,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = ProjectError::ProjectNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = ProjectError::DuplicateProject;
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        let e = ProjectError::NothingToUpdate;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ProjectError::InvalidOrder;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ProjectError::ValidationError("Invalid title".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ProjectError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,65 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ShortLinkError {
+    #[error("Link not found")]
+    LinkNotFound,
+    #[error("A link with this code already exists")]
+    DuplicateCode,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for ShortLinkError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NothingToUpdate | Self::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Self::LinkNotFound => StatusCode::NOT_FOUND,
+            Self::DuplicateCode => StatusCode::CONFLICT,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+// 23505 is a unique violation, a code that's already taken
+impl From<sqlx::Error> for ShortLinkError {
+    fn from(e: sqlx::Error) -> Self {
+        match e
+            .as_database_error()
+            .and_then(|db_err| db_err.code())
+            .as_deref()
+        {
+            Some("23505") => Self::DuplicateCode,
+            _ => Self::UnexpectedError(anyhow::anyhow!("{e:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = ShortLinkError::LinkNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = ShortLinkError::DuplicateCode;
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        let e = ShortLinkError::NothingToUpdate;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ShortLinkError::ValidationError("Invalid code".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = ShortLinkError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,70 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SkillError {
+    #[error("Skill not found")]
+    SkillNotFound,
+    #[error("A skill with this name already exists")]
+    DuplicateSkill,
+    #[error("A linked project doesn't exist")]
+    UnknownProject,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for SkillError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::UnknownProject | Self::NothingToUpdate | Self::ValidationError(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::SkillNotFound => StatusCode::NOT_FOUND,
+            Self::DuplicateSkill => StatusCode::CONFLICT,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+// 23505 is a unique violation (the name), 23503 a foreign key one (a linked project)
+impl From<sqlx::Error> for SkillError {
+    fn from(e: sqlx::Error) -> Self {
+        match e
+            .as_database_error()
+            .and_then(|db_err| db_err.code())
+            .as_deref()
+        {
+            Some("23505") => Self::DuplicateSkill,
+            Some("23503") => Self::UnknownProject,
+            _ => Self::UnexpectedError(anyhow::anyhow!("{e:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = SkillError::SkillNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = SkillError::DuplicateSkill;
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        let e = SkillError::UnknownProject;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = SkillError::ValidationError("Invalid name".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = SkillError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
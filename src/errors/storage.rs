@@ -0,0 +1,78 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+use crate::storage::StorageError;
+
+// what a request through one of the local backend's presigned urls can fail with
+#[derive(thiserror::Error, Debug)]
+pub enum SignedUrlError {
+    #[error("Invalid or expired signature")]
+    InvalidSignature,
+    #[error("Object not found")]
+    NotFound,
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for SignedUrlError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidSignature => StatusCode::FORBIDDEN,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::StorageError(StorageError::InvalidKey(_)) => StatusCode::BAD_REQUEST,
+            Self::StorageError(_) | Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+// what serving a public media file can fail with
+#[derive(thiserror::Error, Debug)]
+pub enum MediaError {
+    #[error("Media not found")]
+    NotFound,
+    // uploaded but not through its virus scan yet
+    #[error("Media is still being scanned")]
+    Scanning,
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for MediaError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Scanning => StatusCode::CONFLICT,
+            Self::StorageError(StorageError::InvalidKey(_)) => StatusCode::BAD_REQUEST,
+            Self::StorageError(_) | Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = SignedUrlError::InvalidSignature;
+        assert_eq!(e.status_code(), StatusCode::FORBIDDEN);
+        let e = SignedUrlError::NotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = SignedUrlError::StorageError(StorageError::InvalidSettings(String::new()));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        let e = MediaError::Scanning;
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,65 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+
+use crate::errors::ApiProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum UsesError {
+    #[error("Item not found")]
+    ItemNotFound,
+    #[error("An item with this name already exists in the category")]
+    DuplicateItem,
+    #[error("No fields provided to update")]
+    NothingToUpdate,
+    #[error("{0}")]
+    ValidationError(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl ResponseError for UsesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NothingToUpdate | Self::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Self::ItemNotFound => StatusCode::NOT_FOUND,
+            Self::DuplicateItem => StatusCode::CONFLICT,
+            Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ApiProblem::from_error(self).error_response()
+    }
+}
+
+// 23505 is a unique violation, the name is already taken in its category
+impl From<sqlx::Error> for UsesError {
+    fn from(e: sqlx::Error) -> Self {
+        match e
+            .as_database_error()
+            .and_then(|db_err| db_err.code())
+            .as_deref()
+        {
+            Some("23505") => Self::DuplicateItem,
+            _ => Self::UnexpectedError(anyhow::anyhow!("{e:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_status_code() {
+        let e = UsesError::ItemNotFound;
+        assert_eq!(e.status_code(), StatusCode::NOT_FOUND);
+        let e = UsesError::DuplicateItem;
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        let e = UsesError::NothingToUpdate;
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = UsesError::ValidationError("Invalid name".to_string());
+        assert_eq!(e.status_code(), StatusCode::BAD_REQUEST);
+        let e = UsesError::UnexpectedError(anyhow::anyhow!("Unexpected error"));
+        assert_eq!(e.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::metrics::AlertKind;
+
+// how many events a slow dashboard can fall behind before it starts skipping
+const EVENT_BUFFER: usize = 256;
+
+// everything the admin dashboard gets pushed over its websocket
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DashboardEvent {
+    ContactMessage {
+        message_id: Uuid,
+        sender_name: String,
+        received_at: DateTime<Utc>,
+    },
+    LoginAttempt {
+        username: String,
+        success: bool,
+        ip_address: Option<String>,
+        attempted_at: DateTime<Utc>,
+    },
+    ErrorSpike {
+        errors: u64,
+        requests: u64,
+        sampled_at: DateTime<Utc>,
+    },
+    Alert {
+        kind: AlertKind,
+        message: String,
+        fired_at: DateTime<Utc>,
+    },
+}
+
+// fan-out point the rest of the app publishes into, every open dashboard
+// socket holds its own receiver
+#[derive(Clone)]
+pub struct EventBus(broadcast::Sender<DashboardEvent>);
+
+impl EventBus {
+    #[must_use]
+    pub fn new() -> Self {
+        Self(broadcast::channel(EVENT_BUFFER).0)
+    }
+
+    // fire and forget, nobody listening just means no dashboard is open
+    pub fn publish(&self, event: DashboardEvent) {
+        let _ = self.0.send(event);
+    }
+
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<DashboardEvent> {
+        self.0.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spike(errors: u64) -> DashboardEvent {
+        DashboardEvent::ErrorSpike {
+            errors,
+            requests: 10,
+            sampled_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn publishing_without_subscribers_is_a_no_op() {
+        EventBus::new().publish(spike(1));
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_receives_published_events() {
+        let bus = EventBus::new();
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+
+        let event = spike(3);
+        bus.publish(event.clone());
+
+        assert_eq!(first.recv().await.unwrap(), event);
+        assert_eq!(second.recv().await.unwrap(), event);
+    }
+
+    #[test]
+    fn events_are_tagged_by_type() {
+        let json = serde_json::to_value(spike(2)).unwrap();
+        assert_eq!(json["type"], "error_spike");
+        assert_eq!(json["errors"], 2);
+    }
+}
@@ -0,0 +1,362 @@
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::header::USER_AGENT;
+use secrecy::{ExposeSecret, SecretString};
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::configuration::GithubSettings;
+
+// the contribution calendar covers the last year, pinned items are capped at six by github
+const ACTIVITY_QUERY: &str = r"
+query($login: String!) {
+  user(login: $login) {
+    login
+    contributionsCollection {
+      totalCommitContributions
+      totalPullRequestContributions
+      totalIssueContributions
+      totalPullRequestReviewContributions
+      contributionCalendar {
+        totalContributions
+        weeks { contributionDays { date contributionCount } }
+      }
+    }
+    pinnedItems(first: 6, types: REPOSITORY) {
+      nodes {
+        ... on Repository {
+          name
+          description
+          url
+          stargazerCount
+          forkCount
+          primaryLanguage { name }
+        }
+      }
+    }
+  }
+}";
+
+// what's cached in `github_activity` and served, shaped for the frontend rather than github
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GithubActivity {
+    pub username: String,
+    pub total_contributions: i64,
+    pub commits: i64,
+    pub pull_requests: i64,
+    pub issues: i64,
+    pub reviews: i64,
+    // one entry per day, oldest first
+    pub calendar: Vec<ContributionDay>,
+    pub pinned_repos: Vec<PinnedRepo>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContributionDay {
+    pub date: NaiveDate,
+    pub count: i64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PinnedRepo {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub stars: i64,
+    pub forks: i64,
+    pub language: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct GithubActivitySnapshot {
+    #[serde(flatten)]
+    pub activity: GithubActivity,
+    // how stale the numbers are, the frontend shows it next to them
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(serde::Deserialize)]
+struct GraphqlResponse {
+    data: Option<GraphqlData>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(serde::Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GraphqlData {
+    user: Option<GraphqlUser>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlUser {
+    login: String,
+    contributions_collection: ContributionsCollection,
+    pinned_items: PinnedItems,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContributionsCollection {
+    total_commit_contributions: i64,
+    total_pull_request_contributions: i64,
+    total_issue_contributions: i64,
+    total_pull_request_review_contributions: i64,
+    contribution_calendar: ContributionCalendar,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContributionCalendar {
+    total_contributions: i64,
+    weeks: Vec<CalendarWeek>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarWeek {
+    contribution_days: Vec<CalendarDay>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarDay {
+    date: NaiveDate,
+    contribution_count: i64,
+}
+
+#[derive(serde::Deserialize)]
+struct PinnedItems {
+    nodes: Vec<PinnedNode>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PinnedNode {
+    name: String,
+    description: Option<String>,
+    url: String,
+    stargazer_count: i64,
+    fork_count: i64,
+    primary_language: Option<PrimaryLanguage>,
+}
+
+#[derive(serde::Deserialize)]
+struct PrimaryLanguage {
+    name: String,
+}
+
+impl From<GraphqlUser> for GithubActivity {
+    fn from(user: GraphqlUser) -> Self {
+        let contributions = user.contributions_collection;
+        Self {
+            username: user.login,
+            total_contributions: contributions.contribution_calendar.total_contributions,
+            commits: contributions.total_commit_contributions,
+            pull_requests: contributions.total_pull_request_contributions,
+            issues: contributions.total_issue_contributions,
+            reviews: contributions.total_pull_request_review_contributions,
+            calendar: contributions
+                .contribution_calendar
+                .weeks
+                .into_iter()
+                .flat_map(|week| week.contribution_days)
+                .map(|day| ContributionDay {
+                    date: day.date,
+                    count: day.contribution_count,
+                })
+                .collect(),
+            pinned_repos: user
+                .pinned_items
+                .nodes
+                .into_iter()
+                .map(|repo| PinnedRepo {
+                    name: repo.name,
+                    description: repo.description.filter(|d| !d.is_empty()),
+                    url: repo.url,
+                    stars: repo.stargazer_count,
+                    forks: repo.fork_count,
+                    language: repo.primary_language.map(|language| language.name),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GithubClient {
+    http_client: reqwest::Client,
+    base_url: String,
+    username: String,
+    api_token: SecretString,
+}
+
+impl GithubClient {
+    // `None` while the username or token is missing, the activity endpoint stays a 404 then
+    #[must_use]
+    pub fn from_settings(settings: &GithubSettings) -> Option<Self> {
+        let (Some(username), Some(api_token)) = (&settings.username, &settings.api_token) else {
+            tracing::info!("GitHub not configured, activity won't be fetched");
+            return None;
+        };
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .build()
+            .map_err(|e| tracing::error!(error.cause_chain = ?e, "Failed to build GitHub client"))
+            .ok()?;
+
+        Some(Self {
+            http_client,
+            base_url: settings.api_base_url.trim_end_matches('/').to_string(),
+            username: username.clone(),
+            api_token: api_token.clone(),
+        })
+    }
+
+    /// # Errors
+    /// returns an error if github can't be reached, rejects the token or doesn't know the user
+    pub async fn fetch_activity(&self) -> Result<GithubActivity, anyhow::Error> {
+        let response: GraphqlResponse = self
+            .http_client
+            .post(format!("{}/graphql", self.base_url))
+            .bearer_auth(self.api_token.expose_secret())
+            // github turns away requests without one
+            .header(USER_AGENT, "portfolio-server")
+            .json(&serde_json::json!({
+                "query": ACTIVITY_QUERY,
+                "variables": { "login": self.username },
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        activity_from_response(response, &self.username)
+    }
+}
+
+// graphql reports most failures with a 200 and an `errors` list
+fn activity_from_response(
+    response: GraphqlResponse,
+    username: &str,
+) -> Result<GithubActivity, anyhow::Error> {
+    if !response.errors.is_empty() {
+        let messages: Vec<_> = response.errors.into_iter().map(|e| e.message).collect();
+        anyhow::bail!("GitHub rejected the query: {}", messages.join("; "));
+    }
+    response
+        .data
+        .and_then(|data| data.user)
+        .map(GithubActivity::from)
+        .with_context(|| format!("No GitHub user named `{username}`"))
+}
+
+// a failed fetch leaves the previous row in place, its `fetched_at` shows how stale it is
+/// # Errors
+/// returns an error if the fetch fails or the result can't be stored
+pub async fn refresh_github_activity(
+    pool: &PgPool,
+    client: &GithubClient,
+) -> Result<(), anyhow::Error> {
+    let activity = client.fetch_activity().await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO github_activity (activity, fetched_at)
+        VALUES ($1, NOW())
+        ON CONFLICT (id) DO UPDATE
+        SET activity = EXCLUDED.activity, fetched_at = EXCLUDED.fetched_at
+        "#,
+        serde_json::to_value(&activity)?
+    )
+    .execute(pool)
+    .await
+    .context("Failed to cache GitHub activity")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn response(body: serde_json::Value) -> GraphqlResponse {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn the_calendar_is_flattened_into_days() {
+        let activity = activity_from_response(
+            response(serde_json::json!({
+                "data": { "user": {
+                    "login": "calvin-devogel",
+                    "contributionsCollection": {
+                        "totalCommitContributions": 120,
+                        "totalPullRequestContributions": 14,
+                        "totalIssueContributions": 3,
+                        "totalPullRequestReviewContributions": 9,
+                        "contributionCalendar": {
+                            "totalContributions": 146,
+                            "weeks": [
+                                { "contributionDays": [
+                                    { "date": "2026-01-04", "contributionCount": 2, "color": "#40c463" },
+                                    { "date": "2026-01-05", "contributionCount": 0, "color": "#ebedf0" }
+                                ] },
+                                { "contributionDays": [
+                                    { "date": "2026-01-11", "contributionCount": 5, "color": "#30a14e" }
+                                ] }
+                            ]
+                        }
+                    },
+                    "pinnedItems": { "nodes": [{
+                        "name": "portfolio-server",
+                        "description": "",
+                        "url": "https://github.com/calvin-devogel/portfolio-server",
+                        "stargazerCount": 4,
+                        "forkCount": 1,
+                        "primaryLanguage": { "name": "Rust" }
+                    }] }
+                } }
+            })),
+            "calvin-devogel",
+        )
+        .unwrap();
+
+        assert_eq!(activity.total_contributions, 146);
+        assert_eq!(activity.reviews, 9);
+        let counts: Vec<_> = activity.calendar.iter().map(|day| day.count).collect();
+        assert_eq!(counts, [2, 0, 5]);
+        assert_eq!(activity.pinned_repos[0].language.as_deref(), Some("Rust"));
+        // an empty description is shown the same as a missing one
+        assert_eq!(activity.pinned_repos[0].description, None);
+    }
+
+    #[test]
+    fn graphql_errors_fail_the_fetch() {
+        let e = activity_from_response(
+            response(serde_json::json!({
+                "data": null,
+                "errors": [{ "message": "Bad credentials" }]
+            })),
+            "calvin-devogel",
+        )
+        .unwrap_err();
+
+        assert!(e.to_string().contains("Bad credentials"));
+    }
+
+    #[test]
+    fn unknown_users_fail_the_fetch() {
+        let e = activity_from_response(
+            response(serde_json::json!({ "data": { "user": null } })),
+            "nobody",
+        )
+        .unwrap_err();
+
+        assert!(e.to_string().contains("nobody"));
+    }
+}
@@ -0,0 +1,39 @@
+use sha2::{Digest, Sha256};
+
+// hash of the deserialized request payload, stored next to the idempotency key
+// so a key reused with a different payload can be told apart from a genuine retry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestFingerprint(String);
+
+impl RequestFingerprint {
+    // hashing the typed payload instead of the raw body means whitespace and
+    // key order differences between retries don't count as a different request
+    /// # Errors
+    /// returns an `anyhow` error if the payload can't be serialized
+    pub fn of<T: serde::Serialize>(payload: &T) -> Result<Self, anyhow::Error> {
+        let bytes = serde_json::to_vec(payload)?;
+        Ok(Self(hex::encode(Sha256::digest(&bytes))))
+    }
+}
+
+impl AsRef<str> for RequestFingerprint {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_payload_same_fingerprint() {
+        let a = RequestFingerprint::of(&serde_json::json!({ "read": true })).unwrap();
+        let b = RequestFingerprint::of(&serde_json::json!({ "read": true })).unwrap();
+        let c = RequestFingerprint::of(&serde_json::json!({ "read": false })).unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.as_ref().len(), 64);
+    }
+}
@@ -1,3 +1,8 @@
+use regex::Regex;
+use uuid::Uuid;
+
+use crate::configuration::{IdempotencyKeyFormat, IdempotencySettings};
+
 // let's remind ourselves of what is happening here
 // this is the idempotency key, associated with any action we're trying
 // execute idempotently
@@ -8,6 +13,29 @@ impl IdempotencyKey {
     const MAX_LENGTH: usize = 50;
 }
 
+impl IdempotencyKey {
+    // the basic checks from `try_from`, plus whatever format the policy demands
+    /// # Errors
+    /// returns an `anyhow` error if the key is empty, too long or doesn't match the policy
+    pub fn parse(s: String, policy: &IdempotencyKeyPolicy) -> Result<Self, anyhow::Error> {
+        let key = Self::try_from(s)?;
+        match policy {
+            IdempotencyKeyPolicy::Any => {}
+            IdempotencyKeyPolicy::Uuid => {
+                if Uuid::parse_str(key.as_ref()).is_err() {
+                    anyhow::bail!("The idempotency key must be a UUID.")
+                }
+            }
+            IdempotencyKeyPolicy::Pattern(pattern) => {
+                if !pattern.is_match(key.as_ref()) {
+                    anyhow::bail!("The idempotency key does not match the required format.")
+                }
+            }
+        }
+        Ok(key)
+    }
+}
+
 // we need a TryFrom to ensure the key fits our criteria, specifically:
 // - the key must be non-empty
 // - the key must be no more than 50 characters in length
@@ -28,6 +56,36 @@ impl TryFrom<String> for IdempotencyKey {
     }
 }
 
+// which keys clients are allowed to send, built once from `IdempotencySettings`
+// low-entropy keys like "1" make collisions between anonymous clients plausible
+#[derive(Debug, Clone, Default)]
+pub enum IdempotencyKeyPolicy {
+    #[default]
+    Any,
+    Uuid,
+    Pattern(Regex),
+}
+
+impl TryFrom<&IdempotencySettings> for IdempotencyKeyPolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(settings: &IdempotencySettings) -> Result<Self, Self::Error> {
+        match settings.key_format {
+            IdempotencyKeyFormat::Any => Ok(Self::Any),
+            IdempotencyKeyFormat::Uuid => Ok(Self::Uuid),
+            IdempotencyKeyFormat::Pattern => {
+                let pattern = settings.key_pattern.as_deref().ok_or_else(|| {
+                    anyhow::anyhow!("key_format is pattern but no key_pattern is set")
+                })?;
+                // anchor it so a pattern can't accidentally match a substring
+                let regex = Regex::new(&format!("^(?:{pattern})$"))
+                    .map_err(|e| anyhow::anyhow!("Invalid idempotency key pattern: {e}"))?;
+                Ok(Self::Pattern(regex))
+            }
+        }
+    }
+}
+
 // key -> String (consumes)
 // ex: `let s: String = idempotency_key.into()`
 // or: `String::from(idempotency_key)`
@@ -64,6 +122,43 @@ mod tests {
         assert!(long_key_result.is_err());
     }
 
+    #[test]
+    fn policy_restricts_key_format() {
+        let uuid = IdempotencyKeyPolicy::Uuid;
+        assert!(IdempotencyKey::parse(Uuid::new_v4().to_string(), &uuid).is_ok());
+        assert!(IdempotencyKey::parse("1".to_string(), &uuid).is_err());
+
+        let settings = IdempotencySettings {
+            key_format: IdempotencyKeyFormat::Pattern,
+            key_pattern: Some("[a-z0-9-]{16,}".to_string()),
+            ..IdempotencySettings::default()
+        };
+        let pattern = IdempotencyKeyPolicy::try_from(&settings).unwrap();
+        assert!(IdempotencyKey::parse("abcdef0123456789".to_string(), &pattern).is_ok());
+        assert!(IdempotencyKey::parse("short".to_string(), &pattern).is_err());
+        // anchored, a matching substring isn't enough
+        assert!(IdempotencyKey::parse("ABC-abcdef0123456789".to_string(), &pattern).is_err());
+
+        assert!(IdempotencyKey::parse("1".to_string(), &IdempotencyKeyPolicy::Any).is_ok());
+    }
+
+    #[test]
+    fn pattern_policy_needs_a_valid_pattern() {
+        let missing = IdempotencySettings {
+            key_format: IdempotencyKeyFormat::Pattern,
+            key_pattern: None,
+            ..IdempotencySettings::default()
+        };
+        assert!(IdempotencyKeyPolicy::try_from(&missing).is_err());
+
+        let invalid = IdempotencySettings {
+            key_format: IdempotencyKeyFormat::Pattern,
+            key_pattern: Some("(".to_string()),
+            ..IdempotencySettings::default()
+        };
+        assert!(IdempotencyKeyPolicy::try_from(&invalid).is_err());
+    }
+
     #[test]
     fn string_from_key() {
         let key = IdempotencyKey::try_from("another_valid_key".to_string()).unwrap();
@@ -1,8 +1,11 @@
+mod fingerprint;
 mod key;
 mod persistence;
 
-pub use key::IdempotencyKey;
+pub use fingerprint::RequestFingerprint;
+pub use key::{IdempotencyKey, IdempotencyKeyPolicy};
 pub use persistence::{
-    NextAction, execute_idempotent, execute_idempotent_with, get_idempotency_key,
-    get_saved_response, save_response, try_processing,
+    IDEMPOTENT_PROCESSED_AT_HEADER, IDEMPOTENT_REPLAYED_HEADER, NextAction, execute_idempotent,
+    execute_idempotent_with, get_idempotency_key, get_saved_response, save_response,
+    try_processing,
 };
@@ -1,12 +1,23 @@
 use crate::errors::IdempotencyError;
 
-use super::IdempotencyKey;
-use actix_web::{HttpRequest, HttpResponse, body::to_bytes, http::StatusCode};
+use super::{IdempotencyKey, IdempotencyKeyPolicy, RequestFingerprint};
+use crate::configuration::IdempotencySettings;
+use crate::metrics::AppMetrics;
+use actix_web::{
+    HttpRequest, HttpResponse, Responder,
+    body::{BodySize, BoxBody, MessageBody, to_bytes},
+    http::StatusCode,
+    web,
+};
 use sqlx::{Executor, PgPool, Postgres, Transaction};
 use std::future::Future;
 use std::pin::Pin;
 use uuid::Uuid;
 
+// set on replayed responses so clients can tell them apart from fresh processing
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+pub const IDEMPOTENT_PROCESSED_AT_HEADER: &str = "Idempotent-Processed-At";
+
 // header pair type for sqlx
 #[derive(Debug, sqlx::Type)]
 #[sqlx(type_name = "header_pair")]
@@ -28,17 +39,21 @@ pub enum NextAction {
 // tries to insert a new row with key + user_id (this will need to change)
 // if the row is able to be inserted -> StartProcessing a transaction
 // if the row already exists -> fetch saved response and return it
+// a duplicate of a request that's still running blocks on the insert until the
+// first one commits, so it sees the saved response (or a released key) afterwards
 /// as for why (NextAction::StartProcessing, None) is an unreachable state:
 ///     - if n_inserted_rows > 0, return (NextAction::StartProcessing, Some(transaction))
 ///     - if n_inserted_rows == 0, return *either*
 ///         - (NextAction::ReturnSavedResponse(response), None) or
 ///         - (IdempotencyError::RequestInFlight)
+///         - (IdempotencyError::PayloadMismatch) if the key was claimed by a different payload
 /// so no path allows the match statement to find (NextAction, None)
 pub async fn try_processing(
     pool: &PgPool,
     idempotency_key: &IdempotencyKey,
     user_id: Option<Uuid>,
     operation: &str,
+    fingerprint: &RequestFingerprint,
 ) -> Result<(NextAction, Option<Transaction<'static, Postgres>>), IdempotencyError> {
     let mut transaction = pool.begin().await?;
     let query = sqlx::query!(
@@ -47,19 +62,28 @@ pub async fn try_processing(
             user_id,
             idempotency_key,
             operation,
+            request_fingerprint,
             created_at
         )
-        VALUES ($1, $2, $3, now())
+        VALUES ($1, $2, $3, $4, now())
         ON CONFLICT DO NOTHING
         "#,
         user_id, // can be NULL now
         idempotency_key.as_ref(),
-        operation
+        operation,
+        fingerprint.as_ref()
     );
     let n_inserted_rows = transaction.execute(query).await?.rows_affected();
     if n_inserted_rows > 0 {
         Ok((NextAction::StartProcessing, Some(transaction)))
     } else {
+        // replaying a response saved for some other payload would be worse than failing
+        let stored_fingerprint =
+            get_stored_fingerprint(pool, idempotency_key, user_id, operation).await?;
+        if stored_fingerprint.is_some_and(|stored| stored != fingerprint.as_ref()) {
+            return Err(IdempotencyError::PayloadMismatch);
+        }
+
         let saved_response = get_saved_response(pool, idempotency_key, user_id, operation).await?;
 
         saved_response.map_or_else(
@@ -69,11 +93,40 @@ pub async fn try_processing(
     }
 }
 
+// rows written before fingerprinting have no fingerprint to compare against
+async fn get_stored_fingerprint(
+    pool: &PgPool,
+    idempotency_key: &IdempotencyKey,
+    user_id: Option<Uuid>,
+    operation: &str,
+) -> Result<Option<String>, IdempotencyError> {
+    let stored = sqlx::query_scalar!(
+        r#"
+        SELECT request_fingerprint
+        FROM idempotency
+        WHERE
+            idempotency_key = $2
+            AND operation = $3
+            AND (user_id = $1 OR (user_id IS NULL AND $1 IS NULL))
+        "#,
+        user_id,
+        idempotency_key.as_ref(),
+        operation
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(stored.flatten())
+}
+
 // deconstruct response into head + body
 // converts the body to bytes (since response streams can't be replayed)
 // stores status code, headers, and body in the database
 // commits the transaction
 // returns HttpResponse
+// streamed bodies and bodies over `max_body_bytes` aren't stored: the key is released
+// instead, so a retry runs the action again rather than replaying a truncated response
+// each skip is counted on `metrics` when one is given
 #[allow(clippy::future_not_send)]
 pub async fn save_response(
     mut transaction: Transaction<'static, Postgres>,
@@ -81,8 +134,36 @@ pub async fn save_response(
     user_id: Option<Uuid>,
     operation: &str,
     http_response: HttpResponse,
+    max_body_bytes: u64,
+    metrics: Option<&AppMetrics>,
 ) -> Result<HttpResponse, IdempotencyError> {
     let (response_head, body) = http_response.into_parts();
+    let body_size = body.size();
+    let skipped = match body_size {
+        BodySize::None => None,
+        BodySize::Sized(n) if n <= max_body_bytes => None,
+        BodySize::Sized(_) => Some("oversized"),
+        BodySize::Stream => Some("streamed"),
+    };
+    if let Some(reason) = skipped {
+        if let Some(metrics) = metrics {
+            metrics
+                .idempotency_responses_not_stored_total
+                .with_label_values(&[reason])
+                .inc();
+        }
+        tracing::warn!(
+            operation,
+            reason,
+            body_size = ?body_size,
+            max_body_bytes,
+            "Response too large to store for idempotent replay, releasing the key"
+        );
+        release_key(&mut transaction, idempotency_key, user_id, operation).await?;
+        transaction.commit().await?;
+        return Ok(response_head.set_body(body));
+    }
+
     // MessageBody::Error is not `Send` + `Sync`
     // -> it does not play nicely with `anyhow`
     let body = to_bytes(body).await.map_err(|e| anyhow::anyhow!("{e}"))?;
@@ -131,9 +212,35 @@ pub async fn save_response(
     Ok(http_response)
 }
 
+// the action's writes still commit, only the replay slot goes away
+async fn release_key(
+    transaction: &mut Transaction<'static, Postgres>,
+    idempotency_key: &IdempotencyKey,
+    user_id: Option<Uuid>,
+    operation: &str,
+) -> Result<(), IdempotencyError> {
+    transaction
+        .execute(sqlx::query!(
+            r#"
+            DELETE FROM idempotency
+            WHERE
+                idempotency_key = $2
+                AND operation = $3
+                AND (user_id = $1 OR (user_id IS NULL AND $1 IS NULL))
+            "#,
+            user_id,
+            idempotency_key.as_ref(),
+            operation
+        ))
+        .await?;
+    Ok(())
+}
+
 // queries the database for saved Response data
 // reconstructs the HttpResponse for saved response data
 // returns `None` if not found
+// the body is replayed as stored, so the first request's shape wins: its envelope choice and
+// its `request_id`, whatever `X-Response-Envelope` the replaying request sends
 pub async fn get_saved_response(
     pool: &PgPool,
     idempotency_key: &IdempotencyKey,
@@ -145,7 +252,8 @@ pub async fn get_saved_response(
         SELECT
             response_status_code as "response_status_code!",
             response_headers as "response_headers!: Vec<HeaderPairRecord>",
-            response_body as "response_body!"
+            response_body as "response_body!",
+            created_at
         FROM idempotency
         WHERE
             idempotency_key = $2
@@ -165,6 +273,8 @@ pub async fn get_saved_response(
         for HeaderPairRecord { name, value } in r.response_headers {
             response.append_header((name, value));
         }
+        response.insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"));
+        response.insert_header((IDEMPOTENT_PROCESSED_AT_HEADER, r.created_at.to_rfc3339()));
         Ok(Some(response.body(r.response_body)))
     } else {
         Ok(None)
@@ -177,7 +287,13 @@ pub async fn get_saved_response(
 
 // there are a few places where an idempotency key is required, use this wherever it is
 pub fn get_idempotency_key(request: &HttpRequest) -> Result<IdempotencyKey, IdempotencyError> {
-    let idempotency_key: IdempotencyKey = request
+    // without a configured policy (ie. bare test requests) any well-formed key goes
+    static ANY: IdempotencyKeyPolicy = IdempotencyKeyPolicy::Any;
+    let policy = request
+        .app_data::<web::Data<IdempotencyKeyPolicy>>()
+        .map_or(&ANY, |policy| policy.get_ref());
+
+    let raw_key = request
         .headers()
         .get("Idempotency-Key")
         .and_then(|header| header.to_str().ok())
@@ -185,37 +301,44 @@ pub fn get_idempotency_key(request: &HttpRequest) -> Result<IdempotencyKey, Idem
             tracing::warn!("Missing Idempotency-Key header");
             IdempotencyError::MissingIdempotencyKey
         })?
-        .to_string()
-        .try_into()
-        .map_err(|e| {
-            tracing::warn!(error = ?e, "Invalid idempotency key format");
-            IdempotencyError::InvalidKeyFormat
-        })?;
+        .to_string();
 
-    Ok(idempotency_key)
+    IdempotencyKey::parse(raw_key, policy).map_err(|e| {
+        tracing::warn!(error = ?e, "Invalid idempotency key format");
+        IdempotencyError::InvalidKeyFormat
+    })
 }
 
 // wrapper for execute_idempotent_with that calls the default process_fn
 // (try_processing) that all non-test callers will use.
-pub async fn execute_idempotent<F, E>(
+pub async fn execute_idempotent<F, R, E>(
     request: &HttpRequest,
     pool: &PgPool,
     user_id: Option<Uuid>,
+    fingerprint: &RequestFingerprint,
     action: F,
 ) -> Result<HttpResponse, E>
 where
     F: for<'a> FnOnce(
         &'a mut Transaction<'static, Postgres>,
-    ) -> Pin<Box<dyn Future<Output = Result<HttpResponse, E>> + 'a>>,
+    ) -> Pin<Box<dyn Future<Output = Result<R, E>> + 'a>>,
+    R: Responder<Body = BoxBody>,
     E: From<IdempotencyError> + std::fmt::Debug,
 {
-    execute_idempotent_with(request, pool, user_id, action, |pool, key, user_id, op| {
-        Box::pin(async move {
-            try_processing(pool, key, user_id, op)
-                .await
-                .map_err(|e| E::from(e))
-        })
-    })
+    execute_idempotent_with(
+        request,
+        pool,
+        user_id,
+        fingerprint,
+        action,
+        |pool, key, user_id, op, fingerprint| {
+            Box::pin(async move {
+                try_processing(pool, key, user_id, op, fingerprint)
+                    .await
+                    .map_err(|e| E::from(e))
+            })
+        },
+    )
     .await
 }
 
@@ -230,11 +353,13 @@ where
 ///     - a reference to an HTTP request (this is where the actual data inserted/edited/etc. comes from)
 ///     - a reference to the Postgres connection pool
 ///     - an optional user_id depending on whether or not the action is anonymous
+///     - a fingerprint of the request payload, so a reused key with a different payload is rejected
 ///     - an arbitrary `action: F` that:
 ///         - is valid for all possible lifetimes 'a
 ///         - is executed a single time inside the idempotency pipeline
 ///         - returns a pinned, safe-to-poll pointer (valid for 'a) to a dynamically-dispatched future, the output of which:
-///             - is a Result that on success, is an HTTP response
+///             - is a Result that on success, is anything that responds with an HTTP response
+///               (an `ApiResponse` usually, rendered against `request` before it's stored)
 ///             - and on error, is an arbitrary error E
 ///         - takes as its parameter: a mutable reference to the active Postgres transaction to
 ///           ensure that in addition to being idempotent, this action is also atomic
@@ -252,7 +377,8 @@ where
 ///             - a reference to the Postgres connection pool
 ///             - the idempotency key (a caller-provided user_id + operation-scoped idempotency key)
 ///             - an optional user_id (for authenticated/anonymous actions)
-///             - and an operation identifier (ie. "POST:/v1/contact")
+///             - an operation identifier (ie. "POST:/v1/contact")
+///             - and the request fingerprint
 /// and returns:
 ///     - a Result that on success, is the HTTP response returned by `action`
 ///     - and on error, is the generic error E from either `action`, `process` or itself, which must:
@@ -271,38 +397,44 @@ where
 ///
 /// Trade-Offs:
 /// - response body is fully buffered in memory before persistence (?)
-/// - in-flight duplicates returns an error path rather than waiting for the first write completion
+/// - an in-flight duplicate blocks on the key's row until the first request commits, holding a
+///   connection meanwhile, then replays its response, or runs the action itself if the
+///   first request rolled back or released the key
 /// - operation scope must include METHOD:PATH to prevent key collisions
+/// - a replay has the first request's envelope shape and `request_id`, not the replaying one's
 #[doc(hidden)]
 #[allow(clippy::future_not_send)]
 // reusable idempotency flow for all handlers that need it
-pub async fn execute_idempotent_with<F, P, E>(
+pub async fn execute_idempotent_with<F, P, R, E>(
     request: &HttpRequest,
     pool: &PgPool,
     user_id: Option<Uuid>,
+    fingerprint: &RequestFingerprint,
     action: F,
     process_fn: P,
 ) -> Result<HttpResponse, E>
 where
     F: for<'a> FnOnce(
         &'a mut Transaction<'static, Postgres>,
-    ) -> Pin<Box<dyn Future<Output = Result<HttpResponse, E>> + 'a>>,
+    ) -> Pin<Box<dyn Future<Output = Result<R, E>> + 'a>>,
     P: for<'p> FnOnce(
         &'p PgPool,
         &'p IdempotencyKey,
         Option<Uuid>,
         &'p str, // operation identifier
+        &'p RequestFingerprint,
     ) -> Pin<
         Box<
             dyn Future<Output = Result<(NextAction, Option<Transaction<'static, Postgres>>), E>>
                 + 'p,
         >,
     >,
+    R: Responder<Body = BoxBody>,
     E: From<IdempotencyError> + std::fmt::Debug,
 {
     let key = get_idempotency_key(request).map_err(E::from)?;
     let operation = format!("{}:{}", request.method().as_str(), request.path());
-    let (next, tx_opt) = process_fn(pool, &key, user_id, &operation)
+    let (next, tx_opt) = process_fn(pool, &key, user_id, &operation, fingerprint)
         // propogate error directly from process_fn so we actually know what happened
         .await?;
 
@@ -311,10 +443,25 @@ where
 
         (NextAction::StartProcessing, Some(mut tx)) => {
             // wrap all this in tx
-            let response = action(&mut tx).await?;
-            let response = save_response(tx, &key, user_id, &operation, response)
-                .await
-                .map_err(E::from)?;
+            let max_body_bytes = request
+                .app_data::<web::Data<IdempotencySettings>>()
+                .map_or_else(
+                    || IdempotencySettings::default().max_stored_body_bytes,
+                    |settings| settings.max_stored_body_bytes,
+                );
+            let response = action(&mut tx).await?.respond_to(request);
+            let metrics = request.app_data::<web::Data<AppMetrics>>();
+            let response = save_response(
+                tx,
+                &key,
+                user_id,
+                &operation,
+                response,
+                max_body_bytes,
+                metrics.map(|metrics| metrics.get_ref()),
+            )
+            .await
+            .map_err(E::from)?;
             Ok(response)
         }
 
@@ -346,6 +493,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn get_idempotency_key_respects_configured_policy() {
+        let request = TestRequest::default()
+            .insert_header(("Idempotency-Key", "1"))
+            .app_data(web::Data::new(IdempotencyKeyPolicy::Uuid))
+            .to_http_request();
+        assert!(matches!(
+            get_idempotency_key(&request),
+            Err(IdempotencyError::InvalidKeyFormat)
+        ));
+    }
+
     #[test]
     fn get_idempotency_key_invalid_format() {
         let request = TestRequest::default()
@@ -0,0 +1,5 @@
+mod queue;
+mod worker;
+
+pub use queue::{JobHandlers, enqueue_job};
+pub use worker::{ExecutionOutcome, run_job_worker_until_stopped, try_execute_job};
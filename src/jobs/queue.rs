@@ -0,0 +1,60 @@
+use anyhow::Context;
+use sqlx::PgExecutor;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<(), anyhow::Error>> + Send>>;
+type JobHandler = Arc<dyn Fn(serde_json::Value) -> JobFuture + Send + Sync>;
+
+// what runs each kind of job, a kind without a handler fails every attempt
+#[derive(Clone, Default)]
+pub struct JobHandlers(HashMap<String, JobHandler>);
+
+impl JobHandlers {
+    #[must_use]
+    pub fn on<F, Fut>(mut self, kind: &str, handler: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.0.insert(
+            kind.to_string(),
+            Arc::new(move |payload| Box::pin(handler(payload))),
+        );
+        self
+    }
+
+    pub(crate) fn get(&self, kind: &str) -> Option<&JobHandler> {
+        self.0.get(kind)
+    }
+}
+
+// takes an executor so a job can be queued in the same transaction as the change that needs it
+/// # Errors
+/// returns an error if the payload can't be serialized or the insert fails
+#[tracing::instrument(name = "Enqueue job", skip(executor, payload))]
+pub async fn enqueue_job(
+    executor: impl PgExecutor<'_>,
+    kind: &str,
+    payload: &impl serde::Serialize,
+) -> Result<Uuid, anyhow::Error> {
+    let payload = serde_json::to_value(payload).context("Failed to serialize the job payload")?;
+
+    let job_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO jobs (kind, payload)
+        VALUES ($1, $2)
+        RETURNING job_id
+        "#,
+        kind,
+        payload
+    )
+    .fetch_one(executor)
+    .await
+    .context("Failed to enqueue job")?;
+
+    Ok(job_id)
+}
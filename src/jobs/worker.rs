@@ -0,0 +1,187 @@
+use anyhow::Context;
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::configuration::JobSettings;
+use crate::jobs::JobHandlers;
+
+pub enum ExecutionOutcome {
+    TaskCompleted,
+    EmptyQueue,
+}
+
+struct ClaimedJob {
+    job_id: Uuid,
+    kind: String,
+    payload: serde_json::Value,
+    attempts: i32,
+    max_attempts: i32,
+}
+
+/// # Errors
+/// only returns if the worker can't keep running, failed jobs are retried instead
+pub async fn run_job_worker_until_stopped(
+    pool: PgPool,
+    settings: JobSettings,
+    handlers: JobHandlers,
+) -> Result<(), anyhow::Error> {
+    let poll_interval = Duration::from_millis(settings.poll_interval_ms);
+
+    loop {
+        match try_execute_job(&pool, &handlers, &settings).await {
+            Ok(ExecutionOutcome::TaskCompleted) => {}
+            Ok(ExecutionOutcome::EmptyQueue) => tokio::time::sleep(poll_interval).await,
+            Err(e) => {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    "Failed to run the job queue"
+                );
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+// claims one due job, runs it with no transaction open and then records how it went
+// the claim counts the attempt and leases the job for `lease_secs`, so a worker that
+// panics or dies mid-job still used up an attempt and the job comes back once the lease
+// runs out, a job that does that on its last attempt is dead when it's next claimed
+/// # Errors
+/// returns an error if the queue can't be read or the outcome can't be recorded,
+/// a failing job is recorded as a retry or as dead rather than returned
+#[tracing::instrument(name = "Execute job", skip_all, fields(job_id, kind))]
+pub async fn try_execute_job(
+    pool: &PgPool,
+    handlers: &JobHandlers,
+    settings: &JobSettings,
+) -> Result<ExecutionOutcome, anyhow::Error> {
+    let job = sqlx::query_as!(
+        ClaimedJob,
+        r#"
+        UPDATE jobs
+        SET attempts = attempts + 1, run_at = NOW() + make_interval(secs => $1)
+        WHERE job_id = (
+            SELECT job_id
+            FROM jobs
+            WHERE status = 'queued' AND run_at <= NOW()
+            ORDER BY run_at
+            FOR UPDATE SKIP LOCKED
+            LIMIT 1
+        )
+        RETURNING job_id, kind, payload, attempts, max_attempts
+        "#,
+        settings.lease().as_secs_f64()
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to claim a job")?;
+    let Some(job) = job else {
+        return Ok(ExecutionOutcome::EmptyQueue);
+    };
+    tracing::Span::current()
+        .record("job_id", tracing::field::display(job.job_id))
+        .record("kind", tracing::field::display(&job.kind));
+
+    // the last attempt's worker never came back to record it
+    if job.attempts > job.max_attempts {
+        tracing::error!(
+            attempts = job.max_attempts,
+            "Job never finished its last attempt, moving it to the dead letters"
+        );
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET status = 'dead', attempts = max_attempts,
+                last_error = 'The worker stopped while running the job', finished_at = NOW()
+            WHERE job_id = $1
+            "#,
+            job.job_id
+        )
+        .execute(pool)
+        .await
+        .context("Failed to mark the job as dead")?;
+        return Ok(ExecutionOutcome::TaskCompleted);
+    }
+
+    let outcome = match handlers.get(&job.kind) {
+        Some(handler) => handler(job.payload).await,
+        None => Err(anyhow::anyhow!("No handler for job kind `{}`", job.kind)),
+    };
+    let attempts = job.attempts;
+
+    // `attempts` only matches while the lease is ours, a job that outran it and was
+    // claimed again is left for whoever has it now
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to start a transaction")?;
+    match outcome {
+        Ok(()) => {
+            sqlx::query!(
+                r#"
+                UPDATE jobs
+                SET status = 'succeeded', last_error = NULL, finished_at = NOW()
+                WHERE job_id = $1 AND attempts = $2
+                "#,
+                job.job_id,
+                attempts
+            )
+            .execute(transaction.as_mut())
+            .await
+            .context("Failed to mark the job as succeeded")?;
+        }
+        Err(e) if attempts >= job.max_attempts => {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                attempts,
+                "Job failed for the last time, moving it to the dead letters"
+            );
+            sqlx::query!(
+                r#"
+                UPDATE jobs
+                SET status = 'dead', last_error = $3, finished_at = NOW()
+                WHERE job_id = $1 AND attempts = $2
+                "#,
+                job.job_id,
+                attempts,
+                format!("{e:#}")
+            )
+            .execute(transaction.as_mut())
+            .await
+            .context("Failed to mark the job as dead")?;
+        }
+        Err(e) => {
+            let retry_in = settings.retry_backoff(attempts);
+            tracing::warn!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                attempts,
+                retry_in_secs = retry_in.as_secs(),
+                "Job failed, retrying later"
+            );
+            sqlx::query!(
+                r#"
+                UPDATE jobs
+                SET last_error = $3, run_at = NOW() + make_interval(secs => $4)
+                WHERE job_id = $1 AND attempts = $2
+                "#,
+                job.job_id,
+                attempts,
+                format!("{e:#}"),
+                retry_in.as_secs_f64()
+            )
+            .execute(transaction.as_mut())
+            .await
+            .context("Failed to reschedule the job")?;
+        }
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit the job outcome")?;
+    Ok(ExecutionOutcome::TaskCompleted)
+}
@@ -0,0 +1,203 @@
+use actix_web::{
+    body::MessageBody,
+    cookie::{Cookie, CookieJar, Key},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{COOKIE, HeaderMap, HeaderValue},
+    middleware::Next,
+    web,
+};
+use secrecy::{ExposeSecret, SecretString};
+
+// default cookie names used by actix-session and actix-web-flash-messages
+const SESSION_COOKIE_NAME: &str = "id";
+const FLASH_COOKIE_NAME: &str = "_flash";
+
+// how each cookie is protected by its owner
+#[derive(Clone, Copy)]
+enum Seal {
+    // actix-session encrypts the session key
+    Private,
+    // flash messages are only signed
+    Signed,
+}
+
+// the current key signs/encrypts everything going out, previous keys are only
+// used to recognise cookies that were issued before the last rotation
+#[derive(Clone)]
+pub struct KeyRing {
+    current: Key,
+    previous: Vec<Key>,
+}
+
+impl KeyRing {
+    /// # Errors
+    /// returns an error if any of the secrets is shorter than the 64 bytes a cookie `Key` needs
+    pub fn new(current: &SecretString, previous: &[SecretString]) -> Result<Self, anyhow::Error> {
+        let to_key = |secret: &SecretString| {
+            Key::try_from(secret.expose_secret().as_bytes())
+                .map_err(|e| anyhow::anyhow!("Invalid cookie signing key: {e}"))
+        };
+
+        Ok(Self {
+            current: to_key(current)?,
+            previous: previous.iter().map(to_key).collect::<Result<_, _>>()?,
+        })
+    }
+
+    #[must_use]
+    pub const fn current(&self) -> &Key {
+        &self.current
+    }
+
+    // re-seal a cookie that only opens with a retired key using the current key
+    // returns `None` if the cookie is already current (or doesn't open at all)
+    fn reseal(&self, cookie: &Cookie<'static>, seal: Seal) -> Option<Cookie<'static>> {
+        let name = cookie.name().to_owned();
+        let mut jar = CookieJar::new();
+        jar.add_original(cookie.clone());
+
+        let open = |key: &Key| match seal {
+            Seal::Private => jar.private(key).get(&name),
+            Seal::Signed => jar.signed(key).get(&name),
+        };
+
+        if open(&self.current).is_some() {
+            return None;
+        }
+        let plain = self.previous.iter().find_map(open)?;
+
+        let mut resealed = CookieJar::new();
+        match seal {
+            Seal::Private => resealed.private_mut(&self.current).add(plain),
+            Seal::Signed => resealed.signed_mut(&self.current).add(plain),
+        }
+        resealed.get(&name).cloned()
+    }
+
+    // rebuilds the Cookie header with every session/flash cookie re-sealed under the
+    // current key, or `None` if nothing needed to change
+    fn reseal_cookie_header(&self, headers: &HeaderMap) -> Option<HeaderValue> {
+        let mut changed = false;
+        let cookies: Vec<Cookie<'static>> = headers
+            .get_all(COOKIE)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|raw| Cookie::parse_encoded(raw.trim().to_owned()).ok())
+            .map(|cookie| {
+                let seal = match cookie.name() {
+                    SESSION_COOKIE_NAME => Seal::Private,
+                    FLASH_COOKIE_NAME => Seal::Signed,
+                    _ => return cookie,
+                };
+                self.reseal(&cookie, seal).map_or(cookie, |resealed| {
+                    changed = true;
+                    resealed
+                })
+            })
+            .collect();
+
+        if !changed {
+            return None;
+        }
+
+        let header = cookies
+            .iter()
+            .map(|cookie| cookie.encoded().stripped().to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+// has to run before the session and flash message middleware parse (and cache) the cookies.
+// the session middleware re-issues its cookie on every request, so sessions migrate to the
+// current key the first time they're used after a rotation
+#[allow(clippy::future_not_send)]
+pub async fn rotate_cookie_keys(
+    mut request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    if let Some(key_ring) = request.app_data::<web::Data<KeyRing>>().cloned()
+        && !key_ring.previous.is_empty()
+        && let Some(header) = key_ring.reseal_cookie_header(request.headers())
+    {
+        request.headers_mut().insert(COOKIE, header);
+    }
+
+    next.call(request).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn secret(c: char) -> SecretString {
+        SecretString::from(c.to_string().repeat(64))
+    }
+
+    fn cookie_header(cookie: &Cookie<'static>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            COOKIE,
+            HeaderValue::from_str(&cookie.encoded().stripped().to_string()).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn short_secrets_are_rejected() {
+        let short = SecretString::from("too-short");
+        assert!(KeyRing::new(&short, &[]).is_err());
+        assert!(KeyRing::new(&secret('a'), &[short]).is_err());
+    }
+
+    #[test]
+    fn cookies_sealed_with_a_previous_key_are_resealed() {
+        let old_ring = KeyRing::new(&secret('a'), &[]).unwrap();
+        let new_ring = KeyRing::new(&secret('b'), &[secret('a')]).unwrap();
+
+        let mut jar = CookieJar::new();
+        jar.private_mut(old_ring.current())
+            .add(Cookie::new(SESSION_COOKIE_NAME, "session-key"));
+        let old_cookie = jar.get(SESSION_COOKIE_NAME).cloned().unwrap();
+
+        let header = new_ring
+            .reseal_cookie_header(&cookie_header(&old_cookie))
+            .expect("cookie should have been resealed");
+        let resealed = Cookie::parse_encoded(header.to_str().unwrap().to_owned()).unwrap();
+
+        let mut jar = CookieJar::new();
+        jar.add_original(resealed);
+        assert_eq!(
+            jar.private(new_ring.current())
+                .get(SESSION_COOKIE_NAME)
+                .unwrap()
+                .value(),
+            "session-key"
+        );
+    }
+
+    #[test]
+    fn current_and_unknown_cookies_are_left_alone() {
+        let ring = KeyRing::new(&secret('b'), &[secret('a')]).unwrap();
+
+        let mut jar = CookieJar::new();
+        jar.signed_mut(ring.current())
+            .add(Cookie::new(FLASH_COOKIE_NAME, "[]"));
+        let current_cookie = jar.get(FLASH_COOKIE_NAME).cloned().unwrap();
+        assert!(
+            ring.reseal_cookie_header(&cookie_header(&current_cookie))
+                .is_none()
+        );
+
+        let stranger = KeyRing::new(&secret('c'), &[]).unwrap();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(stranger.current())
+            .add(Cookie::new(FLASH_COOKIE_NAME, "[]"));
+        let forged_cookie = jar.get(FLASH_COOKIE_NAME).cloned().unwrap();
+        assert!(
+            ring.reseal_cookie_header(&cookie_header(&forged_cookie))
+                .is_none()
+        );
+    }
+}
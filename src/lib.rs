@@ -1,11 +1,34 @@
+pub mod antivirus;
 pub mod authentication;
+pub mod blog_cache;
+pub mod build_info;
+pub mod client_ip;
 pub mod configuration;
+pub mod cors;
 pub mod crypto;
+pub mod email;
 pub mod errors;
+pub mod events;
+pub mod github;
 pub mod idempotency;
+pub mod jobs;
+pub mod key_ring;
+pub mod metrics;
+pub mod pdf;
+pub mod rate_limit;
+pub mod redis_pool;
+pub mod reload;
 pub mod routes;
+pub mod scheduler;
+pub mod secrets;
+pub mod self_test;
 pub mod session_state;
+pub mod session_store;
+pub mod spotify;
 pub mod startup;
+pub mod storage;
 pub mod telemetry;
+pub mod tls;
 pub mod types;
 pub mod utils;
+pub mod workers;
@@ -1,12 +1,29 @@
 use jsonwebtoken::crypto::aws_lc::DEFAULT_PROVIDER as JWT_PROVIDER;
+use opentelemetry_sdk::trace::SdkTracer;
 use rustls::crypto::CryptoProvider;
 use std::fmt::{Debug, Display};
+use std::io::Stdout;
 use tokio::task::JoinError;
+use tracing_subscriber::fmt::writer::{MakeWriterExt, Tee};
+#[cfg(feature = "console")]
+use tracing_subscriber::layer::SubscriberExt;
+
+#[cfg(feature = "console")]
+use portfolio_server::telemetry::console_layer;
 
 use portfolio_server::{
-    configuration::get_configuration,
+    antivirus::{Clamd, SCAN_UPLOAD_JOB, scan_upload},
+    configuration::{ConsoleSettings, get_configuration},
+    email::{EmailClient, run_email_delivery_worker_until_stopped},
+    github::{GithubClient, refresh_github_activity},
+    jobs::{JobHandlers, run_job_worker_until_stopped},
+    metrics::{run_metrics_cleanup, run_pending_rollups},
     startup::Application,
-    telemetry::{get_subscriber, init_subscriber},
+    storage::Storage,
+    telemetry::{
+        LokiWriter, RedactingWriter, Redactor, get_subscriber_with_tracer, init_loki,
+        init_subscriber, init_tracer_provider, tracer,
+    },
 };
 
 #[tokio::main]
@@ -21,44 +38,125 @@ async fn main() -> anyhow::Result<()> {
         tracing::warn!("JWT crypto provider was already installed");
     }
 
-    // start logging (or console?)
-    init_tracing();
+    // every invalid setting at once, before anything is bound or connected
+    let configuration = get_configuration()?;
 
-    let configuration = get_configuration().expect("Failed to read configuration.");
-    let application = Application::build(configuration).await.map_err(|e| {
-        tracing::error!(
-            error.cause_chain = ?e,
-            error.message = %e,
-            "Application failed to build"
-        );
-        e
-    })?;
-    let application_task = tokio::spawn(application.run_until_stopped());
+    // start logging, and the tokio console next to it when enabled
+    let tracer_provider = init_tracer_provider(&configuration.telemetry)?;
+    let (loki_writer, loki_shipper) = init_loki(&configuration.telemetry)?;
+    init_tracing(
+        tracer_provider
+            .as_ref()
+            .map(|provider| tracer(provider, &configuration.telemetry)),
+        RedactingWriter::new(
+            (std::io::stdout as fn() -> Stdout).and(loki_writer),
+            Redactor::new(
+                &configuration.telemetry.redaction,
+                &configuration.application.session_hash_key,
+            ),
+        ),
+        &configuration.telemetry.console,
+    );
+    let mut application = Application::build(configuration.clone())
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Application failed to build"
+            );
+            e
+        })?;
+    let pool = application.db_pool();
+    let mut job_handlers = JobHandlers::default();
+    if let Some(clamd) = Clamd::from_settings(&configuration.storage.antivirus) {
+        let storage = Storage::from_settings(
+            &configuration.storage,
+            &configuration.application.base_url,
+            &configuration.application.hmac_secret,
+        )?;
+        let pool = pool.clone();
+        job_handlers = job_handlers.on(SCAN_UPLOAD_JOB, move |payload| {
+            let (clamd, storage, pool) = (clamd.clone(), storage.clone(), pool.clone());
+            async move { scan_upload(payload, &clamd, &storage, &pool).await }
+        });
+    }
+    application.schedule("metrics_cleanup", {
+        let pool = pool.clone();
+        let metrics = application.metrics();
+        let retention_days = configuration.metrics.retention_days;
+        move || {
+            let (pool, metrics) = (pool.clone(), metrics.clone());
+            async move { run_metrics_cleanup(&pool, retention_days, &metrics).await }
+        }
+    });
+    if let Some(github_client) = GithubClient::from_settings(&configuration.github) {
+        let pool = pool.clone();
+        application.schedule("github_activity", move || {
+            let (pool, github_client) = (pool.clone(), github_client.clone());
+            async move { refresh_github_activity(&pool, &github_client).await }
+        });
+    }
+    application.schedule("metrics_rollup", {
+        let pool = pool.clone();
+        move || {
+            let pool = pool.clone();
+            async move { run_pending_rollups(&pool).await }
+        }
+    });
+    if let Some(email_client) = EmailClient::from_settings(&configuration.email) {
+        let configuration = configuration.clone();
+        application.register_worker("Email delivery", move || {
+            run_email_delivery_worker_until_stopped(configuration.clone(), email_client.clone())
+        });
+    }
+    application.reload_on_hangup();
+    application.register_worker("Job queue", move || {
+        run_job_worker_until_stopped(
+            pool.clone(),
+            configuration.jobs.clone(),
+            job_handlers.clone(),
+        )
+    });
+    // the workers are supervised inside, only the API stopping ends the process
+    report_exit("API", tokio::spawn(application.run_until_stopped()).await);
 
-    // put a tokio-select in here when you're ready
-    tokio::select! {
-        o = application_task => report_exit("API", o)
+    // flush whatever spans are still buffered
+    if let Some(provider) = tracer_provider
+        && let Err(e) = provider.shutdown()
+    {
+        tracing::warn!(error.cause_chain = ?e, "Failed to flush traces on shutdown");
+    }
+    // and whatever log lines haven't been pushed yet
+    if let Some(shipper) = loki_shipper {
+        shipper.shutdown().await;
     }
 
     Ok(())
 }
 
-#[cfg(feature = "console")]
-fn init_tracing() {
-    if std::env::var("TOKIO_CONSOLE").is_ok() {
-        console_subscriber::init();
-    } else {
-        let subscriber = get_subscriber("portfolio_server".into(), "info".into(), std::io::stdout);
+// bunyan lines go to stdout and, when configured, loki, both after redaction
+type LogSink = RedactingWriter<Tee<fn() -> Stdout, LokiWriter>>;
+
+fn init_tracing(tracer: Option<SdkTracer>, sink: LogSink, console: &ConsoleSettings) {
+    let subscriber =
+        get_subscriber_with_tracer("portfolio_server".into(), "info".into(), sink, tracer);
+    #[cfg(feature = "console")]
+    {
+        init_subscriber(subscriber.with(console_layer(console)));
+        if console.enabled {
+            tracing::info!(address = %console.bind_address, "Tokio console listening");
+        }
+    }
+    #[cfg(not(feature = "console"))]
+    {
         init_subscriber(subscriber);
+        if console.enabled {
+            tracing::warn!("telemetry.console is enabled but this build has no `console` feature");
+        }
     }
 }
 
-#[cfg(not(feature = "console"))]
-fn init_tracing() {
-    let subscriber = get_subscriber("portfolio_server".into(), "info".into(), std::io::stdout);
-    init_subscriber(subscriber);
-}
-
 // return when the provided task exits (ie. when a background delivery worker finishes)
 fn report_exit(task_name: &str, outcome: Result<Result<(), impl Debug + Display>, JoinError>) {
     match outcome {
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::metrics::AppMetrics;
+use crate::redis_pool::{self, RedisConnection, RedisPool};
+use crate::session_state::TypedSession;
+
+const MGET_BATCH: usize = 1000;
+// the session store keeps each session under its bare key, 64 random alphanumerics
+// nothing else we write to redis looks like that (rate limit keys are namespaced)
+const SESSION_KEY_LENGTH: usize = 64;
+
+// sets the active_sessions gauge from what's actually in the store, expired
+// sessions drop out on their own and a restart doesn't reset the count
+pub fn spawn_active_sessions_sampler(pool: RedisPool, metrics: AppMetrics, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            let counts = match redis_pool::get(&pool).await {
+                Ok(mut connection) => count_sessions(&mut connection).await,
+                Err(e) => Err(e),
+            };
+            match counts {
+                Ok(counts) => {
+                    metrics
+                        .active_sessions
+                        .set(i64::try_from(counts.sessions).unwrap_or(i64::MAX));
+                    metrics
+                        .active_users
+                        .set(i64::try_from(counts.users).unwrap_or(i64::MAX));
+                }
+                Err(e) => {
+                    tracing::warn!(error.cause_chain = ?e, "Failed to count active sessions");
+                }
+            }
+        }
+    });
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SessionCounts {
+    pub sessions: usize,
+    // distinct users across those sessions, someone signed in on two devices counts once
+    pub users: usize,
+}
+
+// only signed-in sessions count, ones still waiting on a totp code don't
+/// # Errors
+/// returns a `redis` error if a SCAN or MGET call fails
+pub async fn count_sessions(
+    connection: &mut RedisConnection,
+) -> Result<SessionCounts, redis::RedisError> {
+    let pattern = "[a-zA-Z0-9]".repeat(SESSION_KEY_LENGTH);
+    let keys = redis_pool::scan_keys(connection, &pattern).await?;
+    let mut sessions = 0;
+    let mut users = HashSet::new();
+
+    for keys in keys.chunks(MGET_BATCH) {
+        // a session can expire between the SCAN and the MGET, it comes back as nil
+        let states: Vec<Option<String>> =
+            redis::cmd("MGET").arg(keys).query_async(connection).await?;
+        for user_id in states
+            .iter()
+            .flatten()
+            .filter_map(|state| TypedSession::signed_in_user(state))
+        {
+            sessions += 1;
+            users.insert(user_id);
+        }
+    }
+
+    Ok(SessionCounts {
+        sessions,
+        users: users.len(),
+    })
+}
@@ -0,0 +1,367 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::configuration::AlertSettings;
+use crate::email::{EmailClient, render_email};
+use crate::events::{DashboardEvent, EventBus};
+
+// probes hit these around the clock, they'd hide a site nobody is visiting
+const PROBE_ENDPOINTS: [&str; 4] = ["/health_check", "/live", "/ready", "/metrics"];
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    ErrorRate,
+    P95Latency,
+    ZeroTraffic,
+}
+
+impl AlertKind {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::ErrorRate => "error rate",
+            Self::P95Latency => "p95 latency",
+            Self::ZeroTraffic => "zero traffic",
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub message: String,
+}
+
+// what the evaluator reads from server_metrics each pass
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertWindowStats {
+    pub requests: i64,
+    pub server_errors: i64,
+    pub p95_latency_ms: Option<f64>,
+    // requests outside the probe endpoints over the zero-traffic window
+    pub visitor_requests: i64,
+}
+
+#[must_use]
+pub fn evaluate_alerts(settings: &AlertSettings, stats: &AlertWindowStats) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+
+    if let Some(threshold) = settings.error_rate_percent
+        && stats.requests > 0
+    {
+        #[allow(clippy::cast_precision_loss)]
+        let error_rate = stats.server_errors as f64 * 100.0 / stats.requests as f64;
+        if error_rate > threshold {
+            alerts.push(Alert {
+                kind: AlertKind::ErrorRate,
+                message: format!(
+                    "{error_rate:.1}% of requests failed over the last {} minutes (threshold {threshold}%)",
+                    settings.window_minutes
+                ),
+            });
+        }
+    }
+
+    if let (Some(threshold), Some(p95)) = (settings.p95_latency_ms, stats.p95_latency_ms)
+        && p95 > threshold
+    {
+        alerts.push(Alert {
+            kind: AlertKind::P95Latency,
+            message: format!(
+                "p95 latency was {p95:.0} ms over the last {} minutes (threshold {threshold} ms)",
+                settings.window_minutes
+            ),
+        });
+    }
+
+    if let Some(minutes) = settings.zero_traffic_minutes
+        && stats.visitor_requests == 0
+    {
+        alerts.push(Alert {
+            kind: AlertKind::ZeroTraffic,
+            message: format!("No visitor requests in the last {minutes} minutes"),
+        });
+    }
+
+    alerts
+}
+
+// each kind is sent at most once per cooldown, whether it stays breached or
+// flaps around its threshold, clearing in between doesn't reset the clock
+pub struct AlertCooldowns {
+    cooldown: Duration,
+    notified_at: HashMap<AlertKind, Instant>,
+}
+
+impl AlertCooldowns {
+    #[must_use]
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            notified_at: HashMap::new(),
+        }
+    }
+
+    // the subset of `alerts` that should be sent now
+    pub fn filter(&mut self, alerts: Vec<Alert>, now: Instant) -> Vec<Alert> {
+        alerts
+            .into_iter()
+            .filter(|alert| {
+                let due = self
+                    .notified_at
+                    .get(&alert.kind)
+                    .is_none_or(|notified_at| now.duration_since(*notified_at) >= self.cooldown);
+                if due {
+                    self.notified_at.insert(alert.kind, now);
+                }
+                due
+            })
+            .collect()
+    }
+}
+
+pub fn spawn_alert_evaluator(
+    pool: PgPool,
+    settings: AlertSettings,
+    events: EventBus,
+    email_client: Option<EmailClient>,
+) {
+    if settings.error_rate_percent.is_none()
+        && settings.p95_latency_ms.is_none()
+        && settings.zero_traffic_minutes.is_none()
+    {
+        tracing::info!("No alert thresholds configured, alert evaluator disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut cooldowns = AlertCooldowns::new(Duration::from_secs(settings.cooldown_secs));
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(settings.evaluation_interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            let stats = match fetch_window_stats(&settings, &pool).await {
+                Ok(stats) => stats,
+                Err(e) => {
+                    tracing::warn!(error.cause_chain = ?e, "Failed to read alert window stats");
+                    continue;
+                }
+            };
+
+            let alerts = cooldowns.filter(evaluate_alerts(&settings, &stats), Instant::now());
+            for alert in alerts {
+                notify(&client, email_client.as_ref(), &settings, &events, &alert).await;
+            }
+        }
+    });
+}
+
+async fn notify(
+    client: &reqwest::Client,
+    email_client: Option<&EmailClient>,
+    settings: &AlertSettings,
+    events: &EventBus,
+    alert: &Alert,
+) {
+    tracing::warn!(kind = ?alert.kind, message = %alert.message, "Alert threshold breached");
+
+    events.publish(DashboardEvent::Alert {
+        kind: alert.kind,
+        message: alert.message.clone(),
+        fired_at: Utc::now(),
+    });
+
+    if let Some(webhook_url) = &settings.webhook_url {
+        let result = client
+            .post(webhook_url)
+            .timeout(Duration::from_secs(10))
+            .json(alert)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        if let Err(e) = result {
+            tracing::error!(error.cause_chain = ?e, "Failed to deliver alert webhook");
+        }
+    }
+
+    if let Some(email_client) = email_client
+        && !settings.email_recipients.is_empty()
+    {
+        let email = render_email(
+            "alert",
+            minijinja::context! { kind => alert.kind.label(), message => &alert.message },
+        );
+        let result = match email {
+            Ok(email) => email_client
+                .send(&settings.email_recipients, &email)
+                .await
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            tracing::error!(error.cause_chain = ?e, "Failed to deliver alert email");
+        }
+    }
+}
+
+async fn fetch_window_stats(
+    settings: &AlertSettings,
+    pool: &PgPool,
+) -> Result<AlertWindowStats, sqlx::Error> {
+    let window = sqlx::query!(
+        r#"
+        SELECT
+            COALESCE(ROUND(SUM(1 / sample_rate)), 0)::BIGINT AS "requests!",
+            COALESCE(ROUND(SUM(1 / sample_rate) FILTER (WHERE status_code >= 500)), 0)::BIGINT
+                AS "server_errors!",
+            percentile_cont(0.95) WITHIN GROUP (ORDER BY response_time_ms) AS p95_latency_ms
+        FROM server_metrics
+        WHERE created_at > NOW() - make_interval(mins => $1)
+        "#,
+        i32::try_from(settings.window_minutes).unwrap_or(i32::MAX)
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let visitor_requests = match settings.zero_traffic_minutes {
+        Some(minutes) => count_visitor_requests(minutes, pool).await?,
+        None => 0,
+    };
+
+    Ok(AlertWindowStats {
+        requests: window.requests,
+        server_errors: window.server_errors,
+        p95_latency_ms: window.p95_latency_ms,
+        visitor_requests,
+    })
+}
+
+async fn count_visitor_requests(minutes: u64, pool: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(ROUND(SUM(1 / sample_rate)), 0)::BIGINT AS "count!"
+        FROM server_metrics
+        WHERE created_at > NOW() - make_interval(mins => $1)
+            AND endpoint <> ALL($2)
+        "#,
+        i32::try_from(minutes).unwrap_or(i32::MAX),
+        &PROBE_ENDPOINTS.map(str::to_owned)
+    )
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn settings() -> AlertSettings {
+        AlertSettings {
+            error_rate_percent: Some(5.0),
+            p95_latency_ms: Some(1000.0),
+            zero_traffic_minutes: Some(30),
+            ..AlertSettings::default()
+        }
+    }
+
+    fn healthy() -> AlertWindowStats {
+        AlertWindowStats {
+            requests: 100,
+            server_errors: 1,
+            p95_latency_ms: Some(120.0),
+            visitor_requests: 40,
+        }
+    }
+
+    fn kinds(alerts: &[Alert]) -> Vec<AlertKind> {
+        alerts.iter().map(|alert| alert.kind).collect()
+    }
+
+    #[test]
+    fn healthy_windows_raise_nothing() {
+        assert!(evaluate_alerts(&settings(), &healthy()).is_empty());
+    }
+
+    #[test]
+    fn each_breached_threshold_raises_its_alert() {
+        let stats = AlertWindowStats {
+            server_errors: 10,
+            p95_latency_ms: Some(2500.0),
+            visitor_requests: 0,
+            ..healthy()
+        };
+
+        assert_eq!(
+            kinds(&evaluate_alerts(&settings(), &stats)),
+            vec![
+                AlertKind::ErrorRate,
+                AlertKind::P95Latency,
+                AlertKind::ZeroTraffic
+            ]
+        );
+    }
+
+    #[test]
+    fn unset_thresholds_are_never_evaluated() {
+        let stats = AlertWindowStats {
+            server_errors: 100,
+            visitor_requests: 0,
+            ..healthy()
+        };
+
+        assert!(evaluate_alerts(&AlertSettings::default(), &stats).is_empty());
+    }
+
+    #[test]
+    fn ongoing_alerts_are_repeated_only_after_the_cooldown() {
+        let mut cooldowns = AlertCooldowns::new(Duration::from_secs(600));
+        let alert = Alert {
+            kind: AlertKind::ErrorRate,
+            message: "failing".to_string(),
+        };
+        let start = Instant::now();
+
+        assert_eq!(cooldowns.filter(vec![alert.clone()], start).len(), 1);
+        assert!(
+            cooldowns
+                .filter(vec![alert.clone()], start + Duration::from_secs(60))
+                .is_empty()
+        );
+        assert_eq!(
+            cooldowns
+                .filter(vec![alert.clone()], start + Duration::from_secs(600))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn a_flapping_alert_waits_out_the_cooldown() {
+        let mut cooldowns = AlertCooldowns::new(Duration::from_secs(600));
+        let alert = Alert {
+            kind: AlertKind::ZeroTraffic,
+            message: "quiet".to_string(),
+        };
+        let start = Instant::now();
+
+        assert_eq!(cooldowns.filter(vec![alert.clone()], start).len(), 1);
+        cooldowns.filter(vec![], start + Duration::from_secs(60));
+        assert!(
+            cooldowns
+                .filter(vec![alert.clone()], start + Duration::from_secs(120))
+                .is_empty()
+        );
+        assert_eq!(
+            cooldowns
+                .filter(vec![alert], start + Duration::from_secs(600))
+                .len(),
+            1
+        );
+    }
+}
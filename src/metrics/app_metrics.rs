@@ -0,0 +1,233 @@
+use prometheus::core::Collector;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+
+// counted by `SlowStatementLayer` from sqlx's own slow statement events, the layer is
+// installed with the subscriber before any `AppMetrics` exists so the counter is process-wide
+// labelled by sqlx's summary of the statement, its first few words
+pub static DB_SLOW_STATEMENTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "db_slow_statements_total",
+            "SQL statements slower than database.slow_statement_ms",
+        ),
+        &["statement"],
+    )
+    .expect("valid db_slow_statements_total options")
+});
+
+// rows the buffered metrics writers shed because their queue was full, labelled by queue,
+// bumped from request handlers and middleware so it lives outside `AppMetrics` too
+pub static METRICS_ROWS_DROPPED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "metrics_rows_dropped_total",
+            "Page visits and server metrics dropped because the write queue was full",
+        ),
+        &["queue"],
+    )
+    .expect("valid metrics_rows_dropped_total options")
+});
+
+// process-wide instruments, exposed in Prometheus text format on /metrics
+#[derive(Clone)]
+pub struct AppMetrics {
+    registry: Registry,
+    pub http_requests_total: IntCounterVec,
+    pub http_request_duration_seconds: HistogramVec,
+    pub http_quiet_requests_total: IntCounterVec,
+    pub http_panics_total: IntCounterVec,
+    pub metrics_cleanup_deleted_rows_total: IntCounterVec,
+    pub idempotency_responses_not_stored_total: IntCounterVec,
+    pub db_slow_statements_total: IntCounterVec,
+    pub metrics_rows_dropped_total: IntCounterVec,
+    pub active_sessions: IntGauge,
+    pub active_users: IntGauge,
+    pub db_connections_active: IntGauge,
+    pub db_connections_idle: IntGauge,
+    pub redis_connections_active: IntGauge,
+    pub redis_connections_idle: IntGauge,
+    pub redis_connections_waiting: IntGauge,
+}
+
+impl AppMetrics {
+    /// # Errors
+    /// returns a `prometheus` error if an instrument can't be registered
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new_custom(Some("portfolio".to_string()), None)?;
+
+        let http_requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "path", "status"],
+        )?;
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency in seconds",
+            ),
+            &["method", "path"],
+        )?;
+        // labelled by the configured quiet route pattern, not the path, so it stays small
+        let http_quiet_requests_total = IntCounterVec::new(
+            Opts::new(
+                "http_quiet_requests_total",
+                "HTTP requests to quiet routes, left out of the request logs and per-route series",
+            ),
+            &["route", "status"],
+        )?;
+        let http_panics_total = IntCounterVec::new(
+            Opts::new(
+                "http_panics_total",
+                "Requests whose handler panicked, answered with a 500",
+            ),
+            &["path"],
+        )?;
+        let metrics_cleanup_deleted_rows_total = IntCounterVec::new(
+            Opts::new(
+                "metrics_cleanup_deleted_rows_total",
+                "Rows purged by the metrics retention cleanup",
+            ),
+            &["table"],
+        )?;
+        let idempotency_responses_not_stored_total = IntCounterVec::new(
+            Opts::new(
+                "idempotency_responses_not_stored_total",
+                "Idempotent responses too large or streamed to store for replay",
+            ),
+            &["reason"],
+        )?;
+        let db_slow_statements_total = DB_SLOW_STATEMENTS_TOTAL.clone();
+        let metrics_rows_dropped_total = METRICS_ROWS_DROPPED_TOTAL.clone();
+
+        let active_sessions = IntGauge::new(
+            "active_sessions",
+            "Live login sessions in the session store",
+        )?;
+        let active_users =
+            IntGauge::new("active_users", "Distinct users with a live login session")?;
+
+        let db_connections_active = IntGauge::new(
+            "db_connections_active",
+            "Postgres pool connections currently checked out",
+        )?;
+        let db_connections_idle = IntGauge::new(
+            "db_connections_idle",
+            "Postgres pool connections open but idle",
+        )?;
+        let redis_connections_active = IntGauge::new(
+            "redis_connections_active",
+            "Redis pool connections currently checked out",
+        )?;
+        let redis_connections_idle = IntGauge::new(
+            "redis_connections_idle",
+            "Redis pool connections open but idle",
+        )?;
+        let redis_connections_waiting = IntGauge::new(
+            "redis_connections_waiting",
+            "Callers waiting for a Redis pool connection",
+        )?;
+
+        registry.register(Box::new(http_requests_total.clone()))?;
+        registry.register(Box::new(http_request_duration_seconds.clone()))?;
+        registry.register(Box::new(http_quiet_requests_total.clone()))?;
+        registry.register(Box::new(http_panics_total.clone()))?;
+        registry.register(Box::new(metrics_cleanup_deleted_rows_total.clone()))?;
+        registry.register(Box::new(idempotency_responses_not_stored_total.clone()))?;
+        registry.register(Box::new(db_slow_statements_total.clone()))?;
+        registry.register(Box::new(metrics_rows_dropped_total.clone()))?;
+        registry.register(Box::new(active_sessions.clone()))?;
+        registry.register(Box::new(active_users.clone()))?;
+        registry.register(Box::new(db_connections_active.clone()))?;
+        registry.register(Box::new(db_connections_idle.clone()))?;
+        registry.register(Box::new(redis_connections_active.clone()))?;
+        registry.register(Box::new(redis_connections_idle.clone()))?;
+        registry.register(Box::new(redis_connections_waiting.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests_total,
+            http_request_duration_seconds,
+            http_quiet_requests_total,
+            http_panics_total,
+            metrics_cleanup_deleted_rows_total,
+            idempotency_responses_not_stored_total,
+            db_slow_statements_total,
+            metrics_rows_dropped_total,
+            active_sessions,
+            active_users,
+            db_connections_active,
+            db_connections_idle,
+            redis_connections_active,
+            redis_connections_idle,
+            redis_connections_waiting,
+        })
+    }
+
+    // lets later instruments live next to the request metrics
+    /// # Errors
+    /// returns a `prometheus` error if a collector with the same name is already registered
+    pub fn register(
+        &self,
+        collector: Box<dyn prometheus::core::Collector>,
+    ) -> Result<(), prometheus::Error> {
+        self.registry.register(collector)
+    }
+
+    // (all requests, requests that ended in a 5xx) since startup
+    #[must_use]
+    pub fn request_totals(&self) -> (u64, u64) {
+        let mut total = 0;
+        let mut server_errors = 0;
+        for family in Collector::collect(&self.http_requests_total) {
+            for metric in family.get_metric() {
+                // counters only ever hold whole numbers here
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let count = metric.get_counter().get_value() as u64;
+                total += count;
+                if metric
+                    .get_label()
+                    .iter()
+                    .any(|label| label.name() == "status" && label.value().starts_with('5'))
+                {
+                    server_errors += count;
+                }
+            }
+        }
+        (total, server_errors)
+    }
+
+    /// # Errors
+    /// returns an `anyhow` error if the metrics can't be encoded
+    pub fn render(&self) -> Result<String, anyhow::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recorded_requests_are_rendered() {
+        let metrics = AppMetrics::new().unwrap();
+        metrics
+            .http_requests_total
+            .with_label_values(&["GET", "/health_check", "200"])
+            .inc();
+
+        metrics
+            .http_requests_total
+            .with_label_values(&["POST", "/v1/contact", "500"])
+            .inc();
+        assert_eq!(metrics.request_totals(), (2, 1));
+
+        let rendered = metrics.render().unwrap();
+        assert!(rendered.contains(
+            r#"portfolio_http_requests_total{method="GET",path="/health_check",status="200"} 1"#
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot,
+};
+
+use crate::metrics::METRICS_ROWS_DROPPED_TOTAL;
+
+// how many batches can queue up behind a slow insert before rows get dropped
+const QUEUED_BATCHES: usize = 10;
+
+enum Queued<T> {
+    Row(T),
+    // answered once everything queued before it has been written
+    Flush(oneshot::Sender<()>),
+}
+
+// rows queued by requests and written in batches by a background task, so a request never
+// waits on an insert, `table` labels the rows dropped when the queue is full
+pub(crate) struct BatchWriter<T> {
+    sender: mpsc::Sender<Queued<T>>,
+    table: &'static str,
+}
+
+impl<T> Clone for BatchWriter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            table: self.table,
+        }
+    }
+}
+
+impl<T: Send + 'static> BatchWriter<T> {
+    // `write` gets every batch, whenever it fills up, `flush_interval` passes or `flush` asks
+    pub(crate) fn spawn<F, Fut>(
+        batch_size: usize,
+        flush_interval: Duration,
+        table: &'static str,
+        write: F,
+    ) -> Self
+    where
+        F: Fn(Vec<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let batch_size = batch_size.max(1);
+        let (sender, mut receiver) = mpsc::channel(batch_size * QUEUED_BATCHES);
+
+        tokio::spawn(async move {
+            let mut batch = Vec::with_capacity(batch_size);
+            let mut ticker = tokio::time::interval(flush_interval);
+
+            loop {
+                tokio::select! {
+                    received = receiver.recv() => match received {
+                        Some(Queued::Row(row)) => {
+                            batch.push(row);
+                            if batch.len() >= batch_size {
+                                write_batch(&write, &mut batch).await;
+                            }
+                        }
+                        Some(Queued::Flush(done)) => {
+                            write_batch(&write, &mut batch).await;
+                            let _ = done.send(());
+                        }
+                        None => {
+                            // every handle is gone, write what's left and stop
+                            write_batch(&write, &mut batch).await;
+                            break;
+                        }
+                    },
+                    _ = ticker.tick() => write_batch(&write, &mut batch).await,
+                }
+            }
+        });
+
+        Self { sender, table }
+    }
+
+    // shedding rows beats back-pressuring requests when the database falls behind
+    pub(crate) fn push(&self, row: T) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(Queued::Row(row)) {
+            METRICS_ROWS_DROPPED_TOTAL
+                .with_label_values(&[self.table])
+                .inc();
+            tracing::warn!(table = self.table, "Metrics queue is full, dropping row");
+        }
+    }
+
+    // waits until every row pushed so far is written, or the writer has stopped
+    pub(crate) async fn flush(&self) {
+        let (done, written) = oneshot::channel();
+        if self.sender.send(Queued::Flush(done)).await.is_ok() {
+            let _ = written.await;
+        }
+    }
+}
+
+async fn write_batch<T, F, Fut>(write: &F, batch: &mut Vec<T>)
+where
+    F: Fn(Vec<T>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    if batch.is_empty() {
+        return;
+    }
+    let rows = std::mem::replace(batch, Vec::with_capacity(batch.capacity()));
+    write(rows).await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn rows_are_written_in_batches_and_on_flush() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let writer = BatchWriter::spawn(2, Duration::from_secs(3600), "test_batches", {
+            let batches = batches.clone();
+            move |rows: Vec<u32>| {
+                batches.lock().unwrap().push(rows);
+                std::future::ready(())
+            }
+        });
+
+        for row in 1..=3 {
+            writer.push(row);
+        }
+        writer.flush().await;
+
+        // the interval can flush a short batch early, but never splits a full one
+        let batches = batches.lock().unwrap();
+        assert!(batches.iter().all(|batch| batch.len() <= 2));
+        assert_eq!(batches.concat(), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn rows_past_the_queue_are_dropped_and_counted() {
+        // the first batch never finishes writing, so the queue behind it fills up
+        let writer = BatchWriter::spawn(
+            1,
+            Duration::from_secs(3600),
+            "test_dropped",
+            |_: Vec<u32>| std::future::pending(),
+        );
+        let dropped = METRICS_ROWS_DROPPED_TOTAL.with_label_values(&["test_dropped"]);
+
+        // nothing runs the writer until this test yields, so the whole queue is still free
+        for row in 0..u32::try_from(QUEUED_BATCHES).unwrap() + 2 {
+            writer.push(row);
+        }
+
+        assert_eq!(dropped.get(), 2);
+    }
+}
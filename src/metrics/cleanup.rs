@@ -0,0 +1,38 @@
+use sqlx::PgPool;
+
+use crate::metrics::{AppMetrics, CleanupReport, cleanup_old_metrics};
+
+// one purge of analytics rows past their retention, run by the scheduler as `metrics_cleanup`
+/// # Errors
+/// returns an error if the purge fails, the next scheduled run tries again
+pub async fn run_metrics_cleanup(
+    pool: &PgPool,
+    retention_days: u32,
+    metrics: &AppMetrics,
+) -> Result<(), anyhow::Error> {
+    let report = cleanup_old_metrics(retention_days, pool).await?;
+    record_cleanup(&report, metrics);
+    Ok(())
+}
+
+fn record_cleanup(report: &CleanupReport, metrics: &AppMetrics) {
+    tracing::info!(
+        page_visits = report.page_visits,
+        performance_metrics = report.performance_metrics,
+        server_metrics = report.server_metrics,
+        slow_requests = report.slow_requests,
+        "Metrics cleanup finished"
+    );
+
+    for (table, deleted) in [
+        ("page_visits", report.page_visits),
+        ("performance_metrics", report.performance_metrics),
+        ("server_metrics", report.server_metrics),
+        ("slow_requests", report.slow_requests),
+    ] {
+        metrics
+            .metrics_cleanup_deleted_rows_total
+            .with_label_values(&[table])
+            .inc_by(deleted);
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::configuration::DigitalOceanSettings;
+
+// the monitoring api reports bandwidth in megabits per second
+const BYTES_PER_MEGABIT: f64 = 125_000.0;
+const LOOKBACK_SECS: i64 = 24 * 60 * 60;
+
+// public interface traffic for the droplet over the last 24 hours
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct BandwidthSnapshot {
+    pub inbound_bytes: u64,
+    pub outbound_bytes: u64,
+    pub fetched_at: DateTime<Utc>,
+}
+
+// last successful reading, `None` until the first poll succeeds or when the
+// integration isn't configured
+// a failed poll keeps the previous reading, `fetched_at` shows how stale it is
+#[derive(Clone)]
+pub struct DigitalOceanBandwidth(watch::Receiver<Option<BandwidthSnapshot>>);
+
+impl DigitalOceanBandwidth {
+    #[must_use]
+    pub fn latest(&self) -> Option<BandwidthSnapshot> {
+        self.0.borrow().clone()
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct MetricsResponse {
+    data: MetricsData,
+}
+
+#[derive(serde::Deserialize)]
+struct MetricsData {
+    result: Vec<MetricSeries>,
+}
+
+#[derive(serde::Deserialize)]
+struct MetricSeries {
+    // (unix timestamp, rate in Mbps as a string)
+    values: Vec<(i64, String)>,
+}
+
+#[must_use]
+pub fn spawn_bandwidth_poller(settings: DigitalOceanSettings) -> DigitalOceanBandwidth {
+    let (sender, receiver) = watch::channel::<Option<BandwidthSnapshot>>(None);
+
+    let (Some(api_token), Some(droplet_id)) =
+        (settings.api_token.clone(), settings.droplet_id.clone())
+    else {
+        tracing::info!("DigitalOcean monitoring not configured, bandwidth polling disabled");
+        return DigitalOceanBandwidth(receiver);
+    };
+
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!(error.cause_chain = ?e, "Failed to build DigitalOcean client");
+                return;
+            }
+        };
+        let mut ticker = tokio::time::interval(Duration::from_secs(settings.refresh_interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            let end = Utc::now();
+            let fetch = |direction| {
+                fetch_bandwidth(
+                    &client,
+                    &settings.api_base_url,
+                    api_token.expose_secret(),
+                    &droplet_id,
+                    direction,
+                    end,
+                )
+            };
+
+            match tokio::try_join!(fetch("inbound"), fetch("outbound")) {
+                Ok((inbound_bytes, outbound_bytes)) => {
+                    let snapshot = BandwidthSnapshot {
+                        inbound_bytes,
+                        outbound_bytes,
+                        fetched_at: end,
+                    };
+                    if sender.send(Some(snapshot)).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    error.cause_chain = ?e,
+                    "Failed to fetch DigitalOcean bandwidth, keeping the last reading"
+                ),
+            }
+        }
+    });
+
+    DigitalOceanBandwidth(receiver)
+}
+
+async fn fetch_bandwidth(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_token: &str,
+    droplet_id: &str,
+    direction: &str,
+    end: DateTime<Utc>,
+) -> Result<u64, anyhow::Error> {
+    let end = end.timestamp();
+    let response: MetricsResponse = client
+        .get(format!(
+            "{base_url}/v2/monitoring/metrics/droplet/bandwidth"
+        ))
+        .bearer_auth(api_token)
+        .query(&[
+            ("host_id", droplet_id),
+            ("interface", "public"),
+            ("direction", direction),
+            ("start", &(end - LOOKBACK_SECS).to_string()),
+            ("end", &end.to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(total_bytes(&response))
+}
+
+// integrates the sampled rates over time, each rate holds until the next sample
+fn total_bytes(response: &MetricsResponse) -> u64 {
+    let megabits: f64 = response
+        .data
+        .result
+        .iter()
+        .flat_map(|series| series.values.windows(2))
+        .filter_map(|pair| {
+            let (start, rate) = &pair[0];
+            let (end, _) = &pair[1];
+            let rate = rate.parse::<f64>().ok()?;
+            #[allow(clippy::cast_precision_loss)]
+            let elapsed = (end - start) as f64;
+            Some(rate * elapsed)
+        })
+        .sum();
+
+    // bandwidth totals comfortably fit in a u64 and are never negative
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let bytes = (megabits * BYTES_PER_MEGABIT).max(0.0) as u64;
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rates_are_integrated_into_bytes() {
+        let response: MetricsResponse = serde_json::from_value(serde_json::json!({
+            "status": "success",
+            "data": {
+                "resultType": "matrix",
+                "result": [{
+                    "metric": { "direction": "inbound", "interface": "public" },
+                    "values": [[0, "8"], [60, "16"], [120, "not-a-number"], [180, "0"]]
+                }]
+            }
+        }))
+        .unwrap();
+
+        // 8 Mbps for 60s + 16 Mbps for 60s, the unparseable sample contributes nothing
+        assert_eq!(total_bytes(&response), (8 + 16) * 60 * 125_000);
+    }
+
+    #[test]
+    fn empty_series_total_zero() {
+        let response: MetricsResponse = serde_json::from_value(serde_json::json!({
+            "data": { "result": [] }
+        }))
+        .unwrap();
+
+        assert_eq!(total_bytes(&response), 0);
+    }
+}
@@ -0,0 +1,253 @@
+use actix_web::web::Bytes;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, StreamExt, wrappers::ReceiverStream};
+use uuid::Uuid;
+
+// a year of raw rows is already more than a notebook wants in one go
+const MAX_EXPORT_DAYS: u64 = 366;
+// rows are buffered into chunks of roughly this size before being sent
+const EXPORT_CHUNK_BYTES: usize = 16 * 1024;
+const EXPORT_CHANNEL_CAPACITY: usize = 16;
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportDataset {
+    Visits,
+    Performance,
+    ServerMetrics,
+}
+
+impl ExportDataset {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Visits => "visits",
+            Self::Performance => "performance",
+            Self::ServerMetrics => "server_metrics",
+        }
+    }
+}
+
+// json is newline-delimited, one object per row, so it can be streamed too
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ExportQuery {
+    pub dataset: ExportDataset,
+    // both inclusive, as UTC days
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+impl ExportQuery {
+    /// # Errors
+    /// returns a message for the client if the date range is backwards or too long
+    pub fn validate(&self) -> Result<(), String> {
+        if self.to < self.from {
+            return Err("`to` must not be before `from`".to_string());
+        }
+        if self.from.checked_add_days(Days::new(MAX_EXPORT_DAYS)) <= Some(self.to) {
+            return Err(format!("exports cover at most {MAX_EXPORT_DAYS} days"));
+        }
+        Ok(())
+    }
+
+    // [start of `from`, start of the day after `to`)
+    fn bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start_of = |day: NaiveDate| day.and_time(chrono::NaiveTime::MIN).and_utc();
+        let end = self.to.checked_add_days(Days::new(1)).unwrap_or(self.to);
+        (start_of(self.from), start_of(end))
+    }
+}
+
+#[derive(serde::Serialize)]
+struct VisitExportRow {
+    visit_id: Uuid,
+    path: String,
+    referrer: Option<String>,
+    session_hash: Option<String>,
+    duration_ms: Option<i32>,
+    user_agent: Option<String>,
+    utm_source: Option<String>,
+    utm_medium: Option<String>,
+    utm_campaign: Option<String>,
+    sample_rate: f64,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize)]
+struct PerformanceExportRow {
+    metric_id: Uuid,
+    path: String,
+    metric_name: String,
+    value: f64,
+    sample_rate: f64,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize)]
+struct ServerMetricExportRow {
+    endpoint: String,
+    method: String,
+    status_code: i32,
+    response_time_ms: f64,
+    sample_rate: f64,
+    created_at: DateTime<Utc>,
+}
+
+// rows are read with a cursor and pushed through a channel, so large ranges never
+// sit in memory, the query stops early if the client disconnects
+#[must_use]
+pub fn stream_export(
+    query: ExportQuery,
+    pool: PgPool,
+) -> ReceiverStream<Result<Bytes, anyhow::Error>> {
+    let (sender, receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let (from, until) = query.bounds();
+        let result = match query.dataset {
+            ExportDataset::Visits => {
+                let rows = sqlx::query_as!(
+                    VisitExportRow,
+                    r#"
+                    SELECT visit_id, path, referrer, session_hash, duration_ms, user_agent,
+                        utm_source, utm_medium, utm_campaign, sample_rate, created_at
+                    FROM page_visits
+                    WHERE created_at >= $1 AND created_at < $2
+                    ORDER BY created_at
+                    "#,
+                    from,
+                    until
+                )
+                .fetch(&pool);
+                write_rows(rows, query.format, &sender).await
+            }
+            ExportDataset::Performance => {
+                let rows = sqlx::query_as!(
+                    PerformanceExportRow,
+                    r#"
+                    SELECT metric_id, path, metric_name, value, sample_rate, created_at
+                    FROM performance_metrics
+                    WHERE created_at >= $1 AND created_at < $2
+                    ORDER BY created_at
+                    "#,
+                    from,
+                    until
+                )
+                .fetch(&pool);
+                write_rows(rows, query.format, &sender).await
+            }
+            ExportDataset::ServerMetrics => {
+                let rows = sqlx::query_as!(
+                    ServerMetricExportRow,
+                    r#"
+                    SELECT endpoint, method, status_code, response_time_ms, sample_rate, created_at
+                    FROM server_metrics
+                    WHERE created_at >= $1 AND created_at < $2
+                    ORDER BY created_at
+                    "#,
+                    from,
+                    until
+                )
+                .fetch(&pool);
+                write_rows(rows, query.format, &sender).await
+            }
+        };
+
+        // the status line is long gone by now, cutting the body short is all that's left
+        if let Err(e) = result {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                dataset = query.dataset.as_str(),
+                "Metrics export failed"
+            );
+            let _ = sender.send(Err(e)).await;
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+async fn write_rows<T: serde::Serialize>(
+    mut rows: impl Stream<Item = Result<T, sqlx::Error>> + Unpin,
+    format: ExportFormat,
+    sender: &mpsc::Sender<Result<Bytes, anyhow::Error>>,
+) -> Result<(), anyhow::Error> {
+    let mut buffer = Vec::with_capacity(EXPORT_CHUNK_BYTES);
+    let mut first = true;
+
+    while let Some(row) = rows.next().await {
+        let row = row?;
+        match format {
+            ExportFormat::Csv => {
+                // the header row comes from the field names of the first record
+                let mut csv = csv::WriterBuilder::new()
+                    .has_headers(first)
+                    .from_writer(&mut buffer);
+                csv.serialize(&row)?;
+                csv.flush()?;
+            }
+            ExportFormat::Json => {
+                serde_json::to_writer(&mut buffer, &row)?;
+                buffer.push(b'\n');
+            }
+        }
+        first = false;
+
+        if buffer.len() >= EXPORT_CHUNK_BYTES
+            && sender
+                .send(Ok(Bytes::from(std::mem::take(&mut buffer))))
+                .await
+                .is_err()
+        {
+            // client went away
+            return Ok(());
+        }
+    }
+
+    if !buffer.is_empty() {
+        let _ = sender.send(Ok(Bytes::from(buffer))).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn query(from: &str, to: &str) -> ExportQuery {
+        ExportQuery {
+            dataset: ExportDataset::Visits,
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+            format: ExportFormat::Csv,
+        }
+    }
+
+    #[test]
+    fn export_ranges_are_validated() {
+        assert!(query("2026-04-01", "2026-04-01").validate().is_ok());
+        assert!(query("2026-01-01", "2026-12-31").validate().is_ok());
+        assert!(query("2026-04-02", "2026-04-01").validate().is_err());
+        assert!(query("2025-01-01", "2026-04-01").validate().is_err());
+    }
+
+    #[test]
+    fn bounds_cover_whole_utc_days() {
+        let (from, until) = query("2026-04-01", "2026-04-02").bounds();
+        assert_eq!(from.to_rfc3339(), "2026-04-01T00:00:00+00:00");
+        assert_eq!(until.to_rfc3339(), "2026-04-03T00:00:00+00:00");
+    }
+}
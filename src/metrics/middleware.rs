@@ -0,0 +1,78 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web,
+};
+use std::time::Instant;
+
+use crate::metrics::{AppMetrics, ServerMetric, ServerMetricsRecorder};
+use crate::telemetry::QuietRoutes;
+
+// label requests by route pattern rather than raw path, so ids in the url
+// don't blow up the series count
+const UNMATCHED_PATH: &str = "unmatched";
+
+#[allow(clippy::future_not_send)]
+pub async fn track_request_metrics(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(metrics) = request.app_data::<web::Data<AppMetrics>>().cloned() else {
+        return next.call(request).await;
+    };
+
+    let quiet = request
+        .app_data::<web::Data<QuietRoutes>>()
+        .and_then(|quiet| quiet.matching(&request).map(str::to_owned));
+    if let Some(route) = quiet {
+        let result = next.call(request).await;
+        let status = match &result {
+            Ok(response) => response.status(),
+            Err(e) => e.as_response_error().status_code(),
+        };
+        metrics
+            .http_quiet_requests_total
+            .with_label_values(&[route.as_str(), status.as_str()])
+            .inc();
+        return result;
+    }
+
+    let recorder = request
+        .app_data::<web::Data<ServerMetricsRecorder>>()
+        .cloned();
+    let method = request.method().to_string();
+    let raw_path = request.path().to_string();
+    let path = request
+        .match_pattern()
+        .unwrap_or_else(|| UNMATCHED_PATH.to_string());
+    let started = Instant::now();
+
+    let result = next.call(request).await;
+
+    // errors raised by middleware never become a ServiceResponse here
+    let status = match &result {
+        Ok(response) => response.status(),
+        Err(e) => e.as_response_error().status_code(),
+    };
+    metrics
+        .http_requests_total
+        .with_label_values(&[method.as_str(), path.as_str(), status.as_str()])
+        .inc();
+    metrics
+        .http_request_duration_seconds
+        .with_label_values(&[method.as_str(), path.as_str()])
+        .observe(started.elapsed().as_secs_f64());
+
+    if let Some(recorder) = recorder {
+        recorder.record(ServerMetric {
+            endpoint: path,
+            path: raw_path,
+            method,
+            status_code: status.as_u16(),
+            response_time_ms: started.elapsed().as_secs_f64() * 1000.0,
+        });
+    }
+
+    result
+}
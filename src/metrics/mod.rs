@@ -0,0 +1,48 @@
+mod active_sessions;
+mod alerts;
+mod app_metrics;
+mod batch_writer;
+mod cleanup;
+mod digitalocean;
+mod export;
+mod middleware;
+mod models;
+mod page_visits;
+mod pool_sampler;
+mod realtime;
+mod repository;
+mod rollup;
+mod sampling;
+mod server_metrics;
+mod signature;
+mod vitals_cache;
+
+pub use active_sessions::{SessionCounts, count_sessions, spawn_active_sessions_sampler};
+pub use alerts::{
+    Alert, AlertCooldowns, AlertKind, AlertWindowStats, evaluate_alerts, spawn_alert_evaluator,
+};
+pub use app_metrics::{AppMetrics, DB_SLOW_STATEMENTS_TOTAL, METRICS_ROWS_DROPPED_TOTAL};
+pub use cleanup::run_metrics_cleanup;
+pub use digitalocean::{BandwidthSnapshot, DigitalOceanBandwidth, spawn_bandwidth_poller};
+pub use export::{ExportDataset, ExportFormat, ExportQuery, stream_export};
+pub use middleware::track_request_metrics;
+pub use models::{
+    BatchItemResult, CampaignVisits, CurrentPageView, EndpointErrors, MetricsSummary,
+    MetricsWindow, MetricsWindowQuery, PageVisitBatchRequest, PageVisitRequest, PathVisits,
+    PerformanceMetricRequest, RealtimeSnapshot, RecentError, SessionStats, SessionSummary,
+    SlowRequest, VitalPercentiles, WebVital,
+};
+pub use page_visits::{PageVisitRecorder, spawn_page_visit_writer};
+pub use pool_sampler::spawn_pool_sampler;
+pub use realtime::{RealtimeStats, RealtimeStatsFeed, spawn_realtime_sampler};
+pub use repository::{
+    CleanupReport, active_visitors, campaign_breakdown, cleanup_old_metrics, current_page_views,
+    days_pending_rollup, error_breakdown, hash_session_id, insert_performance_metric,
+    metrics_summary, recent_errors, recent_sessions, rollup_daily_metrics, session_stats,
+    slow_requests, vital_percentiles,
+};
+pub use rollup::run_pending_rollups;
+pub use sampling::{effective_rate, keep_event, keep_session};
+pub use server_metrics::{ServerMetric, ServerMetricsRecorder, spawn_server_metrics_writer};
+pub use signature::{INGEST_SIGNATURE_HEADER, require_ingest_signature};
+pub use vitals_cache::VitalsCache;
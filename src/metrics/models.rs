@@ -0,0 +1,369 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::errors::MetricsIngestError;
+
+const MAX_PATH_LENGTH: usize = 2048;
+// a single page view longer than a day is a stuck tab, not a reader
+const MAX_DURATION_MS: i32 = 86_400_000;
+// generous upper bound for any timing vital, in milliseconds
+const MAX_VITAL_VALUE: f64 = 600_000.0;
+const MAX_UTM_LENGTH: usize = 200;
+// a year of hourly buckets is as far back as any report looks
+const MAX_WINDOW_HOURS: i32 = 365 * 24;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct PageVisitRequest {
+    pub path: String,
+    pub referrer: Option<String>,
+    // random per-tab id generated by the frontend, hashed before it's stored
+    pub session_id: Option<Uuid>,
+    pub duration_ms: Option<i32>,
+    // copied from the landing url by the frontend, stored lowercased
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+}
+
+// visits the frontend queued up and flushed together
+#[derive(serde::Deserialize, Debug)]
+pub struct PageVisitBatchRequest {
+    pub visits: Vec<PageVisitRequest>,
+}
+
+// outcome for one visit in a batch, `index` points back into the request
+#[derive(serde::Serialize, Debug)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct PerformanceMetricRequest {
+    pub path: String,
+    pub metric_name: WebVital,
+    pub value: f64,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WebVital {
+    Lcp,
+    Fcp,
+    Cls,
+    Inp,
+    Ttfb,
+}
+
+impl WebVital {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lcp => "LCP",
+            Self::Fcp => "FCP",
+            Self::Cls => "CLS",
+            Self::Inp => "INP",
+            Self::Ttfb => "TTFB",
+        }
+    }
+}
+
+impl PageVisitRequest {
+    /// # Errors
+    /// returns a `MetricsIngestError` describing the first field that failed validation
+    pub fn validate(&self) -> Result<(), MetricsIngestError> {
+        validate_path(&self.path)?;
+
+        if let Some(referrer) = &self.referrer
+            && (referrer.len() > MAX_PATH_LENGTH || referrer.chars().any(char::is_control))
+        {
+            return Err(MetricsIngestError::InvalidReferrer);
+        }
+
+        if let Some(duration_ms) = self.duration_ms
+            && !(0..=MAX_DURATION_MS).contains(&duration_ms)
+        {
+            return Err(MetricsIngestError::InvalidDuration);
+        }
+
+        let utm_ok = [&self.utm_source, &self.utm_medium, &self.utm_campaign]
+            .into_iter()
+            .flatten()
+            .all(|value| value.len() <= MAX_UTM_LENGTH && !value.chars().any(char::is_control));
+        if !utm_ok {
+            return Err(MetricsIngestError::InvalidUtm);
+        }
+
+        Ok(())
+    }
+}
+
+impl PerformanceMetricRequest {
+    /// # Errors
+    /// returns a `MetricsIngestError` describing the first field that failed validation
+    pub fn validate(&self) -> Result<(), MetricsIngestError> {
+        validate_path(&self.path)?;
+
+        if !self.value.is_finite() || !(0.0..=MAX_VITAL_VALUE).contains(&self.value) {
+            return Err(MetricsIngestError::InvalidValue);
+        }
+
+        Ok(())
+    }
+}
+
+// visits attributed to one utm source/medium/campaign combination
+#[derive(serde::Serialize, Debug)]
+pub struct CampaignVisits {
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub visits: i64,
+    pub sessions: i64,
+}
+
+// visits grouped by the frontend's session id, a bounce is a single-page session
+#[derive(serde::Serialize, Debug)]
+pub struct SessionStats {
+    pub sessions: i64,
+    pub avg_page_depth: f64,
+    pub avg_duration_ms: f64,
+    // fraction of sessions, 0.0 to 1.0
+    pub bounce_rate: f64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SessionSummary {
+    pub session_hash: String,
+    pub page_depth: i64,
+    pub total_duration_ms: i64,
+    pub entry_path: String,
+    pub started_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PathVisits {
+    pub path: String,
+    pub visits: i64,
+}
+
+// headline numbers for the dashboard's metrics page
+#[derive(serde::Serialize, Debug)]
+pub struct MetricsSummary {
+    pub window_hours: i32,
+    pub visits: i64,
+    pub top_paths: Vec<PathVisits>,
+    pub sessions: SessionStats,
+}
+
+// how many visitors are currently on a page, by their latest visit
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CurrentPageView {
+    pub path: String,
+    pub viewers: i64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct RecentError {
+    pub endpoint: String,
+    pub method: String,
+    pub status_code: i32,
+    pub response_time_ms: f64,
+    pub occurred_at: DateTime<Utc>,
+}
+
+// 4xx or 5xx responses from one route, `requests` is everything that route
+// served so the errors can be read as a rate
+#[derive(serde::Serialize, Debug)]
+pub struct EndpointErrors {
+    pub endpoint: String,
+    pub method: String,
+    // "4xx" or "5xx"
+    pub status_class: String,
+    pub status_codes: Vec<i32>,
+    pub errors: i64,
+    pub requests: i64,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+// a request that took at least `metrics.slow_request_threshold_ms`
+#[derive(serde::Serialize, Debug)]
+pub struct SlowRequest {
+    pub path: String,
+    pub method: String,
+    pub status_code: i32,
+    pub duration_ms: f64,
+    pub occurred_at: DateTime<Utc>,
+}
+
+// point-in-time view of who's on the site and what's failing
+#[derive(serde::Serialize, Debug)]
+pub struct RealtimeSnapshot {
+    pub active_visitors: i64,
+    pub current_pages: Vec<CurrentPageView>,
+    pub recent_errors: Vec<RecentError>,
+    pub generated_at: DateTime<Utc>,
+}
+
+// tail latency for one vital on one page
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct VitalPercentiles {
+    pub path: String,
+    pub metric_name: String,
+    pub samples: i64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+// lookback for the admin reports, written as `24h` or `7d` in query strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricsWindow {
+    hours: i32,
+}
+
+impl MetricsWindow {
+    #[must_use]
+    pub const fn hours(self) -> i32 {
+        self.hours
+    }
+}
+
+impl Default for MetricsWindow {
+    fn default() -> Self {
+        Self { hours: 30 * 24 }
+    }
+}
+
+impl std::str::FromStr for MetricsWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid window `{s}`, expected e.g. `24h` or `7d`");
+
+        let (amount, hours_per_unit) = match s.strip_suffix('h') {
+            Some(amount) => (amount, 1),
+            None => (s.strip_suffix('d').ok_or_else(invalid)?, 24),
+        };
+        let hours = amount
+            .parse::<i32>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(hours_per_unit))
+            .filter(|hours| (1..=MAX_WINDOW_HOURS).contains(hours))
+            .ok_or_else(invalid)?;
+
+        Ok(Self { hours })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MetricsWindow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct MetricsWindowQuery {
+    #[serde(default)]
+    pub window: MetricsWindow,
+}
+
+// only site-relative paths, so full URLs (and whatever's in their query strings) stay out
+fn validate_path(path: &str) -> Result<(), MetricsIngestError> {
+    if !path.starts_with('/')
+        || path.len() > MAX_PATH_LENGTH
+        || path.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(MetricsIngestError::InvalidPath);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn visit(path: &str) -> PageVisitRequest {
+        PageVisitRequest {
+            path: path.to_string(),
+            referrer: None,
+            session_id: None,
+            duration_ms: None,
+            utm_source: None,
+            utm_medium: None,
+            utm_campaign: None,
+        }
+    }
+
+    #[test]
+    fn page_visit_validation_works() {
+        assert!(visit("/blog/hello-world").validate().is_ok());
+        assert!(matches!(
+            visit("https://example.com/").validate(),
+            Err(MetricsIngestError::InvalidPath)
+        ));
+        assert!(matches!(
+            visit("/has space").validate(),
+            Err(MetricsIngestError::InvalidPath)
+        ));
+        assert!(matches!(
+            visit(&format!("/{}", "a".repeat(MAX_PATH_LENGTH))).validate(),
+            Err(MetricsIngestError::InvalidPath)
+        ));
+
+        let negative_duration = PageVisitRequest {
+            duration_ms: Some(-1),
+            ..visit("/")
+        };
+        assert!(matches!(
+            negative_duration.validate(),
+            Err(MetricsIngestError::InvalidDuration)
+        ));
+
+        let long_campaign = PageVisitRequest {
+            utm_campaign: Some("a".repeat(MAX_UTM_LENGTH + 1)),
+            ..visit("/")
+        };
+        assert!(matches!(
+            long_campaign.validate(),
+            Err(MetricsIngestError::InvalidUtm)
+        ));
+    }
+
+    #[test]
+    fn metrics_windows_parse_hours_and_days() {
+        assert_eq!("24h".parse::<MetricsWindow>().unwrap().hours(), 24);
+        assert_eq!("7d".parse::<MetricsWindow>().unwrap().hours(), 168);
+        assert!("0h".parse::<MetricsWindow>().is_err());
+        assert!("366d".parse::<MetricsWindow>().is_err());
+        assert!("7w".parse::<MetricsWindow>().is_err());
+        assert!("d".parse::<MetricsWindow>().is_err());
+    }
+
+    #[test]
+    fn performance_metric_validation_works() {
+        let metric = |value| PerformanceMetricRequest {
+            path: "/".to_string(),
+            metric_name: WebVital::Lcp,
+            value,
+        };
+
+        assert!(metric(1250.5).validate().is_ok());
+        assert!(matches!(
+            metric(f64::NAN).validate(),
+            Err(MetricsIngestError::InvalidValue)
+        ));
+        assert!(matches!(
+            metric(-3.0).validate(),
+            Err(MetricsIngestError::InvalidValue)
+        ));
+    }
+}
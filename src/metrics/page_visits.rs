@@ -0,0 +1,141 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::metrics::batch_writer::BatchWriter;
+use crate::metrics::{PageVisitRequest, hash_session_id};
+use crate::startup::SessionHashKey;
+
+// a visit as it will be stored, the session id is already hashed
+struct QueuedVisit {
+    path: String,
+    referrer: Option<String>,
+    session_hash: Option<String>,
+    duration_ms: Option<i32>,
+    user_agent: Option<String>,
+    utm_source: Option<String>,
+    utm_medium: Option<String>,
+    utm_campaign: Option<String>,
+    sample_rate: f64,
+}
+
+// handle the ingest handlers record into, rows are written by a background task
+// so a visit never waits on the insert
+#[derive(Clone)]
+pub struct PageVisitRecorder {
+    writer: BatchWriter<QueuedVisit>,
+    hash_key: SessionHashKey,
+}
+
+impl PageVisitRecorder {
+    // `visit` is expected to be validated and to have made the sample already
+    pub fn record(&self, visit: &PageVisitRequest, user_agent: Option<&str>, sample_rate: f64) {
+        let queued = QueuedVisit {
+            path: visit.path.clone(),
+            referrer: visit.referrer.clone(),
+            session_hash: visit
+                .session_id
+                .as_ref()
+                .map(|session_id| hash_session_id(&self.hash_key, session_id)),
+            duration_ms: visit.duration_ms,
+            user_agent: user_agent.map(ToString::to_string),
+            utm_source: visit.utm_source.clone(),
+            utm_medium: visit.utm_medium.clone(),
+            utm_campaign: visit.utm_campaign.clone(),
+            sample_rate,
+        };
+        self.writer.push(queued);
+    }
+
+    // waits until every visit recorded so far is written, or the writer has stopped
+    pub async fn flush(&self) {
+        self.writer.flush().await;
+    }
+}
+
+#[must_use]
+pub fn spawn_page_visit_writer(
+    pool: PgPool,
+    batch_size: usize,
+    flush_interval: Duration,
+    hash_key: SessionHashKey,
+) -> PageVisitRecorder {
+    let writer = BatchWriter::spawn(batch_size, flush_interval, "page_visits", move |batch| {
+        let pool = pool.clone();
+        async move { flush(&pool, &batch).await }
+    });
+
+    PageVisitRecorder { writer, hash_key }
+}
+
+async fn flush(pool: &PgPool, batch: &[QueuedVisit]) {
+    if let Err(e) = insert_queued_visits(batch, pool).await {
+        tracing::warn!(
+            error.cause_chain = ?e,
+            rows = batch.len(),
+            "Failed to write page visits"
+        );
+    }
+}
+
+// one statement for the whole batch, utm tags are stored trimmed and lowercased
+async fn insert_queued_visits(batch: &[QueuedVisit], pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut visit_ids = Vec::with_capacity(batch.len());
+    let mut paths = Vec::with_capacity(batch.len());
+    let mut referrers = Vec::with_capacity(batch.len());
+    let mut session_hashes = Vec::with_capacity(batch.len());
+    let mut durations = Vec::with_capacity(batch.len());
+    let mut user_agents = Vec::with_capacity(batch.len());
+    let mut utm_sources = Vec::with_capacity(batch.len());
+    let mut utm_mediums = Vec::with_capacity(batch.len());
+    let mut utm_campaigns = Vec::with_capacity(batch.len());
+    let mut sample_rates = Vec::with_capacity(batch.len());
+    for visit in batch {
+        visit_ids.push(Uuid::new_v4());
+        paths.push(visit.path.clone());
+        referrers.push(visit.referrer.clone());
+        session_hashes.push(visit.session_hash.clone());
+        durations.push(visit.duration_ms);
+        user_agents.push(visit.user_agent.clone());
+        utm_sources.push(visit.utm_source.clone());
+        utm_mediums.push(visit.utm_medium.clone());
+        utm_campaigns.push(visit.utm_campaign.clone());
+        sample_rates.push(visit.sample_rate);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO page_visits (
+            visit_id, path, referrer, session_hash, duration_ms, user_agent,
+            utm_source, utm_medium, utm_campaign, sample_rate
+        )
+        SELECT
+            visit_id, path, referrer, session_hash, duration_ms, user_agent,
+            LOWER(NULLIF(TRIM(utm_source), '')),
+            LOWER(NULLIF(TRIM(utm_medium), '')),
+            LOWER(NULLIF(TRIM(utm_campaign), '')),
+            sample_rate
+        FROM UNNEST(
+            $1::UUID[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::INT[], $6::TEXT[],
+            $7::TEXT[], $8::TEXT[], $9::TEXT[], $10::DOUBLE PRECISION[]
+        ) AS v(
+            visit_id, path, referrer, session_hash, duration_ms, user_agent,
+            utm_source, utm_medium, utm_campaign, sample_rate
+        )
+        "#,
+        &visit_ids,
+        &paths,
+        &referrers as &[Option<String>],
+        &session_hashes as &[Option<String>],
+        &durations as &[Option<i32>],
+        &user_agents as &[Option<String>],
+        &utm_sources as &[Option<String>],
+        &utm_mediums as &[Option<String>],
+        &utm_campaigns as &[Option<String>],
+        &sample_rates
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
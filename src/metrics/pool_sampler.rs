@@ -0,0 +1,38 @@
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::metrics::AppMetrics;
+use crate::redis_pool::RedisPool;
+
+// the pools only know their current state, so the gauges are refreshed on a timer
+pub fn spawn_pool_sampler(pool: PgPool, redis: RedisPool, metrics: AppMetrics, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+            record_pool_state(&pool, &metrics);
+            record_redis_pool_state(&redis, &metrics);
+        }
+    });
+}
+
+fn record_pool_state(pool: &PgPool, metrics: &AppMetrics) {
+    let size = i64::from(pool.size());
+    let idle = i64::try_from(pool.num_idle()).unwrap_or(i64::MAX);
+
+    metrics.db_connections_active.set((size - idle).max(0));
+    metrics.db_connections_idle.set(idle);
+}
+
+fn record_redis_pool_state(pool: &RedisPool, metrics: &AppMetrics) {
+    let status = pool.status();
+    let size = i64::try_from(status.size).unwrap_or(i64::MAX);
+    let idle = i64::try_from(status.available).unwrap_or(i64::MAX);
+
+    metrics.redis_connections_active.set((size - idle).max(0));
+    metrics.redis_connections_idle.set(idle);
+    metrics
+        .redis_connections_waiting
+        .set(i64::try_from(status.waiting).unwrap_or(i64::MAX));
+}
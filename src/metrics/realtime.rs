@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::events::{DashboardEvent, EventBus};
+use crate::metrics::{AppMetrics, CurrentPageView, current_page_views};
+
+// snapshot pushed to the dashboard over SSE
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RealtimeStats {
+    // distinct users with a live login session, as last counted by the sessions sampler
+    pub active_users: i64,
+    // what recent visitors are looking at right now
+    pub current_page_views: Vec<CurrentPageView>,
+    // requests handled since the previous sample
+    pub recent_requests: u64,
+    // 5xx responses since the previous sample
+    pub recent_errors: u64,
+    pub sampled_at: DateTime<Utc>,
+}
+
+// every dashboard subscribes to the same sampler, so the database is hit once
+// per interval no matter how many streams are open
+// `None` until the first sample lands
+#[derive(Clone)]
+pub struct RealtimeStatsFeed(watch::Receiver<Option<RealtimeStats>>);
+
+impl RealtimeStatsFeed {
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<Option<RealtimeStats>> {
+        self.0.clone()
+    }
+}
+
+#[must_use]
+pub fn spawn_realtime_sampler(
+    pool: PgPool,
+    metrics: AppMetrics,
+    interval: Duration,
+    events: EventBus,
+    error_spike_threshold: u64,
+) -> RealtimeStatsFeed {
+    let (sender, receiver) = watch::channel::<Option<RealtimeStats>>(None);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let (mut last_total, mut last_errors) = metrics.request_totals();
+
+        loop {
+            ticker.tick().await;
+
+            let current_page_views = match current_page_views(&pool).await {
+                Ok(views) => views,
+                Err(e) => {
+                    tracing::warn!(error.cause_chain = ?e, "Failed to read current page views");
+                    sender
+                        .borrow()
+                        .as_ref()
+                        .map(|stats| stats.current_page_views.clone())
+                        .unwrap_or_default()
+                }
+            };
+            let (total, errors) = metrics.request_totals();
+
+            let stats = RealtimeStats {
+                active_users: metrics.active_users.get(),
+                current_page_views,
+                recent_requests: total.saturating_sub(last_total),
+                recent_errors: errors.saturating_sub(last_errors),
+                sampled_at: Utc::now(),
+            };
+            (last_total, last_errors) = (total, errors);
+
+            if stats.recent_errors >= error_spike_threshold {
+                events.publish(DashboardEvent::ErrorSpike {
+                    errors: stats.recent_errors,
+                    requests: stats.recent_requests,
+                    sampled_at: stats.sampled_at,
+                });
+            }
+
+            // the feed keeps a receiver alive, so this only fails on shutdown
+            if sender.send(Some(stats)).is_err() {
+                break;
+            }
+        }
+    });
+
+    RealtimeStatsFeed(receiver)
+}
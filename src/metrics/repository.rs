@@ -0,0 +1,565 @@
+use chrono::NaiveDate;
+use siphasher::sip128::{Hasher128, SipHasher13};
+use sqlx::PgPool;
+use std::hash::Hasher;
+use uuid::Uuid;
+
+use crate::metrics::{
+    CampaignVisits, CurrentPageView, EndpointErrors, MetricsSummary, MetricsWindow, PathVisits,
+    PerformanceMetricRequest, RecentError, SessionStats, SessionSummary, SlowRequest,
+    VitalPercentiles,
+};
+use crate::startup::SessionHashKey;
+
+// a visitor counts as on the site for this long after their last page view
+const ACTIVE_VISITOR_MINUTES: i32 = 5;
+const RECENT_ERROR_LIMIT: i64 = 50;
+const SLOW_REQUEST_LIMIT: i64 = 200;
+
+// reports read raw rows for this many recent hours and daily rollups before that
+const RAW_METRICS_HOURS: i32 = 48;
+
+// visits only need to be grouped per session, never traced back to one
+// keyed, so the hashes can't be matched against guessed ids without the server's key
+#[must_use]
+pub fn hash_session_id(key: &SessionHashKey, session_id: &Uuid) -> String {
+    let mut hasher = SipHasher13::new_with_key(&key.0);
+    hasher.write(session_id.as_bytes());
+    hex::encode(hasher.finish128().as_bytes())
+}
+
+#[tracing::instrument(name = "Insert performance metric", skip_all, fields(path = %metric.path))]
+/// # Errors
+/// returns a `sqlx` error if the row can't be inserted
+pub async fn insert_performance_metric(
+    metric: &PerformanceMetricRequest,
+    sample_rate: f64,
+    pool: &PgPool,
+) -> Result<Uuid, sqlx::Error> {
+    let metric_id = Uuid::new_v4();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO performance_metrics (metric_id, path, metric_name, value, sample_rate)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        metric_id,
+        metric.path,
+        metric.metric_name.as_str(),
+        metric.value,
+        sample_rate
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(metric_id)
+}
+
+#[tracing::instrument(name = "Campaign breakdown", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn campaign_breakdown(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<Vec<CampaignVisits>, sqlx::Error> {
+    sqlx::query_as!(
+        CampaignVisits,
+        r#"
+        SELECT
+            utm_source,
+            utm_medium,
+            utm_campaign,
+            ROUND(SUM(1 / sample_rate))::BIGINT AS "visits!",
+            ROUND(COUNT(DISTINCT session_hash) * AVG(1 / sample_rate))::BIGINT AS "sessions!"
+        FROM page_visits
+        WHERE created_at > NOW() - make_interval(hours => $1)
+            AND (utm_source IS NOT NULL OR utm_medium IS NOT NULL OR utm_campaign IS NOT NULL)
+        GROUP BY utm_source, utm_medium, utm_campaign
+        ORDER BY SUM(1 / sample_rate) DESC
+        LIMIT 100
+        "#,
+        window.hours()
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Web vital percentiles", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn vital_percentiles(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<Vec<VitalPercentiles>, sqlx::Error> {
+    // days before the rollup cutoff come from performance_metric_daily, their
+    // percentiles are averaged weighted by samples so those are approximate
+    sqlx::query_as!(
+        VitalPercentiles,
+        r#"
+        WITH bounds AS (
+            SELECT
+                NOW() - make_interval(hours => $1) AS since,
+                ((NOW() - make_interval(hours => $2)) AT TIME ZONE 'UTC')::DATE AS cutoff_day
+        ),
+        combined AS (
+            SELECT d.path, d.metric_name, d.samples, d.p50, d.p75, d.p95
+            FROM performance_metric_daily d, bounds b
+            WHERE d.day >= (b.since AT TIME ZONE 'UTC')::DATE AND d.day < b.cutoff_day
+            UNION ALL
+            SELECT
+                m.path,
+                m.metric_name,
+                COUNT(*),
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY m.value),
+                percentile_cont(0.75) WITHIN GROUP (ORDER BY m.value),
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY m.value)
+            FROM performance_metrics m, bounds b
+            WHERE m.created_at > b.since
+                AND m.created_at >= b.cutoff_day::TIMESTAMP AT TIME ZONE 'UTC'
+            GROUP BY m.path, m.metric_name
+        )
+        SELECT
+            path AS "path!",
+            metric_name AS "metric_name!",
+            SUM(samples)::BIGINT AS "samples!",
+            SUM(p50 * samples) / SUM(samples) AS "p50!",
+            SUM(p75 * samples) / SUM(samples) AS "p75!",
+            SUM(p95 * samples) / SUM(samples) AS "p95!"
+        FROM combined
+        GROUP BY path, metric_name
+        ORDER BY path, metric_name
+        "#,
+        window.hours(),
+        RAW_METRICS_HOURS
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Session stats", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn session_stats(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<SessionStats, sqlx::Error> {
+    // visits without a session id can't be grouped and are left out
+    // sessions are sampled whole, so each one is weighted by its visits' rate
+    sqlx::query_as!(
+        SessionStats,
+        r#"
+        WITH sessions AS (
+            SELECT
+                COUNT(*) AS page_depth,
+                COALESCE(SUM(duration_ms), 0) AS duration_ms,
+                MAX(1 / sample_rate) AS weight
+            FROM page_visits
+            WHERE created_at > NOW() - make_interval(hours => $1)
+                AND session_hash IS NOT NULL
+            GROUP BY session_hash
+        )
+        SELECT
+            COALESCE(ROUND(SUM(weight)), 0)::BIGINT AS "sessions!",
+            COALESCE(SUM(page_depth * weight) / SUM(weight), 0) AS "avg_page_depth!",
+            COALESCE(SUM(duration_ms * weight) / SUM(weight), 0) AS "avg_duration_ms!",
+            COALESCE(SUM((page_depth = 1)::INT * weight) / SUM(weight), 0) AS "bounce_rate!"
+        FROM sessions
+        "#,
+        window.hours()
+    )
+    .fetch_one(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Recent sessions", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn recent_sessions(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<Vec<SessionSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        SessionSummary,
+        r#"
+        SELECT
+            session_hash AS "session_hash!",
+            COUNT(*) AS "page_depth!",
+            COALESCE(SUM(duration_ms), 0)::BIGINT AS "total_duration_ms!",
+            (ARRAY_AGG(path ORDER BY created_at))[1] AS "entry_path!",
+            MIN(created_at) AS "started_at!",
+            MAX(created_at) AS "last_seen_at!"
+        FROM page_visits
+        WHERE created_at > NOW() - make_interval(hours => $1)
+            AND session_hash IS NOT NULL
+        GROUP BY session_hash
+        ORDER BY MAX(created_at) DESC
+        LIMIT 100
+        "#,
+        window.hours()
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Active visitors", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn active_visitors(pool: &PgPool) -> Result<i64, sqlx::Error> {
+    // visits without a session id each count as their own visitor
+    sqlx::query_scalar!(
+        r#"
+        SELECT
+            COALESCE(
+                ROUND(COUNT(DISTINCT COALESCE(session_hash, visit_id::TEXT)) * AVG(1 / sample_rate)),
+                0
+            )::BIGINT AS "count!"
+        FROM page_visits
+        WHERE created_at > NOW() - make_interval(mins => $1)
+        "#,
+        ACTIVE_VISITOR_MINUTES
+    )
+    .fetch_one(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Current page views", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn current_page_views(pool: &PgPool) -> Result<Vec<CurrentPageView>, sqlx::Error> {
+    sqlx::query_as!(
+        CurrentPageView,
+        r#"
+        WITH latest AS (
+            SELECT DISTINCT ON (COALESCE(session_hash, visit_id::TEXT)) path, sample_rate
+            FROM page_visits
+            WHERE created_at > NOW() - make_interval(mins => $1)
+            ORDER BY COALESCE(session_hash, visit_id::TEXT), created_at DESC
+        )
+        SELECT path, ROUND(SUM(1 / sample_rate))::BIGINT AS "viewers!"
+        FROM latest
+        GROUP BY path
+        ORDER BY SUM(1 / sample_rate) DESC, path
+        "#,
+        ACTIVE_VISITOR_MINUTES
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Recent errors", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn recent_errors(pool: &PgPool) -> Result<Vec<RecentError>, sqlx::Error> {
+    sqlx::query_as!(
+        RecentError,
+        r#"
+        SELECT endpoint, method, status_code, response_time_ms, created_at AS occurred_at
+        FROM server_metrics
+        WHERE status_code >= 400
+        ORDER BY created_at DESC
+        LIMIT $1
+        "#,
+        RECENT_ERROR_LIMIT
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Error breakdown", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn error_breakdown(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<Vec<EndpointErrors>, sqlx::Error> {
+    sqlx::query_as!(
+        EndpointErrors,
+        r#"
+        WITH totals AS (
+            SELECT endpoint, method, SUM(1 / sample_rate) AS requests
+            FROM server_metrics
+            WHERE created_at > NOW() - make_interval(hours => $1)
+            GROUP BY endpoint, method
+        )
+        SELECT
+            e.endpoint,
+            e.method,
+            (e.status_code / 100)::TEXT || 'xx' AS "status_class!",
+            ARRAY_AGG(DISTINCT e.status_code ORDER BY e.status_code) AS "status_codes!",
+            ROUND(SUM(1 / e.sample_rate))::BIGINT AS "errors!",
+            ROUND(MAX(t.requests))::BIGINT AS "requests!",
+            MAX(e.created_at) AS "last_seen_at!"
+        FROM server_metrics e
+        JOIN totals t USING (endpoint, method)
+        WHERE e.created_at > NOW() - make_interval(hours => $1)
+            AND e.status_code >= 400
+        GROUP BY e.endpoint, e.method, e.status_code / 100
+        ORDER BY SUM(1 / e.sample_rate) DESC, e.endpoint, e.method
+        LIMIT 100
+        "#,
+        window.hours()
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Slow requests", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn slow_requests(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<Vec<SlowRequest>, sqlx::Error> {
+    sqlx::query_as!(
+        SlowRequest,
+        r#"
+        SELECT path, method, status_code, duration_ms, created_at AS occurred_at
+        FROM slow_requests
+        WHERE created_at > NOW() - make_interval(hours => $1)
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+        window.hours(),
+        SLOW_REQUEST_LIMIT
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Metrics summary", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if any of the queries fail
+pub async fn metrics_summary(
+    window: MetricsWindow,
+    pool: &PgPool,
+) -> Result<MetricsSummary, sqlx::Error> {
+    // whole days before the rollup cutoff are read from page_visit_daily, so the
+    // start of a long window is only accurate to the day
+    let rows = sqlx::query!(
+        r#"
+        WITH bounds AS (
+            SELECT
+                NOW() - make_interval(hours => $1) AS since,
+                ((NOW() - make_interval(hours => $2)) AT TIME ZONE 'UTC')::DATE AS cutoff_day
+        ),
+        combined AS (
+            SELECT d.path, d.visits::DOUBLE PRECISION AS visits
+            FROM page_visit_daily d, bounds b
+            WHERE d.day >= (b.since AT TIME ZONE 'UTC')::DATE AND d.day < b.cutoff_day
+            UNION ALL
+            SELECT v.path, SUM(1 / v.sample_rate)
+            FROM page_visits v, bounds b
+            WHERE v.created_at > b.since
+                AND v.created_at >= b.cutoff_day::TIMESTAMP AT TIME ZONE 'UTC'
+            GROUP BY v.path
+        )
+        SELECT
+            path AS "path!",
+            ROUND(SUM(visits))::BIGINT AS "visits!",
+            ROUND(SUM(SUM(visits)) OVER ())::BIGINT AS "total_visits!"
+        FROM combined
+        GROUP BY path
+        ORDER BY SUM(visits) DESC, path
+        LIMIT 10
+        "#,
+        window.hours(),
+        RAW_METRICS_HOURS
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(MetricsSummary {
+        window_hours: window.hours(),
+        visits: rows.first().map_or(0, |row| row.total_visits),
+        top_paths: rows
+            .into_iter()
+            .map(|row| PathVisits {
+                path: row.path,
+                visits: row.visits,
+            })
+            .collect(),
+        // distinct sessions can't be summed across days, these always come from raw visits
+        sessions: session_stats(window, pool).await?,
+    })
+}
+
+#[tracing::instrument(name = "Days pending rollup", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the query fails
+pub async fn days_pending_rollup(pool: &PgPool) -> Result<Vec<NaiveDate>, sqlx::Error> {
+    // every finished UTC day with raw data that hasn't been rolled up yet
+    sqlx::query_scalar!(
+        r#"
+        SELECT series.day::DATE AS "day!"
+        FROM generate_series(
+            (
+                SELECT MIN((created_at AT TIME ZONE 'UTC')::DATE)
+                FROM (
+                    SELECT MIN(created_at) AS created_at FROM page_visits
+                    UNION ALL
+                    SELECT MIN(created_at) FROM performance_metrics
+                ) earliest
+            ),
+            (NOW() AT TIME ZONE 'UTC')::DATE - 1,
+            INTERVAL '1 day'
+        ) AS series(day)
+        WHERE NOT EXISTS (
+            SELECT 1 FROM metrics_rollup_days r WHERE r.day = series.day::DATE
+        )
+        ORDER BY 1
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(name = "Roll up daily metrics", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if the rollup fails, nothing for that day is kept in that case
+pub async fn rollup_daily_metrics(day: NaiveDate, pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO page_visit_daily (day, path, visits, sessions)
+        SELECT
+            $1,
+            path,
+            ROUND(SUM(1 / sample_rate))::BIGINT,
+            ROUND(COUNT(DISTINCT session_hash) * AVG(1 / sample_rate))::BIGINT
+        FROM page_visits
+        WHERE created_at >= $1::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+            AND created_at < ($1::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+        GROUP BY path
+        ON CONFLICT (day, path) DO UPDATE
+        SET visits = EXCLUDED.visits,
+            sessions = EXCLUDED.sessions
+        "#,
+        day
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO performance_metric_daily (day, path, metric_name, samples, p50, p75, p95)
+        SELECT
+            $1,
+            path,
+            metric_name,
+            COUNT(*),
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY value),
+            percentile_cont(0.75) WITHIN GROUP (ORDER BY value),
+            percentile_cont(0.95) WITHIN GROUP (ORDER BY value)
+        FROM performance_metrics
+        WHERE created_at >= $1::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+            AND created_at < ($1::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+        GROUP BY path, metric_name
+        ON CONFLICT (day, path, metric_name) DO UPDATE
+        SET samples = EXCLUDED.samples,
+            p50 = EXCLUDED.p50,
+            p75 = EXCLUDED.p75,
+            p95 = EXCLUDED.p95
+        "#,
+        day
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO metrics_rollup_days (day)
+        VALUES ($1)
+        ON CONFLICT (day) DO UPDATE SET completed_at = NOW()
+        "#,
+        day
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await
+}
+
+// rows removed by a single cleanup pass
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    pub page_visits: u64,
+    pub performance_metrics: u64,
+    pub server_metrics: u64,
+    pub slow_requests: u64,
+}
+
+#[tracing::instrument(name = "Clean up old metrics", skip(pool))]
+/// # Errors
+/// returns a `sqlx` error if any of the deletes fail, earlier deletes stay committed
+pub async fn cleanup_old_metrics(
+    retention_days: u32,
+    pool: &PgPool,
+) -> Result<CleanupReport, sqlx::Error> {
+    let retention_days = i32::try_from(retention_days).unwrap_or(i32::MAX);
+
+    let page_visits = sqlx::query!(
+        "DELETE FROM page_visits WHERE created_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    let performance_metrics = sqlx::query!(
+        "DELETE FROM performance_metrics WHERE created_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    let server_metrics = sqlx::query!(
+        "DELETE FROM server_metrics WHERE created_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    let slow_requests = sqlx::query!(
+        "DELETE FROM slow_requests WHERE created_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(CleanupReport {
+        page_visits,
+        performance_metrics,
+        server_metrics,
+        slow_requests,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn session_hashes_are_stable_and_opaque() {
+        let key = SessionHashKey(*b"0123456789abcdef");
+        let session_id = Uuid::new_v4();
+
+        let hash = hash_session_id(&key, &session_id);
+        assert_eq!(hash, hash_session_id(&key, &session_id));
+        assert_ne!(hash, hash_session_id(&key, &Uuid::new_v4()));
+        assert!(!hash.contains(&session_id.simple().to_string()));
+    }
+
+    #[test]
+    fn session_hashes_depend_on_the_key() {
+        let session_id = Uuid::new_v4();
+
+        assert_ne!(
+            hash_session_id(&SessionHashKey(*b"0123456789abcdef"), &session_id),
+            hash_session_id(&SessionHashKey(*b"fedcba9876543210"), &session_id)
+        );
+    }
+}
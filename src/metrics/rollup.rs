@@ -0,0 +1,18 @@
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::metrics::{days_pending_rollup, rollup_daily_metrics};
+
+// rolls finished days into the daily tables, run by the scheduler as `metrics_rollup`
+// catches up on any days missed while the server was down
+/// # Errors
+/// returns an error on the first day that fails, it and later days are retried next run
+pub async fn run_pending_rollups(pool: &PgPool) -> Result<(), anyhow::Error> {
+    for day in days_pending_rollup(pool).await? {
+        rollup_daily_metrics(day, pool)
+            .await
+            .with_context(|| format!("Failed to roll up metrics for {day}"))?;
+        tracing::info!(%day, "Metrics rolled up");
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use siphasher::sip::SipHasher13;
+use std::hash::Hasher;
+use uuid::Uuid;
+
+use crate::startup::SessionHashKey;
+
+// a zero rate would make every stored row weigh infinitely much
+const MIN_SAMPLE_RATE: f64 = 0.001;
+
+// the rate actually applied for a configured one
+#[must_use]
+pub fn effective_rate(configured: f64) -> f64 {
+    if configured.is_nan() {
+        return 1.0;
+    }
+    configured.clamp(MIN_SAMPLE_RATE, 1.0)
+}
+
+#[must_use]
+pub fn keep_event(rate: f64) -> bool {
+    rate >= 1.0 || rand::random::<f64>() < rate
+}
+
+// the same session always gets the same answer, so page depth and bounce
+// numbers are computed over whole sessions
+#[must_use]
+pub fn keep_session(key: &SessionHashKey, session_id: &Uuid, rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    let mut hasher = SipHasher13::new_with_key(&key.0);
+    hasher.write(session_id.as_bytes());
+    #[allow(clippy::cast_precision_loss)]
+    let position = hasher.finish() as f64 / u64::MAX as f64;
+    position < rate
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rates_are_clamped() {
+        assert!((effective_rate(0.0) - MIN_SAMPLE_RATE).abs() < f64::EPSILON);
+        assert!((effective_rate(2.0) - 1.0).abs() < f64::EPSILON);
+        assert!((effective_rate(f64::NAN) - 1.0).abs() < f64::EPSILON);
+        assert!((effective_rate(0.25) - 0.25).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn session_sampling_is_consistent_and_roughly_proportional() {
+        let key = SessionHashKey(*b"0123456789abcdef");
+        let sessions: Vec<Uuid> = (0..10_000).map(|_| Uuid::new_v4()).collect();
+
+        let kept = sessions
+            .iter()
+            .filter(|session_id| keep_session(&key, session_id, 0.1))
+            .count();
+        assert!((800..1200).contains(&kept), "kept {kept} of 10000");
+
+        for session_id in &sessions[..100] {
+            assert_eq!(
+                keep_session(&key, session_id, 0.1),
+                keep_session(&key, session_id, 0.1)
+            );
+        }
+        assert!(
+            sessions
+                .iter()
+                .all(|session_id| keep_session(&key, session_id, 1.0))
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::configuration::SamplingSettings;
+use crate::metrics::batch_writer::BatchWriter;
+use crate::metrics::{effective_rate, keep_event};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerMetric {
+    // route pattern, what server_metrics groups by
+    pub endpoint: String,
+    // raw request path, only stored for slow requests
+    pub path: String,
+    pub method: String,
+    pub status_code: u16,
+    pub response_time_ms: f64,
+}
+
+// a request and where it ends up: server_metrics at `sample_rate` when it made
+// the sample, slow_requests when it crossed the threshold
+struct QueuedMetric {
+    metric: ServerMetric,
+    sample_rate: Option<f64>,
+    slow: bool,
+}
+
+// handle the middleware records into, rows are written by a background task
+// so requests never wait on the insert
+#[derive(Clone)]
+pub struct ServerMetricsRecorder {
+    writer: BatchWriter<QueuedMetric>,
+    success_rate: f64,
+    error_rate: f64,
+    slow_threshold_ms: Option<f64>,
+}
+
+impl ServerMetricsRecorder {
+    pub fn record(&self, metric: ServerMetric) {
+        let rate = if metric.status_code >= 400 {
+            self.error_rate
+        } else {
+            self.success_rate
+        };
+        let sample_rate = keep_event(rate).then_some(rate);
+        // slow requests are rare enough to keep whatever the sample rate
+        let slow = self
+            .slow_threshold_ms
+            .is_some_and(|threshold| metric.response_time_ms >= threshold);
+        if sample_rate.is_none() && !slow {
+            return;
+        }
+
+        let queued = QueuedMetric {
+            metric,
+            sample_rate,
+            slow,
+        };
+        self.writer.push(queued);
+    }
+}
+
+#[must_use]
+pub fn spawn_server_metrics_writer(
+    pool: PgPool,
+    batch_size: usize,
+    flush_interval: Duration,
+    sampling: &SamplingSettings,
+    slow_request_threshold_ms: u64,
+) -> ServerMetricsRecorder {
+    let writer = BatchWriter::spawn(batch_size, flush_interval, "server_metrics", move |batch| {
+        let pool = pool.clone();
+        async move { flush(&pool, &batch).await }
+    });
+
+    ServerMetricsRecorder {
+        writer,
+        success_rate: effective_rate(sampling.server_metrics),
+        error_rate: effective_rate(sampling.errors),
+        #[allow(clippy::cast_precision_loss)]
+        slow_threshold_ms: (slow_request_threshold_ms > 0)
+            .then_some(slow_request_threshold_ms as f64),
+    }
+}
+
+async fn flush(pool: &PgPool, batch: &[QueuedMetric]) {
+    let sampled: Vec<_> = batch
+        .iter()
+        .filter_map(|queued| Some((&queued.metric, queued.sample_rate?)))
+        .collect();
+    if !sampled.is_empty()
+        && let Err(e) = insert_server_metrics(&sampled, pool).await
+    {
+        tracing::warn!(
+            error.cause_chain = ?e,
+            rows = sampled.len(),
+            "Failed to write server metrics"
+        );
+    }
+
+    let slow: Vec<_> = batch
+        .iter()
+        .filter(|queued| queued.slow)
+        .map(|queued| &queued.metric)
+        .collect();
+    if !slow.is_empty()
+        && let Err(e) = insert_slow_requests(&slow, pool).await
+    {
+        tracing::warn!(
+            error.cause_chain = ?e,
+            rows = slow.len(),
+            "Failed to write slow requests"
+        );
+    }
+}
+
+// one multi-row insert per batch
+async fn insert_server_metrics(
+    batch: &[(&ServerMetric, f64)],
+    pool: &PgPool,
+) -> Result<(), sqlx::Error> {
+    let mut endpoints = Vec::with_capacity(batch.len());
+    let mut methods = Vec::with_capacity(batch.len());
+    let mut status_codes = Vec::with_capacity(batch.len());
+    let mut response_times = Vec::with_capacity(batch.len());
+    let mut sample_rates = Vec::with_capacity(batch.len());
+    for (metric, sample_rate) in batch {
+        endpoints.push(metric.endpoint.clone());
+        methods.push(metric.method.clone());
+        status_codes.push(i32::from(metric.status_code));
+        response_times.push(metric.response_time_ms);
+        sample_rates.push(*sample_rate);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO server_metrics (endpoint, method, status_code, response_time_ms, sample_rate)
+        SELECT * FROM UNNEST(
+            $1::TEXT[], $2::TEXT[], $3::INT[], $4::DOUBLE PRECISION[], $5::DOUBLE PRECISION[]
+        )
+        "#,
+        &endpoints,
+        &methods,
+        &status_codes,
+        &response_times,
+        &sample_rates
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_slow_requests(batch: &[&ServerMetric], pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut paths = Vec::with_capacity(batch.len());
+    let mut methods = Vec::with_capacity(batch.len());
+    let mut status_codes = Vec::with_capacity(batch.len());
+    let mut durations = Vec::with_capacity(batch.len());
+    for metric in batch {
+        paths.push(metric.path.clone());
+        methods.push(metric.method.clone());
+        status_codes.push(i32::from(metric.status_code));
+        durations.push(metric.response_time_ms);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO slow_requests (path, method, status_code, duration_ms)
+        SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::INT[], $4::DOUBLE PRECISION[])
+        "#,
+        &paths,
+        &methods,
+        &status_codes,
+        &durations
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::HeaderName,
+    middleware::Next,
+    web,
+};
+use chrono::Utc;
+use hmac::{Hmac, KeyInit, Mac};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::Sha256;
+use std::str::FromStr;
+
+use crate::configuration::MetricsSettings;
+use crate::errors::MetricsIngestError;
+
+// `t=<unix seconds>,v1=<hex hmac-sha256 of "<t>.<body>">`
+pub const INGEST_SIGNATURE_HEADER: HeaderName = HeaderName::from_static("x-metrics-signature");
+
+// `navigator.sendBeacon` can't set headers, so the same value is also taken from `?signature=`
+#[derive(serde::Deserialize)]
+struct SignatureQuery {
+    signature: Option<String>,
+}
+
+#[derive(Debug)]
+struct IngestSignature {
+    timestamp: i64,
+    mac: Vec<u8>,
+}
+
+impl FromStr for IngestSignature {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (mut timestamp, mut mac) = (None, None);
+        for part in value.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", t)) => timestamp = t.parse().ok(),
+                Some(("v1", v1)) => mac = hex::decode(v1).ok(),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            timestamp: timestamp.ok_or(())?,
+            mac: mac.ok_or(())?,
+        })
+    }
+}
+
+impl IngestSignature {
+    fn verify(&self, key: &SecretString, body: &[u8], now: i64, max_age_secs: u64) -> bool {
+        now.abs_diff(self.timestamp) <= max_age_secs
+            && ingest_mac(key, self.timestamp, body)
+                .verify_slice(&self.mac)
+                .is_ok()
+    }
+}
+
+fn ingest_mac(key: &SecretString, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.expose_secret().as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    mac
+}
+
+// the key ships in the frontend bundle, so this only keeps out fabricated analytics from
+// anyone who hasn't gone looking for it; the timestamp stops captured requests being replayed later
+// the body is read here to check it and handed back to the handler untouched
+#[allow(clippy::future_not_send)]
+pub async fn require_ingest_signature(
+    mut request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(settings) = request.app_data::<web::Data<MetricsSettings>>().cloned() else {
+        return next.call(request).await;
+    };
+    let Some(key) = &settings.ingest_signing_key else {
+        return next.call(request).await;
+    };
+
+    let signature = request
+        .headers()
+        .get(INGEST_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .or_else(|| {
+            web::Query::<SignatureQuery>::from_query(request.query_string())
+                .ok()
+                .and_then(|query| query.into_inner().signature)
+        })
+        .and_then(|value| value.parse::<IngestSignature>().ok())
+        .ok_or(MetricsIngestError::InvalidSignature)?;
+
+    let body = request.extract::<web::Bytes>().await?;
+    if !signature.verify(
+        key,
+        &body,
+        Utc::now().timestamp(),
+        settings.ingest_signature_max_age_secs,
+    ) {
+        return Err(MetricsIngestError::InvalidSignature.into());
+    }
+    request.set_payload(body.into());
+
+    next.call(request).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sign(key: &SecretString, timestamp: i64, body: &[u8]) -> String {
+        let mac = ingest_mac(key, timestamp, body).finalize().into_bytes();
+        format!("t={timestamp},v1={}", hex::encode(mac))
+    }
+
+    #[test]
+    fn matching_signatures_verify() {
+        let key = SecretString::from("frontend-key");
+        let signature: IngestSignature = sign(&key, 1_700_000_000, b"{}").parse().unwrap();
+
+        assert!(signature.verify(&key, b"{}", 1_700_000_100, 300));
+        assert!(!signature.verify(&key, b"{\"path\":\"/\"}", 1_700_000_100, 300));
+        assert!(!signature.verify(&"other-key".into(), b"{}", 1_700_000_100, 300));
+    }
+
+    #[test]
+    fn stale_and_future_timestamps_are_rejected() {
+        let key = SecretString::from("frontend-key");
+        let signature: IngestSignature = sign(&key, 1_700_000_000, b"{}").parse().unwrap();
+
+        assert!(!signature.verify(&key, b"{}", 1_700_000_301, 300));
+        assert!(!signature.verify(&key, b"{}", 1_699_999_699, 300));
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        assert!("t=1700000000".parse::<IngestSignature>().is_err());
+        assert!("t=soon,v1=abcd".parse::<IngestSignature>().is_err());
+        assert!(
+            "t=1700000000,v1=not-hex"
+                .parse::<IngestSignature>()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::metrics::{MetricsWindow, VitalPercentiles};
+
+// percentile_cont sorts every sample in the window, so repeated dashboard loads
+// reuse the last result for a while instead of re-running it
+// a zero ttl turns caching off
+pub struct VitalsCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<MetricsWindow, (Instant, Vec<VitalPercentiles>)>>,
+}
+
+impl VitalsCache {
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, window: MetricsWindow) -> Option<Vec<VitalPercentiles>> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(&window)
+            .filter(|(cached_at, _)| cached_at.elapsed() < self.ttl)
+            .map(|(_, percentiles)| percentiles.clone())
+    }
+
+    pub fn insert(&self, window: MetricsWindow, percentiles: Vec<VitalPercentiles>) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // windows are user supplied, so expired entries are dropped rather than left to pile up
+        entries.retain(|_, (cached_at, _)| cached_at.elapsed() < self.ttl);
+        entries.insert(window, (Instant::now(), percentiles));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn percentiles() -> Vec<VitalPercentiles> {
+        vec![VitalPercentiles {
+            path: "/".to_string(),
+            metric_name: "LCP".to_string(),
+            samples: 3,
+            p50: 1.0,
+            p75: 2.0,
+            p95: 3.0,
+        }]
+    }
+
+    #[test]
+    fn cached_percentiles_are_returned_until_they_expire() {
+        let cache = VitalsCache::new(Duration::from_secs(60));
+        let window = "7d".parse().unwrap();
+
+        assert!(cache.get(window).is_none());
+        cache.insert(window, percentiles());
+        assert_eq!(cache.get(window).unwrap().len(), 1);
+        assert!(cache.get("24h".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn a_zero_ttl_disables_the_cache() {
+        let cache = VitalsCache::new(Duration::ZERO);
+        let window = MetricsWindow::default();
+
+        cache.insert(window, percentiles());
+        assert!(cache.get(window).is_none());
+    }
+}
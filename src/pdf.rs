@@ -0,0 +1,361 @@
+use std::fmt::Write as _;
+
+// A4, in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const BULLET_INDENT: f32 = 12.0;
+const LINE_SPACING: f32 = 1.3;
+
+// helvetica's widths for ' '..='~' in thousandths of the font size, from its afm
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+// the standard 14 fonts every reader ships, so nothing has to be embedded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+    Oblique,
+}
+
+impl Font {
+    const ALL: [Self; 3] = [Self::Regular, Self::Bold, Self::Oblique];
+
+    const fn resource(self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+            Self::Oblique => "F3",
+        }
+    }
+
+    const fn base_font(self) -> &'static str {
+        match self {
+            Self::Regular => "Helvetica",
+            Self::Bold => "Helvetica-Bold",
+            Self::Oblique => "Helvetica-Oblique",
+        }
+    }
+
+    // bold runs a little wider than the regular widths, measured generously so lines still fit
+    #[allow(clippy::cast_precision_loss)]
+    fn width(self, text: &str, size: f32) -> f32 {
+        let thousandths: u32 = text.chars().map(|c| u32::from(char_width(c))).sum();
+        let width = thousandths as f32 * size / 1000.0;
+        match self {
+            Self::Bold => width * 1.1,
+            Self::Regular | Self::Oblique => width,
+        }
+    }
+}
+
+fn char_width(c: char) -> u16 {
+    match c {
+        ' '..='~' => HELVETICA_WIDTHS[c as usize - ' ' as usize],
+        _ => 556,
+    }
+}
+
+// winansi is latin-1 plus typographic punctuation in 0x80..0x9f, anything else prints as `?`
+#[allow(clippy::cast_possible_truncation)]
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+// a text-only document laid out top to bottom, a new page starts when the current one is full
+pub struct PdfDocument {
+    title: String,
+    pages: Vec<Vec<u8>>,
+    page: Vec<u8>,
+    // the top of the next line
+    cursor: f32,
+}
+
+impl PdfDocument {
+    #[must_use]
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            pages: Vec::new(),
+            page: Vec::new(),
+            cursor: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    // wrapped to the page width, blank lines in `text` are kept as paragraph breaks
+    pub fn text(&mut self, text: &str, font: Font, size: f32) {
+        for paragraph in text.lines() {
+            let lines = wrap(paragraph, font, size, PAGE_WIDTH - 2.0 * MARGIN);
+            if lines.is_empty() {
+                self.space(size * LINE_SPACING);
+            }
+            for line in lines {
+                self.line(MARGIN, &line, font, size);
+            }
+        }
+    }
+
+    pub fn bullet(&mut self, text: &str, size: f32) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN - BULLET_INDENT;
+        for (i, line) in wrap(text, Font::Regular, size, width).iter().enumerate() {
+            self.break_page_for(size * LINE_SPACING);
+            if i == 0 {
+                self.draw(MARGIN, "•", Font::Regular, size);
+            }
+            self.line(MARGIN + BULLET_INDENT, line, Font::Regular, size);
+        }
+    }
+
+    // `left` against the left margin and `right` flush with the right one, e.g. a role and its dates
+    pub fn row(&mut self, left: &str, right: &str, font: Font, size: f32) {
+        let right_width = Font::Regular.width(right, size);
+        let left_width = PAGE_WIDTH - 2.0 * MARGIN - right_width - size;
+        for (i, line) in wrap(left, font, size, left_width).iter().enumerate() {
+            self.break_page_for(size * LINE_SPACING);
+            if i == 0 {
+                self.draw(
+                    PAGE_WIDTH - MARGIN - right_width,
+                    right,
+                    Font::Regular,
+                    size,
+                );
+            }
+            self.line(MARGIN, line, font, size);
+        }
+    }
+
+    // a thin line across the page under a section heading
+    pub fn rule(&mut self) {
+        self.break_page_for(4.0);
+        let y = self.cursor - 2.0;
+        let _ = writeln!(
+            Buffer(&mut self.page),
+            "0.5 w {MARGIN:.2} {y:.2} m {:.2} {y:.2} l S",
+            PAGE_WIDTH - MARGIN
+        );
+        self.cursor -= 4.0;
+    }
+
+    pub fn space(&mut self, points: f32) {
+        self.cursor -= points;
+    }
+
+    #[must_use]
+    pub fn finish(mut self) -> Vec<u8> {
+        if !self.page.is_empty() || self.pages.is_empty() {
+            self.pages.push(std::mem::take(&mut self.page));
+        }
+        write_document(&self.title, &self.pages)
+    }
+
+    fn line(&mut self, x: f32, text: &str, font: Font, size: f32) {
+        self.break_page_for(size * LINE_SPACING);
+        self.draw(x, text, font, size);
+        self.cursor -= size * LINE_SPACING;
+    }
+
+    fn draw(&mut self, x: f32, text: &str, font: Font, size: f32) {
+        let baseline = self.cursor - size;
+        let _ = write!(
+            Buffer(&mut self.page),
+            "BT /{} {size:.2} Tf {x:.2} {baseline:.2} Td (",
+            font.resource()
+        );
+        for byte in encode(text) {
+            if matches!(byte, b'(' | b')' | b'\\') {
+                self.page.push(b'\\');
+            }
+            self.page.push(byte);
+        }
+        self.page.extend_from_slice(b") Tj ET\n");
+    }
+
+    fn break_page_for(&mut self, height: f32) {
+        if self.cursor - height < MARGIN && !self.page.is_empty() {
+            self.pages.push(std::mem::take(&mut self.page));
+            self.cursor = PAGE_HEIGHT - MARGIN;
+        }
+    }
+}
+
+// greedy, a single word wider than the line gets a line of its own and runs over
+fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && font.width(&format!("{current} {word}"), size) > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// `write!` onto the byte buffers content streams are built in
+struct Buffer<'a>(&'a mut Vec<u8>);
+
+impl std::fmt::Write for Buffer<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+// catalog, page tree, info and fonts first, then each page followed by its content stream
+fn write_document(title: &str, pages: &[Vec<u8>]) -> Vec<u8> {
+    const FIRST_PAGE: usize = 4 + Font::ALL.len();
+
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let kids = (0..pages.len())
+        .map(|i| format!("{} 0 R", FIRST_PAGE + 2 * i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(format!("<< /Type /Pages /Kids [{kids}] /Count {} >>", pages.len()).into_bytes());
+
+    let mut info = b"<< /Title (".to_vec();
+    for byte in encode(title) {
+        if matches!(byte, b'(' | b')' | b'\\') {
+            info.push(b'\\');
+        }
+        info.push(byte);
+    }
+    info.extend_from_slice(b") /Producer (portfolio-server) >>");
+    objects.push(info);
+
+    let mut fonts = String::new();
+    for (i, font) in Font::ALL.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font.base_font()
+            )
+            .into_bytes(),
+        );
+        let _ = write!(fonts, "/{} {} 0 R ", font.resource(), 4 + i);
+    }
+
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << {fonts}>> >> /Contents {} 0 R >>",
+                FIRST_PAGE + 2 * i + 1
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend_from_slice(content);
+        stream.extend_from_slice(b"\nendstream");
+        objects.push(stream);
+    }
+
+    // the binary comment line tells tools the file isn't plain text
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(table, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        table,
+        "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.extend_from_slice(table.as_bytes());
+    pdf
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn page_count(pdf: &[u8]) -> usize {
+        String::from_utf8_lossy(pdf)
+            .matches("/Type /Page /Parent")
+            .count()
+    }
+
+    #[test]
+    fn lines_wrap_between_words() {
+        let lines = wrap("one two three four", Font::Regular, 10.0, 40.0);
+
+        assert_eq!(lines, ["one two", "three", "four"]);
+    }
+
+    #[test]
+    fn the_xref_points_at_every_object() {
+        let mut document = PdfDocument::new("Resume");
+        document.text("Jane Doe (she/her)", Font::Bold, 20.0);
+        let pdf = document.finish();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.contains("(Jane Doe \\(she/her\\)) Tj"));
+        let xref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        let table = std::str::from_utf8(&pdf[xref..]).unwrap();
+        let offsets = table
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "));
+        for (i, offset) in offsets.enumerate() {
+            let offset: usize = offset[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn full_pages_continue_on_the_next() {
+        let mut document = PdfDocument::new("Resume");
+        for i in 0..100 {
+            document.bullet(&format!("Highlight number {i}"), 10.0);
+        }
+
+        assert_eq!(page_count(&document.finish()), 2);
+    }
+
+    #[test]
+    fn characters_outside_winansi_are_replaced() {
+        assert_eq!(encode("café – 東京"), b"caf\xe9 \x96 ??");
+    }
+}
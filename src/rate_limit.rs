@@ -0,0 +1,719 @@
+use actix_web::{
+    FromRequest, ResponseError,
+    body::MessageBody,
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::{
+        Method, StatusCode,
+        header::{HeaderValue, RETRY_AFTER},
+    },
+    middleware::Next,
+    web,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use crate::client_ip::client_ip;
+use crate::configuration::{
+    ContactSettings, RateLimitAlgorithm, RateLimitKey, RateLimitPolicy, RateLimitSettings,
+};
+use crate::errors::ApiProblem;
+use crate::redis_pool::{self, RedisPool};
+use crate::reload::Reloadable;
+use crate::session_state::TypedSession;
+use crate::types::email::ValidatedEmail;
+
+type FormFields = HashMap<String, String>;
+
+// refills the bucket for the time since it was last touched, then takes a token
+// returns 0 when one was available, otherwise the whole seconds until one will be
+// uses the server's clock so every instance agrees on elapsed time
+const TOKEN_BUCKET_SCRIPT: &str = r"
+local capacity = tonumber(ARGV[1])
+local refill_per_sec = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) + tonumber(time[2]) / 1000000
+
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'updated_at')
+local tokens = tonumber(bucket[1]) or capacity
+local updated_at = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - updated_at) * refill_per_sec)
+
+local retry_after = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+else
+    retry_after = math.ceil((1 - tokens) / refill_per_sec)
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated_at', tostring(now))
+redis.call('EXPIRE', KEYS[1], math.ceil(capacity / refill_per_sec) + 1)
+return retry_after
+";
+
+impl RateLimitPolicy {
+    // `route` is the matched pattern, or the raw path when nothing matched
+    fn applies_to(&self, method: &Method, route: &str) -> bool {
+        let method_ok = self.methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method.as_str()));
+        let route_ok = self
+            .routes
+            .iter()
+            .any(|pattern| route_matches(pattern, route));
+
+        method_ok && route_ok
+    }
+
+    // an allowlisted email is only what the client typed into the body, so it only
+    // skips budgets keyed on the body, and never one that keeps brute force out
+    const fn skipped_for_allowlisted_email(&self) -> bool {
+        matches!(
+            self.key,
+            RateLimitKey::FormField(_) | RateLimitKey::JsonField(_)
+        ) && !self.fail_closed
+    }
+
+    // only a client's own address or account can earn a ban, a body-keyed subject is
+    // whatever the client typed, so banning it would let anyone lock a stranger out
+    const fn penalizes_offenders(&self) -> bool {
+        matches!(self.key, RateLimitKey::Ip | RateLimitKey::User)
+    }
+}
+
+// a trailing `*` matches every route under a prefix
+pub(crate) fn route_matches(pattern: &str, route: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => route.starts_with(prefix),
+        None => route == pattern,
+    }
+}
+
+impl RateLimitSettings {
+    fn policies_for(&self, method: &Method, route: &str) -> Vec<&RateLimitPolicy> {
+        if self
+            .exempt_routes
+            .iter()
+            .any(|pattern| route_matches(pattern, route))
+        {
+            return Vec::new();
+        }
+
+        self.policies
+            .iter()
+            .filter(|policy| policy.applies_to(method, route))
+            .collect()
+    }
+
+    // the client address comes from the connection or a trusted proxy, so it skips everything
+    fn is_allowlisted_ip(&self, ip: Option<IpAddr>) -> bool {
+        ip.is_some_and(|ip| self.allowlist.ips.iter().any(|net| net.contains(&ip)))
+    }
+
+    fn is_allowlisted_email(&self, form: &FormFields, strip_plus_addresses: bool) -> bool {
+        ["username", "email"]
+            .iter()
+            .filter_map(|field| form.get(*field))
+            .any(|value| {
+                let value = email_subject(value, strip_plus_addresses);
+                self.allowlist
+                    .emails
+                    .iter()
+                    .any(|email| email_subject(email, strip_plus_addresses) == value)
+            })
+    }
+}
+
+// an address the way the contact form stores it, so every spelling of one sender
+// draws from one budget, anything that isn't an address is just trimmed and lowercased
+fn email_subject(value: &str, strip_plus_addresses: bool) -> String {
+    ValidatedEmail::parse(value, strip_plus_addresses)
+        .map_or_else(|_| value.trim().to_lowercase(), String::from)
+}
+
+// budgets from `rate_limit.policies`, counted in Redis so every
+// worker (and every instance) draws from the same count
+// the policies are re-read on every request, so a reload applies to the next one
+#[derive(Clone)]
+pub struct RateLimiter {
+    pool: RedisPool,
+    settings: Reloadable<RateLimitSettings>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub const fn new(pool: RedisPool, settings: Reloadable<RateLimitSettings>) -> Self {
+        Self { pool, settings }
+    }
+
+    // `None` while `subject` is within the policy's budget, otherwise the seconds to wait
+    async fn check(
+        &self,
+        policy: &RateLimitPolicy,
+        subject: &str,
+    ) -> Result<Option<u64>, redis::RedisError> {
+        if policy.penalizes_offenders()
+            && let Some(ban_remaining) = self.ban_remaining(subject).await?
+        {
+            return Ok(Some(ban_remaining));
+        }
+
+        let key = self.policy_key(&policy.name, subject);
+        let retry_after = match policy.algorithm {
+            RateLimitAlgorithm::FixedWindow => self.check_fixed_window(policy, &key).await?,
+            RateLimitAlgorithm::TokenBucket => self.check_token_bucket(policy, &key).await?,
+        };
+        if retry_after.is_some() && policy.penalizes_offenders() {
+            self.record_strike(subject).await?;
+        }
+
+        Ok(retry_after)
+    }
+
+    // the subject is every key's hash tag, so a cluster keeps all of one subject's keys in
+    // one slot and the ban pipeline and a reset's DEL never span slots
+    fn policy_key(&self, policy: &str, subject: &str) -> String {
+        format!("{}:{policy}:{{{subject}}}", self.settings.get().namespace)
+    }
+
+    // bans and strikes belong to the subject rather than a policy,
+    // an address banned on one route is banned on every route keyed by address
+    fn penalty_key(&self, kind: &str, subject: &str) -> String {
+        format!(
+            "{}:penalty:{kind}:{{{subject}}}",
+            self.settings.get().namespace
+        )
+    }
+
+    async fn ban_remaining(&self, subject: &str) -> Result<Option<u64>, redis::RedisError> {
+        let ttl: i64 = redis::cmd("TTL")
+            .arg(self.penalty_key("ban", subject))
+            .query_async(&mut redis_pool::get(&self.pool).await?)
+            .await?;
+
+        Ok(u64::try_from(ttl).ok().filter(|&ttl| ttl > 0))
+    }
+
+    // one more 429, enough of them in a row and the subject is banned,
+    // for longer each time it comes back and does it again
+    async fn record_strike(&self, subject: &str) -> Result<(), redis::RedisError> {
+        let settings = self.settings.get();
+        let penalties = &settings.penalties;
+        if penalties.strikes_before_ban == 0 {
+            return Ok(());
+        }
+        let mut connection = redis_pool::get(&self.pool).await?;
+        let strikes_key = self.penalty_key("strikes", subject);
+
+        let (strikes,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(&strikes_key, 1)
+            .expire(
+                &strikes_key,
+                i64::try_from(penalties.strike_window_secs.max(1)).unwrap_or(i64::MAX),
+            )
+            .ignore()
+            .query_async(&mut connection)
+            .await?;
+        if strikes < penalties.strikes_before_ban {
+            return Ok(());
+        }
+
+        let bans_key = self.penalty_key("bans", subject);
+        let (bans,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(&bans_key, 1)
+            .expire(
+                &bans_key,
+                i64::try_from(penalties.max_ban_secs.max(1)).unwrap_or(i64::MAX),
+            )
+            .ignore()
+            .query_async(&mut connection)
+            .await?;
+        let ban_secs = penalties.ban_secs_for(bans).max(1);
+
+        let _: () = redis::pipe()
+            .atomic()
+            .set_ex(self.penalty_key("ban", subject), bans, ban_secs)
+            .ignore()
+            .del(&strikes_key)
+            .ignore()
+            .query_async(&mut connection)
+            .await?;
+        tracing::warn!(subject = %subject, bans, ban_secs, "Banned a repeat rate limit offender");
+
+        Ok(())
+    }
+
+    async fn check_fixed_window(
+        &self,
+        policy: &RateLimitPolicy,
+        key: &str,
+    ) -> Result<Option<u64>, redis::RedisError> {
+        let window_secs = policy.window_secs.max(1);
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+        let window_start = now - now % window_secs;
+        let key = format!("{key}:{window_start}");
+
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .expire(&key, i64::try_from(window_secs).unwrap_or(i64::MAX))
+            .ignore()
+            .query_async(&mut redis_pool::get(&self.pool).await?)
+            .await?;
+
+        Ok((count > policy.max_requests).then(|| window_start + window_secs - now))
+    }
+
+    // one script call, so concurrent requests can't both spend the last token
+    async fn check_token_bucket(
+        &self,
+        policy: &RateLimitPolicy,
+        key: &str,
+    ) -> Result<Option<u64>, redis::RedisError> {
+        let capacity = policy.burst.unwrap_or(policy.max_requests).max(1);
+        #[allow(clippy::cast_precision_loss)]
+        let refill_per_sec = policy.max_requests.max(1) as f64 / policy.window_secs.max(1) as f64;
+
+        let retry_after: u64 = redis::cmd("EVAL")
+            .arg(TOKEN_BUCKET_SCRIPT)
+            .arg(1)
+            .arg(format!("{key}:bucket"))
+            .arg(capacity)
+            .arg(refill_per_sec)
+            .query_async(&mut redis_pool::get(&self.pool).await?)
+            .await?;
+
+        Ok((retry_after > 0).then_some(retry_after))
+    }
+
+    // every subject that's over a budget right now, found by scanning each policy's keys
+    /// # Errors
+    /// returns a `redis` error if a SCAN or read fails
+    pub async fn throttled(&self) -> Result<Vec<ThrottledKey>, redis::RedisError> {
+        let mut connection = redis_pool::get(&self.pool).await?;
+        let now = Utc::now().timestamp();
+        let mut throttled = Vec::new();
+        let settings = self.settings.get();
+
+        for policy in &settings.policies {
+            let prefix = format!("{}:{}:{{", settings.namespace, policy.name);
+            let window_secs = i64::try_from(policy.window_secs.max(1)).unwrap_or(i64::MAX);
+            // a fixed window's key ends in the window it counts, a bucket's in `bucket`
+            let suffix = match policy.algorithm {
+                RateLimitAlgorithm::FixedWindow => format!("}}:{}", now - now % window_secs),
+                RateLimitAlgorithm::TokenBucket => "}:bucket".to_string(),
+            };
+            let pattern = format!("{}*{}", escape_glob(&prefix), escape_glob(&suffix));
+
+            for key in redis_pool::scan_keys(&mut connection, &pattern).await? {
+                let Some(subject) = key
+                    .strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix(&suffix))
+                else {
+                    continue;
+                };
+                // a key can expire between the SCAN and the read, it comes back as nil
+                let retry_after = match policy.algorithm {
+                    RateLimitAlgorithm::FixedWindow => {
+                        let count: Option<u64> = redis::cmd("GET")
+                            .arg(&key)
+                            .query_async(&mut connection)
+                            .await?;
+                        (count.unwrap_or_default() > policy.max_requests)
+                            .then(|| window_secs - now % window_secs)
+                    }
+                    RateLimitAlgorithm::TokenBucket => {
+                        let (tokens, updated_at): (Option<f64>, Option<f64>) = redis::cmd("HMGET")
+                            .arg(&key)
+                            .arg("tokens")
+                            .arg("updated_at")
+                            .query_async(&mut connection)
+                            .await?;
+                        bucket_retry_after(policy, tokens, updated_at, now)
+                    }
+                };
+
+                if let Some(retry_after) = retry_after {
+                    throttled.push(ThrottledKey {
+                        policy: policy.name.clone(),
+                        subject: subject.to_string(),
+                        retry_after_secs: u64::try_from(retry_after).unwrap_or_default(),
+                    });
+                }
+            }
+        }
+
+        Ok(throttled)
+    }
+
+    // subjects serving a ban, with how long is left
+    /// # Errors
+    /// returns a `redis` error if a SCAN or TTL fails
+    pub async fn banned(&self) -> Result<Vec<BannedSubject>, redis::RedisError> {
+        let mut connection = redis_pool::get(&self.pool).await?;
+        let prefix = format!("{}:penalty:ban:{{", self.settings.get().namespace);
+        let pattern = format!("{}*}}", escape_glob(&prefix));
+        let mut banned = Vec::new();
+
+        for key in redis_pool::scan_keys(&mut connection, &pattern).await? {
+            let Some(subject) = key
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix('}'))
+            else {
+                continue;
+            };
+            let ttl: i64 = redis::cmd("TTL")
+                .arg(&key)
+                .query_async(&mut connection)
+                .await?;
+            // expired between the SCAN and the TTL
+            if let Ok(retry_after_secs) = u64::try_from(ttl) {
+                banned.push(BannedSubject {
+                    subject: subject.to_string(),
+                    retry_after_secs,
+                });
+            }
+        }
+
+        Ok(banned)
+    }
+
+    // clears `subject`'s count under `policy`, `None` when no policy has that name
+    /// # Errors
+    /// returns a `redis` error if the delete fails
+    pub async fn reset(
+        &self,
+        policy: &str,
+        subject: &str,
+    ) -> Result<Option<()>, redis::RedisError> {
+        let settings = self.settings.get();
+        let Some(policy) = settings.policies.iter().find(|p| p.name == policy) else {
+            return Ok(None);
+        };
+        let key = self.policy_key(&policy.name, subject);
+        let window_secs = policy.window_secs.max(1);
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+
+        // a reset is a pardon, so any ban and strikes against the subject go too
+        let _: () = redis::cmd("DEL")
+            .arg(format!("{key}:{}", now - now % window_secs))
+            .arg(format!("{key}:bucket"))
+            .arg(self.penalty_key("ban", subject))
+            .arg(self.penalty_key("strikes", subject))
+            .query_async(&mut redis_pool::get(&self.pool).await?)
+            .await?;
+        tracing::info!(policy = %policy.name, subject = %subject, "Rate limit reset");
+
+        Ok(Some(()))
+    }
+}
+
+// a subject that's over a policy's budget, as listed for the admin dashboard
+#[derive(serde::Serialize, Debug)]
+pub struct ThrottledKey {
+    pub policy: String,
+    pub subject: String,
+    pub retry_after_secs: u64,
+}
+
+// a subject banned for repeatedly going over its budgets
+#[derive(serde::Serialize, Debug)]
+pub struct BannedSubject {
+    pub subject: String,
+    pub retry_after_secs: u64,
+}
+
+// whole seconds until a drained bucket has a token again, `None` while it has one
+fn bucket_retry_after(
+    policy: &RateLimitPolicy,
+    tokens: Option<f64>,
+    updated_at: Option<f64>,
+    now: i64,
+) -> Option<i64> {
+    let (tokens, updated_at) = (tokens?, updated_at?);
+    #[allow(clippy::cast_precision_loss)]
+    let refill_per_sec = policy.max_requests.max(1) as f64 / policy.window_secs.max(1) as f64;
+    #[allow(clippy::cast_precision_loss)]
+    let tokens = tokens + (now as f64 - updated_at).max(0.0) * refill_per_sec;
+
+    #[allow(clippy::cast_possible_truncation)]
+    (tokens < 1.0).then(|| ((1.0 - tokens) / refill_per_sec).ceil() as i64)
+}
+
+// subjects come from request data, so keep them from acting as a scan pattern
+fn escape_glob(literal: &str) -> String {
+    literal
+        .chars()
+        .flat_map(|c| match c {
+            '*' | '?' | '[' | ']' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+#[allow(clippy::future_not_send)]
+/// # Errors
+/// returns a 429 with a `Retry-After` header once any policy covering the route is over budget
+pub async fn enforce_rate_limits(
+    mut request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(limiter) = request.app_data::<web::Data<RateLimiter>>().cloned() else {
+        return next.call(request).await;
+    };
+    let route = request
+        .match_pattern()
+        .unwrap_or_else(|| request.path().to_owned());
+    let settings = limiter.settings.get();
+    let policies = settings.policies_for(request.method(), &route);
+    if policies.is_empty() || settings.is_allowlisted_ip(client_ip(request.request())) {
+        return next.call(request).await;
+    }
+
+    // an allowlisted email only ever skips a budget keyed on the body, so without one
+    // there's nothing to read
+    let body = if policies.iter().any(|policy| {
+        matches!(
+            policy.key,
+            RateLimitKey::FormField(_) | RateLimitKey::JsonField(_)
+        )
+    }) {
+        read_body(&mut request).await?
+    } else {
+        RequestBody::default()
+    };
+
+    let strip_plus_addresses = request
+        .app_data::<web::Data<ContactSettings>>()
+        .is_some_and(|contact| contact.strip_plus_addresses);
+    let email_allowlisted = settings.is_allowlisted_email(&body.form, strip_plus_addresses);
+
+    for policy in policies {
+        if email_allowlisted && policy.skipped_for_allowlisted_email() {
+            continue;
+        }
+        // nothing to count against, e.g. a login without a username, which the handler rejects
+        let Some(subject) =
+            subject_for(&policy.key, &mut request, &body, strip_plus_addresses).await?
+        else {
+            continue;
+        };
+
+        match limiter.check(policy, &subject).await {
+            Ok(None) => {}
+            Ok(Some(retry_after)) => {
+                tracing::warn!(policy = %policy.name, subject = %subject, "Rate limit exceeded");
+                let mut response = ApiProblem::new(StatusCode::TOO_MANY_REQUESTS)
+                    .with_detail(format!("Too many requests, retry in {retry_after} seconds"))
+                    .error_response();
+                response
+                    .headers_mut()
+                    .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+                let e = anyhow::anyhow!("Rate limit `{}` exceeded", policy.name);
+                return Err(InternalError::from_response(e, response).into());
+            }
+            // an unreachable Redis shouldn't take the site down with it,
+            // except where the budget is what keeps brute force out
+            Err(e) if policy.fail_closed => {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    policy = %policy.name,
+                    "Failed to check rate limit, rejecting the request"
+                );
+                let response = ApiProblem::new(StatusCode::SERVICE_UNAVAILABLE).error_response();
+                return Err(InternalError::from_response(e, response).into());
+            }
+            Err(e) => tracing::warn!(
+                error.cause_chain = ?e,
+                policy = %policy.name,
+                "Failed to check rate limit"
+            ),
+        }
+    }
+
+    next.call(request).await
+}
+
+async fn subject_for(
+    key: &RateLimitKey,
+    request: &mut ServiceRequest,
+    body: &RequestBody,
+    strip_plus_addresses: bool,
+) -> Result<Option<String>, actix_web::Error> {
+    // forwarded headers only count from trusted proxies,
+    // otherwise a client could pick a fresh budget per request
+    let ip = format!(
+        "ip:{}",
+        client_ip(request.request()).map_or_else(|| "unknown".to_string(), |ip| ip.to_string())
+    );
+
+    let subject = match key {
+        RateLimitKey::Ip => Some(ip),
+        RateLimitKey::User => {
+            let session = {
+                let (http_request, payload) = request.parts_mut();
+                TypedSession::from_request(http_request, payload).await?
+            };
+            Some(
+                session
+                    .get_user_id()
+                    .ok()
+                    .flatten()
+                    .map_or(ip, |user_id| format!("user:{user_id}")),
+            )
+        }
+        RateLimitKey::FormField(field) => body.form.get(field).map(|value| match field.as_str() {
+            "email" => format!("{field}:{}", email_subject(value, strip_plus_addresses)),
+            _ => format!("{field}:{}", value.to_lowercase()),
+        }),
+        RateLimitKey::JsonField(pointer) => body
+            .json
+            .as_ref()
+            .and_then(|json| json.pointer(pointer))
+            .and_then(|value| match value {
+                serde_json::Value::String(value) => Some(value.to_lowercase()),
+                serde_json::Value::Number(value) => Some(value.to_string()),
+                _ => None,
+            })
+            .map(|value| {
+                // named after the last segment, e.g. `session_id:...`
+                let field = pointer.rsplit('/').next().unwrap_or_default();
+                format!("{field}:{value}")
+            }),
+    };
+
+    Ok(subject)
+}
+
+// the body as a form or as json, whichever it parses as
+#[derive(Default)]
+struct RequestBody {
+    form: FormFields,
+    json: Option<serde_json::Value>,
+}
+
+// reads the whole body and puts it back, so the handler's extractor still sees it
+// the content type isn't trusted, beacons send json as text/plain
+async fn read_body(request: &mut ServiceRequest) -> Result<RequestBody, actix_web::Error> {
+    let body = request.extract::<web::Bytes>().await?;
+    let json = serde_json::from_slice(&body).ok();
+    let form = if json.is_some() {
+        FormFields::new()
+    } else {
+        std::str::from_utf8(&body)
+            .ok()
+            .and_then(|body| web::Query::<FormFields>::from_query(body).ok())
+            .map(web::Query::into_inner)
+            .unwrap_or_default()
+    };
+    request.set_payload(Payload::from(body));
+
+    Ok(RequestBody { form, json })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn policy(routes: &[&str], methods: &[&str]) -> RateLimitPolicy {
+        RateLimitPolicy {
+            name: "test".to_string(),
+            routes: routes.iter().map(ToString::to_string).collect(),
+            methods: methods.iter().map(ToString::to_string).collect(),
+            max_requests: 1,
+            window_secs: 60,
+            key: RateLimitKey::Ip,
+            fail_closed: false,
+            algorithm: RateLimitAlgorithm::FixedWindow,
+            burst: None,
+        }
+    }
+
+    #[test]
+    fn policies_match_exact_routes_and_methods() {
+        let login = policy(&["/v1/login"], &["POST"]);
+        assert!(login.applies_to(&Method::POST, "/v1/login"));
+        assert!(!login.applies_to(&Method::GET, "/v1/login"));
+        assert!(!login.applies_to(&Method::POST, "/v1/login/extra"));
+    }
+
+    #[test]
+    fn trailing_wildcards_match_a_prefix() {
+        let everything = policy(&["/v1/*"], &[]);
+        assert!(everything.applies_to(&Method::GET, "/v1/blog"));
+        assert!(everything.applies_to(&Method::PATCH, "/v1/admin/users/{user_id}/role"));
+        assert!(!everything.applies_to(&Method::GET, "/health_check"));
+    }
+
+    #[test]
+    fn allowlisted_emails_only_skip_body_keyed_budgets_that_fail_open() {
+        let by_ip = policy(&["/v1/contact"], &["POST"]);
+        let by_email = RateLimitPolicy {
+            key: RateLimitKey::FormField("email".to_string()),
+            ..policy(&["/v1/contact"], &["POST"])
+        };
+        let by_username = RateLimitPolicy {
+            key: RateLimitKey::FormField("username".to_string()),
+            fail_closed: true,
+            ..policy(&["/v1/login"], &["POST"])
+        };
+
+        assert!(!by_ip.skipped_for_allowlisted_email());
+        assert!(by_email.skipped_for_allowlisted_email());
+        assert!(!by_username.skipped_for_allowlisted_email());
+    }
+
+    #[test]
+    fn only_address_and_account_subjects_earn_bans() {
+        let by_ip = policy(&["/v1/login"], &["POST"]);
+        let by_user = RateLimitPolicy {
+            key: RateLimitKey::User,
+            ..policy(&["/v1/admin/*"], &[])
+        };
+        let by_username = RateLimitPolicy {
+            key: RateLimitKey::FormField("username".to_string()),
+            fail_closed: true,
+            ..policy(&["/v1/login"], &["POST"])
+        };
+        let by_session = RateLimitPolicy {
+            key: RateLimitKey::JsonField("/session_id".to_string()),
+            ..policy(&["/v1/metrics/visit"], &["POST"])
+        };
+
+        assert!(by_ip.penalizes_offenders());
+        assert!(by_user.penalizes_offenders());
+        assert!(!by_username.penalizes_offenders());
+        assert!(!by_session.penalizes_offenders());
+    }
+
+    #[test]
+    fn email_subjects_match_however_the_address_is_written() {
+        assert_eq!(
+            email_subject(" Foo@Example.COM", false),
+            email_subject("foo@example.com", false)
+        );
+        assert_eq!(
+            email_subject("foo+news@example.com", true),
+            "foo@example.com"
+        );
+        assert_eq!(
+            email_subject("foo+news@example.com", false),
+            "foo+news@example.com"
+        );
+        assert_eq!(email_subject(" Admin ", true), "admin");
+    }
+
+    #[test]
+    fn subjects_are_escaped_before_scanning() {
+        assert_eq!(
+            escape_glob("rate_limit:login:username:a*b"),
+            r"rate_limit:login:username:a\*b"
+        );
+        assert_eq!(escape_glob(r"[x]?\"), r"\[x\]\?\\");
+    }
+}
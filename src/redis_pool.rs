@@ -0,0 +1,242 @@
+use deadpool_redis::{
+    Config, PoolConfig, PoolError, Runtime, Status, Timeouts, cluster,
+    sentinel::{self, SentinelNodeConnectionInfo, SentinelServerType, TlsMode},
+};
+use redis::{
+    Cmd, ErrorKind, Pipeline, RedisError, RedisFuture, Value,
+    aio::ConnectionLike,
+    cluster_routing::{RoutingInfo, SingleNodeRoutingInfo},
+};
+use std::time::Duration;
+
+use crate::configuration::{RedisSettings, RedisTopology};
+
+const SCAN_BATCH: usize = 1000;
+
+// one pool whatever the deployment looks like, callers only ever see `get` and `status`
+#[derive(Clone)]
+pub enum RedisPool {
+    Standalone(deadpool_redis::Pool),
+    // a new connection asks the sentinels where the master is, so after a failover
+    // the pool reconnects to whichever node was promoted
+    Sentinel(sentinel::Pool),
+    Cluster(cluster::Pool),
+}
+
+pub enum RedisConnection {
+    Standalone(deadpool_redis::Connection),
+    Sentinel(sentinel::Connection),
+    Cluster(cluster::Connection),
+}
+
+impl RedisPool {
+    #[must_use]
+    pub fn status(&self) -> Status {
+        match self {
+            Self::Standalone(pool) => pool.status(),
+            Self::Sentinel(pool) => pool.status(),
+            Self::Cluster(pool) => pool.status(),
+        }
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Self::Standalone(connection) => connection.req_packed_command(cmd),
+            Self::Sentinel(connection) => connection.req_packed_command(cmd),
+            Self::Cluster(connection) => connection.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Self::Standalone(connection) => connection.req_packed_commands(cmd, offset, count),
+            Self::Sentinel(connection) => connection.req_packed_commands(cmd, offset, count),
+            Self::Cluster(connection) => connection.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Self::Standalone(connection) => connection.get_db(),
+            Self::Sentinel(connection) => connection.get_db(),
+            Self::Cluster(connection) => connection.get_db(),
+        }
+    }
+}
+
+// connections are opened as they're needed, up to `max_connections`, and kept for reuse
+// one is checked out right away so a bad uri or an unreachable server fails startup
+// `redis_uri` is a comma-separated list of nodes for the sentinel and cluster topologies
+/// # Errors
+/// returns a `redis` error if the uri or topology is invalid or the first connection fails
+pub async fn connect(redis_uri: &str, settings: &RedisSettings) -> Result<RedisPool, RedisError> {
+    let timeout = Some(Duration::from_secs(settings.acquire_timeout_secs));
+    let pool_config = PoolConfig {
+        timeouts: Timeouts {
+            wait: timeout,
+            create: timeout,
+            recycle: timeout,
+        },
+        ..PoolConfig::new(settings.max_connections.max(1))
+    };
+    let nodes: Vec<String> = redis_uri
+        .split(',')
+        .map(str::trim)
+        .filter(|node| !node.is_empty())
+        .map(str::to_owned)
+        .collect();
+    let invalid = |e: &dyn std::fmt::Display| {
+        RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "Invalid Redis pool settings",
+            e.to_string(),
+        ))
+    };
+
+    let pool = match settings.topology {
+        RedisTopology::Standalone => {
+            let mut config = Config::from_url(redis_uri);
+            config.pool = Some(pool_config);
+            RedisPool::Standalone(
+                config
+                    .create_pool(Some(Runtime::Tokio1))
+                    .map_err(|e| invalid(&e))?,
+            )
+        }
+        RedisTopology::Sentinel => {
+            let service_name = settings
+                .service_name
+                .clone()
+                .ok_or_else(|| invalid(&"the sentinel topology needs `redis.service_name`"))?;
+            let mut config =
+                sentinel::Config::from_urls(nodes, service_name, SentinelServerType::Master);
+            // the sentinels answer with a bare address, so tls to the master is configured here
+            config.node_connection_info = Some(SentinelNodeConnectionInfo {
+                tls_mode: settings.tls.then_some(TlsMode::Secure),
+                redis_connection_info: None,
+            });
+            config.pool = Some(pool_config);
+            RedisPool::Sentinel(
+                config
+                    .create_pool(Some(Runtime::Tokio1))
+                    .map_err(|e| invalid(&e))?,
+            )
+        }
+        RedisTopology::Cluster => {
+            let mut config = cluster::Config::from_urls(nodes);
+            config.pool = Some(pool_config);
+            RedisPool::Cluster(
+                config
+                    .create_pool(Some(Runtime::Tokio1))
+                    .map_err(|e| invalid(&e))?,
+            )
+        }
+    };
+
+    get(&pool).await?;
+    Ok(pool)
+}
+
+// a pool timeout reads as any other redis failure to callers, they only care that it failed
+/// # Errors
+/// returns a `redis` error if no connection frees up in time or a new one can't be opened
+pub async fn get(pool: &RedisPool) -> Result<RedisConnection, RedisError> {
+    match pool {
+        RedisPool::Standalone(pool) => pool.get().await.map(RedisConnection::Standalone),
+        RedisPool::Sentinel(pool) => pool.get().await.map(RedisConnection::Sentinel),
+        RedisPool::Cluster(pool) => pool.get().await.map(RedisConnection::Cluster),
+    }
+    .map_err(|e| match e {
+        PoolError::Backend(e) => e,
+        e => RedisError::from((
+            ErrorKind::IoError,
+            "No Redis connection available",
+            e.to_string(),
+        )),
+    })
+}
+
+// SCAN rather than KEYS so a large keyspace never blocks the server
+// a cluster's SCAN only walks the node it lands on, so there each master is walked in turn
+/// # Errors
+/// returns a `redis` error if a SCAN fails or a cluster's masters can't be listed
+pub async fn scan_keys(
+    connection: &mut RedisConnection,
+    pattern: &str,
+) -> Result<Vec<String>, RedisError> {
+    let nodes = match connection {
+        RedisConnection::Cluster(connection) => cluster_masters(connection)
+            .await?
+            .into_iter()
+            .map(Some)
+            .collect(),
+        _ => vec![None],
+    };
+    let mut keys = Vec::new();
+
+    for node in nodes {
+        let mut cursor = 0_u64;
+        loop {
+            let mut scan = redis::cmd("SCAN");
+            scan.arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH);
+            let (next, batch): (u64, Vec<String>) = match (&mut *connection, &node) {
+                (RedisConnection::Cluster(connection), Some(node)) => redis::from_redis_value(
+                    &connection
+                        .route_command(&scan, RoutingInfo::SingleNode(node.clone()))
+                        .await?,
+                )?,
+                _ => scan.query_async(connection).await?,
+            };
+            keys.extend(batch);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+    }
+
+    Ok(keys)
+}
+
+// each master once, addressed the way the cluster client keys its own connections
+async fn cluster_masters(
+    connection: &mut cluster::Connection,
+) -> Result<Vec<SingleNodeRoutingInfo>, RedisError> {
+    // one entry per slot range, `[start, end, [host, port, id], replicas...]`
+    let ranges: Vec<Vec<Value>> = redis::cmd("CLUSTER")
+        .arg("SLOTS")
+        .query_async(connection)
+        .await?;
+    let mut masters = Vec::new();
+
+    for range in ranges {
+        let Some(master) = range.get(2) else {
+            continue;
+        };
+        // newer servers append the node's metadata, so the fields are read by position
+        let master: Vec<Value> = redis::from_redis_value(master)?;
+        let (Some(host), Some(port)) = (master.first(), master.get(1)) else {
+            continue;
+        };
+        let master = SingleNodeRoutingInfo::ByAddress {
+            host: redis::from_redis_value(host)?,
+            port: redis::from_redis_value(port)?,
+        };
+        if !masters.contains(&master) {
+            masters.push(master);
+        }
+    }
+
+    Ok(masters)
+}
@@ -0,0 +1,148 @@
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::configuration::{RateLimitSettings, Settings, get_configuration};
+use crate::cors::OriginPattern;
+use crate::telemetry::spawn_blocking_with_tracing;
+use crate::tls::{TlsCertificate, TlsError};
+
+// a value that can be swapped while the server runs, readers hold on to
+// the snapshot they got so a reload never changes anything mid-request
+#[derive(Debug)]
+pub struct Reloadable<T>(Arc<RwLock<Arc<T>>>);
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Reloadable<T> {
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(value))))
+    }
+
+    // a panic elsewhere can't leave a half-written value behind, the swap is a single store
+    #[must_use]
+    pub fn get(&self) -> Arc<T> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn replace(&self, value: T) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(value);
+    }
+}
+
+// the settings that take effect without a restart, everything else is read once at startup
+#[derive(Clone, Debug)]
+pub struct ReloadableSettings {
+    pub rate_limit: Reloadable<RateLimitSettings>,
+    pub cors_allowed_origins: Reloadable<Vec<String>>,
+    pub cors_allowed_origin_patterns: Reloadable<Vec<OriginPattern>>,
+    pub maintenance_mode: Reloadable<bool>,
+    // `None` when the server speaks plain http, https can't be switched on by a reload
+    pub tls: Option<TlsCertificate>,
+}
+
+impl ReloadableSettings {
+    /// # Errors
+    /// returns a `TlsError` if the configured certificate can't be loaded
+    pub fn new(settings: &Settings) -> Result<Self, TlsError> {
+        let tls = settings
+            .application
+            .tls_paths()
+            .map(|(cert_path, key_path)| TlsCertificate::load(cert_path, key_path))
+            .transpose()?;
+
+        Ok(Self {
+            rate_limit: Reloadable::new(settings.rate_limit.clone()),
+            cors_allowed_origins: Reloadable::new(settings.cors.allowed_origins.clone()),
+            cors_allowed_origin_patterns: Reloadable::new(
+                settings.cors.allowed_origin_patterns.clone(),
+            ),
+            maintenance_mode: Reloadable::new(settings.application.maintenance_mode),
+            tls,
+        })
+    }
+
+    pub fn apply(&self, settings: &Settings) {
+        self.rate_limit.replace(settings.rate_limit.clone());
+        self.cors_allowed_origins
+            .replace(settings.cors.allowed_origins.clone());
+        self.cors_allowed_origin_patterns
+            .replace(settings.cors.allowed_origin_patterns.clone());
+        self.maintenance_mode
+            .replace(settings.application.maintenance_mode);
+
+        if let (Some(tls), Some((cert_path, key_path))) =
+            (&self.tls, settings.application.tls_paths())
+            && let Err(e) = tls.reload(cert_path, key_path)
+        {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to reload the tls certificate, keeping the current one"
+            );
+        }
+    }
+}
+
+// re-reads the configuration on every SIGHUP, a file that no longer parses
+// is logged and ignored so a typo can't take the running settings with it
+// on a blocking thread, resolving secrets can mean a blocking call to vault
+/// # Errors
+/// returns an error if the signal handler can't be installed
+#[cfg(unix)]
+pub async fn reload_on_hangup(reloadable: ReloadableSettings) -> Result<(), anyhow::Error> {
+    use anyhow::Context;
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = signal(SignalKind::hangup())?;
+    while hangups.recv().await.is_some() {
+        let reloaded = {
+            let reloadable = reloadable.clone();
+            spawn_blocking_with_tracing(move || {
+                let settings = get_configuration()?;
+                reloadable.apply(&settings);
+                Ok::<_, anyhow::Error>(())
+            })
+            .await
+            .context("Failed to spawn blocking task.")
+            .and_then(|reloaded| reloaded)
+        };
+        match reloaded {
+            Ok(()) => tracing::info!("Configuration reloaded"),
+            Err(e) => tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to reload the configuration, keeping the current settings"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn reload_on_hangup(_reloadable: ReloadableSettings) -> Result<(), anyhow::Error> {
+    std::future::pending().await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snapshots_outlive_a_replace() {
+        let origins = Reloadable::new(vec!["https://a.example".to_string()]);
+        let before = origins.get();
+
+        origins.replace(vec!["https://b.example".to_string()]);
+
+        assert_eq!(before.as_slice(), ["https://a.example"]);
+        assert_eq!(origins.get().as_slice(), ["https://b.example"]);
+    }
+}
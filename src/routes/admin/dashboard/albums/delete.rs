@@ -0,0 +1,62 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::AlbumError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{album::AlbumDeleteRequest, api_response::ApiResponse},
+    utils::e500,
+};
+
+// the photos stay in the media library, only the album goes
+#[tracing::instrument(
+    name = "Delete album",
+    skip_all,
+    fields(user_id = %*user_id, album_id = %album.album_id)
+)]
+pub async fn delete_album(
+    album: web::Json<AlbumDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let album_to_delete = album.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&album_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_album(tx, album_to_delete).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_delete_album(
+    transaction: &mut Transaction<'static, Postgres>,
+    album: AlbumDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let album_id = album.album_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM albums
+        WHERE album_id = $1
+        "#,
+        album_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Album delete query failed");
+        AlbumError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Album not found: {}", album_id);
+        return Err(AlbumError::AlbumNotFound.into());
+    }
+    tracing::info!("Album {} deleted successfully", album_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
@@ -0,0 +1,48 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::{
+    errors::AlbumError,
+    types::{
+        album::{AlbumRecord, AlbumRecordRaw},
+        api_response::ApiResponse,
+    },
+};
+
+// drafts included, read from the primary so an album shows up right after it's saved
+#[tracing::instrument(name = "Get all albums", skip(pool))]
+pub async fn get_all_albums(
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<Vec<AlbumRecord>>, AlbumError> {
+    let albums = sqlx::query_as!(
+        AlbumRecordRaw,
+        r#"
+        SELECT
+            album_id,
+            title,
+            slug,
+            description,
+            photos AS "photos: serde_json::Value",
+            published,
+            display_order,
+            created_at,
+            updated_at
+        FROM albums
+        ORDER BY display_order, created_at DESC"#
+    )
+    .fetch_all(pool.as_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch albums: {e:?}");
+        AlbumError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .into_iter()
+    .map(AlbumRecord::try_from)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| {
+        tracing::error!("Failed to deserialize album photos: {e:?}");
+        AlbumError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    Ok(ApiResponse::ok(albums))
+}
@@ -0,0 +1,156 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::AlbumError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        album::{AlbumEditRequest, AlbumOrderRequest},
+        api_response::ApiResponse,
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit album", skip_all)]
+pub async fn edit_album(
+    album_edit_request: web::Json<AlbumEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let album = album_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    album.validate().map_err(actix_web::Error::from)?;
+    if album.is_empty() {
+        return Err(AlbumError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&album).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_album(tx, album).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_album(
+    transaction: &mut Transaction<'static, Postgres>,
+    album: AlbumEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let album_id = album.album_id;
+
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE albums SET updated_at = NOW()");
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                builder.push(concat!(", ", $col, " = "));
+                builder.push_bind(val);
+            }
+        };
+    }
+
+    let photos = album
+        .photos
+        .as_ref()
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| AlbumError::UnexpectedError(anyhow::anyhow!(e)))?;
+
+    push_if_some!(album.title, "title");
+    push_if_some!(album.description, "description");
+    push_if_some!(photos, "photos");
+    push_if_some!(album.published, "published");
+
+    builder.push(" WHERE album_id = ");
+    builder.push_bind(album_id);
+
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Album update query failed");
+            AlbumError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Album not found: {}", album_id);
+        return Err(AlbumError::AlbumNotFound.into());
+    }
+
+    tracing::info!("Album {} updated successfully", album_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
+
+#[tracing::instrument(
+    name = "Reorder albums",
+    skip_all,
+    fields(count = order.album_ids.len())
+)]
+pub async fn reorder_albums(
+    order: web::Json<AlbumOrderRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let order = order.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    let fingerprint = RequestFingerprint::of(&order).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_reorder_albums(tx, order).await })
+    })
+    .await
+}
+
+// all or nothing, a list that misses or repeats an album changes nothing
+#[allow(clippy::future_not_send)]
+async fn process_reorder_albums(
+    transaction: &mut Transaction<'static, Postgres>,
+    order: AlbumOrderRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    // locked so two reorders can't interleave
+    let current = sqlx::query_scalar!(r#"SELECT album_id FROM albums FOR UPDATE"#)
+        .fetch_all(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Album order query failed");
+            AlbumError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+        })?;
+
+    let requested: HashSet<Uuid> = order.album_ids.iter().copied().collect();
+    if requested.len() != order.album_ids.len()
+        || requested != current.into_iter().collect::<HashSet<_>>()
+    {
+        tracing::warn!("Album order doesn't match the stored albums");
+        return Err(AlbumError::InvalidOrder.into());
+    }
+
+    // only albums that actually move count as updated, so the gallery's Last-Modified moves too
+    sqlx::query!(
+        r#"
+        UPDATE albums
+        SET display_order = ordering.position - 1, updated_at = NOW()
+        FROM UNNEST($1::uuid[]) WITH ORDINALITY AS ordering(album_id, position)
+        WHERE albums.album_id = ordering.album_id
+            AND albums.display_order <> ordering.position - 1
+        "#,
+        &order.album_ids
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Album reorder query failed");
+        AlbumError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    tracing::info!("Albums reordered");
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
@@ -0,0 +1,99 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::AlbumError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        album::{AlbumForm, AlbumId, AlbumResponse},
+        api_response::ApiResponse,
+    },
+    utils::{e500, slugify},
+};
+
+#[tracing::instrument(
+    name = "Insert album",
+    skip(album, pool, request, user_id),
+    fields(
+        album_id = tracing::field::Empty
+    )
+)]
+pub async fn insert_album(
+    album: web::Json<AlbumForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let album = album.into_inner();
+    let user_id = Some(**user_id);
+
+    album.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&album).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_album(tx, album).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_new_album(
+    transaction: &mut Transaction<'static, Postgres>,
+    album: AlbumForm,
+) -> Result<ApiResponse<AlbumResponse>, actix_web::Error> {
+    let album_id = AlbumId(Uuid::new_v4());
+    let slug = slugify(&album.title);
+    let photos = serde_json::to_value(&album.photos).map_err(|e| {
+        AlbumError::UnexpectedError(anyhow::anyhow!("Failed to serialize photos: {e:?}"))
+    })?;
+    tracing::Span::current().record("album_id", tracing::field::display(&album_id));
+
+    // new albums lead the gallery until they're reordered, like projects
+    let insert_result = sqlx::query!(
+        r#"
+        INSERT INTO albums (
+            album_id,
+            title,
+            slug,
+            description,
+            photos,
+            published,
+            display_order,
+            created_at,
+            updated_at)
+        SELECT $1, $2, $3, $4, $5, $6, COALESCE(MIN(display_order) - 1, 0), NOW(), NOW()
+        FROM albums"#,
+        *album_id,
+        album.title,
+        slug,
+        album.description,
+        photos,
+        album.published
+    )
+    .execute(transaction.as_mut())
+    .await;
+
+    match insert_result {
+        Ok(_) => {
+            tracing::info!("Album saved successfully with: {}", album_id);
+            Ok(ApiResponse::accepted(AlbumResponse::new(
+                "Album received successfully",
+                album_id,
+            )))
+        }
+        Err(e) => {
+            if let sqlx::Error::Database(db_err) = &e
+                && db_err.code().as_deref() == Some("23505")
+            {
+                tracing::warn!("Duplicate album detected");
+                return Err(AlbumError::DuplicateAlbum.into());
+            }
+
+            tracing::error!("Failed to save album: {e:?}");
+            Err(AlbumError::UnexpectedError(anyhow::anyhow!("Saving album failed: {e:?}")).into())
+        }
+    }
+}
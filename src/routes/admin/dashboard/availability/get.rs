@@ -0,0 +1,53 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::{
+    errors::AvailabilityError,
+    types::{
+        api_response::ApiResponse,
+        availability::{AvailabilityRecord, AvailabilityStatus},
+        pagination::{PaginationMeta, PaginationQuery},
+    },
+};
+
+// every availability that has been advertised, newest first
+#[tracing::instrument(name = "Get availability history", skip(pool))]
+pub async fn get_availability_history(
+    query: web::Query<PaginationQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<Vec<AvailabilityRecord>>, AvailabilityError> {
+    let query = query.into_inner();
+
+    let total_count = sqlx::query_scalar!("SELECT COUNT(*) FROM availability")
+        .fetch_one(pool.as_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to count availability history: {e:?}");
+            AvailabilityError::UnexpectedError(anyhow::anyhow!(e))
+        })?
+        .unwrap_or(0);
+
+    let history = sqlx::query_as!(
+        AvailabilityRecord,
+        r#"
+        SELECT
+            availability_id,
+            status AS "status: AvailabilityStatus",
+            booked_until,
+            note,
+            created_at
+        FROM availability
+        ORDER BY created_at DESC
+        LIMIT $1 OFFSET $2"#,
+        query.limit(),
+        query.offset()
+    )
+    .fetch_all(pool.as_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch availability history: {e:?}");
+        AvailabilityError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    Ok(ApiResponse::ok(history).with_pagination(PaginationMeta::from_total(total_count, &query)))
+}
@@ -0,0 +1,5 @@
+mod get;
+mod put;
+
+pub use get::*;
+pub use put::*;
@@ -0,0 +1,62 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::AvailabilityError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        availability::{AvailabilityForm, AvailabilityStatus},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Set availability", skip_all, fields(user_id = %*user_id))]
+pub async fn set_availability(
+    availability: web::Json<AvailabilityForm>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let availability = availability.into_inner();
+    let user_id = **user_id;
+
+    availability.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&availability).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, Some(user_id), &fingerprint, move |tx| {
+        Box::pin(async move { process_set_availability(tx, availability, user_id).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_set_availability(
+    transaction: &mut Transaction<'static, Postgres>,
+    availability: AvailabilityForm,
+    user_id: Uuid,
+) -> Result<ApiResponse, actix_web::Error> {
+    // a new row rather than an update, so the history keeps what was advertised before
+    sqlx::query!(
+        r#"
+        INSERT INTO availability (status, booked_until, note, set_by)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        availability.status as AvailabilityStatus,
+        availability.booked_until,
+        availability.note,
+        user_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Failed to save availability: {e:?}");
+        AvailabilityError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    tracing::info!("Availability updated successfully");
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
@@ -1,9 +1,13 @@
-use actix_web::{HttpRequest, HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
 use sqlx::{PgPool, Postgres, Transaction};
 
 use crate::{
-    authentication::UserId, errors::BlogError, idempotency::execute_idempotent,
-    types::article::ArticleDeleteRequest,
+    authentication::UserId,
+    blog_cache::BlogCache,
+    errors::BlogError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, article::ArticleDeleteRequest},
+    utils::e500,
 };
 
 #[tracing::instrument(
@@ -16,21 +20,27 @@ pub async fn delete_article(
     user_id: web::ReqData<UserId>,
     request: HttpRequest,
     pool: web::Data<PgPool>,
+    cache: web::Data<BlogCache>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let article_to_delete = article.0;
     let user_id = Some(**user_id);
 
-    execute_idempotent(&request, &pool, user_id, move |tx| {
+    let fingerprint = RequestFingerprint::of(&article_to_delete).map_err(e500)?;
+
+    let response = execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
         Box::pin(async move { process_delete_article(tx, article_to_delete).await })
     })
-    .await
+    .await?;
+
+    cache.invalidate_all();
+    Ok(response)
 }
 
 #[allow(clippy::future_not_send)]
 async fn process_delete_article(
     transaction: &mut Transaction<'static, Postgres>,
     article: ArticleDeleteRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let post_id = article.post_id;
 
     let result = sqlx::query!(
@@ -49,8 +59,13 @@ async fn process_delete_article(
 
     match result.rows_affected() {
         1 => {
+            // moves the list's Last-Modified, which only sees the posts that are left
+            sqlx::query!("UPDATE blog_deletions SET last_deleted_at = NOW()")
+                .execute(transaction.as_mut())
+                .await
+                .map_err(|e| BlogError::UnexpectedError(anyhow::anyhow!("{e:?}")))?;
             tracing::info!("Post {} deleted successfully", post_id);
-            Ok(HttpResponse::Ok().finish())
+            Ok(ApiResponse::empty(StatusCode::OK))
         }
         0 => {
             tracing::warn!("Blog post not found: {}", post_id);
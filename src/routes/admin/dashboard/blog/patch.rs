@@ -1,13 +1,18 @@
 // start easy, just update published flag
-use actix_web::{HttpRequest, HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
 use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
 
 use crate::{
     authentication::UserId,
+    blog_cache::BlogCache,
     // ArticleError?
     errors::BlogError,
-    idempotency::execute_idempotent,
-    types::article::{ArticleEditRequest, ArticlePublishRequest},
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        article::{ArticleEditRequest, ArticlePublishRequest},
+    },
+    utils::e500,
 };
 
 #[tracing::instrument(name = "Edit blog post", skip_all)]
@@ -16,23 +21,30 @@ pub async fn edit_article(
     user_id: web::ReqData<UserId>,
     request: HttpRequest,
     pool: web::Data<PgPool>,
+    cache: web::Data<BlogCache>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let article_to_edit = article_edit_request.into_inner();
     let user_id = Some(*user_id.into_inner());
 
     article_to_edit.validate().map_err(actix_web::Error::from)?;
 
-    execute_idempotent(&request, &pool, user_id, move |tx| {
+    let fingerprint = RequestFingerprint::of(&article_to_edit).map_err(e500)?;
+
+    let response = execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
         Box::pin(async move { process_edit_article(tx, article_to_edit).await })
     })
-    .await
+    .await?;
+
+    // only once the change has committed, so the next read can't cache the old post again
+    cache.invalidate_all();
+    Ok(response)
 }
 
 #[allow(clippy::future_not_send)]
 async fn process_edit_article(
     transaction: &mut Transaction<'static, Postgres>,
     article: ArticleEditRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let post_id = article.post_id;
 
     let mut builder = QueryBuilder::<Postgres>::new("UPDATE blog_posts SET ");
@@ -82,7 +94,7 @@ async fn process_edit_article(
     match result.rows_affected() {
         1 => {
             tracing::info!("Post {} updated successfully", post_id);
-            Ok(HttpResponse::Accepted().finish())
+            Ok(ApiResponse::empty(StatusCode::ACCEPTED))
         }
         0 => {
             tracing::warn!("Blog post not found: {}", post_id);
@@ -108,21 +120,27 @@ pub async fn publish_article(
     user_id: web::ReqData<UserId>,
     request: HttpRequest,
     pool: web::Data<PgPool>,
+    cache: web::Data<BlogCache>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let article_to_publish = article.0;
     let user_id = Some(*user_id.into_inner());
 
-    execute_idempotent(&request, &pool, user_id, move |tx| {
+    let fingerprint = RequestFingerprint::of(&article_to_publish).map_err(e500)?;
+
+    let response = execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
         Box::pin(async move { process_publish_article(tx, article_to_publish).await })
     })
-    .await
+    .await?;
+
+    cache.invalidate_all();
+    Ok(response)
 }
 
 #[allow(clippy::future_not_send)]
 async fn process_publish_article(
     transaction: &mut Transaction<'static, Postgres>,
     article: ArticlePublishRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let post_id = article.post_id;
     let is_published = article.published;
 
@@ -144,7 +162,7 @@ async fn process_publish_article(
     match result.rows_affected() {
         1 => {
             tracing::info!("Post {} updated successfully", post_id);
-            Ok(HttpResponse::Accepted().finish())
+            Ok(ApiResponse::empty(StatusCode::ACCEPTED))
         }
         0 => {
             tracing::warn!("Blog post not found: {}", post_id);
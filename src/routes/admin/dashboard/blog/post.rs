@@ -5,8 +5,12 @@ use uuid::Uuid;
 use crate::{
     authentication::UserId,
     errors::BlogError,
-    idempotency::execute_idempotent,
-    types::article::{ArticleForm, ArticleId, ArticleResponse},
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        article::{ArticleForm, ArticleId, ArticleResponse},
+    },
+    utils::{e500, slugify},
 };
 
 #[tracing::instrument(
@@ -27,7 +31,9 @@ pub async fn insert_article(
 
     blog_to_post.validate().map_err(actix_web::Error::from)?;
 
-    execute_idempotent(&request, &pool, user_id, move |tx| {
+    let fingerprint = RequestFingerprint::of(&blog_to_post).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
         Box::pin(async move { process_new_article(tx, blog_to_post).await })
     })
     .await
@@ -37,9 +43,9 @@ pub async fn insert_article(
 async fn process_new_article(
     transaction: &mut Transaction<'static, Postgres>,
     article: ArticleForm,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<ArticleResponse>, actix_web::Error> {
     let post_id = ArticleId(Uuid::new_v4());
-    let slug = get_article_slug(&article.title);
+    let slug = slugify(&article.title);
     let sections_json = article.sections_as_json().map_err(|e| {
         BlogError::UnexpectedError(anyhow::anyhow!("Failed to serialize sections: {e:?}"))
     })?;
@@ -71,8 +77,10 @@ async fn process_new_article(
     match insert_result {
         Ok(_) => {
             tracing::info!("Post saved successfully with: {}", post_id);
-            Ok(HttpResponse::Accepted()
-                .json(ArticleResponse::new("Post received successfully", post_id)))
+            Ok(ApiResponse::accepted(ArticleResponse::new(
+                "Post received successfully",
+                post_id,
+            )))
         }
         Err(e) => {
             if let sqlx::Error::Database(db_err) = &e
@@ -87,24 +95,3 @@ async fn process_new_article(
         }
     }
 }
-
-fn get_article_slug(title: &str) -> String {
-    title
-        .replace(' ', "-")
-        .chars()
-        .filter(|c| c.is_ascii_alphabetic() || *c == '-')
-        .collect::<String>()
-        .to_ascii_lowercase()
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn article_slug() {
-        let title = "New Blog Title".to_string();
-        let slug = get_article_slug(&title);
-        assert_eq!(slug, "new-blog-title".to_string())
-    }
-}
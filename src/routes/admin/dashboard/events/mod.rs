@@ -0,0 +1,5 @@
+mod socket;
+mod stream;
+
+pub use socket::*;
+pub use stream::*;
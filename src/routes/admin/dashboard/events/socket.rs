@@ -0,0 +1,59 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use actix_ws::{Message, MessageStream, Session};
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::events::{DashboardEvent, EventBus};
+
+// upgrades to a websocket and relays every dashboard event as a json text frame,
+// auth is handled by the admin scope before the upgrade happens
+#[allow(clippy::future_not_send)]
+#[tracing::instrument(name = "Open dashboard event socket", skip_all)]
+pub async fn dashboard_events(
+    request: HttpRequest,
+    body: web::Payload,
+    bus: web::Data<EventBus>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (response, session, messages) = actix_ws::handle(&request, body)?;
+
+    actix_web::rt::spawn(relay_events(session, messages, bus.subscribe()));
+
+    Ok(response)
+}
+
+async fn relay_events(
+    mut session: Session,
+    mut messages: MessageStream,
+    mut events: Receiver<DashboardEvent>,
+) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(json) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if session.text(json).await.is_err() {
+                        return;
+                    }
+                }
+                // a slow client just misses what fell out of the buffer
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Dashboard socket lagged behind");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = messages.recv() => match message {
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                // the socket is push-only, anything else from the client is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = session.close(None).await;
+}
@@ -0,0 +1,32 @@
+use actix_web::{
+    HttpResponse,
+    http::header::CACHE_CONTROL,
+    web::{self, Bytes},
+};
+use tokio_stream::{
+    StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+
+use crate::events::EventBus;
+
+// the same events as the socket, as server-sent events for a dashboard that only listens,
+// one `data:` frame per event
+#[tracing::instrument(name = "Stream dashboard events", skip_all)]
+pub async fn dashboard_event_stream(bus: web::Data<EventBus>) -> HttpResponse {
+    let events = BroadcastStream::new(bus.subscribe()).filter_map(|event| match event {
+        Ok(event) => {
+            Some(serde_json::to_string(&event).map(|json| Bytes::from(format!("data: {json}\n\n"))))
+        }
+        // a slow client just misses what fell out of the buffer
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::warn!(skipped, "Dashboard event stream lagged behind");
+            None
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .streaming(events)
+}
@@ -0,0 +1,61 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ExperienceError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, experience::ExperienceDeleteRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Delete experience",
+    skip_all,
+    fields(user_id = %*user_id, experience_id = %experience.experience_id)
+)]
+pub async fn delete_experience(
+    experience: web::Json<ExperienceDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let experience_to_delete = experience.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&experience_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_experience(tx, experience_to_delete).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_delete_experience(
+    transaction: &mut Transaction<'static, Postgres>,
+    experience: ExperienceDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let experience_id = experience.experience_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM experience
+        WHERE experience_id = $1
+        "#,
+        experience_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Experience delete query failed");
+        ExperienceError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Experience not found: {}", experience_id);
+        return Err(ExperienceError::ExperienceNotFound.into());
+    }
+    tracing::info!("Experience {} deleted successfully", experience_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
@@ -0,0 +1,82 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ExperienceError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, experience::ExperienceEditRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit experience", skip_all)]
+pub async fn edit_experience(
+    experience_edit_request: web::Json<ExperienceEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let experience_to_edit = experience_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    experience_to_edit
+        .validate()
+        .map_err(actix_web::Error::from)?;
+    if experience_to_edit.is_empty() {
+        return Err(ExperienceError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&experience_to_edit).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_experience(tx, experience_to_edit).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_experience(
+    transaction: &mut Transaction<'static, Postgres>,
+    experience: ExperienceEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let experience_id = experience.experience_id;
+
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE experience SET updated_at = NOW()");
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                builder.push(concat!(", ", $col, " = "));
+                builder.push_bind(val);
+            }
+        };
+    }
+
+    push_if_some!(experience.company, "company");
+    push_if_some!(experience.role, "role");
+    push_if_some!(experience.start_date, "start_date");
+    push_if_some!(experience.end_date, "end_date");
+    push_if_some!(experience.highlights, "highlights");
+    push_if_some!(experience.tech, "tech");
+
+    builder.push(" WHERE experience_id = ");
+    builder.push_bind(experience_id);
+
+    // the dates are checked against the stored ones by the table's constraint
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Experience update query failed");
+            ExperienceError::from(e)
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Experience not found: {}", experience_id);
+        return Err(ExperienceError::ExperienceNotFound.into());
+    }
+
+    tracing::info!("Experience {} updated successfully", experience_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
@@ -0,0 +1,83 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::ExperienceError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        experience::{ExperienceForm, ExperienceId, ExperienceResponse},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Insert experience",
+    skip(experience, pool, request, user_id),
+    fields(
+        experience_id = tracing::field::Empty
+    )
+)]
+pub async fn insert_experience(
+    experience: web::Json<ExperienceForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let experience = experience.into_inner();
+    let user_id = Some(**user_id);
+
+    experience.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&experience).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_experience(tx, experience).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_new_experience(
+    transaction: &mut Transaction<'static, Postgres>,
+    experience: ExperienceForm,
+) -> Result<ApiResponse<ExperienceResponse>, actix_web::Error> {
+    let experience_id = ExperienceId(Uuid::new_v4());
+    tracing::Span::current().record("experience_id", tracing::field::display(&experience_id));
+
+    sqlx::query!(
+        r#"
+        INSERT INTO experience(
+        experience_id,
+        company,
+        role,
+        start_date,
+        end_date,
+        highlights,
+        tech,
+        created_at,
+        updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())"#,
+        *experience_id,
+        experience.company,
+        experience.role,
+        experience.start_date,
+        experience.end_date,
+        &experience.highlights,
+        &experience.tech
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Failed to save experience: {e:?}");
+        ExperienceError::from(e)
+    })?;
+
+    tracing::info!("Experience saved successfully with: {}", experience_id);
+    Ok(ApiResponse::accepted(ExperienceResponse::new(
+        "Experience received successfully",
+        experience_id,
+    )))
+}
@@ -1,10 +1,11 @@
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{
     errors::MessageGetError,
+    types::api_response::ApiResponse,
     types::pagination::{PaginationMeta, PaginationQuery},
 };
 
@@ -14,7 +15,7 @@ use crate::{
 // does this need any other functionality?
 
 #[derive(serde::Serialize)]
-struct MessageRecord {
+pub struct MessageRecord {
     message_id: Uuid,
     email: String,
     sender_name: String,
@@ -23,10 +24,11 @@ struct MessageRecord {
     read_message: Option<bool>,
 }
 
+// the shape before `ApiResponse`, still sent to clients that haven't asked for the envelope
 #[derive(serde::Serialize)]
-struct MessagesResponse {
+struct MessagesResponse<'a> {
     // Keep your old top-level list key:
-    messages: Vec<MessageRecord>, // <- use your existing message DTO type
+    messages: &'a [MessageRecord], // <- use your existing message DTO type
 
     // Keep old pagination keys:
     page: i64,
@@ -39,7 +41,7 @@ struct MessagesResponse {
 pub async fn get_messages(
     query: web::Query<PaginationQuery>,
     pool: web::Data<PgPool>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<Vec<MessageRecord>>, actix_web::Error> {
     let q = query.into_inner();
     let page_size = q.page_size();
     let offset = q.offset();
@@ -72,13 +74,15 @@ pub async fn get_messages(
 
     let meta = PaginationMeta::from_total(total_count, &q);
 
-    let response = MessagesResponse {
-        messages,
-        page: meta.page,
-        page_size: meta.page_size,
-        total_items: meta.total_items,
-        total_pages: meta.total_pages,
-    };
-
-    Ok(HttpResponse::Ok().json(response))
+    Ok(ApiResponse::ok(messages)
+        .with_legacy_body(|messages| {
+            serde_json::to_value(MessagesResponse {
+                messages,
+                page: meta.page,
+                page_size: meta.page_size,
+                total_items: meta.total_items,
+                total_pages: meta.total_pages,
+            })
+        })
+        .with_pagination(meta))
 }
@@ -1,10 +1,16 @@
-use actix_web::{HttpRequest, HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
 use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::{authentication::UserId, errors::MessagePatchError, idempotency::execute_idempotent};
+use crate::{
+    authentication::UserId,
+    errors::MessagePatchError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::api_response::ApiResponse,
+    utils::e500,
+};
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct MessagePatchRequest {
     message_id: Uuid,
     read: bool,
@@ -23,8 +29,9 @@ pub async fn patch_message(
 ) -> Result<HttpResponse, actix_web::Error> {
     let message_to_patch = message.0;
     let user_id = Some(**user_id);
+    let fingerprint = RequestFingerprint::of(&message_to_patch).map_err(e500)?;
 
-    execute_idempotent(&request, &pool, user_id, move |tx| {
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
         Box::pin(async move { process_patch_message(tx, message_to_patch).await })
     })
     .await
@@ -34,7 +41,7 @@ pub async fn patch_message(
 async fn process_patch_message(
     transaction: &mut Transaction<'static, Postgres>,
     message: MessagePatchRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let message_id = message.message_id;
     let is_read = message.read;
 
@@ -57,7 +64,7 @@ async fn process_patch_message(
     match result.rows_affected() {
         1 => {
             tracing::info!("Message {} updated successfully", message_id);
-            Ok(HttpResponse::Accepted().finish())
+            Ok(ApiResponse::empty(StatusCode::ACCEPTED))
         }
         0 => {
             tracing::warn!("Message not found: {}", message_id);
@@ -0,0 +1,22 @@
+use actix_web::web;
+
+use crate::metrics::{MetricsWindowQuery, campaign_breakdown};
+use crate::startup::DbPools;
+use crate::types::api_response::ApiResponse;
+use crate::utils::{QueryTimeout, with_query_timeout};
+
+// visits per utm source/medium/campaign, busiest first
+#[tracing::instrument(name = "Get campaign breakdown", skip(pools, timeout))]
+pub async fn get_campaigns(
+    query: web::Query<MetricsWindowQuery>,
+    pools: web::Data<DbPools>,
+    timeout: web::Data<QueryTimeout>,
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
+    let campaigns =
+        with_query_timeout(**timeout, campaign_breakdown(query.window, &pools.reader)).await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "window_hours": query.window.hours(),
+        "campaigns": campaigns,
+    })))
+}
@@ -0,0 +1,22 @@
+use actix_web::web;
+
+use crate::metrics::{MetricsWindowQuery, error_breakdown};
+use crate::startup::DbPools;
+use crate::types::api_response::ApiResponse;
+use crate::utils::{QueryTimeout, with_query_timeout};
+
+// 4xx/5xx counts per route and status class, noisiest first
+#[tracing::instrument(name = "Get error breakdown", skip(pools, timeout))]
+pub async fn get_error_breakdown(
+    query: web::Query<MetricsWindowQuery>,
+    pools: web::Data<DbPools>,
+    timeout: web::Data<QueryTimeout>,
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
+    let errors =
+        with_query_timeout(**timeout, error_breakdown(query.window, &pools.reader)).await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "window_hours": query.window.hours(),
+        "errors": errors,
+    })))
+}
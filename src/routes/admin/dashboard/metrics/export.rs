@@ -0,0 +1,82 @@
+use actix_web::{
+    HttpResponse,
+    http::{
+        StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    web,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::metrics::{ExportFormat, ExportQuery, stream_export};
+use crate::storage::{EXPORTS_PREFIX, Storage};
+use crate::types::api_response::ApiResponse;
+use crate::utils::{e400, e500};
+
+#[derive(serde::Serialize, Debug)]
+pub struct StoredExport {
+    pub key: String,
+    // presigned, so it works without a session until it expires
+    pub download_url: String,
+}
+
+// raw analytics rows for a date range, streamed as a download
+#[tracing::instrument(name = "Export analytics", skip(pool))]
+pub async fn export_analytics(
+    query: web::Query<ExportQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    query.validate().map_err(e400)?;
+    let query = query.into_inner();
+
+    let (content_type, filename) = export_file(&query);
+
+    Ok(HttpResponse::Ok()
+        .insert_header((CONTENT_TYPE, content_type))
+        .insert_header((
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        ))
+        .streaming(stream_export(query, pool.get_ref().clone())))
+}
+
+// the same rows written to storage instead, for exports too big to sit through a request
+// or to hand to something that can only fetch a url
+#[tracing::instrument(name = "Store an analytics export", skip(pool, storage))]
+pub async fn store_analytics_export(
+    query: web::Json<ExportQuery>,
+    pool: web::Data<PgPool>,
+    storage: web::Data<Storage>,
+) -> Result<ApiResponse<StoredExport>, actix_web::Error> {
+    query.validate().map_err(e400)?;
+    let query = query.into_inner();
+    let (_, filename) = export_file(&query);
+    // a fresh directory per export, so a repeated range never overwrites a link handed out
+    let key = format!("{EXPORTS_PREFIX}/{}/{filename}", Uuid::new_v4());
+
+    storage
+        .put_stream(&key, stream_export(query, pool.get_ref().clone()))
+        .await
+        .map_err(e500)?;
+    let download_url = storage.presigned_download_url(&key).await.map_err(e500)?;
+
+    Ok(ApiResponse::with_status(
+        StatusCode::CREATED,
+        StoredExport { key, download_url },
+    ))
+}
+
+fn export_file(query: &ExportQuery) -> (&'static str, String) {
+    let (content_type, extension) = match query.format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        ExportFormat::Json => ("application/x-ndjson", "ndjson"),
+    };
+    let filename = format!(
+        "{}_{}_{}.{extension}",
+        query.dataset.as_str(),
+        query.from,
+        query.to
+    );
+    (content_type, filename)
+}
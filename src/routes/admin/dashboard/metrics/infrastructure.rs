@@ -0,0 +1,14 @@
+use actix_web::web;
+
+use crate::metrics::DigitalOceanBandwidth;
+use crate::types::api_response::ApiResponse;
+
+// host-level numbers from the cloud provider, served from the poller's last reading
+#[tracing::instrument(name = "Get infrastructure metrics", skip_all)]
+pub async fn get_infrastructure(
+    bandwidth: web::Data<DigitalOceanBandwidth>,
+) -> ApiResponse<serde_json::Value> {
+    ApiResponse::ok(serde_json::json!({
+        "digitalocean_bandwidth_24h": bandwidth.latest(),
+    }))
+}
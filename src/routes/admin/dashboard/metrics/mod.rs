@@ -0,0 +1,19 @@
+mod campaigns;
+mod errors;
+mod export;
+mod infrastructure;
+mod realtime;
+mod sessions;
+mod slow_requests;
+mod summary;
+mod vitals;
+
+pub use campaigns::*;
+pub use errors::*;
+pub use export::*;
+pub use infrastructure::*;
+pub use realtime::*;
+pub use sessions::*;
+pub use slow_requests::*;
+pub use summary::*;
+pub use vitals::*;
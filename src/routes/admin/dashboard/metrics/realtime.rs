@@ -0,0 +1,50 @@
+use actix_web::{
+    HttpResponse,
+    http::header::CACHE_CONTROL,
+    web::{self, Bytes},
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio_stream::{StreamExt, wrappers::WatchStream};
+
+use crate::metrics::{
+    RealtimeSnapshot, RealtimeStatsFeed, active_visitors, current_page_views, recent_errors,
+};
+use crate::types::api_response::ApiResponse;
+use crate::utils::e500;
+
+// server-sent events, one `data:` frame per sample
+#[tracing::instrument(name = "Stream realtime stats", skip_all)]
+pub async fn realtime_stats(feed: web::Data<RealtimeStatsFeed>) -> HttpResponse {
+    let events = WatchStream::new(feed.subscribe())
+        .filter_map(|stats| stats)
+        .map(|stats| {
+            serde_json::to_string(&stats).map(|json| Bytes::from(format!("data: {json}\n\n")))
+        });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .streaming(events)
+}
+
+// one-off version of the stream for the dashboard's first paint, with the
+// per-page and error detail the stream doesn't carry
+#[tracing::instrument(name = "Get realtime snapshot", skip(pool))]
+pub async fn get_realtime_snapshot(
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<RealtimeSnapshot>, actix_web::Error> {
+    let (active_visitors, current_pages, recent_errors) = tokio::try_join!(
+        active_visitors(&pool),
+        current_page_views(&pool),
+        recent_errors(&pool)
+    )
+    .map_err(e500)?;
+
+    Ok(ApiResponse::ok(RealtimeSnapshot {
+        active_visitors,
+        current_pages,
+        recent_errors,
+        generated_at: Utc::now(),
+    }))
+}
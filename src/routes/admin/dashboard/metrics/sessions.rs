@@ -0,0 +1,28 @@
+use actix_web::web;
+
+use crate::metrics::{MetricsWindowQuery, recent_sessions, session_stats};
+use crate::startup::DbPools;
+use crate::types::api_response::ApiResponse;
+use crate::utils::{QueryTimeout, with_query_timeout};
+
+// aggregate depth/duration/bounce plus the most recent sessions, newest first
+#[tracing::instrument(name = "Get sessions report", skip(pools, timeout))]
+pub async fn get_session_report(
+    query: web::Query<MetricsWindowQuery>,
+    pools: web::Data<DbPools>,
+    timeout: web::Data<QueryTimeout>,
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
+    let (stats, sessions) = with_query_timeout(**timeout, async {
+        tokio::try_join!(
+            session_stats(query.window, &pools.reader),
+            recent_sessions(query.window, &pools.reader)
+        )
+    })
+    .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "window_hours": query.window.hours(),
+        "stats": stats,
+        "sessions": sessions,
+    })))
+}
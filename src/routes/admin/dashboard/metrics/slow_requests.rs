@@ -0,0 +1,25 @@
+use actix_web::web;
+
+use crate::configuration::MetricsSettings;
+use crate::metrics::{MetricsWindowQuery, slow_requests};
+use crate::startup::DbPools;
+use crate::types::api_response::ApiResponse;
+use crate::utils::{QueryTimeout, with_query_timeout};
+
+// newest first, capped, so a bad hour doesn't return thousands of rows
+#[tracing::instrument(name = "Get slow requests", skip(pools, settings, timeout))]
+pub async fn get_slow_requests(
+    query: web::Query<MetricsWindowQuery>,
+    pools: web::Data<DbPools>,
+    settings: web::Data<MetricsSettings>,
+    timeout: web::Data<QueryTimeout>,
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
+    let requests =
+        with_query_timeout(**timeout, slow_requests(query.window, &pools.reader)).await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "window_hours": query.window.hours(),
+        "threshold_ms": settings.slow_request_threshold_ms,
+        "requests": requests,
+    })))
+}
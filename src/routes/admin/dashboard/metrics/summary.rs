@@ -0,0 +1,19 @@
+use actix_web::web;
+
+use crate::metrics::{MetricsSummary, MetricsWindowQuery, metrics_summary};
+use crate::startup::DbPools;
+use crate::types::api_response::ApiResponse;
+use crate::utils::{QueryTimeout, with_query_timeout};
+
+// visit totals, top pages and session engagement in one call
+#[tracing::instrument(name = "Get metrics summary", skip(pools, timeout))]
+pub async fn get_metrics_summary(
+    query: web::Query<MetricsWindowQuery>,
+    pools: web::Data<DbPools>,
+    timeout: web::Data<QueryTimeout>,
+) -> Result<ApiResponse<MetricsSummary>, actix_web::Error> {
+    let summary =
+        with_query_timeout(**timeout, metrics_summary(query.window, &pools.reader)).await?;
+
+    Ok(ApiResponse::ok(summary))
+}
@@ -0,0 +1,30 @@
+use actix_web::web;
+
+use crate::metrics::{MetricsWindowQuery, VitalsCache, vital_percentiles};
+use crate::startup::DbPools;
+use crate::types::api_response::ApiResponse;
+use crate::utils::{QueryTimeout, with_query_timeout};
+
+// p50/p75/p95 per page and vital, since averages hide the slow tail
+#[tracing::instrument(name = "Get web vital percentiles", skip(pools, cache, timeout))]
+pub async fn get_vitals(
+    query: web::Query<MetricsWindowQuery>,
+    pools: web::Data<DbPools>,
+    cache: web::Data<VitalsCache>,
+    timeout: web::Data<QueryTimeout>,
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
+    let window = query.window;
+
+    let vitals = if let Some(cached) = cache.get(window) {
+        cached
+    } else {
+        let fresh = with_query_timeout(**timeout, vital_percentiles(window, &pools.reader)).await?;
+        cache.insert(window, fresh.clone());
+        fresh
+    };
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "window_hours": window.hours(),
+        "vitals": vitals,
+    })))
+}
@@ -1,9 +1,39 @@
+mod albums;
+mod availability;
 mod blog;
+mod events;
+mod experience;
 mod messages;
+mod metrics;
+mod now;
+mod profile;
+mod projects;
+mod rate_limits;
+mod resume;
+mod scheduler;
+mod short_links;
+mod skills;
 mod totp;
+mod uploads;
 mod user_actions;
+mod uses;
 
+pub use albums::*;
+pub use availability::*;
 pub use blog::*;
+pub use events::*;
+pub use experience::*;
 pub use messages::*;
+pub use metrics::*;
+pub use now::*;
+pub use profile::*;
+pub use projects::*;
+pub use rate_limits::*;
+pub use resume::*;
+pub use scheduler::*;
+pub use short_links::*;
+pub use skills::*;
 pub use totp::*;
+pub use uploads::*;
 pub use user_actions::*;
+pub use uses::*;
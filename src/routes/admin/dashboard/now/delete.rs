@@ -0,0 +1,61 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::NowEntryError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, now_entry::NowEntryDeleteRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Delete now entry",
+    skip_all,
+    fields(user_id = %*user_id, entry_id = %entry.entry_id)
+)]
+pub async fn delete_now_entry(
+    entry: web::Json<NowEntryDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let entry_to_delete = entry.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&entry_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_now_entry(tx, entry_to_delete).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_delete_now_entry(
+    transaction: &mut Transaction<'static, Postgres>,
+    entry: NowEntryDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let entry_id = entry.entry_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM now_entries
+        WHERE entry_id = $1
+        "#,
+        entry_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Now entry delete query failed");
+        NowEntryError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Now entry not found: {}", entry_id);
+        return Err(NowEntryError::EntryNotFound.into());
+    }
+    tracing::info!("Now entry {} deleted successfully", entry_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
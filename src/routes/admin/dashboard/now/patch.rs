@@ -0,0 +1,77 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::NowEntryError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, now_entry::NowEntryEditRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit now entry", skip_all)]
+pub async fn edit_now_entry(
+    entry_edit_request: web::Json<NowEntryEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let entry = entry_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    entry.validate().map_err(actix_web::Error::from)?;
+    if entry.is_empty() {
+        return Err(NowEntryError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&entry).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_now_entry(tx, entry).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_now_entry(
+    transaction: &mut Transaction<'static, Postgres>,
+    entry: NowEntryEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let entry_id = entry.entry_id;
+
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE now_entries SET updated_at = NOW()");
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                builder.push(concat!(", ", $col, " = "));
+                builder.push_bind(val);
+            }
+        };
+    }
+
+    push_if_some!(entry.kind, "kind");
+    push_if_some!(entry.entry_date, "entry_date");
+    push_if_some!(entry.title, "title");
+    push_if_some!(entry.body, "body");
+
+    builder.push(" WHERE entry_id = ");
+    builder.push_bind(entry_id);
+
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Now entry update query failed");
+            NowEntryError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Now entry not found: {}", entry_id);
+        return Err(NowEntryError::EntryNotFound.into());
+    }
+
+    tracing::info!("Now entry {} updated successfully", entry_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
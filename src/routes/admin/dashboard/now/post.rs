@@ -0,0 +1,79 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::NowEntryError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        now_entry::{NowEntryForm, NowEntryId, NowEntryKind, NowEntryResponse},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Insert now entry",
+    skip(entry, pool, request, user_id),
+    fields(
+        entry_id = tracing::field::Empty
+    )
+)]
+pub async fn insert_now_entry(
+    entry: web::Json<NowEntryForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let entry = entry.into_inner();
+    let user_id = Some(**user_id);
+
+    entry.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&entry).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_now_entry(tx, entry).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_new_now_entry(
+    transaction: &mut Transaction<'static, Postgres>,
+    entry: NowEntryForm,
+) -> Result<ApiResponse<NowEntryResponse>, actix_web::Error> {
+    let entry_id = NowEntryId(Uuid::new_v4());
+    tracing::Span::current().record("entry_id", tracing::field::display(&entry_id));
+
+    sqlx::query!(
+        r#"
+        INSERT INTO now_entries (
+            entry_id,
+            kind,
+            entry_date,
+            title,
+            body,
+            created_at,
+            updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())"#,
+        *entry_id,
+        entry.kind as NowEntryKind,
+        entry.entry_date,
+        entry.title,
+        entry.body
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to save now entry: {e:?}");
+        NowEntryError::UnexpectedError(anyhow::anyhow!("Saving now entry failed: {e:?}"))
+    })?;
+
+    tracing::info!("Now entry saved successfully with: {}", entry_id);
+    Ok(ApiResponse::accepted(NowEntryResponse::new(
+        "Entry received successfully",
+        entry_id,
+    )))
+}
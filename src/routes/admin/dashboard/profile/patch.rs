@@ -0,0 +1,78 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ProfileError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, profile::ProfileEditRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit profile", skip_all)]
+pub async fn edit_profile(
+    profile_edit_request: web::Json<ProfileEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let profile = profile_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    profile.validate().map_err(actix_web::Error::from)?;
+    if profile.is_empty() {
+        return Err(ProfileError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&profile).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_profile(tx, profile).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_profile(
+    transaction: &mut Transaction<'static, Postgres>,
+    profile: ProfileEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE profile SET updated_at = NOW()");
+
+    if let Some(display_name) = profile.display_name {
+        builder.push(", display_name = ");
+        builder.push_bind(display_name);
+    }
+    if let Some(bio) = profile.bio {
+        builder.push(", bio = ");
+        builder.push_bind(bio);
+    }
+    // an empty url clears it
+    if let Some(avatar_url) = profile.avatar_url {
+        builder.push(", avatar_url = NULLIF(");
+        builder.push_bind(avatar_url);
+        builder.push(", '')");
+    }
+    if let Some(social_links) = &profile.social_links {
+        let json = serde_json::to_value(social_links)
+            .map_err(|e| ProfileError::UnexpectedError(anyhow::anyhow!(e)))?;
+        builder.push(", social_links = ");
+        builder.push_bind(json);
+    }
+    if let Some(contact_available) = profile.contact_available {
+        builder.push(", contact_available = ");
+        builder.push_bind(contact_available);
+    }
+
+    builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Profile update query failed");
+            ProfileError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+        })?;
+
+    tracing::info!("Profile updated successfully");
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
@@ -0,0 +1,61 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ProjectError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, project::ProjectDeleteRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Delete project",
+    skip_all,
+    fields(user_id = %*user_id, project_id = %project.project_id)
+)]
+pub async fn delete_project(
+    project: web::Json<ProjectDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let project_to_delete = project.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&project_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_project(tx, project_to_delete).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_delete_project(
+    transaction: &mut Transaction<'static, Postgres>,
+    project: ProjectDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let project_id = project.project_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM projects
+        WHERE project_id = $1
+        "#,
+        project_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Project delete query failed");
+        ProjectError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Project not found: {}", project_id);
+        return Err(ProjectError::ProjectNotFound.into());
+    }
+    tracing::info!("Project {} deleted successfully", project_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
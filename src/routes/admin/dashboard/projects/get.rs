@@ -0,0 +1,16 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::{
+    errors::ProjectError,
+    routes::list_projects,
+    types::{api_response::ApiResponse, project::ProjectRecord},
+};
+
+// drafts included, read from the primary so a project shows up right after it's saved
+#[tracing::instrument(name = "Get all projects", skip(pool))]
+pub async fn get_all_projects(
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<Vec<ProjectRecord>>, ProjectError> {
+    Ok(ApiResponse::ok(list_projects(&pool, false, None).await?))
+}
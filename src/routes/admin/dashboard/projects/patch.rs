@@ -0,0 +1,181 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::ProjectError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        project::{ProjectEditRequest, ProjectOrderRequest},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit project", skip_all)]
+pub async fn edit_project(
+    project_edit_request: web::Json<ProjectEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let project_to_edit = project_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    project_to_edit.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&project_to_edit).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_project(tx, project_to_edit).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_project(
+    transaction: &mut Transaction<'static, Postgres>,
+    project: ProjectEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let project_id = project.project_id;
+
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE projects SET ");
+    let mut separator = builder.separated(", ");
+    let mut changed = false;
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                separator.push(concat!($col, " = "));
+                separator.push_bind_unseparated(val);
+                changed = true;
+            }
+        };
+    }
+
+    // an empty link clears it
+    macro_rules! push_link_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                separator.push(concat!($col, " = NULLIF("));
+                separator.push_bind_unseparated(val);
+                separator.push_unseparated(", '')");
+                changed = true;
+            }
+        };
+    }
+
+    macro_rules! push_json_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = &$field {
+                let json = serde_json::to_value(val)
+                    .map_err(|e| ProjectError::UnexpectedError(anyhow::anyhow!(e)))?;
+                separator.push(concat!($col, " = "));
+                separator.push_bind_unseparated(json);
+                changed = true;
+            }
+        };
+    }
+
+    push_if_some!(project.title, "title");
+    push_if_some!(project.description, "description");
+    push_json_if_some!(project.content, "content");
+    push_if_some!(project.tech_tags, "tech_tags");
+    push_link_if_some!(project.repo_url, "repo_url");
+    push_link_if_some!(project.demo_url, "demo_url");
+    push_json_if_some!(project.images, "images");
+    push_if_some!(project.published, "published");
+
+    if !changed {
+        tracing::warn!("No fields to update for project {}", project_id);
+        return Err(ProjectError::NothingToUpdate.into());
+    }
+
+    builder.push(", updated_at = NOW() WHERE project_id = ");
+    builder.push_bind(project_id);
+
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Project update query failed");
+            ProjectError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Project not found: {}", project_id);
+        return Err(ProjectError::ProjectNotFound.into());
+    }
+    tracing::info!("Project {} updated successfully", project_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
+
+#[tracing::instrument(
+    name = "Reorder projects",
+    skip_all,
+    fields(count = order.project_ids.len())
+)]
+pub async fn reorder_projects(
+    order: web::Json<ProjectOrderRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let order = order.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    let fingerprint = RequestFingerprint::of(&order).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_reorder_projects(tx, order).await })
+    })
+    .await
+}
+
+// all or nothing, a list that misses or repeats a project changes nothing
+#[allow(clippy::future_not_send)]
+async fn process_reorder_projects(
+    transaction: &mut Transaction<'static, Postgres>,
+    order: ProjectOrderRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    // locked so two reorders can't interleave
+    let current = sqlx::query_scalar!(r#"SELECT project_id FROM projects FOR UPDATE"#)
+        .fetch_all(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Project order query failed");
+            ProjectError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+        })?;
+
+    let requested: HashSet<Uuid> = order.project_ids.iter().copied().collect();
+    if requested.len() != order.project_ids.len()
+        || requested != current.into_iter().collect::<HashSet<_>>()
+    {
+        tracing::warn!("Project order doesn't match the stored projects");
+        return Err(ProjectError::InvalidOrder.into());
+    }
+
+    // only projects that actually move count as updated, so the list's Last-Modified moves too
+    sqlx::query!(
+        r#"
+        UPDATE projects
+        SET display_order = ordering.position - 1, updated_at = NOW()
+        FROM UNNEST($1::uuid[]) WITH ORDINALITY AS ordering(project_id, position)
+        WHERE projects.project_id = ordering.project_id
+            AND projects.display_order <> ordering.position - 1
+        "#,
+        &order.project_ids
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Project reorder query failed");
+        ProjectError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    tracing::info!("Projects reordered");
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
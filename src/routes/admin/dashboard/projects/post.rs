@@ -0,0 +1,113 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::ProjectError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        project::{ProjectForm, ProjectId, ProjectResponse},
+    },
+    utils::{e500, slugify},
+};
+
+#[tracing::instrument(
+    name = "Insert project",
+    skip(project, pool, request, user_id),
+    fields(
+        project_id = tracing::field::Empty
+    )
+)]
+pub async fn insert_project(
+    project: web::Json<ProjectForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let project = project.into_inner();
+    let user_id = Some(**user_id);
+
+    project.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&project).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_project(tx, project).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_new_project(
+    transaction: &mut Transaction<'static, Postgres>,
+    project: ProjectForm,
+) -> Result<ApiResponse<ProjectResponse>, actix_web::Error> {
+    let project_id = ProjectId(Uuid::new_v4());
+    let slug = slugify(&project.title);
+    let content = serde_json::to_value(&project.content).map_err(|e| {
+        ProjectError::UnexpectedError(anyhow::anyhow!("Failed to serialize content: {e:?}"))
+    })?;
+    let images = serde_json::to_value(&project.images).map_err(|e| {
+        ProjectError::UnexpectedError(anyhow::anyhow!("Failed to serialize images: {e:?}"))
+    })?;
+    tracing::Span::current().record("project_id", tracing::field::display(&project_id));
+
+    // new projects lead the grid until they're reordered, as they did when it was newest-first
+    let insert_result = sqlx::query!(
+        r#"
+        INSERT INTO projects(
+        project_id,
+        title,
+        slug,
+        description,
+        content,
+        tech_tags,
+        repo_url,
+        demo_url,
+        images,
+        published,
+        display_order,
+        created_at,
+        updated_at)
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE(MIN(display_order) - 1, 0), NOW(), NOW()
+        FROM projects"#,
+        *project_id,
+        project.title,
+        slug,
+        project.description,
+        content,
+        &project.tech_tags,
+        project.repo_url,
+        project.demo_url,
+        images,
+        project.published
+    )
+    .execute(transaction.as_mut())
+    .await;
+
+    match insert_result {
+        Ok(_) => {
+            tracing::info!("Project saved successfully with: {}", project_id);
+            Ok(ApiResponse::accepted(ProjectResponse::new(
+                "Project received successfully",
+                project_id,
+            )))
+        }
+        Err(e) => {
+            if let sqlx::Error::Database(db_err) = &e
+                && db_err.code().as_deref() == Some("23505")
+            {
+                tracing::warn!("Duplicate project detected");
+                return Err(ProjectError::DuplicateProject.into());
+            }
+
+            tracing::error!("Failed to save project: {e:?}");
+            Err(
+                ProjectError::UnexpectedError(anyhow::anyhow!("Saving project failed: {e:?}"))
+                    .into(),
+            )
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use actix_web::{HttpResponse, web};
+
+use crate::rate_limit::RateLimiter;
+use crate::utils::e500;
+
+// `subject` as listed by `get_rate_limits`, e.g. `ip:203.0.113.7` or `username:calvin`
+#[derive(serde::Deserialize, Debug)]
+pub struct RateLimitResetRequest {
+    pub policy: String,
+    pub subject: String,
+}
+
+// lets a throttled subject straight back in instead of waiting out the window
+#[tracing::instrument(name = "Reset rate limit", skip(limiter))]
+pub async fn reset_rate_limit(
+    reset: web::Json<RateLimitResetRequest>,
+    limiter: web::Data<RateLimiter>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match limiter
+        .reset(&reset.policy, &reset.subject)
+        .await
+        .map_err(e500)?
+    {
+        Some(()) => Ok(HttpResponse::NoContent().finish()),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
@@ -0,0 +1,22 @@
+use actix_web::web;
+
+use crate::rate_limit::{BannedSubject, RateLimiter, ThrottledKey};
+use crate::types::api_response::ApiResponse;
+use crate::utils::e500;
+
+#[derive(serde::Serialize)]
+struct RateLimitsResponse {
+    throttled: Vec<ThrottledKey>,
+    banned: Vec<BannedSubject>,
+}
+
+// subjects currently over a budget or banned, to find who needs unblocking
+#[tracing::instrument(name = "Get throttled rate limit keys", skip_all)]
+pub async fn get_rate_limits(
+    limiter: web::Data<RateLimiter>,
+) -> Result<ApiResponse<impl serde::Serialize>, actix_web::Error> {
+    let throttled = limiter.throttled().await.map_err(e500)?;
+    let banned = limiter.banned().await.map_err(e500)?;
+
+    Ok(ApiResponse::ok(RateLimitsResponse { throttled, banned }))
+}
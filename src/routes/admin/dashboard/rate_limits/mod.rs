@@ -0,0 +1,5 @@
+mod delete;
+mod get;
+
+pub use delete::*;
+pub use get::*;
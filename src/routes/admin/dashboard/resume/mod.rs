@@ -0,0 +1,3 @@
+mod put;
+
+pub use put::*;
@@ -0,0 +1,65 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ResumeError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, resume::ResumeForm},
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Update resume", skip_all, fields(user_id = %*user_id))]
+pub async fn update_resume(
+    resume: web::Json<ResumeForm>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let resume = resume.into_inner();
+    let user_id = Some(**user_id);
+
+    resume.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&resume).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_update_resume(tx, resume).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_update_resume(
+    transaction: &mut Transaction<'static, Postgres>,
+    resume: ResumeForm,
+) -> Result<ApiResponse, actix_web::Error> {
+    let education = serde_json::to_value(&resume.education).map_err(e500)?;
+
+    // the row is seeded by its migration, the upsert only matters if it was removed by hand
+    sqlx::query!(
+        r#"
+        INSERT INTO resume (id, name, headline, summary, education, updated_at)
+        VALUES (true, $1, $2, $3, $4, NOW())
+        ON CONFLICT (id) DO UPDATE
+        SET name = EXCLUDED.name,
+            headline = EXCLUDED.headline,
+            summary = EXCLUDED.summary,
+            education = EXCLUDED.education,
+            updated_at = EXCLUDED.updated_at
+        "#,
+        resume.name,
+        resume.headline,
+        resume.summary,
+        education
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Failed to save resume: {e:?}");
+        ResumeError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    tracing::info!("Resume updated successfully");
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
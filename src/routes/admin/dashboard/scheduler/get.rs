@@ -0,0 +1,19 @@
+use actix_web::web;
+
+use crate::scheduler::{ScheduledJobStatus, SchedulerStatus};
+use crate::types::api_response::ApiResponse;
+
+#[derive(serde::Serialize)]
+struct SchedulerResponse {
+    jobs: Vec<ScheduledJobStatus>,
+}
+
+// every registered job with its schedule, how its last run went and when it runs next
+#[tracing::instrument(name = "Get scheduler status", skip_all)]
+pub async fn get_scheduler_status(
+    status: web::Data<SchedulerStatus>,
+) -> ApiResponse<impl serde::Serialize> {
+    ApiResponse::ok(SchedulerResponse {
+        jobs: status.jobs(),
+    })
+}
@@ -0,0 +1,62 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ShortLinkError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, short_link::ShortLinkDeleteRequest},
+    utils::e500,
+};
+
+// the page visits its clicks recorded stay in the metrics
+#[tracing::instrument(
+    name = "Delete short link",
+    skip_all,
+    fields(user_id = %*user_id, link_id = %link.link_id)
+)]
+pub async fn delete_short_link(
+    link: web::Json<ShortLinkDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let link_to_delete = link.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&link_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_short_link(tx, link_to_delete).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_delete_short_link(
+    transaction: &mut Transaction<'static, Postgres>,
+    link: ShortLinkDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let link_id = link.link_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM short_links
+        WHERE link_id = $1
+        "#,
+        link_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Short link delete query failed");
+        ShortLinkError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Short link not found: {}", link_id);
+        return Err(ShortLinkError::LinkNotFound.into());
+    }
+    tracing::info!("Short link {} deleted successfully", link_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
@@ -0,0 +1,39 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::{
+    errors::ShortLinkError,
+    types::{api_response::ApiResponse, short_link::ShortLinkRecord},
+};
+
+// read from the primary, click counts move with every redirect
+#[tracing::instrument(name = "Get short links", skip(pool))]
+pub async fn get_short_links(
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<Vec<ShortLinkRecord>>, ShortLinkError> {
+    let links = sqlx::query_as!(
+        ShortLinkRecord,
+        r#"
+        SELECT
+            link_id,
+            code,
+            target_url,
+            utm_source,
+            utm_medium,
+            utm_campaign,
+            click_count,
+            last_clicked_at,
+            created_at,
+            updated_at
+        FROM short_links
+        ORDER BY created_at DESC"#
+    )
+    .fetch_all(pool.as_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch short links: {e:?}");
+        ShortLinkError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    Ok(ApiResponse::ok(links))
+}
@@ -0,0 +1,87 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::ShortLinkError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        short_link::{ShortLinkEditRequest, normalize_utm},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit short link", skip_all)]
+pub async fn edit_short_link(
+    link_edit_request: web::Json<ShortLinkEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let link = link_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    link.validate().map_err(actix_web::Error::from)?;
+    if link.is_empty() {
+        return Err(ShortLinkError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&link).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_short_link(tx, link).await })
+    })
+    .await
+}
+
+// the click count and history stay with the link when its code changes
+#[allow(clippy::future_not_send)]
+async fn process_edit_short_link(
+    transaction: &mut Transaction<'static, Postgres>,
+    link: ShortLinkEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let link_id = link.link_id;
+
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE short_links SET updated_at = NOW()");
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                builder.push(concat!(", ", $col, " = "));
+                builder.push_bind(val);
+            }
+        };
+    }
+
+    // a blank tag binds as NULL, which clears it
+    let utm_source = link.utm_source.map(Some).map(normalize_utm);
+    let utm_medium = link.utm_medium.map(Some).map(normalize_utm);
+    let utm_campaign = link.utm_campaign.map(Some).map(normalize_utm);
+
+    push_if_some!(link.code, "code");
+    push_if_some!(link.target_url, "target_url");
+    push_if_some!(utm_source, "utm_source");
+    push_if_some!(utm_medium, "utm_medium");
+    push_if_some!(utm_campaign, "utm_campaign");
+
+    builder.push(" WHERE link_id = ");
+    builder.push_bind(link_id);
+
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Short link update query failed");
+            ShortLinkError::from(e)
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Short link not found: {}", link_id);
+        return Err(ShortLinkError::LinkNotFound.into());
+    }
+
+    tracing::info!("Short link {} updated successfully", link_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
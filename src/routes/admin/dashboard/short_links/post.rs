@@ -0,0 +1,87 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::ShortLinkError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        short_link::{ShortLinkForm, ShortLinkId, ShortLinkResponse, generate_code, normalize_utm},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Insert short link",
+    skip(link, pool, request, user_id),
+    fields(
+        link_id = tracing::field::Empty,
+        code = tracing::field::Empty
+    )
+)]
+pub async fn insert_short_link(
+    link: web::Json<ShortLinkForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let link = link.into_inner();
+    let user_id = Some(**user_id);
+
+    link.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&link).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_short_link(tx, link).await })
+    })
+    .await
+}
+
+// a generated code is picked in here, so a replayed request gets the same one back
+#[allow(clippy::future_not_send)]
+async fn process_new_short_link(
+    transaction: &mut Transaction<'static, Postgres>,
+    link: ShortLinkForm,
+) -> Result<ApiResponse<ShortLinkResponse>, actix_web::Error> {
+    let link_id = ShortLinkId(Uuid::new_v4());
+    let code = link.code.unwrap_or_else(generate_code);
+    tracing::Span::current()
+        .record("link_id", tracing::field::display(&link_id))
+        .record("code", tracing::field::display(&code));
+
+    sqlx::query!(
+        r#"
+        INSERT INTO short_links (
+            link_id,
+            code,
+            target_url,
+            utm_source,
+            utm_medium,
+            utm_campaign,
+            created_at,
+            updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())"#,
+        *link_id,
+        code,
+        link.target_url,
+        normalize_utm(link.utm_source),
+        normalize_utm(link.utm_medium),
+        normalize_utm(link.utm_campaign)
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Failed to save short link: {e:?}");
+        ShortLinkError::from(e)
+    })?;
+
+    tracing::info!("Short link saved successfully with: {}", link_id);
+    Ok(ApiResponse::accepted(ShortLinkResponse::new(
+        "Short link received successfully",
+        link_id,
+        code,
+    )))
+}
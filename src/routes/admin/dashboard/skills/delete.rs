@@ -0,0 +1,62 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::SkillError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, skill::SkillDeleteRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Delete skill",
+    skip_all,
+    fields(user_id = %*user_id, skill_id = %skill.skill_id)
+)]
+pub async fn delete_skill(
+    skill: web::Json<SkillDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let skill_to_delete = skill.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&skill_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_skill(tx, skill_to_delete).await })
+    })
+    .await
+}
+
+// its project links go with it
+#[allow(clippy::future_not_send)]
+async fn process_delete_skill(
+    transaction: &mut Transaction<'static, Postgres>,
+    skill: SkillDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let skill_id = skill.skill_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM skills
+        WHERE skill_id = $1
+        "#,
+        skill_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Skill delete query failed");
+        SkillError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Skill not found: {}", skill_id);
+        return Err(SkillError::SkillNotFound.into());
+    }
+    tracing::info!("Skill {} deleted successfully", skill_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
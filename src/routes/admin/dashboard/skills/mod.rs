@@ -0,0 +1,7 @@
+mod delete;
+mod patch;
+mod post;
+
+pub use delete::*;
+pub use patch::*;
+pub use post::*;
@@ -0,0 +1,82 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+
+use super::post::link_projects;
+use crate::{
+    authentication::UserId,
+    errors::SkillError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, skill::SkillEditRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit skill", skip_all)]
+pub async fn edit_skill(
+    skill_edit_request: web::Json<SkillEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let skill_to_edit = skill_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    skill_to_edit.validate().map_err(actix_web::Error::from)?;
+    if skill_to_edit.is_empty() {
+        return Err(SkillError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&skill_to_edit).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_skill(tx, skill_to_edit).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_skill(
+    transaction: &mut Transaction<'static, Postgres>,
+    skill: SkillEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let skill_id = skill.skill_id;
+
+    // updated_at moves even when only the links change
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE skills SET updated_at = NOW()");
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                builder.push(concat!(", ", $col, " = "));
+                builder.push_bind(val);
+            }
+        };
+    }
+
+    push_if_some!(skill.name, "name");
+    push_if_some!(skill.category, "category");
+    push_if_some!(skill.proficiency, "proficiency");
+    push_if_some!(skill.years, "years");
+
+    builder.push(" WHERE skill_id = ");
+    builder.push_bind(skill_id);
+
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Skill update query failed");
+            SkillError::from(e)
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Skill not found: {}", skill_id);
+        return Err(SkillError::SkillNotFound.into());
+    }
+    if let Some(project_ids) = &skill.project_ids {
+        link_projects(transaction, skill_id, project_ids).await?;
+    }
+
+    tracing::info!("Skill {} updated successfully", skill_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
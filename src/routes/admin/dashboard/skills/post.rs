@@ -0,0 +1,109 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::SkillError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        skill::{SkillForm, SkillId, SkillResponse},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Insert skill",
+    skip(skill, pool, request, user_id),
+    fields(
+        skill_id = tracing::field::Empty
+    )
+)]
+pub async fn insert_skill(
+    skill: web::Json<SkillForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let skill = skill.into_inner();
+    let user_id = Some(**user_id);
+
+    skill.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&skill).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_skill(tx, skill).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_new_skill(
+    transaction: &mut Transaction<'static, Postgres>,
+    skill: SkillForm,
+) -> Result<ApiResponse<SkillResponse>, actix_web::Error> {
+    let skill_id = SkillId(Uuid::new_v4());
+    tracing::Span::current().record("skill_id", tracing::field::display(&skill_id));
+
+    sqlx::query!(
+        r#"
+        INSERT INTO skills(
+        skill_id,
+        name,
+        category,
+        proficiency,
+        years,
+        created_at,
+        updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())"#,
+        *skill_id,
+        skill.name,
+        skill.category,
+        skill.proficiency,
+        skill.years
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Failed to save skill: {e:?}");
+        SkillError::from(e)
+    })?;
+
+    link_projects(transaction, *skill_id, &skill.project_ids).await?;
+
+    tracing::info!("Skill saved successfully with: {}", skill_id);
+    Ok(ApiResponse::accepted(SkillResponse::new(
+        "Skill received successfully",
+        skill_id,
+    )))
+}
+
+// replaces the skill's links, an id that isn't a project fails the whole request
+#[allow(clippy::future_not_send)]
+pub(super) async fn link_projects(
+    transaction: &mut Transaction<'static, Postgres>,
+    skill_id: Uuid,
+    project_ids: &[Uuid],
+) -> Result<(), SkillError> {
+    sqlx::query!(
+        r#"DELETE FROM project_skills WHERE skill_id = $1"#,
+        skill_id
+    )
+    .execute(transaction.as_mut())
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO project_skills (project_id, skill_id)
+        SELECT DISTINCT project_id, $2::uuid
+        FROM UNNEST($1::uuid[]) AS linked(project_id)
+        "#,
+        project_ids,
+        skill_id
+    )
+    .execute(transaction.as_mut())
+    .await?;
+    Ok(())
+}
@@ -1,10 +1,12 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{http::StatusCode, web};
 use anyhow::Context;
 use sqlx::PgPool;
 use totp_rs::{Algorithm, Secret, TOTP};
 
 use crate::authentication::UserId;
+use crate::errors::ApiProblem;
 use crate::startup::TotpEncryptionKey;
+use crate::types::api_response::ApiResponse;
 use crate::utils::e500;
 
 #[derive(serde::Deserialize, Debug)]
@@ -18,7 +20,7 @@ pub async fn totp_confirm(
     pool: web::Data<PgPool>,
     user_id: web::ReqData<UserId>,
     encryption_key: web::Data<TotpEncryptionKey>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let user_id = user_id.into_inner();
 
     let row = sqlx::query!(
@@ -32,7 +34,7 @@ pub async fn totp_confirm(
 
     // reject if already enabled or no secret
     if row.totp_enabled {
-        return Ok(HttpResponse::Conflict().finish());
+        return Err(ApiProblem::new(StatusCode::CONFLICT).into());
     }
 
     let encrypted = row
@@ -54,7 +56,7 @@ pub async fn totp_confirm(
     .map_err(e500)?;
 
     if !totp.check_current(&request.code).map_err(e500)? {
-        return Ok(HttpResponse::Unauthorized().finish());
+        return Err(ApiProblem::new(StatusCode::UNAUTHORIZED).into());
     }
 
     sqlx::query!(
@@ -66,5 +68,5 @@ pub async fn totp_confirm(
     .context("Failed to enable TOTP")
     .map_err(e500)?;
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(ApiResponse::empty(StatusCode::OK))
 }
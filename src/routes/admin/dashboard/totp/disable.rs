@@ -1,9 +1,11 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{http::StatusCode, web};
 use anyhow::Context;
 use secrecy::SecretString;
 use sqlx::PgPool;
 
 use crate::authentication::{Credentials, UserId, validate_credentials};
+use crate::configuration::PasswordHashingSettings;
+use crate::types::api_response::ApiResponse;
 use crate::utils::e500;
 
 #[derive(serde::Deserialize, Debug)]
@@ -11,12 +13,13 @@ pub struct DisableTotpRequest {
     password: SecretString,
 }
 
-#[tracing::instrument(name = "TOTP disable", skip(pool, user_id, request))]
+#[tracing::instrument(name = "TOTP disable", skip(pool, hashing, user_id, request))]
 pub async fn totp_disable(
     request: web::Json<DisableTotpRequest>,
     pool: web::Data<PgPool>,
+    hashing: web::Data<PasswordHashingSettings>,
     user_id: web::ReqData<UserId>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let user_id = user_id.into_inner();
 
     let username = sqlx::query_scalar!("SELECT username FROM users WHERE user_id = $1", *user_id,)
@@ -31,7 +34,7 @@ pub async fn totp_disable(
         password: request.into_inner().password,
     };
 
-    validate_credentials(credentials, &pool)
+    validate_credentials(credentials, &hashing, &pool)
         .await
         .map_err(|_| actix_web::error::ErrorUnauthorized("Invalid password"))?;
 
@@ -44,5 +47,5 @@ pub async fn totp_disable(
     .context("Failed to disable TOTP")
     .map_err(e500)?;
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(ApiResponse::empty(StatusCode::OK))
 }
@@ -1,10 +1,12 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{http::StatusCode, web};
 use anyhow::Context;
 use sqlx::PgPool;
 use totp_rs::{Algorithm, Secret, TOTP};
 
 use crate::authentication::UserId;
+use crate::errors::ApiProblem;
 use crate::startup::TotpEncryptionKey;
+use crate::types::api_response::ApiResponse;
 use crate::utils::e500;
 
 #[tracing::instrument(name = "TOTP setup", skip(pool, user_id, encryption_key))]
@@ -12,7 +14,7 @@ pub async fn totp_setup(
     pool: web::Data<PgPool>,
     user_id: web::ReqData<UserId>,
     encryption_key: web::Data<TotpEncryptionKey>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
     let user_id = user_id.into_inner();
 
     let status = sqlx::query!(
@@ -25,7 +27,7 @@ pub async fn totp_setup(
     .map_err(e500)?;
 
     if status.totp_enabled {
-        return Ok(HttpResponse::Conflict().finish());
+        return Err(ApiProblem::new(StatusCode::CONFLICT).into());
     }
 
     // generate a secret and encode
@@ -58,5 +60,7 @@ pub async fn totp_setup(
 
     let otpauth_uri = totp.get_url();
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "otpauth_uri": otpauth_uri })))
+    Ok(ApiResponse::ok(
+        serde_json::json!({ "otpauth_uri": otpauth_uri }),
+    ))
 }
@@ -1,15 +1,16 @@
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use anyhow::Context;
 use sqlx::PgPool;
 
 use crate::authentication::UserId;
+use crate::types::api_response::ApiResponse;
 use crate::utils::e500;
 
 #[tracing::instrument(name = "TOTP status", skip(pool, user_id))]
 pub async fn totp_status(
     pool: web::Data<PgPool>,
     user_id: web::ReqData<UserId>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
     let user_id = user_id.into_inner();
 
     let status = sqlx::query!(
@@ -21,5 +22,7 @@ pub async fn totp_status(
     .context("Failed to retrieve totp status")
     .map_err(e500)?;
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "totp_enabled": status.totp_enabled })))
+    Ok(ApiResponse::ok(
+        serde_json::json!({ "totp_enabled": status.totp_enabled }),
+    ))
 }
@@ -0,0 +1,82 @@
+use actix_web::{http::StatusCode, web};
+use anyhow::Context;
+use sqlx::PgPool;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::antivirus::{ScanUploads, mark_pending};
+use crate::startup::ApplicationBaseUrl;
+use crate::storage::{MEDIA_PREFIX, Storage};
+use crate::types::api_response::ApiResponse;
+use crate::utils::e500;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct UploadRequest {
+    // only the extension is kept, so the served file gets the right content type
+    filename: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct IssuedUpload {
+    pub key: String,
+    // PUT the file's bytes here before it expires
+    pub upload_url: String,
+    // where the file is served from once it's uploaded
+    pub media_url: String,
+}
+
+// a presigned url for one new media file, the bytes go straight to the backend
+#[tracing::instrument(name = "Issue a media upload url", skip(storage, base_url, pool, scan))]
+pub async fn issue_upload(
+    request: web::Json<UploadRequest>,
+    storage: web::Data<Storage>,
+    base_url: web::Data<ApplicationBaseUrl>,
+    pool: web::Data<PgPool>,
+    scan: web::Data<ScanUploads>,
+) -> Result<ApiResponse<IssuedUpload>, actix_web::Error> {
+    let id = format!(
+        "{}{}",
+        Uuid::new_v4(),
+        extension(request.filename.as_deref()).unwrap_or_default()
+    );
+    let key = format!("{MEDIA_PREFIX}/{id}");
+    // pending before the url exists, so `/media` holds the file back until it's been scanned
+    if scan.0 {
+        mark_pending(&pool, &key)
+            .await
+            .context("Failed to mark the upload as pending")
+            .map_err(e500)?;
+    }
+    let upload_url = storage.presigned_upload_url(&key).await.map_err(e500)?;
+
+    Ok(ApiResponse::with_status(
+        StatusCode::CREATED,
+        IssuedUpload {
+            key,
+            upload_url,
+            media_url: format!("{}/media/{id}", base_url.0.trim_end_matches('/')),
+        },
+    ))
+}
+
+// `.png` for `Photo.PNG`, nothing for a name without a plain alphanumeric extension
+fn extension(filename: Option<&str>) -> Option<String> {
+    let extension = Path::new(filename?).extension()?.to_str()?;
+    (extension.len() <= 8 && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then(|| format!(".{}", extension.to_ascii_lowercase()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::extension;
+
+    #[test]
+    fn only_plain_extensions_are_kept() {
+        assert_eq!(extension(Some("Photo.PNG")).as_deref(), Some(".png"));
+        assert_eq!(extension(Some("archive.tar.gz")).as_deref(), Some(".gz"));
+        assert_eq!(extension(Some("no_extension")), None);
+        assert_eq!(extension(Some("evil.p/ng")), None);
+        assert_eq!(extension(Some("x.ph p")), None);
+        assert_eq!(extension(None), None);
+    }
+}
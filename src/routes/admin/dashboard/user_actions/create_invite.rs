@@ -1,6 +1,9 @@
 use crate::{
-    authentication::UserId, idempotency::execute_idempotent, startup::ApplicationBaseUrl,
-    types::user::CreateUser,
+    authentication::UserId,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    startup::ApplicationBaseUrl,
+    types::{api_response::ApiResponse, user::CreateUser},
+    utils::e500,
 };
 use actix_web::{HttpRequest, HttpResponse, web};
 use rand::{RngExt, distr::Alphanumeric};
@@ -19,7 +22,9 @@ pub async fn create_user(
     let user_id = Some(**user_id);
     user_to_create.validate()?;
 
-    execute_idempotent(&request, &pool, user_id, move |tx| {
+    let fingerprint = RequestFingerprint::of(&user_to_create).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
         Box::pin(async move { process_create_new_user(tx, user_to_create, &base_url.0).await })
     })
     .await
@@ -30,7 +35,7 @@ async fn process_create_new_user(
     transaction: &mut sqlx::Transaction<'static, sqlx::Postgres>,
     new_user: CreateUser,
     base_url: &str,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
     // random raw token
     let raw_token: String = rand::rng()
         .sample_iter(&Alphanumeric)
@@ -70,5 +75,5 @@ async fn process_create_new_user(
         "link": format!("{}/invitation/accept?token={}", base_url, raw_token)
     });
 
-    Ok(HttpResponse::Ok().json(response_data))
+    Ok(ApiResponse::ok(response_data))
 }
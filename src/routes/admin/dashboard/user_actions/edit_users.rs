@@ -1,9 +1,12 @@
+use crate::types::api_response::ApiResponse;
 use crate::types::user::{User, UserRole};
-use actix_web::{HttpResponse, web};
+use actix_web::{http::StatusCode, web};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-pub async fn get_all_users(pool: web::Data<PgPool>) -> Result<HttpResponse, actix_web::Error> {
+pub async fn get_all_users(
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<Vec<User>>, actix_web::Error> {
     let users = sqlx::query_as!(
         User,
         r#"
@@ -11,14 +14,16 @@ pub async fn get_all_users(pool: web::Data<PgPool>) -> Result<HttpResponse, acti
             user_id::TEXT as "user_id!",
             username,
             role::TEXT as "role!",
-            must_change_password
+            must_change_password,
+            last_login_at,
+            last_login_ip
         FROM users"#
     )
     .fetch_all(pool.get_ref())
     .await
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
-    Ok(HttpResponse::Ok().json(users))
+    Ok(ApiResponse::ok(users))
 }
 
 pub async fn get_username_by_id(
@@ -32,7 +37,9 @@ pub async fn get_username_by_id(
             user_id::TEXT as "user_id!",
             username,
             role::TEXT as "role!",
-            must_change_password
+            must_change_password,
+            last_login_at,
+            last_login_ip
         FROM users
         WHERE user_id = $1::UUID
         "#,
@@ -54,7 +61,7 @@ pub async fn set_user_role(
     pool: web::Data<PgPool>,
     user_id: web::Path<Uuid>,
     new_role: web::Json<RoleUpdate>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let user_id = user_id.into_inner();
     let new_role = new_role.into_inner();
 
@@ -71,13 +78,13 @@ pub async fn set_user_role(
     .await
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(ApiResponse::empty(StatusCode::OK))
 }
 
 pub async fn reset_password(
     pool: web::Data<PgPool>,
     user_id: web::Path<Uuid>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse, actix_web::Error> {
     let user_id = user_id.into_inner();
 
     sqlx::query!(
@@ -92,5 +99,5 @@ pub async fn reset_password(
     .await
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(ApiResponse::empty(StatusCode::OK))
 }
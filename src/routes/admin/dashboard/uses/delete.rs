@@ -0,0 +1,61 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::UsesError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, uses::UsesItemDeleteRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Delete uses item",
+    skip_all,
+    fields(user_id = %*user_id, item_id = %item.item_id)
+)]
+pub async fn delete_uses_item(
+    item: web::Json<UsesItemDeleteRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let item_to_delete = item.0;
+    let user_id = Some(**user_id);
+
+    let fingerprint = RequestFingerprint::of(&item_to_delete).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_delete_uses_item(tx, item_to_delete).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_delete_uses_item(
+    transaction: &mut Transaction<'static, Postgres>,
+    item: UsesItemDeleteRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let item_id = item.item_id;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM uses_items
+        WHERE item_id = $1
+        "#,
+        item_id
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Uses item delete query failed");
+        UsesError::UnexpectedError(anyhow::anyhow!("{e:?}"))
+    })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Uses item not found: {}", item_id);
+        return Err(UsesError::ItemNotFound.into());
+    }
+    tracing::info!("Uses item {} deleted successfully", item_id);
+    Ok(ApiResponse::empty(StatusCode::OK))
+}
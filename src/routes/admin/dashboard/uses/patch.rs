@@ -0,0 +1,77 @@
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    authentication::UserId,
+    errors::UsesError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{api_response::ApiResponse, uses::UsesItemEditRequest},
+    utils::e500,
+};
+
+#[tracing::instrument(name = "Edit uses item", skip_all)]
+pub async fn edit_uses_item(
+    item_edit_request: web::Json<UsesItemEditRequest>,
+    user_id: web::ReqData<UserId>,
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let item = item_edit_request.into_inner();
+    let user_id = Some(*user_id.into_inner());
+
+    item.validate().map_err(actix_web::Error::from)?;
+    if item.is_empty() {
+        return Err(UsesError::NothingToUpdate.into());
+    }
+
+    let fingerprint = RequestFingerprint::of(&item).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_edit_uses_item(tx, item).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_edit_uses_item(
+    transaction: &mut Transaction<'static, Postgres>,
+    item: UsesItemEditRequest,
+) -> Result<ApiResponse, actix_web::Error> {
+    let item_id = item.item_id;
+
+    let mut builder = QueryBuilder::<Postgres>::new("UPDATE uses_items SET updated_at = NOW()");
+
+    macro_rules! push_if_some {
+        ($field:expr, $col:literal) => {
+            if let Some(val) = $field {
+                builder.push(concat!(", ", $col, " = "));
+                builder.push_bind(val);
+            }
+        };
+    }
+
+    push_if_some!(item.category, "category");
+    push_if_some!(item.name, "name");
+    push_if_some!(item.description, "description");
+    push_if_some!(item.url, "url");
+
+    builder.push(" WHERE item_id = ");
+    builder.push_bind(item_id);
+
+    let result = builder
+        .build()
+        .execute(transaction.as_mut())
+        .await
+        .map_err(|e| {
+            tracing::warn!("Uses item update query failed");
+            UsesError::from(e)
+        })?;
+
+    if result.rows_affected() == 0 {
+        tracing::warn!("Uses item not found: {}", item_id);
+        return Err(UsesError::ItemNotFound.into());
+    }
+
+    tracing::info!("Uses item {} updated successfully", item_id);
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
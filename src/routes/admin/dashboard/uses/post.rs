@@ -0,0 +1,79 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    errors::UsesError,
+    idempotency::{RequestFingerprint, execute_idempotent},
+    types::{
+        api_response::ApiResponse,
+        uses::{UsesItemForm, UsesItemId, UsesItemResponse},
+    },
+    utils::e500,
+};
+
+#[tracing::instrument(
+    name = "Insert uses item",
+    skip(item, pool, request, user_id),
+    fields(
+        item_id = tracing::field::Empty
+    )
+)]
+pub async fn insert_uses_item(
+    item: web::Json<UsesItemForm>,
+    user_id: web::ReqData<UserId>,
+    pool: web::Data<PgPool>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let item = item.into_inner();
+    let user_id = Some(**user_id);
+
+    item.validate().map_err(actix_web::Error::from)?;
+
+    let fingerprint = RequestFingerprint::of(&item).map_err(e500)?;
+
+    execute_idempotent(&request, &pool, user_id, &fingerprint, move |tx| {
+        Box::pin(async move { process_new_uses_item(tx, item).await })
+    })
+    .await
+}
+
+#[allow(clippy::future_not_send)]
+async fn process_new_uses_item(
+    transaction: &mut Transaction<'static, Postgres>,
+    item: UsesItemForm,
+) -> Result<ApiResponse<UsesItemResponse>, actix_web::Error> {
+    let item_id = UsesItemId(Uuid::new_v4());
+    tracing::Span::current().record("item_id", tracing::field::display(&item_id));
+
+    sqlx::query!(
+        r#"
+        INSERT INTO uses_items (
+            item_id,
+            category,
+            name,
+            description,
+            url,
+            created_at,
+            updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())"#,
+        *item_id,
+        item.category,
+        item.name,
+        item.description,
+        item.url
+    )
+    .execute(transaction.as_mut())
+    .await
+    .map_err(|e| {
+        tracing::warn!("Failed to save uses item: {e:?}");
+        UsesError::from(e)
+    })?;
+
+    tracing::info!("Uses item saved successfully with: {}", item_id);
+    Ok(ApiResponse::accepted(UsesItemResponse::new(
+        "Item received successfully",
+        item_id,
+    )))
+}
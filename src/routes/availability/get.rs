@@ -0,0 +1,39 @@
+use actix_web::web;
+
+use crate::{
+    errors::AvailabilityError,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        availability::{AvailabilityRecord, AvailabilityStatus},
+    },
+};
+
+// the hire-me banner, the newest entry in the history is the current availability
+#[tracing::instrument(name = "Get availability", skip(pools))]
+pub async fn get_availability(
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<AvailabilityRecord>, AvailabilityError> {
+    let availability = sqlx::query_as!(
+        AvailabilityRecord,
+        r#"
+        SELECT
+            availability_id,
+            status AS "status: AvailabilityStatus",
+            booked_until,
+            note,
+            created_at
+        FROM availability
+        ORDER BY created_at DESC
+        LIMIT 1"#
+    )
+    .fetch_one(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch availability: {e:?}");
+        AvailabilityError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let last_modified = availability.created_at;
+    Ok(ApiResponse::ok(availability).with_last_modified(last_modified))
+}
@@ -1,10 +1,14 @@
-use actix_web::{HttpRequest, HttpResponse, web};
-use sqlx::PgPool;
+use actix_web::{HttpRequest, web};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
 
 use crate::{
+    blog_cache::{BlogCache, BlogPage, BlogPageKey},
     errors::BlogError,
     session_state::TypedSession,
+    startup::DbPools,
     types::{
+        api_response::ApiResponse,
         article::{ArticleRecord, ArticleRecordRaw},
         pagination::{PaginatedResponse, PaginationMeta, PaginationQuery},
     },
@@ -24,14 +28,15 @@ fn parse_header<T: std::str::FromStr>(req: &HttpRequest, key: &str) -> Option<T>
 
 #[tracing::instrument(
     name = "Get blog posts with pagination",
-    skip(pool, session),
-    fields(page, page_size, on_published, slug)
+    skip(pools, session, cache),
+    fields(page, page_size, on_published, slug, cache_hit)
 )]
 pub async fn get_articles(
     request: HttpRequest,
-    pool: web::Data<PgPool>,
+    pools: web::Data<DbPools>,
     session: TypedSession,
-) -> Result<HttpResponse, actix_web::Error> {
+    cache: web::Data<BlogCache>,
+) -> Result<ApiResponse<Vec<ArticleRecord>>, actix_web::Error> {
     let pagination = PaginationQuery {
         page: parse_header(&request, "BlogPost-Page").unwrap_or(1),
         page_size: parse_header(&request, "BlogPost-Page-Size").unwrap_or(20),
@@ -56,24 +61,53 @@ pub async fn get_articles(
         .record("on_published", on_published)
         .record("slug", slug.as_deref().unwrap_or("no slug"));
 
-    let total_count = sqlx::query_scalar!(
+    // published pages are the same for everyone, so they're served from memory when possible
+    let cache_key = (on_published && cache.is_enabled()).then(|| BlogPageKey {
+        slug: slug.clone(),
+        page: pagination.page(),
+        page_size: pagination.page_size,
+    });
+    if let Some(page) = cache_key.as_ref().and_then(|key| cache.get(key)) {
+        tracing::Span::current().record("cache_hit", true);
+        return Ok(articles_response(
+            page.articles.clone(),
+            page.total,
+            page.last_modified,
+            &pagination,
+        ));
+    }
+    let generation = cache.generation();
+    // a page that's about to be cached comes from the primary, a lagging replica could
+    // otherwise pin the page from before the last edit for the whole ttl
+    let pool = if cache_key.is_some() {
+        &pools.writer
+    } else {
+        &pools.reader
+    };
+
+    // the newest edit among the matching posts stands in for the whole list's age,
+    // or the last delete if that's later, so a deleted post can't outlive a 304
+    let summary = sqlx::query!(
         r#"
-        SELECT COUNT(*)
-        FROM blog_posts 
-        WHERE 
+        SELECT
+            COUNT(*) AS "total!",
+            GREATEST(MAX(updated_at), (SELECT last_deleted_at FROM blog_deletions))
+                AS last_modified
+        FROM blog_posts
+        WHERE
             (NOT $1 OR published = true)
             AND ($2::text IS NULL OR slug = $2)
         "#,
         on_published,
         slug
     )
-    .fetch_one(pool.as_ref())
+    .fetch_one(pool)
     .await
     .map_err(|e| {
         tracing::error!("Failed to get blog post count: {e:?}");
         BlogError::QueryFailed
-    })?
-    .unwrap_or(0);
+    })?;
+    let total_count = summary.total;
 
     let articles: Vec<ArticleRecord> = sqlx::query_as!(
         ArticleRecordRaw,
@@ -99,7 +133,7 @@ pub async fn get_articles(
         pagination.page_size,
         pagination.offset()
     )
-    .fetch_all(pool.as_ref())
+    .fetch_all(pool)
     .await
     .map_err(|e| {
         tracing::error!("Failed to fetch blog posts: {e:?}");
@@ -113,10 +147,41 @@ pub async fn get_articles(
         BlogError::UnexpectedError(anyhow::anyhow!(e))
     })?;
 
-    let response = PaginatedResponse {
-        data: articles,
-        pagination: PaginationMeta::from_total(total_count, &pagination),
-    };
+    if let Some(key) = cache_key {
+        let page = BlogPage {
+            articles: articles.clone(),
+            total: total_count,
+            last_modified: summary.last_modified,
+        };
+        cache.insert(key, Arc::new(page), generation);
+    }
+
+    Ok(articles_response(
+        articles,
+        total_count,
+        summary.last_modified,
+        &pagination,
+    ))
+}
+
+fn articles_response(
+    articles: Vec<ArticleRecord>,
+    total_count: i64,
+    last_modified: Option<DateTime<Utc>>,
+    pagination: &PaginationQuery,
+) -> ApiResponse<Vec<ArticleRecord>> {
+    let pagination = PaginationMeta::from_total(total_count, pagination);
 
-    Ok(HttpResponse::Ok().json(response))
+    let response = ApiResponse::ok(articles)
+        .with_legacy_body(|articles| {
+            serde_json::to_value(PaginatedResponse {
+                data: articles,
+                pagination: &pagination,
+            })
+        })
+        .with_pagination(pagination);
+    match last_modified {
+        Some(last_modified) => response.with_last_modified(last_modified),
+        None => response,
+    }
 }
@@ -1,10 +1,12 @@
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use anyhow::Context;
 use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
 use secrecy::ExposeSecret;
 use sqlx::PgPool;
 
-use crate::{authentication::UserId, startup::JwtPrivateKey, utils::e500};
+use crate::{
+    authentication::UserId, startup::JwtPrivateKey, types::api_response::ApiResponse, utils::e500,
+};
 
 // non-semantic names dangit!
 // SignalR maps sub to ClaimTypes.NameIdentifier
@@ -26,7 +28,7 @@ pub async fn chat_token(
     user_id: UserId,
     jwt_key: web::Data<JwtPrivateKey>,
     pool: web::Data<PgPool>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
     // expires in 60 seconds, once the WebSocket is established, the token
     // is no longer needed
     let exp = chrono::Utc::now()
@@ -58,5 +60,5 @@ pub async fn chat_token(
 
     let token = encode(&Header::new(Algorithm::ES256), &claims, &key).map_err(e500)?;
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "token": token })))
+    Ok(ApiResponse::ok(serde_json::json!({ "token": token })))
 }
@@ -1,15 +1,18 @@
 use actix_web::{HttpRequest, HttpResponse, web};
-use email_address::EmailAddress;
 use sqlx::{PgPool, Postgres, Transaction};
+use std::cell::Cell;
 use std::ops::Deref;
-use std::str::FromStr;
+use std::rc::Rc;
 use uuid::Uuid;
 
-use crate::configuration::MessageRateLimitSettings;
+use crate::configuration::ContactSettings;
 use crate::errors::ContactSubmissionError;
-use crate::idempotency::execute_idempotent;
+use crate::events::{DashboardEvent, EventBus};
+use crate::idempotency::{RequestFingerprint, execute_idempotent};
+use crate::types::{api_response::ApiResponse, email::ValidatedEmail};
+use crate::utils::e500;
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct MessageForm {
     email: String,
     sender_name: String,
@@ -50,16 +53,18 @@ impl MessageResponse {
 
 #[derive(PartialEq, Debug)]
 struct ValidatedMessage {
-    email: String,
+    email: ValidatedEmail,
     sender_name: String,
     message_text: String,
 }
 
 impl MessageForm {
-    fn validate(&self) -> Result<ValidatedMessage, ContactSubmissionError> {
-        let validated_email = EmailAddress::from_str(&self.email)
-            .map(|r| r.email())
-            .map_err(|e| {
+    fn validate(
+        &self,
+        strip_plus_addresses: bool,
+    ) -> Result<ValidatedMessage, ContactSubmissionError> {
+        let validated_email =
+            ValidatedEmail::parse(&self.email, strip_plus_addresses).map_err(|e| {
                 tracing::warn!(
                     email = %self.email,
                     error = ?e,
@@ -107,7 +112,7 @@ impl MessageForm {
 
 #[tracing::instrument(
     name = "Send message to contact table",
-    skip(message, pool, request, message_config),
+    skip(message, pool, request, events, contact),
     fields(
         email = %message.email,
         message_id = tracing::field::Empty
@@ -117,45 +122,39 @@ pub async fn post_message(
     message: web::Form<MessageForm>,
     pool: web::Data<PgPool>,
     request: HttpRequest,
-    message_config: web::Data<MessageRateLimitSettings>,
+    events: web::Data<EventBus>,
+    contact: web::Data<ContactSettings>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let message_to_post = message.0;
-    let config_for_op = message_config.clone();
-
-    execute_idempotent(&request, pool.get_ref(), None, move |tx| {
-        let config_for_op = config_for_op.clone();
-        Box::pin(
-            async move { process_new_message(tx, config_for_op.get_ref(), message_to_post).await },
-        )
+    let strip_plus_addresses = contact.strip_plus_addresses;
+    let fingerprint = RequestFingerprint::of(&message_to_post).map_err(e500)?;
+
+    // the insert and the stored response commit together, the dashboard only hears about
+    // the message after that, and not again when a retry is answered from the stored response
+    let received = Rc::new(Cell::new(None));
+    let pending = Rc::clone(&received);
+    let response = execute_idempotent(&request, pool.get_ref(), None, &fingerprint, move |tx| {
+        Box::pin(async move {
+            process_new_message(tx, &pending, message_to_post, strip_plus_addresses).await
+        })
     })
-    .await
+    .await?;
+    if let Some(event) = received.take() {
+        events.publish(event);
+    }
+
+    Ok(response)
 }
 
 #[allow(clippy::future_not_send)]
 // consume the transaction immediately for Send safety
 async fn process_new_message(
     transaction: &mut Transaction<'static, Postgres>,
-    config: &MessageRateLimitSettings,
+    received: &Cell<Option<DashboardEvent>>,
     message: MessageForm,
-) -> Result<HttpResponse, actix_web::Error> {
-    let validated_input = message.validate()?;
-
-    let rate_ok = sqlx::query_scalar!(
-        "SELECT check_email_rate_limit($1, $2, $3)",
-        &validated_input.email,
-        i32::try_from(config.max_messages).expect("Failed to cast config.max_messages"),
-        i32::try_from(config.window_minutes).expect("Failed to cast config.window_minutes")
-    )
-    .fetch_one(transaction.as_mut())
-    .await
-    .map_err(|e| {
-        ContactSubmissionError::UnexpectedError(anyhow::anyhow!("Unexpected error: {e:?}"))
-    })?
-    .unwrap_or(false);
-
-    if !rate_ok {
-        return Err(ContactSubmissionError::RateLimitExceeded.into());
-    }
+    strip_plus_addresses: bool,
+) -> Result<ApiResponse<MessageResponse>, actix_web::Error> {
+    let validated_input = message.validate(strip_plus_addresses)?;
 
     let message_id = MessageId(Uuid::new_v4());
     tracing::Span::current().record("message_id", tracing::field::display(&message_id));
@@ -166,7 +165,7 @@ async fn process_new_message(
         VALUES ($1, $2, $3, $4, NOW(), FALSE)
         "#,
         *message_id,
-        validated_input.email,
+        validated_input.email.as_str(),
         validated_input.sender_name,
         validated_input.message_text
     )
@@ -176,7 +175,12 @@ async fn process_new_message(
     match result {
         Ok(_) => {
             tracing::info!("Message saved successfully with: {}", message_id);
-            Ok(HttpResponse::Accepted().json(MessageResponse::new(
+            received.set(Some(DashboardEvent::ContactMessage {
+                message_id: *message_id,
+                sender_name: validated_input.sender_name,
+                received_at: chrono::Utc::now(),
+            }));
+            Ok(ApiResponse::accepted(MessageResponse::new(
                 "Message received successfully",
                 message_id,
             )))
@@ -207,7 +211,7 @@ mod test {
             message_text: "This is a test message.".to_string(),
         };
 
-        let mut result = form_with_bad_email.validate();
+        let mut result = form_with_bad_email.validate(false);
         assert!(matches!(result, Err(ContactSubmissionError::InvalidEmail)));
 
         let form_with_bad_name = MessageForm {
@@ -216,7 +220,7 @@ mod test {
             message_text: "This is a test message".to_string(),
         };
 
-        result = form_with_bad_name.validate();
+        result = form_with_bad_name.validate(false);
         assert!(matches!(result, Err(ContactSubmissionError::NameLength)));
 
         let form_with_whitespace_name = MessageForm {
@@ -225,7 +229,7 @@ mod test {
             message_text: "This is a test message".to_string(),
         };
 
-        result = form_with_whitespace_name.validate();
+        result = form_with_whitespace_name.validate(false);
         assert!(matches!(result, Err(ContactSubmissionError::NameLength)));
 
         let form_with_bad_message = MessageForm {
@@ -234,7 +238,7 @@ mod test {
             message_text: "T".to_string(),
         };
 
-        result = form_with_bad_message.validate();
+        result = form_with_bad_message.validate(false);
         assert!(matches!(result, Err(ContactSubmissionError::MessageLength)));
 
         let good_form = MessageForm {
@@ -242,7 +246,7 @@ mod test {
             sender_name: "John Doe".to_string(),
             message_text: "This is a test message".to_string(),
         }
-        .validate();
+        .validate(false);
 
         assert!(good_form.is_ok());
     }
@@ -0,0 +1,51 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::{
+    errors::ExperienceError,
+    startup::DbPools,
+    types::{api_response::ApiResponse, experience::ExperienceRecord},
+};
+
+// the about page's timeline, the same entries the resume lists
+#[tracing::instrument(name = "Get experience", skip(pools))]
+pub async fn get_experience(
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<Vec<ExperienceRecord>>, ExperienceError> {
+    let experience = list_experience(&pools.reader).await?;
+
+    let last_modified = experience.iter().map(|entry| entry.updated_at).max();
+    let response = ApiResponse::ok(experience);
+    Ok(match last_modified {
+        Some(last_modified) => response.with_last_modified(last_modified),
+        None => response,
+    })
+}
+
+// newest first, a current job ahead of anything that has ended
+/// # Errors
+/// returns an `UnexpectedError` if the entries can't be read
+pub async fn list_experience(pool: &PgPool) -> Result<Vec<ExperienceRecord>, ExperienceError> {
+    sqlx::query_as!(
+        ExperienceRecord,
+        r#"
+        SELECT
+            experience_id,
+            company,
+            role,
+            start_date,
+            end_date,
+            highlights,
+            tech,
+            created_at,
+            updated_at
+        FROM experience
+        ORDER BY end_date IS NULL DESC, start_date DESC"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch experience: {e:?}");
+        ExperienceError::UnexpectedError(anyhow::anyhow!(e))
+    })
+}
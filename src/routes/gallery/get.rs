@@ -0,0 +1,91 @@
+use actix_web::web;
+
+use crate::{
+    errors::AlbumError,
+    startup::DbPools,
+    types::{
+        album::{AlbumRecord, AlbumRecordRaw, AlbumSummary, AlbumSummaryRaw},
+        api_response::ApiResponse,
+    },
+};
+
+// published albums as tiles, in the order set by the admin
+#[tracing::instrument(name = "Get gallery", skip(pools))]
+pub async fn get_gallery(
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<Vec<AlbumSummary>>, AlbumError> {
+    let albums = sqlx::query_as!(
+        AlbumSummaryRaw,
+        r#"
+        SELECT
+            album_id,
+            title,
+            slug,
+            description,
+            photos -> 0 AS "cover: serde_json::Value",
+            jsonb_array_length(photos) AS "photo_count!",
+            updated_at
+        FROM albums
+        WHERE published = true
+        ORDER BY display_order, created_at DESC"#
+    )
+    .fetch_all(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch albums: {e:?}");
+        AlbumError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .into_iter()
+    .map(AlbumSummary::try_from)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| {
+        tracing::error!("Failed to deserialize album cover: {e:?}");
+        AlbumError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let last_modified = albums.iter().map(|album| album.updated_at).max();
+    let response = ApiResponse::ok(albums);
+    Ok(match last_modified {
+        Some(last_modified) => response.with_last_modified(last_modified),
+        None => response,
+    })
+}
+
+// one published album with its photos, drafts are a 404 like albums that don't exist
+#[tracing::instrument(name = "Get album", skip(pools))]
+pub async fn get_album(
+    slug: web::Path<String>,
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<AlbumRecord>, AlbumError> {
+    let raw = sqlx::query_as!(
+        AlbumRecordRaw,
+        r#"
+        SELECT
+            album_id,
+            title,
+            slug,
+            description,
+            photos AS "photos: serde_json::Value",
+            published,
+            display_order,
+            created_at,
+            updated_at
+        FROM albums
+        WHERE slug = $1 AND published = true"#,
+        slug.as_str()
+    )
+    .fetch_optional(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch album: {e:?}");
+        AlbumError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .ok_or(AlbumError::AlbumNotFound)?;
+    let album = AlbumRecord::try_from(raw).map_err(|e| {
+        tracing::error!("Failed to deserialize album photos: {e:?}");
+        AlbumError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let last_modified = album.updated_at;
+    Ok(ApiResponse::ok(album).with_last_modified(last_modified))
+}
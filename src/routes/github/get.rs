@@ -0,0 +1,38 @@
+use actix_web::web;
+
+use crate::{
+    errors::GithubError,
+    github::{GithubActivity, GithubActivitySnapshot},
+    startup::DbPools,
+    types::api_response::ApiResponse,
+};
+
+// served from the copy the github_activity job keeps, never from github itself
+#[tracing::instrument(name = "Get GitHub activity", skip(pools))]
+pub async fn get_github_activity(
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<GithubActivitySnapshot>, GithubError> {
+    let row = sqlx::query!(
+        r#"
+        SELECT activity AS "activity: serde_json::Value", fetched_at
+        FROM github_activity"#
+    )
+    .fetch_optional(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch GitHub activity: {e:?}");
+        GithubError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .ok_or(GithubError::NotFetchedYet)?;
+
+    let activity: GithubActivity = serde_json::from_value(row.activity).map_err(|e| {
+        tracing::error!("Failed to deserialize GitHub activity: {e:?}");
+        GithubError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    Ok(ApiResponse::ok(GithubActivitySnapshot {
+        activity,
+        fetched_at: row.fetched_at,
+    })
+    .with_last_modified(row.fetched_at))
+}
@@ -1,5 +1,79 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpResponse, ResponseError, http::StatusCode, web};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::errors::ApiProblem;
+use crate::redis_pool::{self, RedisPool};
+use crate::reload::ReloadableSettings;
+use crate::startup::MIGRATOR;
+
+// a dependency slower than this counts as down, the orchestrator's probe would give up anyway
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub async fn health_check() -> HttpResponse {
     HttpResponse::Ok().finish()
 }
+
+// the process is up and serving, a failure here means restart it
+pub async fn live() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+// whether traffic should be routed here, a failure means wait rather than restart
+#[tracing::instrument(name = "Readiness probe", skip_all)]
+pub async fn ready(
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    reloadable: web::Data<ReloadableSettings>,
+) -> HttpResponse {
+    let mut failing = Vec::new();
+    if *reloadable.maintenance_mode.get() {
+        failing.push("maintenance mode");
+    }
+    match tokio::time::timeout(PROBE_TIMEOUT, migrations_applied(&pool)).await {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => failing.push("migrations"),
+        Ok(Err(e)) => {
+            tracing::warn!(error.cause_chain = ?e, "Database unreachable");
+            failing.push("database");
+        }
+        Err(_) => failing.push("database"),
+    }
+    let ping = async {
+        redis::cmd("PING")
+            .query_async::<()>(&mut redis_pool::get(&redis).await?)
+            .await
+    };
+    match tokio::time::timeout(PROBE_TIMEOUT, ping).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            tracing::warn!(error.cause_chain = ?e, "Redis unreachable");
+            failing.push("redis");
+        }
+        Err(_) => failing.push("redis"),
+    }
+
+    if failing.is_empty() {
+        HttpResponse::Ok().finish()
+    } else {
+        ApiProblem::new(StatusCode::SERVICE_UNAVAILABLE)
+            .with_detail(format!("Not ready: {}", failing.join(", ")))
+            .error_response()
+    }
+}
+
+// every migration this build embeds has been run, a newer build waits for its own
+pub(crate) async fn migrations_applied(pool: &PgPool) -> Result<bool, sqlx::Error> {
+    // unchecked, the table is sqlx's own and only exists once something has migrated
+    let applied: HashSet<i64> =
+        sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    Ok(MIGRATOR
+        .iter()
+        .all(|migration| applied.contains(&migration.version)))
+}
@@ -1,5 +1,7 @@
 use crate::authentication::compute_password_hash;
-use actix_web::{HttpResponse, web};
+use crate::configuration::PasswordHashingSettings;
+use crate::types::api_response::ApiResponse;
+use actix_web::{http::StatusCode, web};
 use secrecy::{ExposeSecret, SecretString};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
@@ -15,7 +17,8 @@ pub struct AcceptInvitationParams {
 pub async fn accept_invitation(
     params: web::Json<AcceptInvitationParams>,
     pool: web::Data<PgPool>,
-) -> Result<HttpResponse, actix_web::Error> {
+    hashing: web::Data<PasswordHashingSettings>,
+) -> Result<ApiResponse, actix_web::Error> {
     let mut hasher = Sha256::new();
     hasher.update(params.token.as_bytes());
     let token_hash = hex::encode(hasher.finalize());
@@ -42,7 +45,7 @@ pub async fn accept_invitation(
 
     let password_secret = SecretString::new(params.password.clone().into());
 
-    let password_hash = compute_password_hash(&password_secret)
+    let password_hash = compute_password_hash(&password_secret, &hashing)
         .map_err(actix_web::error::ErrorInternalServerError)?;
     let new_user_id = uuid::Uuid::new_v4();
 
@@ -83,5 +86,5 @@ pub async fn accept_invitation(
         }
     }
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(ApiResponse::empty(StatusCode::OK))
 }
@@ -1,21 +1,69 @@
-use actix_web::HttpResponse;
+use actix_web::{http::StatusCode, web};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
 
+use crate::authentication::is_session_revoked;
+use crate::errors::ApiProblem;
 use crate::session_state::TypedSession;
+use crate::types::api_response::ApiResponse;
+use crate::utils::e500;
 
-// I feel like this should be extended
+#[derive(serde::Serialize)]
+struct PreviousLogin {
+    login_at: Option<DateTime<Utc>>,
+    ip_address: Option<String>,
+}
+
+#[allow(clippy::future_not_send)]
+#[tracing::instrument(name = "Check if authenticated", skip(session, pool))]
+pub async fn check_auth(
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<String>, actix_web::Error> {
+    let (Ok(Some(_)), Ok(Some(role))) = (session.get_user_id(), session.get_user_role()) else {
+        return Err(ApiProblem::new(StatusCode::UNAUTHORIZED).into());
+    };
+
+    if let Some(session_id) = session.get_session_id().map_err(e500)?
+        && is_session_revoked(session_id, &pool).await.map_err(e500)?
+    {
+        // not an `Err`, the session middleware only writes the purge out on an `Ok`
+        session.log_out();
+        return Ok(ApiResponse::empty(StatusCode::UNAUTHORIZED));
+    }
+
+    // renew session on each check_auth to extend TTL
+    session.renew();
+    Ok(ApiResponse::ok(role.to_string()))
+}
+
+// the login before this one, so access the user doesn't recognise stands out
+// the current login is always the caller's own, so it would never show anything
 #[allow(clippy::future_not_send)]
-#[tracing::instrument(name = "Check if authenticated", skip(session))]
-pub async fn check_auth(session: TypedSession) -> HttpResponse {
-    match session.get_user_id() {
-        Ok(Some(_)) => {
-            // renew session on each check_auth to extend TTL
-            session.renew();
-            let user_role = session.get_user_role();
-            match user_role {
-                Ok(Some(role)) => HttpResponse::Ok().json(role.to_string()),
-                _ => HttpResponse::Unauthorized().finish(),
-            }
-        }
-        _ => HttpResponse::Unauthorized().finish(),
+#[tracing::instrument(name = "Get previous login", skip(session, pool))]
+pub async fn previous_login(
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<impl serde::Serialize>, actix_web::Error> {
+    let Ok(Some(user_id)) = session.get_user_id() else {
+        return Err(ApiProblem::new(StatusCode::UNAUTHORIZED).into());
+    };
+
+    let previous = sqlx::query_as!(
+        PreviousLogin,
+        r#"
+        SELECT previous_login_at AS login_at, previous_login_ip AS ip_address
+        FROM users
+        WHERE user_id = $1
+        "#,
+        user_id
+    )
+    .fetch_optional(pool.as_ref())
+    .await
+    .map_err(e500)?;
+
+    match previous {
+        Some(previous) => Ok(ApiResponse::ok(previous)),
+        None => Err(ApiProblem::new(StatusCode::UNAUTHORIZED).into()),
     }
 }
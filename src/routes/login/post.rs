@@ -1,10 +1,15 @@
-use actix_web::{HttpResponse, ResponseError, dev::ConnectionInfo, error::InternalError, web};
+use actix_web::{ResponseError, error::InternalError, http::StatusCode, web};
 use secrecy::SecretString;
 use sqlx::PgPool;
 
-use crate::authentication::{Credentials, validate_credentials};
+use crate::authentication::{
+    Credentials, LoginMetadata, record_login, revoke_session, validate_credentials,
+};
+use crate::configuration::PasswordHashingSettings;
 use crate::errors::AuthError;
+use crate::events::{DashboardEvent, EventBus};
 use crate::session_state::TypedSession;
+use crate::types::api_response::ApiResponse;
 
 #[derive(serde::Deserialize, Debug)]
 pub struct LoginRequest {
@@ -15,15 +20,17 @@ pub struct LoginRequest {
 #[allow(clippy::missing_errors_doc)]
 #[allow(clippy::future_not_send)]
 #[tracing::instrument(
-    skip(pool, session),
+    skip(metadata, pool, hashing, events, session),
     fields(username=tracing::field::Empty, user_id=tracing::field::Empty)
 )]
 pub async fn login(
-    _conn: ConnectionInfo,
+    metadata: LoginMetadata,
     request: web::Form<LoginRequest>,
     pool: web::Data<PgPool>,
+    hashing: web::Data<PasswordHashingSettings>,
+    events: web::Data<EventBus>,
     session: TypedSession,
-) -> Result<HttpResponse, InternalError<AuthError>> {
+) -> Result<ApiResponse<serde_json::Value>, InternalError<AuthError>> {
     let credentials = Credentials {
         username: request.username.clone(),
         password: request.password.clone(),
@@ -31,9 +38,20 @@ pub async fn login(
 
     tracing::Span::current().record("username", tracing::field::display(&credentials.username));
 
-    match validate_credentials(credentials, &pool).await {
+    let username = credentials.username.clone();
+    let publish_attempt = |success: bool| {
+        events.publish(DashboardEvent::LoginAttempt {
+            username: username.clone(),
+            success,
+            ip_address: metadata.ip_address.clone(),
+            attempted_at: chrono::Utc::now(),
+        });
+    };
+
+    match validate_credentials(credentials, &hashing, &pool).await {
         Ok((user_id, totp_enabled, must_change_password, user_role)) => {
             tracing::Span::current().record("user_id", tracing::field::display(&user_id));
+            publish_attempt(true);
             session.renew();
 
             if totp_enabled {
@@ -42,7 +60,9 @@ pub async fn login(
                     .insert_mfa_pending_user_id(user_id)
                     .map_err(|e| login_error(AuthError::UnexpectedError(e.into())))?;
 
-                Ok(HttpResponse::Accepted().json(serde_json::json!({ "mfa_required": true })))
+                Ok(ApiResponse::accepted(
+                    serde_json::json!({ "mfa_required": true }),
+                ))
             } else {
                 session
                     .insert_user_id(user_id)
@@ -51,20 +71,29 @@ pub async fn login(
                     .insert_user_role(user_role)
                     .map_err(|e| login_error(AuthError::UnexpectedError(e.into())))?;
 
+                // a failed audit write shouldn't lock anyone out
+                match record_login(user_id, &metadata, &pool).await {
+                    Ok(session_id) => session
+                        .insert_session_id(session_id)
+                        .map_err(|e| login_error(AuthError::UnexpectedError(e.into())))?,
+                    Err(e) => tracing::warn!(error.cause_chain = ?e, "Failed to record last login"),
+                }
+
                 if must_change_password {
-                    Ok(
-                        HttpResponse::Ok()
-                            .json(serde_json::json!({ "must_change_password": true })),
-                    )
+                    Ok(ApiResponse::ok(
+                        serde_json::json!({ "must_change_password": true }),
+                    ))
                 } else {
-                    Ok(HttpResponse::Ok().finish())
+                    Ok(ApiResponse::empty(StatusCode::OK))
                 }
             }
         }
         Err(e) => {
             let e = match e {
-                AuthError::RateLimitExceeded => AuthError::RateLimitExceeded,
-                AuthError::InvalidCredentials(_) => AuthError::InvalidCredentials(e.into()),
+                AuthError::InvalidCredentials(_) => {
+                    publish_attempt(false);
+                    AuthError::InvalidCredentials(e.into())
+                }
                 AuthError::UnexpectedError(_) => AuthError::UnexpectedError(e.into()),
             };
             Err(login_error(e))
@@ -74,12 +103,22 @@ pub async fn login(
 
 #[allow(clippy::missing_errors_doc)]
 #[allow(clippy::future_not_send)]
-pub async fn logout(session: TypedSession) -> Result<HttpResponse, actix_web::Error> {
+pub async fn logout(
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse, actix_web::Error> {
+    // drop the device from the session list, the cookie session is purged regardless
+    if let (Ok(Some(user_id)), Ok(Some(session_id))) =
+        (session.get_user_id(), session.get_session_id())
+        && let Err(e) = revoke_session(user_id, session_id, &pool).await
+    {
+        tracing::warn!(error.cause_chain = ?e, "Failed to revoke session on logout");
+    }
     session.log_out();
-    Ok(HttpResponse::Ok().finish())
+    Ok(ApiResponse::empty(StatusCode::OK))
 }
 
 fn login_error(e: AuthError) -> InternalError<AuthError> {
-    let response = HttpResponse::build(e.status_code()).finish();
+    let response = e.error_response();
     InternalError::from_response(e, response)
 }
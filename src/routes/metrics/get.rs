@@ -0,0 +1,26 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+
+use crate::configuration::MetricsSettings;
+use crate::metrics::AppMetrics;
+use crate::utils::e500;
+
+// pull endpoint for Prometheus, only reachable from allowlisted addresses
+// uses the socket peer rather than forwarded headers, which anyone can set
+#[tracing::instrument(name = "Export metrics", skip_all)]
+pub async fn export_metrics(
+    request: HttpRequest,
+    metrics: web::Data<AppMetrics>,
+    settings: web::Data<MetricsSettings>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let allowed = request
+        .peer_addr()
+        .is_some_and(|peer| settings.allowed_ips.contains(&peer.ip()));
+    if !allowed {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let body = metrics.render().map_err(e500)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(body))
+}
@@ -0,0 +1,122 @@
+use actix_web::{
+    HttpRequest,
+    http::{StatusCode, header::USER_AGENT},
+    web,
+};
+use sqlx::PgPool;
+
+use crate::configuration::MetricsSettings;
+use crate::errors::MetricsIngestError;
+use crate::metrics::{
+    BatchItemResult, PageVisitBatchRequest, PageVisitRecorder, PageVisitRequest,
+    PerformanceMetricRequest, effective_rate, insert_performance_metric, keep_event, keep_session,
+};
+use crate::startup::SessionHashKey;
+use crate::types::api_response::ApiResponse;
+
+#[tracing::instrument(name = "Record page visit", skip_all)]
+pub async fn record_page_visit(
+    request: HttpRequest,
+    visit: web::Json<PageVisitRequest>,
+    recorder: web::Data<PageVisitRecorder>,
+    metrics_settings: web::Data<MetricsSettings>,
+    hash_key: web::Data<SessionHashKey>,
+) -> Result<ApiResponse, MetricsIngestError> {
+    visit.validate()?;
+
+    // sampled-out and shed visits are still acknowledged, the frontend doesn't need to know
+    let sample_rate = effective_rate(metrics_settings.sampling.page_visits);
+    if keep_visit(&visit, sample_rate, &hash_key) {
+        recorder.record(&visit, user_agent(&request), sample_rate);
+    }
+
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
+
+// invalid visits are reported back per item instead of failing the whole batch
+// the body is read whatever its content type, `navigator.sendBeacon` sends text/plain
+#[tracing::instrument(name = "Record page visit batch", skip_all, fields(count = tracing::field::Empty))]
+pub async fn record_page_visit_batch(
+    request: HttpRequest,
+    body: web::Bytes,
+    recorder: web::Data<PageVisitRecorder>,
+    metrics_settings: web::Data<MetricsSettings>,
+    hash_key: web::Data<SessionHashKey>,
+) -> Result<ApiResponse<serde_json::Value>, MetricsIngestError> {
+    let batch: PageVisitBatchRequest =
+        serde_json::from_slice(&body).map_err(|_| MetricsIngestError::InvalidBatchBody)?;
+    tracing::Span::current().record("count", batch.visits.len());
+    let max_batch_size = metrics_settings.max_visit_batch_size;
+    if batch.visits.is_empty() || batch.visits.len() > max_batch_size {
+        return Err(MetricsIngestError::InvalidBatchSize(max_batch_size));
+    }
+
+    let mut valid = Vec::with_capacity(batch.visits.len());
+    let results: Vec<BatchItemResult> = batch
+        .visits
+        .iter()
+        .enumerate()
+        .map(|(index, visit)| match visit.validate() {
+            Ok(()) => {
+                valid.push(visit);
+                BatchItemResult {
+                    index,
+                    accepted: true,
+                    error: None,
+                }
+            }
+            Err(e) => BatchItemResult {
+                index,
+                accepted: false,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect();
+
+    let accepted = valid.len();
+    let sample_rate = effective_rate(metrics_settings.sampling.page_visits);
+    for visit in valid {
+        if keep_visit(visit, sample_rate, &hash_key) {
+            recorder.record(visit, user_agent(&request), sample_rate);
+        }
+    }
+
+    Ok(ApiResponse::accepted(serde_json::json!({
+        "accepted": accepted,
+        "rejected": results.len() - accepted,
+        "results": results,
+    })))
+}
+
+#[tracing::instrument(name = "Record performance metric", skip_all)]
+pub async fn record_performance_metric(
+    metric: web::Json<PerformanceMetricRequest>,
+    pool: web::Data<PgPool>,
+    metrics_settings: web::Data<MetricsSettings>,
+) -> Result<ApiResponse, MetricsIngestError> {
+    metric.validate()?;
+
+    let sample_rate = effective_rate(metrics_settings.sampling.performance_metrics);
+    if keep_event(sample_rate) {
+        insert_performance_metric(&metric, sample_rate, &pool)
+            .await
+            .map_err(|e| MetricsIngestError::UnexpectedError(e.into()))?;
+    }
+
+    Ok(ApiResponse::empty(StatusCode::ACCEPTED))
+}
+
+// visits with a session are sampled per session, so kept sessions stay whole
+fn keep_visit(visit: &PageVisitRequest, sample_rate: f64, hash_key: &SessionHashKey) -> bool {
+    visit.session_id.as_ref().map_or_else(
+        || keep_event(sample_rate),
+        |session_id| keep_session(hash_key, session_id, sample_rate),
+    )
+}
+
+fn user_agent(request: &HttpRequest) -> Option<&str> {
+    request
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+}
@@ -1,19 +1,55 @@
 mod admin;
+mod availability;
 mod blog;
 mod chat_token;
 mod contact;
+mod experience;
+mod gallery;
+mod github;
 mod health_check;
 mod home;
 mod invitations;
 mod login;
+mod metrics;
+mod now;
+mod now_playing;
+mod profile;
+mod projects;
+mod resume;
+mod robots;
+mod search;
+mod sessions;
+mod short_links;
+mod skills;
+mod storage;
+mod uses;
 mod verify_totp;
+mod version;
 
 pub use admin::*;
+pub use availability::*;
 pub use blog::*;
 pub use chat_token::*;
 pub use contact::*;
+pub use experience::*;
+pub use gallery::*;
+pub use github::*;
 pub use health_check::*;
 pub use home::*;
 pub use invitations::*;
 pub use login::*;
+pub use metrics::*;
+pub use now::*;
+pub use now_playing::*;
+pub use profile::*;
+pub use projects::*;
+pub use resume::*;
+pub use robots::*;
+pub use search::*;
+pub use sessions::*;
+pub use short_links::*;
+pub use skills::*;
+pub use storage::*;
+pub use uses::*;
 pub use verify_totp::*;
+pub use version::*;
@@ -0,0 +1,65 @@
+use actix_web::web;
+
+use crate::{
+    errors::NowEntryError,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        now_entry::{NowEntryKind, NowEntryQuery, NowEntryRecord},
+        pagination::{PaginationMeta, PaginationQuery},
+    },
+};
+
+// newest first, `?kind=now` for the /now page and `?kind=changelog` for the changelog
+#[tracing::instrument(name = "Get now entries", skip(pools))]
+pub async fn get_now_entries(
+    filter: web::Query<NowEntryQuery>,
+    query: web::Query<PaginationQuery>,
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<Vec<NowEntryRecord>>, NowEntryError> {
+    let kind = filter.into_inner().kind;
+    let query = query.into_inner();
+
+    let total_count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*)
+        FROM now_entries
+        WHERE $1::now_entry_kind IS NULL OR kind = $1"#,
+        kind as Option<NowEntryKind>
+    )
+    .fetch_one(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to count now entries: {e:?}");
+        NowEntryError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .unwrap_or(0);
+
+    let entries = sqlx::query_as!(
+        NowEntryRecord,
+        r#"
+        SELECT
+            entry_id,
+            kind AS "kind: NowEntryKind",
+            entry_date,
+            title,
+            body,
+            created_at,
+            updated_at
+        FROM now_entries
+        WHERE $1::now_entry_kind IS NULL OR kind = $1
+        ORDER BY entry_date DESC, created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        kind as Option<NowEntryKind>,
+        query.limit(),
+        query.offset()
+    )
+    .fetch_all(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch now entries: {e:?}");
+        NowEntryError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    Ok(ApiResponse::ok(entries).with_pagination(PaginationMeta::from_total(total_count, &query)))
+}
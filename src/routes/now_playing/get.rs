@@ -0,0 +1,16 @@
+use actix_web::{http::StatusCode, web};
+
+use crate::{
+    spotify::{NowPlaying, NowPlayingFeed},
+    types::api_response::ApiResponse,
+};
+
+// served from the poller's last reading, the footer can poll this as often as it likes
+// and spotify's tokens stay on the server
+#[tracing::instrument(name = "Get now playing", skip_all)]
+pub async fn get_now_playing(feed: web::Data<NowPlayingFeed>) -> ApiResponse<NowPlaying> {
+    feed.latest().map_or_else(
+        || ApiResponse::empty(StatusCode::NO_CONTENT),
+        ApiResponse::ok,
+    )
+}
@@ -0,0 +1,40 @@
+use actix_web::web;
+
+use crate::{
+    errors::ProfileError,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        profile::{Profile, ProfileRaw},
+    },
+};
+
+// the site header and footer, one row so its updated_at is the whole response's age
+#[tracing::instrument(name = "Get profile", skip(pools))]
+pub async fn get_profile(pools: web::Data<DbPools>) -> Result<ApiResponse<Profile>, ProfileError> {
+    let raw = sqlx::query_as!(
+        ProfileRaw,
+        r#"
+        SELECT
+            display_name,
+            bio,
+            avatar_url,
+            social_links AS "social_links: serde_json::Value",
+            contact_available,
+            updated_at
+        FROM profile"#
+    )
+    .fetch_one(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch profile: {e:?}");
+        ProfileError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+    let profile = Profile::try_from(raw).map_err(|e| {
+        tracing::error!("Failed to deserialize profile: {e:?}");
+        ProfileError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let last_modified = profile.updated_at;
+    Ok(ApiResponse::ok(profile).with_last_modified(last_modified))
+}
@@ -0,0 +1,149 @@
+use actix_web::{HttpRequest, web};
+use sqlx::PgPool;
+
+use crate::{
+    errors::ProjectError,
+    session_state::TypedSession,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        project::{ProjectRecord, ProjectRecordRaw},
+    },
+};
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ProjectsQuery {
+    // a skill's name, any case, e.g. `?skill=rust` for the projects using Rust
+    pub skill: Option<String>,
+}
+
+// the homepage grid, published projects only, in the order set by the admin
+#[tracing::instrument(name = "Get projects", skip(pools))]
+pub async fn get_projects(
+    query: web::Query<ProjectsQuery>,
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<Vec<ProjectRecord>>, ProjectError> {
+    let skill = query.into_inner().skill;
+    let projects = list_projects(&pools.reader, true, skill.as_deref()).await?;
+
+    // the newest edit stands in for the whole list's age, like the blog's
+    // not when filtered, linking a skill doesn't touch the projects it's linked to
+    let last_modified = projects.iter().map(|project| project.updated_at).max();
+    let response = ApiResponse::ok(projects);
+    Ok(match last_modified {
+        Some(last_modified) if skill.is_none() => response.with_last_modified(last_modified),
+        _ => response,
+    })
+}
+
+// a project's case study, its content blocks in order
+// signed in, drafts are included unless `Project-OnPublished: true` is sent, like the blog's
+#[tracing::instrument(name = "Get project", skip(request, pools, session))]
+pub async fn get_project(
+    request: HttpRequest,
+    slug: web::Path<String>,
+    pools: web::Data<DbPools>,
+    session: TypedSession,
+) -> Result<ApiResponse<ProjectRecord>, ProjectError> {
+    let is_authenticated = session
+        .get_user_id()
+        .map_err(|e| ProjectError::UnexpectedError(anyhow::anyhow!(e)))?
+        .is_some();
+
+    let on_published = !is_authenticated
+        || request
+            .headers()
+            .get("Project-OnPublished")
+            .and_then(|value| value.to_str().ok()?.parse().ok())
+            .unwrap_or(false);
+
+    let raw = sqlx::query_as!(
+        ProjectRecordRaw,
+        r#"
+        SELECT
+            project_id,
+            title,
+            slug,
+            description,
+            content AS "content: serde_json::Value",
+            tech_tags,
+            repo_url,
+            demo_url,
+            images AS "images: serde_json::Value",
+            published,
+            display_order,
+            created_at,
+            updated_at
+        FROM projects
+        WHERE slug = $1 AND (NOT $2 OR published = true)"#,
+        slug.as_str(),
+        on_published
+    )
+    .fetch_optional(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch project: {e:?}");
+        ProjectError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .ok_or(ProjectError::ProjectNotFound)?;
+    let project = ProjectRecord::try_from(raw).map_err(|e| {
+        tracing::error!("Failed to deserialize project content: {e:?}");
+        ProjectError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let last_modified = project.updated_at;
+    Ok(ApiResponse::ok(project).with_last_modified(last_modified))
+}
+
+/// # Errors
+/// returns an error if the query fails or a stored project no longer deserializes
+pub async fn list_projects(
+    pool: &PgPool,
+    published_only: bool,
+    skill: Option<&str>,
+) -> Result<Vec<ProjectRecord>, ProjectError> {
+    sqlx::query_as!(
+        ProjectRecordRaw,
+        r#"
+        SELECT
+            project_id,
+            title,
+            slug,
+            description,
+            content AS "content: serde_json::Value",
+            tech_tags,
+            repo_url,
+            demo_url,
+            images AS "images: serde_json::Value",
+            published,
+            display_order,
+            created_at,
+            updated_at
+        FROM projects
+        WHERE
+            (NOT $1 OR published = true)
+            AND ($2::text IS NULL OR EXISTS (
+                SELECT 1
+                FROM project_skills
+                JOIN skills USING (skill_id)
+                WHERE project_skills.project_id = projects.project_id
+                    AND lower(skills.name) = lower($2)
+            ))
+        ORDER BY display_order, created_at DESC"#,
+        published_only,
+        skill
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch projects: {e:?}");
+        ProjectError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .into_iter()
+    .map(ProjectRecord::try_from)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| {
+        tracing::error!("Failed to deserialize project content: {e:?}");
+        ProjectError::UnexpectedError(anyhow::anyhow!(e))
+    })
+}
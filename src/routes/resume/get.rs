@@ -0,0 +1,78 @@
+use actix_web::{
+    HttpResponse,
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    web,
+};
+use sqlx::PgPool;
+
+use crate::{
+    errors::ResumeError,
+    routes::list_experience,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        resume::{Resume, ResumeRecordRaw, ResumeSkillRaw},
+    },
+};
+
+// no Last-Modified on either, skills are edited separately and don't touch the resume row
+#[tracing::instrument(name = "Get resume", skip(pools))]
+pub async fn get_resume(pools: web::Data<DbPools>) -> Result<ApiResponse<Resume>, ResumeError> {
+    Ok(ApiResponse::ok(load_resume(&pools.reader).await?))
+}
+
+#[tracing::instrument(name = "Get resume pdf", skip(pools))]
+pub async fn get_resume_pdf(pools: web::Data<DbPools>) -> Result<HttpResponse, ResumeError> {
+    let resume = load_resume(&pools.reader).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((CONTENT_TYPE, "application/pdf"))
+        .insert_header((CONTENT_DISPOSITION, "attachment; filename=\"resume.pdf\""))
+        .body(resume.to_pdf()))
+}
+
+/// # Errors
+/// returns an `UnexpectedError` if the resume, experience or skills can't be read or don't deserialize
+pub async fn load_resume(pool: &PgPool) -> Result<Resume, ResumeError> {
+    let raw = sqlx::query_as!(
+        ResumeRecordRaw,
+        r#"
+        SELECT
+            name,
+            headline,
+            summary,
+            education AS "education: serde_json::Value",
+            updated_at
+        FROM resume"#
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch resume: {e:?}");
+        ResumeError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let experience = list_experience(pool).await.map_err(|e| {
+        tracing::error!("Failed to fetch experience for the resume: {e:?}");
+        ResumeError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    let skills = sqlx::query_as!(
+        ResumeSkillRaw,
+        r#"
+        SELECT name, category
+        FROM skills
+        ORDER BY category, proficiency DESC, name"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch skills for the resume: {e:?}");
+        ResumeError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    Resume::from_parts(raw, experience, skills).map_err(|e| {
+        tracing::error!("Failed to deserialize resume: {e:?}");
+        ResumeError::UnexpectedError(anyhow::anyhow!(e))
+    })
+}
@@ -0,0 +1,84 @@
+use actix_web::{
+    HttpResponse,
+    http::header::{CacheControl, CacheDirective, ContentType},
+    web,
+};
+
+use crate::configuration::RobotsSettings;
+
+// crawlers fetch it often, an hour is plenty for a file that only changes on deploy
+const ROBOTS_MAX_AGE_SECS: u32 = 3600;
+
+pub async fn robots_txt(settings: web::Data<RobotsSettings>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::plaintext())
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(ROBOTS_MAX_AGE_SECS),
+        ]))
+        .body(render(&settings))
+}
+
+fn render(settings: &RobotsSettings) -> String {
+    let mut lines = vec!["User-agent: *".to_string()];
+    if !settings.allow_indexing {
+        lines.push("Disallow: /".to_string());
+    } else if settings.disallow.is_empty() {
+        // an empty Disallow is the spec's way of allowing everything
+        lines.push("Disallow:".to_string());
+    } else {
+        lines.extend(
+            settings
+                .disallow
+                .iter()
+                .map(|path| format!("Disallow: {path}")),
+        );
+    }
+    if let Some(delay) = settings.crawl_delay_secs {
+        lines.push(format!("Crawl-delay: {delay}"));
+    }
+    if let Some(sitemap_url) = &settings.sitemap_url {
+        lines.push(String::new());
+        lines.push(format!("Sitemap: {sitemap_url}"));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn everything_is_disallowed_unless_indexing_is_allowed() {
+        let settings = RobotsSettings {
+            disallow: vec!["/v1/".to_string()],
+            ..RobotsSettings::default()
+        };
+
+        assert_eq!(render(&settings), "User-agent: *\nDisallow: /\n");
+    }
+
+    #[test]
+    fn indexing_keeps_to_the_disallow_list() {
+        let settings = RobotsSettings {
+            allow_indexing: true,
+            disallow: vec!["/v1/".to_string(), "/media/drafts/".to_string()],
+            sitemap_url: Some("https://devogel.dev/sitemap.xml".to_string()),
+            crawl_delay_secs: Some(10),
+        };
+
+        assert_eq!(
+            render(&settings),
+            "User-agent: *\nDisallow: /v1/\nDisallow: /media/drafts/\nCrawl-delay: 10\n\n\
+             Sitemap: https://devogel.dev/sitemap.xml\n"
+        );
+        assert_eq!(
+            render(&RobotsSettings {
+                allow_indexing: true,
+                ..RobotsSettings::default()
+            }),
+            "User-agent: *\nDisallow:\n"
+        );
+    }
+}
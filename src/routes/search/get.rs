@@ -0,0 +1,130 @@
+use actix_web::web;
+
+use crate::{
+    authentication::UserId,
+    errors::SearchError,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        search::{MATCH_START, MATCH_STOP, SearchQuery, SearchResult, SearchResultRaw},
+    },
+};
+
+// mounted publicly and under the admin scope, where the admin middleware has put a `UserId`
+// on the request; only that one also searches drafts and contact messages
+#[tracing::instrument(name = "Search", skip(pools, user_id), fields(include_private))]
+pub async fn search(
+    query: web::Query<SearchQuery>,
+    user_id: Option<web::ReqData<UserId>>,
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<Vec<SearchResult>>, SearchError> {
+    let terms = query.terms()?;
+    let include_private = user_id.is_some();
+    tracing::Span::current().record("include_private", include_private);
+
+    let options = format!(
+        "StartSel={MATCH_START}, StopSel={MATCH_STOP}, MaxFragments=2, MaxWords=30, MinWords=10"
+    );
+
+    // weighted title (A), summary (B) then body (C), ranked across all three kinds at once
+    let results = sqlx::query_as!(
+        SearchResultRaw,
+        r#"
+        SELECT
+            kind AS "kind!",
+            id AS "id!",
+            title AS "title!",
+            slug,
+            snippet AS "snippet!",
+            rank AS "rank!",
+            created_at AS "created_at!"
+        FROM (
+            SELECT
+                'post' AS kind,
+                post_id AS id,
+                title,
+                slug,
+                ts_headline('english', excerpt || ' ' || markdown.body, query, $3) AS snippet,
+                ts_rank(document, query) AS rank,
+                created_at
+            FROM websearch_to_tsquery('english', $1) AS query,
+                blog_posts
+                CROSS JOIN LATERAL (
+                    SELECT COALESCE(string_agg(section->>'content', ' '), '') AS body
+                    FROM jsonb_array_elements(blog_posts.sections) AS section
+                    WHERE section->>'type' = 'markdown'
+                ) AS markdown
+                CROSS JOIN LATERAL (
+                    SELECT
+                        setweight(to_tsvector('english', title), 'A')
+                        || setweight(to_tsvector('english', excerpt), 'B')
+                        || setweight(to_tsvector('english', markdown.body), 'C') AS document
+                ) AS vector
+            WHERE (published OR $2) AND document @@ query
+
+            UNION ALL
+
+            SELECT
+                'project',
+                project_id,
+                title,
+                slug,
+                ts_headline('english', description || ' ' || markdown.body, query, $3),
+                ts_rank(document, query),
+                created_at
+            FROM websearch_to_tsquery('english', $1) AS query,
+                projects
+                CROSS JOIN LATERAL (
+                    SELECT COALESCE(string_agg(section->>'content', ' '), '') AS body
+                    FROM jsonb_array_elements(projects.content) AS section
+                    WHERE section->>'type' = 'markdown'
+                ) AS markdown
+                CROSS JOIN LATERAL (
+                    SELECT
+                        setweight(to_tsvector('english', title), 'A')
+                        || setweight(
+                            to_tsvector('english', description || ' ' || array_to_string(tech_tags, ' ')),
+                            'B'
+                        )
+                        || setweight(to_tsvector('english', markdown.body), 'C') AS document
+                ) AS vector
+            WHERE (published OR $2) AND document @@ query
+
+            UNION ALL
+
+            SELECT
+                'message',
+                message_id,
+                sender_name,
+                NULL,
+                ts_headline('english', message_text, query, $3),
+                ts_rank(document, query),
+                created_at
+            FROM websearch_to_tsquery('english', $1) AS query,
+                messages
+                CROSS JOIN LATERAL (
+                    SELECT
+                        setweight(to_tsvector('simple', sender_name || ' ' || email), 'A')
+                        || setweight(to_tsvector('english', message_text), 'B') AS document
+                ) AS vector
+            WHERE $2 AND document @@ query
+        ) AS results
+        ORDER BY rank DESC, created_at DESC
+        LIMIT $4"#,
+        terms,
+        include_private,
+        options,
+        query.limit()
+    )
+    .fetch_all(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to search: {e:?}");
+        SearchError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .into_iter()
+    .map(SearchResult::try_from)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ApiResponse::ok(results))
+}
@@ -0,0 +1,23 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::authentication::{DeviceSession, UserId, list_sessions};
+use crate::session_state::TypedSession;
+use crate::types::api_response::ApiResponse;
+use crate::utils::e500;
+
+// every device the user is currently signed in on, the requesting one is flagged as current
+#[allow(clippy::future_not_send)]
+#[tracing::instrument(name = "Get sessions", skip(session, pool))]
+pub async fn get_sessions(
+    user_id: UserId,
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse<Vec<DeviceSession>>, actix_web::Error> {
+    let current_session_id = session.get_session_id().map_err(e500)?;
+    let sessions = list_sessions(*user_id, current_session_id, &pool)
+        .await
+        .map_err(e500)?;
+
+    Ok(ApiResponse::ok(sessions))
+}
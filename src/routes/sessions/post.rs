@@ -0,0 +1,27 @@
+use actix_web::{http::StatusCode, web};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::authentication::{UserId, revoke_session};
+use crate::errors::ApiProblem;
+use crate::types::api_response::ApiResponse;
+use crate::utils::e500;
+
+// "this wasn't me": the revoked device is logged out on its next request
+#[tracing::instrument(name = "Revoke session", skip(pool))]
+pub async fn post_revoke_session(
+    user_id: UserId,
+    session_id: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> Result<ApiResponse, actix_web::Error> {
+    let session_id = session_id.into_inner();
+
+    if revoke_session(*user_id, session_id, &pool)
+        .await
+        .map_err(e500)?
+    {
+        Ok(ApiResponse::empty(StatusCode::OK))
+    } else {
+        Err(ApiProblem::new(StatusCode::NOT_FOUND).into())
+    }
+}
@@ -0,0 +1,69 @@
+use actix_web::http::header::{
+    CacheControl, CacheDirective, HeaderName, LOCATION, REFERER, USER_AGENT,
+};
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::PgPool;
+
+use crate::{
+    configuration::MetricsSettings,
+    errors::ShortLinkError,
+    metrics::{PageVisitRecorder, PageVisitRequest, effective_rate, keep_event},
+};
+
+// counts the click and sends the visitor on, never cached so every click comes back here
+// the click is also recorded as a page visit under `/l/{code}` with the link's utm tags,
+// sampled like any other visit, the count on the link itself is always exact
+#[tracing::instrument(
+    name = "Follow short link",
+    skip(request, pool, metrics_settings, recorder)
+)]
+pub async fn follow_short_link(
+    request: HttpRequest,
+    code: web::Path<String>,
+    pool: web::Data<PgPool>,
+    metrics_settings: web::Data<MetricsSettings>,
+    recorder: web::Data<PageVisitRecorder>,
+) -> Result<HttpResponse, ShortLinkError> {
+    let code = code.into_inner();
+    let link = sqlx::query!(
+        r#"
+        UPDATE short_links
+        SET click_count = click_count + 1, last_clicked_at = NOW()
+        WHERE code = $1
+        RETURNING target_url, utm_source, utm_medium, utm_campaign"#,
+        code
+    )
+    .fetch_optional(pool.as_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to count short link click: {e:?}");
+        ShortLinkError::UnexpectedError(anyhow::anyhow!(e))
+    })?
+    .ok_or(ShortLinkError::LinkNotFound)?;
+
+    let visit = PageVisitRequest {
+        path: format!("/l/{code}"),
+        referrer: header(&request, REFERER).map(ToString::to_string),
+        session_id: None,
+        duration_ms: None,
+        utm_source: link.utm_source,
+        utm_medium: link.utm_medium,
+        utm_campaign: link.utm_campaign,
+    };
+    let sample_rate = effective_rate(metrics_settings.sampling.page_visits);
+    if visit.validate().is_ok() && keep_event(sample_rate) {
+        recorder.record(&visit, header(&request, USER_AGENT), sample_rate);
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, link.target_url))
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .finish())
+}
+
+fn header(request: &HttpRequest, name: HeaderName) -> Option<&str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
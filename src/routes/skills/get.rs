@@ -0,0 +1,44 @@
+use actix_web::web;
+
+use crate::{
+    errors::SkillError,
+    startup::DbPools,
+    types::{api_response::ApiResponse, skill::SkillRecord},
+};
+
+// grouped by category for the frontend, strongest first within each
+#[tracing::instrument(name = "Get skills", skip(pools))]
+pub async fn get_skills(
+    pools: web::Data<DbPools>,
+) -> Result<ApiResponse<Vec<SkillRecord>>, SkillError> {
+    let skills = sqlx::query_as!(
+        SkillRecord,
+        r#"
+        SELECT
+            skill_id,
+            name,
+            category,
+            proficiency,
+            years,
+            ARRAY(
+                SELECT project_skills.project_id
+                FROM project_skills
+                JOIN projects USING (project_id)
+                WHERE project_skills.skill_id = skills.skill_id AND projects.published
+                ORDER BY projects.display_order, projects.created_at DESC
+            ) AS "project_ids!",
+            created_at,
+            updated_at
+        FROM skills
+        ORDER BY category, proficiency DESC, name"#
+    )
+    .fetch_all(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch skills: {e:?}");
+        SkillError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    // no Last-Modified, publishing a project changes `project_ids` without touching the skill
+    Ok(ApiResponse::ok(skills))
+}
@@ -0,0 +1,22 @@
+use actix_web::{HttpResponse, web};
+
+use crate::errors::SignedUrlError;
+use crate::routes::SignedUrlQuery;
+use crate::storage::{PresignedMethod, Storage};
+
+#[tracing::instrument(name = "Download a stored object", skip(storage, query))]
+pub async fn download_object(
+    key: web::Path<String>,
+    query: web::Query<SignedUrlQuery>,
+    storage: web::Data<Storage>,
+) -> Result<HttpResponse, SignedUrlError> {
+    if !storage.verify_local_signature(PresignedMethod::Get, &key, query.expires, &query.signature)
+    {
+        return Err(SignedUrlError::InvalidSignature);
+    }
+
+    let bytes = storage.get(&key).await?.ok_or(SignedUrlError::NotFound)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/octet-stream")
+        .body(bytes))
+}
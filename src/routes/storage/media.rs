@@ -0,0 +1,88 @@
+use actix_files::NamedFile;
+use actix_web::http::header::{
+    CACHE_CONTROL, CONTENT_SECURITY_POLICY, CacheControl, CacheDirective, HeaderName, HeaderValue,
+    LOCATION, X_CONTENT_TYPE_OPTIONS,
+};
+use actix_web::{HttpRequest, HttpResponse, web};
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::antivirus::{ScanStatus, ScanUploads, scan_status};
+use crate::errors::MediaError;
+use crate::storage::{MEDIA_PREFIX, Storage};
+
+// how long `/media/{id}` lets browsers and CDNs cache a file, from `storage.media_max_age_secs`
+#[derive(Clone, Copy, Debug)]
+pub struct MediaMaxAge(pub u64);
+
+// `clean` once an upload has been through clamd, absent while scanning is off
+pub const SCAN_STATUS_HEADER: HeaderName = HeaderName::from_static("x-scan-status");
+
+// public media straight off the local backend's disk, with etags and range requests,
+// the s3 backend redirects to a presigned url since the bucket serves its own objects
+// with scanning on, uploads waiting on clamd are a 409 and quarantined ones are gone,
+// and a key with no scan row is a 404, whether it never existed or bypassed the scan
+#[tracing::instrument(name = "Serve media", skip(request, storage, max_age, pool, scan))]
+pub async fn get_media(
+    request: HttpRequest,
+    id: web::Path<String>,
+    storage: web::Data<Storage>,
+    max_age: web::Data<MediaMaxAge>,
+    pool: web::Data<PgPool>,
+    scan: web::Data<ScanUploads>,
+) -> Result<HttpResponse, MediaError> {
+    let key = format!("{MEDIA_PREFIX}/{id}");
+    let status = if scan.0 {
+        scan_status(&pool, &key)
+            .await
+            .context("Failed to look up the upload's scan status")?
+    } else {
+        None
+    };
+    match status {
+        Some(ScanStatus::Pending) => return Err(MediaError::Scanning),
+        Some(ScanStatus::Infected) => return Err(MediaError::NotFound),
+        None if scan.0 => return Err(MediaError::NotFound),
+        Some(ScanStatus::Clean) | None => {}
+    }
+
+    let Some(path) = storage.local_file(&key)? else {
+        let url = storage.presigned_download_url(&key).await?;
+        let mut response = HttpResponse::TemporaryRedirect();
+        if let Some(status) = status {
+            response.insert_header((SCAN_STATUS_HEADER, status.as_str()));
+        }
+        return Ok(response
+            .insert_header((LOCATION, url))
+            .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+            .finish());
+    };
+
+    let file = NamedFile::open_async(&path)
+        .await
+        .map_err(|_| MediaError::NotFound)?;
+    if !file.metadata().is_file() {
+        return Err(MediaError::NotFound);
+    }
+
+    let mut response = file.into_response(&request);
+    let headers = response.headers_mut();
+    headers.insert(
+        CACHE_CONTROL,
+        HeaderValue::from_str(&format!("public, max-age={}", max_age.0))
+            .expect("a number is a valid header value"),
+    );
+    // uploads are whatever someone sent us, never let one run as a page on this origin
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    headers.insert(
+        CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static("default-src 'none'; sandbox"),
+    );
+    if let Some(status) = status {
+        headers.insert(
+            SCAN_STATUS_HEADER,
+            HeaderValue::from_static(status.as_str()),
+        );
+    }
+    Ok(response)
+}
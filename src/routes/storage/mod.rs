@@ -0,0 +1,14 @@
+mod get;
+mod media;
+mod put;
+
+pub use get::*;
+pub use media::*;
+pub use put::*;
+
+// the query string of a presigned url from the local backend
+#[derive(serde::Deserialize)]
+pub struct SignedUrlQuery {
+    expires: i64,
+    signature: String,
+}
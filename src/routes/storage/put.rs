@@ -0,0 +1,46 @@
+use actix_web::{HttpResponse, web};
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::antivirus::{SCAN_UPLOAD_JOB, ScanUpload, ScanUploads, mark_pending};
+use crate::errors::SignedUrlError;
+use crate::jobs::enqueue_job;
+use crate::routes::SignedUrlQuery;
+use crate::storage::{PresignedMethod, Storage};
+
+#[tracing::instrument(
+    name = "Upload a stored object",
+    skip(storage, query, body, pool, scan)
+)]
+pub async fn upload_object(
+    key: web::Path<String>,
+    query: web::Query<SignedUrlQuery>,
+    storage: web::Data<Storage>,
+    pool: web::Data<PgPool>,
+    scan: web::Data<ScanUploads>,
+    body: web::Bytes,
+) -> Result<HttpResponse, SignedUrlError> {
+    if !storage.verify_local_signature(PresignedMethod::Put, &key, query.expires, &query.signature)
+    {
+        return Err(SignedUrlError::InvalidSignature);
+    }
+
+    // held back from `/media` until the scan job has looked at it
+    if scan.0 {
+        mark_pending(&pool, &key)
+            .await
+            .context("Failed to mark the upload as pending")?;
+    }
+    storage.put(&key, body.to_vec()).await?;
+    if scan.0 {
+        enqueue_job(
+            pool.get_ref(),
+            SCAN_UPLOAD_JOB,
+            &ScanUpload {
+                key: key.into_inner(),
+            },
+        )
+        .await?;
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,43 @@
+use actix_web::web;
+
+use crate::{
+    errors::UsesError,
+    startup::DbPools,
+    types::{
+        api_response::ApiResponse,
+        uses::{UsesGroup, UsesItemRecord},
+    },
+};
+
+// the /uses page, grouped by category, both in alphabetical order
+#[tracing::instrument(name = "Get uses", skip(pools))]
+pub async fn get_uses(pools: web::Data<DbPools>) -> Result<ApiResponse<Vec<UsesGroup>>, UsesError> {
+    let items = sqlx::query_as!(
+        UsesItemRecord,
+        r#"
+        SELECT
+            item_id,
+            category,
+            name,
+            description,
+            url,
+            created_at,
+            updated_at
+        FROM uses_items
+        ORDER BY lower(category), category, lower(name)"#
+    )
+    .fetch_all(&pools.reader)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch uses items: {e:?}");
+        UsesError::UnexpectedError(anyhow::anyhow!(e))
+    })?;
+
+    // the newest edit stands in for the whole page's age, like the blog's
+    let last_modified = items.iter().map(|item| item.updated_at).max();
+    let response = ApiResponse::ok(UsesGroup::group(items));
+    Ok(match last_modified {
+        Some(last_modified) => response.with_last_modified(last_modified),
+        None => response,
+    })
+}
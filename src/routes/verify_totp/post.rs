@@ -4,13 +4,16 @@
 // if valid: session.clear_mfa_pending(); session.insert_user_id(user_id); return 200 (plus?)
 // if invalid: 401, do not clear pending session
 
-use actix_web::{HttpResponse, web};
+use actix_web::{http::StatusCode, web};
 use anyhow::Context;
 use sqlx::PgPool;
 use totp_rs::{Algorithm, Secret, TOTP};
 
+use crate::authentication::{LoginMetadata, record_login};
+use crate::errors::ApiProblem;
 use crate::session_state::TypedSession;
 use crate::startup::TotpEncryptionKey;
+use crate::types::api_response::ApiResponse;
 use crate::types::user::UserRole;
 use crate::utils::e500;
 
@@ -22,14 +25,15 @@ pub struct VerifyTotpRequest {
 #[allow(clippy::future_not_send)]
 #[tracing::instrument(
     name = "Verify TOTP code",
-    skip(pool, session, request, encryption_key)
+    skip(metadata, pool, session, request, encryption_key)
 )]
 pub async fn verify_totp(
+    metadata: LoginMetadata,
     request: web::Json<VerifyTotpRequest>,
     pool: web::Data<PgPool>,
     session: TypedSession,
     encryption_key: web::Data<TotpEncryptionKey>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<ApiResponse<serde_json::Value>, actix_web::Error> {
     let user_id = session
         .get_mfa_pending_user_id()
         .map_err(e500)?
@@ -64,13 +68,21 @@ pub async fn verify_totp(
         session.clear_mfa_pending();
         session.insert_user_id(user_id).map_err(e500)?;
         session.insert_user_role(user_role).map_err(e500)?;
+
+        match record_login(user_id, &metadata, &pool).await {
+            Ok(session_id) => session.insert_session_id(session_id).map_err(e500)?,
+            Err(e) => tracing::warn!(error.cause_chain = ?e, "Failed to record last login"),
+        }
+
         if must_change_password {
-            Ok(HttpResponse::Ok().json(serde_json::json!({ "must_change_password": true })))
+            Ok(ApiResponse::ok(
+                serde_json::json!({ "must_change_password": true }),
+            ))
         } else {
-            Ok(HttpResponse::Ok().finish())
+            Ok(ApiResponse::empty(StatusCode::OK))
         }
     } else {
-        Ok(HttpResponse::Unauthorized().finish())
+        Err(ApiProblem::new(StatusCode::UNAUTHORIZED).into())
     }
 }
 
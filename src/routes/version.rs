@@ -0,0 +1,13 @@
+use actix_web::{
+    HttpResponse,
+    http::header::{CacheControl, CacheDirective},
+};
+
+use crate::build_info::build_info;
+
+// which build is answering, for checking a deploy actually went out behind the load balancer
+pub async fn version() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .json(build_info())
+}
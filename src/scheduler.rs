@@ -0,0 +1,243 @@
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use futures_util::FutureExt;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tokio::task::JoinSet;
+
+use crate::configuration::SchedulerSettings;
+use crate::errors::panic_message;
+
+type ScheduledFuture = Pin<Box<dyn Future<Output = Result<(), anyhow::Error>> + Send>>;
+type ScheduledRun = Arc<dyn Fn() -> ScheduledFuture + Send + Sync>;
+
+#[derive(Clone)]
+struct ScheduledJob {
+    name: String,
+    schedule: Schedule,
+    run: ScheduledRun,
+}
+
+// what the admin dashboard shows for each registered job
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ScheduledJobStatus {
+    pub name: String,
+    // `None` when the job has no schedule configured and never runs
+    pub schedule: Option<String>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_succeeded: Option<bool>,
+    pub last_error: Option<String>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+// shared between the scheduler and the admin endpoint
+#[derive(Clone, Default)]
+pub struct SchedulerStatus(Arc<RwLock<BTreeMap<String, ScheduledJobStatus>>>);
+
+impl SchedulerStatus {
+    #[must_use]
+    pub fn jobs(&self) -> Vec<ScheduledJobStatus> {
+        self.0
+            .read()
+            .map(|jobs| jobs.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn update(&self, name: &str, update: impl FnOnce(&mut ScheduledJobStatus)) {
+        if let Ok(mut jobs) = self.0.write()
+            && let Some(job) = jobs.get_mut(name)
+        {
+            update(job);
+        }
+    }
+}
+
+// runs named jobs (cleanup, rollups, digests) on the cron expressions in `scheduler.schedules`
+// it's restartable, so it can be supervised like any other worker
+#[derive(Clone)]
+pub struct Scheduler {
+    schedules: HashMap<String, Schedule>,
+    jobs: Vec<ScheduledJob>,
+    status: SchedulerStatus,
+}
+
+impl Scheduler {
+    /// # Errors
+    /// returns an error naming the first schedule that isn't a valid cron expression
+    pub fn new(settings: &SchedulerSettings) -> Result<Self, anyhow::Error> {
+        let schedules = settings
+            .schedules
+            .iter()
+            .map(|(name, expression)| {
+                Schedule::from_str(expression)
+                    .map(|schedule| (name.clone(), schedule))
+                    .map_err(|e| anyhow::anyhow!("Invalid schedule for `{name}`: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            schedules,
+            jobs: Vec::new(),
+            status: SchedulerStatus::default(),
+        })
+    }
+
+    #[must_use]
+    pub fn status(&self) -> SchedulerStatus {
+        self.status.clone()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    pub fn register<F, Fut>(&mut self, name: &str, run: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        let schedule = self.schedules.get(name).cloned();
+        if let Ok(mut jobs) = self.status.0.write() {
+            jobs.insert(
+                name.to_string(),
+                ScheduledJobStatus {
+                    name: name.to_string(),
+                    schedule: schedule.as_ref().map(ToString::to_string),
+                    last_run_at: None,
+                    last_succeeded: None,
+                    last_error: None,
+                    next_run_at: None,
+                },
+            );
+        }
+
+        let Some(schedule) = schedule else {
+            tracing::warn!(job = %name, "No schedule configured, the job won't run");
+            return;
+        };
+        self.jobs.push(ScheduledJob {
+            name: name.to_string(),
+            schedule,
+            run: Arc::new(move || Box::pin(run())),
+        });
+    }
+
+    /// # Errors
+    /// only returns once every job's schedule has run out
+    pub async fn run_until_stopped(self) -> Result<(), anyhow::Error> {
+        let mut tasks = JoinSet::new();
+        for job in self.jobs {
+            tasks.spawn(run_on_schedule(job, self.status.clone()));
+        }
+        while tasks.join_next().await.is_some() {}
+        Ok(())
+    }
+}
+
+async fn run_on_schedule(job: ScheduledJob, status: SchedulerStatus) {
+    while let Some(next_run_at) = job.schedule.upcoming(Utc).next() {
+        status.update(&job.name, |job| job.next_run_at = Some(next_run_at));
+        tokio::time::sleep((next_run_at - Utc::now()).to_std().unwrap_or_default()).await;
+
+        let started_at = Utc::now();
+        // a panic is just a failed run, the job keeps its schedule
+        let outcome = AssertUnwindSafe((job.run)())
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|panic| {
+                Err(anyhow::anyhow!(
+                    "Panicked: {}",
+                    panic_message(panic.as_ref())
+                ))
+            });
+        if let Err(e) = &outcome {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                job = %job.name,
+                "Scheduled job failed"
+            );
+        }
+        status.update(&job.name, |job| {
+            job.last_run_at = Some(started_at);
+            job.last_succeeded = Some(outcome.is_ok());
+            job.last_error = outcome.as_ref().err().map(|e| format!("{e:#}"));
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_schedules_are_valid() {
+        let scheduler = Scheduler::new(&SchedulerSettings::default()).unwrap();
+        let rollup = &scheduler.schedules["metrics_rollup"];
+        let after = "2026-04-09T23:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            rollup.after(&after).next().unwrap().to_rfc3339(),
+            "2026-04-10T00:10:00+00:00"
+        );
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let settings = SchedulerSettings {
+            schedules: HashMap::from([("broken".to_string(), "every tuesday".to_string())]),
+        };
+        let e = Scheduler::new(&settings).err().unwrap();
+        assert!(e.to_string().contains("broken"));
+    }
+
+    #[tokio::test]
+    async fn panicking_jobs_fail_and_keep_their_schedule() {
+        let settings = SchedulerSettings {
+            schedules: HashMap::from([("panics".to_string(), "* * * * * *".to_string())]),
+        };
+        let mut scheduler = Scheduler::new(&settings).unwrap();
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        scheduler.register("panics", {
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { panic!("scheduled job panicked") }
+            }
+        });
+        let status = scheduler.status();
+
+        let stopped = tokio::time::timeout(
+            std::time::Duration::from_millis(2500),
+            scheduler.run_until_stopped(),
+        )
+        .await;
+
+        assert!(stopped.is_err(), "the scheduler is still running");
+        assert!(runs.load(std::sync::atomic::Ordering::SeqCst) >= 2);
+        let job = &status.jobs()[0];
+        assert_eq!(job.last_succeeded, Some(false));
+        assert!(
+            job.last_error
+                .as_deref()
+                .is_some_and(|e| e.contains("scheduled job panicked"))
+        );
+    }
+
+    #[test]
+    fn jobs_without_a_schedule_are_listed_but_not_run() {
+        let mut scheduler = Scheduler::new(&SchedulerSettings::default()).unwrap();
+        scheduler.register("metrics_cleanup", || async { Ok(()) });
+        scheduler.register("unscheduled", || async { Ok(()) });
+
+        let jobs = scheduler.status().jobs();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].schedule.as_deref(), Some("0 0 * * * *"));
+        assert_eq!(jobs[1].schedule, None);
+        assert_eq!(scheduler.jobs.len(), 1);
+    }
+}
@@ -0,0 +1,198 @@
+use secrecy::{ExposeSecret, SecretString};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::configuration::{SecretSource, Settings, VaultSettings};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SecretError {
+    #[error("Failed to read the secret file `{path}`")]
+    File {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("A secret uses vault but `secrets.vault.address` or `secrets.vault.token` is missing")]
+    VaultNotConfigured,
+    #[error("Failed to read `{path}` from vault")]
+    Vault {
+        path: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("The vault secret `{path}` has no `{key}`")]
+    MissingKey { path: String, key: String },
+}
+
+// kv v2 nests the secret's fields one level down, `{"data": {"data": {...}}}`
+#[derive(serde::Deserialize)]
+struct VaultResponse {
+    data: VaultData,
+}
+
+#[derive(serde::Deserialize)]
+struct VaultData {
+    data: HashMap<String, serde_json::Value>,
+}
+
+// swaps each secret for the one its provider holds, a failure stops the app from starting
+/// # Errors
+/// returns a `SecretError` if a file can't be read or vault can't be reached or lacks the key
+pub fn resolve(settings: &mut Settings) -> Result<(), SecretError> {
+    let secrets = settings.secrets.clone();
+    if let Some(hmac_secret) = fetch(&secrets.hmac_secret, &secrets.vault)? {
+        settings.application.hmac_secret = hmac_secret;
+    }
+    if let Some(password) = fetch(&secrets.database_password, &secrets.vault)? {
+        settings.database.password = password;
+    }
+    if let Some(redis_uri) = fetch(&secrets.redis_uri, &secrets.vault)? {
+        settings.redis_uri = redis_uri;
+    }
+
+    Ok(())
+}
+
+// `None` for the config provider, the value that's already there stands
+/// # Errors
+/// see `resolve`
+pub fn fetch(
+    source: &SecretSource,
+    vault: &VaultSettings,
+) -> Result<Option<SecretString>, SecretError> {
+    match source {
+        SecretSource::Config => Ok(None),
+        SecretSource::File { path } => std::fs::read_to_string(path)
+            .map(|contents| Some(contents.trim().into()))
+            .map_err(|source| SecretError::File {
+                path: path.clone(),
+                source,
+            }),
+        SecretSource::Vault { path, key } => read_vault(vault, path, key).map(Some),
+    }
+}
+
+fn read_vault(vault: &VaultSettings, path: &str, key: &str) -> Result<SecretString, SecretError> {
+    let (Some(address), Some(token)) = (&vault.address, &vault.token) else {
+        return Err(SecretError::VaultNotConfigured);
+    };
+    let url = format!(
+        "{}/v1/{}/data/{}",
+        address.trim_end_matches('/'),
+        vault.mount.trim_matches('/'),
+        path.trim_start_matches('/')
+    );
+
+    // `get_configuration` is sync but also runs inside the runtime, where the blocking
+    // client refuses to work, so the request goes out from a thread of its own
+    let response = std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                reqwest::blocking::Client::builder()
+                    .timeout(Duration::from_secs(vault.timeout_secs))
+                    .build()?
+                    .get(&url)
+                    .header("X-Vault-Token", token.expose_secret())
+                    .send()?
+                    .error_for_status()?
+                    .json::<VaultResponse>()
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+    .map_err(|source| SecretError::Vault {
+        path: path.to_string(),
+        source,
+    })?;
+
+    match response.data.data.get(key) {
+        Some(serde_json::Value::String(value)) => Ok(value.as_str().into()),
+        _ => Err(SecretError::MissingKey {
+            path: path.to_string(),
+            key: key.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    // answers one request with `body` and hands back what was asked
+    fn fake_vault(body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request[..read]).to_lowercase()
+        });
+        (address, handle)
+    }
+
+    #[test]
+    fn file_secrets_are_trimmed() {
+        let path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::write(&path, "hunter2\n").unwrap();
+        let source = SecretSource::File {
+            path: path.to_string_lossy().into_owned(),
+        };
+
+        let secret = fetch(&source, &VaultSettings::default()).unwrap().unwrap();
+
+        assert_eq!(secret.expose_secret(), "hunter2");
+    }
+
+    #[test]
+    fn missing_files_are_an_error() {
+        let source = SecretSource::File {
+            path: "/nonexistent/secret".to_string(),
+        };
+
+        let e = fetch(&source, &VaultSettings::default()).unwrap_err();
+
+        assert!(matches!(e, SecretError::File { .. }));
+    }
+
+    #[test]
+    fn vault_secrets_are_read_from_kv_v2() {
+        let (address, request) = fake_vault(r#"{"data":{"data":{"password":"hunter2"}}}"#);
+        let vault = VaultSettings {
+            address: Some(address),
+            token: Some("root".into()),
+            ..VaultSettings::default()
+        };
+        let source = SecretSource::Vault {
+            path: "portfolio/database".to_string(),
+            key: "password".to_string(),
+        };
+
+        let secret = fetch(&source, &vault).unwrap().unwrap();
+
+        assert_eq!(secret.expose_secret(), "hunter2");
+        let request = request.join().unwrap();
+        assert!(request.starts_with("get /v1/secret/data/portfolio/database "));
+        assert!(request.contains("x-vault-token: root"));
+    }
+
+    #[test]
+    fn vault_needs_an_address_and_token() {
+        let source = SecretSource::Vault {
+            path: "portfolio/database".to_string(),
+            key: "password".to_string(),
+        };
+
+        let e = fetch(&source, &VaultSettings::default()).unwrap_err();
+
+        assert!(matches!(e, SecretError::VaultNotConfigured));
+    }
+}
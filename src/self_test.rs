@@ -0,0 +1,140 @@
+use secrecy::{ExposeSecret, SecretString};
+use std::time::Duration;
+
+use crate::configuration::{Environment, Settings};
+use crate::redis_pool::{self, RedisPool};
+use crate::routes::migrations_applied;
+use crate::startup::{DbPools, MIGRATOR};
+
+// a dependency that hasn't answered by then won't answer the first request either
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// every check that failed, so one restart shows everything there is to fix
+#[derive(thiserror::Error, Debug)]
+#[error("Startup checks failed: {}", .0.join("; "))]
+pub struct StartupCheckError(pub Vec<String>);
+
+// run before the listener is bound, the pools are lazy and would otherwise
+// only find out on the first real request
+// hands back the redis pool it connected, so the server doesn't connect twice
+/// # Errors
+/// returns a `StartupCheckError` listing every check that failed
+pub async fn run_startup_checks(
+    configuration: &Settings,
+    environment: &Environment,
+    pools: &DbPools,
+) -> Result<RedisPool, StartupCheckError> {
+    let mut failures = Vec::new();
+
+    if let Err(e) = check_database(configuration, pools).await {
+        failures.push(e);
+    }
+    let redis = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        redis_pool::connect(
+            configuration.redis_uri.expose_secret(),
+            &configuration.redis,
+        ),
+    )
+    .await;
+    let redis = match redis {
+        Ok(Ok(redis)) => Some(redis),
+        Ok(Err(e)) => {
+            failures.push(format!("redis: {e}"));
+            None
+        }
+        Err(_) => {
+            failures.push("redis: timed out connecting".to_string());
+            None
+        }
+    };
+    if matches!(environment, Environment::Production) {
+        failures.extend(
+            placeholder_secrets(configuration)
+                .into_iter()
+                .map(|name| format!("secrets: `{name}` still has the value from base.yaml")),
+        );
+    }
+
+    match redis {
+        Some(redis) if failures.is_empty() => {
+            tracing::info!("Startup checks passed");
+            Ok(redis)
+        }
+        _ => {
+            for failure in &failures {
+                tracing::error!(check = %failure, "Startup check failed");
+            }
+            Err(StartupCheckError(failures))
+        }
+    }
+}
+
+// connects, migrates when asked to, then makes sure nothing this build expects is missing
+async fn check_database(configuration: &Settings, pools: &DbPools) -> Result<(), String> {
+    tokio::time::timeout(CHECK_TIMEOUT, pools.writer.acquire())
+        .await
+        .map_err(|_| "database: timed out connecting".to_string())?
+        .map_err(|e| format!("database: {e}"))?;
+    tracing::info!("Database connectivity verified");
+
+    // the migrator holds a postgres advisory lock while it runs, so instances starting
+    // together take turns and the later ones find nothing left to apply
+    if configuration.application.migrate_on_start {
+        MIGRATOR
+            .run(&pools.writer)
+            .await
+            .map_err(|e| format!("migrations: {e}"))?;
+        tracing::info!("Database migrations applied");
+    }
+    if configuration.application.allow_pending_migrations {
+        return Ok(());
+    }
+    match migrations_applied(&pools.writer).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(
+            "migrations: the database is behind this build, run `sqlx migrate run` \
+            or set `application.migrate_on_start`"
+                .to_string(),
+        ),
+        Err(e) => Err(format!("migrations: {e}")),
+    }
+}
+
+// secrets that are still the throwaway values base.yaml ships for local development
+fn placeholder_secrets(configuration: &Settings) -> Vec<&'static str> {
+    let Ok(base) = config::Config::builder()
+        .add_source(config::File::with_name("configuration/base.yaml"))
+        .build()
+    else {
+        return Vec::new();
+    };
+    let secrets: [(&'static str, &SecretString); 5] = [
+        (
+            "application.hmac_secret",
+            &configuration.application.hmac_secret,
+        ),
+        (
+            "application.totp_encryption_key",
+            &configuration.application.totp_encryption_key,
+        ),
+        (
+            "application.session_hash_key",
+            &configuration.application.session_hash_key,
+        ),
+        (
+            "application.jwt_private_key",
+            &configuration.application.jwt_private_key,
+        ),
+        ("database.password", &configuration.database.password),
+    ];
+
+    secrets
+        .into_iter()
+        .filter(|(name, secret)| {
+            base.get_string(name)
+                .is_ok_and(|placeholder| placeholder == secret.expose_secret())
+        })
+        .map(|(name, _)| name)
+        .collect()
+}
@@ -1,5 +1,6 @@
 use actix_session::{Session, SessionExt, SessionGetError, SessionInsertError};
 use actix_web::{FromRequest, HttpRequest, dev::Payload};
+use std::collections::HashMap;
 use std::future::{Ready, ready};
 use uuid::Uuid;
 
@@ -13,6 +14,7 @@ impl TypedSession {
     const USER_ID_KEY: &'static str = "user_id";
     const MFA_PENDING_KEY: &'static str = "mfa_pending_user_id";
     const USER_ROLE_KEY: &'static str = "user_role";
+    const SESSION_ID_KEY: &'static str = "session_id";
 
     pub fn renew(&self) {
         self.0.renew();
@@ -54,9 +56,26 @@ impl TypedSession {
         }
     }
 
+    // links the cookie session to its row in user_sessions
+    pub fn insert_session_id(&self, session_id: Uuid) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::SESSION_ID_KEY, session_id)
+    }
+
+    pub fn get_session_id(&self) -> Result<Option<Uuid>, SessionGetError> {
+        self.0.get(Self::SESSION_ID_KEY)
+    }
+
     pub fn log_out(self) {
         self.0.purge();
     }
+
+    // for reading sessions straight out of the store, which keeps them as a json map
+    // a session waiting on its totp code only holds the pending user id, so it's `None`
+    #[must_use]
+    pub fn signed_in_user(state: &str) -> Option<Uuid> {
+        let mut state = serde_json::from_str::<HashMap<String, String>>(state).ok()?;
+        serde_json::from_str(&state.remove(Self::USER_ID_KEY)?).ok()
+    }
 }
 
 impl FromRequest for TypedSession {
@@ -69,3 +88,23 @@ impl FromRequest for TypedSession {
         ready(Ok(Self(req.get_session())))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_signed_in_states_have_a_user() {
+        let user_id = Uuid::new_v4();
+        let stored_id = serde_json::to_string(&user_id).unwrap();
+        let signed_in = serde_json::json!({ "user_id": stored_id, "user_role": "\"admin\"" });
+        let mfa_pending = serde_json::json!({ "mfa_pending_user_id": stored_id });
+
+        assert_eq!(
+            TypedSession::signed_in_user(&signed_in.to_string()),
+            Some(user_id)
+        );
+        assert_eq!(TypedSession::signed_in_user(&mfa_pending.to_string()), None);
+        assert_eq!(TypedSession::signed_in_user("not json"), None);
+    }
+}
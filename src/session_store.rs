@@ -0,0 +1,125 @@
+use actix_session::storage::{
+    LoadError, SaveError, SessionKey, SessionStore, UpdateError, generate_session_key,
+};
+use actix_web::cookie::time::Duration;
+use redis::{FromRedisValue, Value};
+use std::collections::HashMap;
+
+use crate::redis_pool::{self, RedisPool};
+
+// actix-session's redis store only takes a single-node pool, this one goes through
+// `RedisPool` so sessions live on whatever topology is configured, sentinel included
+// keys and values are laid out the same way, so existing sessions survive the switch
+#[derive(Clone)]
+pub struct PooledSessionStore {
+    pool: RedisPool,
+}
+
+impl PooledSessionStore {
+    #[must_use]
+    pub const fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+
+    // a connection that was dropped under us (a failover, a server-side timeout)
+    // is only noticed when it's used, so that one case is retried on a fresh one
+    async fn execute<T: FromRedisValue>(&self, cmd: &redis::Cmd) -> Result<T, anyhow::Error> {
+        match cmd
+            .query_async(&mut redis_pool::get(&self.pool).await?)
+            .await
+        {
+            Err(e) if e.is_connection_dropped() => {
+                tracing::debug!("Redis connection dropped, retrying on a new one");
+                Ok(cmd
+                    .query_async(&mut redis_pool::get(&self.pool).await?)
+                    .await?)
+            }
+            result => Ok(result?),
+        }
+    }
+}
+
+impl SessionStore for PooledSessionStore {
+    async fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<Option<HashMap<String, String>>, LoadError> {
+        let value: Option<String> = self
+            .execute(redis::cmd("GET").arg(session_key.as_ref()))
+            .await
+            .map_err(LoadError::Other)?;
+
+        value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|e| LoadError::Deserialization(e.into()))
+    }
+
+    async fn save(
+        &self,
+        session_state: HashMap<String, String>,
+        ttl: &Duration,
+    ) -> Result<SessionKey, SaveError> {
+        let body = serde_json::to_string(&session_state)
+            .map_err(|e| SaveError::Serialization(e.into()))?;
+        let session_key = generate_session_key();
+
+        self.execute::<()>(
+            redis::cmd("SET")
+                .arg(session_key.as_ref())
+                .arg(body)
+                .arg("NX")
+                .arg("EX")
+                .arg(ttl.whole_seconds()),
+        )
+        .await
+        .map_err(SaveError::Other)?;
+
+        Ok(session_key)
+    }
+
+    async fn update(
+        &self,
+        session_key: SessionKey,
+        session_state: HashMap<String, String>,
+        ttl: &Duration,
+    ) -> Result<SessionKey, UpdateError> {
+        let body = serde_json::to_string(&session_state)
+            .map_err(|e| UpdateError::Serialization(e.into()))?;
+
+        let reply: Value = self
+            .execute(
+                redis::cmd("SET")
+                    .arg(session_key.as_ref())
+                    .arg(body)
+                    .arg("XX")
+                    .arg("EX")
+                    .arg(ttl.whole_seconds()),
+            )
+            .await
+            .map_err(UpdateError::Other)?;
+
+        match reply {
+            // expired between the load and now, saved under a fresh key instead
+            Value::Nil => self.save(session_state, ttl).await.map_err(|e| match e {
+                SaveError::Serialization(e) => UpdateError::Serialization(e),
+                SaveError::Other(e) => UpdateError::Other(e),
+            }),
+            _ => Ok(session_key),
+        }
+    }
+
+    async fn update_ttl(&self, session_key: &SessionKey, ttl: &Duration) -> anyhow::Result<()> {
+        self.execute::<()>(
+            redis::cmd("EXPIRE")
+                .arg(session_key.as_ref())
+                .arg(ttl.whole_seconds()),
+        )
+        .await
+    }
+
+    async fn delete(&self, session_key: &SessionKey) -> anyhow::Result<()> {
+        self.execute::<()>(redis::cmd("DEL").arg(session_key.as_ref()))
+            .await
+    }
+}
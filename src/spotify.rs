@@ -0,0 +1,328 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use secrecy::{ExposeSecret, SecretString};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::configuration::SpotifySettings;
+
+// refreshed this long before spotify says it expires, so a poll never races the expiry
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+// what the footer shows, the current track or, when nothing is playing, the last one
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct NowPlaying {
+    pub is_playing: bool,
+    pub track: Track,
+    // when a recently played track finished, `None` while it's playing
+    pub played_at: Option<DateTime<Utc>>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Track {
+    pub title: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub album_art_url: Option<String>,
+    pub url: Option<String>,
+}
+
+// last successful poll, `None` until the first one succeeds, when nothing has ever been
+// played, or when the integration isn't configured
+// a failed poll keeps the previous track
+#[derive(Clone)]
+pub struct NowPlayingFeed(watch::Receiver<Option<NowPlaying>>);
+
+impl NowPlayingFeed {
+    #[must_use]
+    pub fn latest(&self) -> Option<NowPlaying> {
+        self.0.borrow().clone()
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    // spotify may rotate the refresh token, the new one replaces the configured one
+    refresh_token: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct CurrentlyPlaying {
+    is_playing: bool,
+    // `None` for ads and podcast episodes
+    item: Option<SpotifyTrack>,
+}
+
+#[derive(serde::Deserialize)]
+struct RecentlyPlayed {
+    items: Vec<PlayHistory>,
+}
+
+#[derive(serde::Deserialize)]
+struct PlayHistory {
+    track: SpotifyTrack,
+    played_at: DateTime<Utc>,
+}
+
+#[derive(serde::Deserialize)]
+struct SpotifyTrack {
+    name: String,
+    artists: Vec<SpotifyArtist>,
+    album: SpotifyAlbum,
+    #[serde(default)]
+    external_urls: ExternalUrls,
+}
+
+#[derive(serde::Deserialize)]
+struct SpotifyArtist {
+    name: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SpotifyAlbum {
+    name: String,
+    // largest first
+    #[serde(default)]
+    images: Vec<SpotifyImage>,
+}
+
+#[derive(serde::Deserialize)]
+struct SpotifyImage {
+    url: String,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct ExternalUrls {
+    spotify: Option<String>,
+}
+
+impl From<SpotifyTrack> for Track {
+    fn from(track: SpotifyTrack) -> Self {
+        Self {
+            title: track.name,
+            artists: track
+                .artists
+                .into_iter()
+                .map(|artist| artist.name)
+                .collect(),
+            album: track.album.name,
+            album_art_url: track.album.images.into_iter().next().map(|image| image.url),
+            url: track.external_urls.spotify,
+        }
+    }
+}
+
+struct AccessToken {
+    value: SecretString,
+    expires_at: Instant,
+}
+
+struct SpotifyClient {
+    http_client: reqwest::Client,
+    settings: SpotifySettings,
+    client_id: String,
+    client_secret: SecretString,
+    refresh_token: SecretString,
+    access_token: Option<AccessToken>,
+}
+
+#[must_use]
+pub fn spawn_now_playing_poller(settings: SpotifySettings) -> NowPlayingFeed {
+    let (sender, receiver) = watch::channel::<Option<NowPlaying>>(None);
+
+    let (Some(client_id), Some(client_secret), Some(refresh_token)) = (
+        settings.client_id.clone(),
+        settings.client_secret.clone(),
+        settings.refresh_token.clone(),
+    ) else {
+        tracing::info!("Spotify not configured, now playing polling disabled");
+        return NowPlayingFeed(receiver);
+    };
+
+    tokio::spawn(async move {
+        let http_client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!(error.cause_chain = ?e, "Failed to build Spotify client");
+                return;
+            }
+        };
+        let mut ticker = tokio::time::interval(Duration::from_secs(settings.refresh_interval_secs));
+        let mut client = SpotifyClient {
+            http_client,
+            settings,
+            client_id,
+            client_secret,
+            refresh_token,
+            access_token: None,
+        };
+
+        loop {
+            ticker.tick().await;
+
+            match client.now_playing().await {
+                Ok(now_playing) => {
+                    if sender.send(now_playing).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    error.cause_chain = ?e,
+                    "Failed to fetch the Spotify player, keeping the last track"
+                ),
+            }
+        }
+    });
+
+    NowPlayingFeed(receiver)
+}
+
+impl SpotifyClient {
+    async fn now_playing(&mut self) -> Result<Option<NowPlaying>, anyhow::Error> {
+        let fetched_at = Utc::now();
+        let access_token = self.access_token().await?;
+
+        let response = self
+            .get(&access_token, "/v1/me/player/currently-playing")
+            .await?;
+        // 204 when no device is active
+        if response.status() != StatusCode::NO_CONTENT {
+            let current: CurrentlyPlaying = response.json().await?;
+            if let (true, Some(track)) = (current.is_playing, current.item) {
+                return Ok(Some(NowPlaying {
+                    is_playing: true,
+                    track: track.into(),
+                    played_at: None,
+                    fetched_at,
+                }));
+            }
+        }
+
+        let recent: RecentlyPlayed = self
+            .get(&access_token, "/v1/me/player/recently-played?limit=1")
+            .await?
+            .json()
+            .await?;
+        Ok(recent.items.into_iter().next().map(|play| NowPlaying {
+            is_playing: false,
+            track: play.track.into(),
+            played_at: Some(play.played_at),
+            fetched_at,
+        }))
+    }
+
+    async fn get(
+        &mut self,
+        access_token: &SecretString,
+        path: &str,
+    ) -> Result<reqwest::Response, anyhow::Error> {
+        let response = self
+            .http_client
+            .get(format!(
+                "{}{path}",
+                self.settings.api_base_url.trim_end_matches('/')
+            ))
+            .bearer_auth(access_token.expose_secret())
+            .send()
+            .await?;
+        // revoked early, the next poll starts with a fresh one
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.access_token = None;
+        }
+        Ok(response.error_for_status()?)
+    }
+
+    // spotify's access tokens last an hour, the refresh token is what's configured
+    async fn access_token(&mut self) -> Result<SecretString, anyhow::Error> {
+        if let Some(token) = &self.access_token
+            && token.expires_at > Instant::now() + TOKEN_EXPIRY_MARGIN
+        {
+            return Ok(token.value.clone());
+        }
+
+        let response: TokenResponse = self
+            .http_client
+            .post(format!(
+                "{}/api/token",
+                self.settings.accounts_base_url.trim_end_matches('/')
+            ))
+            .basic_auth(&self.client_id, Some(self.client_secret.expose_secret()))
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", self.refresh_token.expose_secret()),
+            ])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Spotify refused to refresh the access token")?
+            .json()
+            .await?;
+
+        if let Some(refresh_token) = response.refresh_token {
+            self.refresh_token = SecretString::from(refresh_token);
+        }
+        let value = SecretString::from(response.access_token);
+        self.access_token = Some(AccessToken {
+            value: value.clone(),
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tracks_keep_the_largest_album_art() {
+        let track: SpotifyTrack = serde_json::from_value(serde_json::json!({
+            "name": "Windowlicker",
+            "artists": [{ "name": "Aphex Twin" }],
+            "album": {
+                "name": "Windowlicker",
+                "images": [
+                    { "url": "https://i.scdn.co/image/640", "width": 640, "height": 640 },
+                    { "url": "https://i.scdn.co/image/64", "width": 64, "height": 64 }
+                ]
+            },
+            "external_urls": { "spotify": "https://open.spotify.com/track/1" }
+        }))
+        .unwrap();
+
+        let track = Track::from(track);
+
+        assert_eq!(track.artists, ["Aphex Twin"]);
+        assert_eq!(
+            track.album_art_url.as_deref(),
+            Some("https://i.scdn.co/image/640")
+        );
+        assert_eq!(
+            track.url.as_deref(),
+            Some("https://open.spotify.com/track/1")
+        );
+    }
+
+    #[test]
+    fn local_files_have_no_art_or_link() {
+        let track: SpotifyTrack = serde_json::from_value(serde_json::json!({
+            "name": "Demo",
+            "artists": [],
+            "album": { "name": "" },
+            "external_urls": {}
+        }))
+        .unwrap();
+
+        let track = Track::from(track);
+
+        assert_eq!(track.album_art_url, None);
+        assert_eq!(track.url, None);
+    }
+}
@@ -1,49 +1,124 @@
-use actix_cors::Cors;
 use actix_session::{
     SessionMiddleware,
     config::{PersistentSession, TtlExtensionPolicy},
-    storage::RedisSessionStore,
 };
 use actix_web::{
-    App, HttpResponse, HttpServer,
-    cookie::{Key, SameSite},
+    App, HttpServer, ResponseError,
+    cookie::SameSite,
     dev::Server,
-    http,
-    middleware::from_fn,
+    guard, http,
+    middleware::{Condition, from_fn},
     web::{self, Data},
 };
 use actix_web_flash_messages::{FlashMessagesFramework, storage::CookieMessageStore};
+use listenfd::ListenFd;
 use secrecy::{ExposeSecret, SecretString};
-use sqlx::{PgPool, postgres::PgPoolOptions};
+use sqlx::{PgPool, migrate::Migrator};
+use std::future::Future;
 use std::net::TcpListener;
 use tracing_actix_web::TracingLogger;
 
 use crate::{
+    antivirus::ScanUploads,
     authentication::{
-        cross_site_request_forgery_protection, reject_anonymous_users, reject_non_admin,
-        update_user_password,
+        AdminListener, cross_site_request_forgery_protection, reject_anonymous_users,
+        reject_non_admin, restrict_admin_surface, update_user_password,
     },
-    configuration::{CorsSettings, DatabaseSettings, RateLimitSettings, Settings, TtlSettings},
+    blog_cache::BlogCache,
+    build_info::build_info,
+    client_ip::TrustedProxies,
+    configuration::{
+        AlertSettings, BlogCacheSettings, ContactSettings, CorsSettings, DatabaseSettings,
+        DigitalOceanSettings, EmailSettings, HttpServerSettings, IdempotencySettings,
+        MetricsSettings, PasswordHashingSettings, RobotsSettings, Settings, SpotifySettings,
+        TelemetrySettings, TtlSettings, environment,
+    },
+    cors::cors,
+    email::EmailClient,
+    errors::{ApiProblem, catch_panics, problem_details},
+    events::EventBus,
+    idempotency::IdempotencyKeyPolicy,
+    key_ring::{KeyRing, rotate_cookie_keys},
+    metrics::{
+        AppMetrics, PageVisitRecorder, VitalsCache, require_ingest_signature,
+        spawn_active_sessions_sampler, spawn_alert_evaluator, spawn_bandwidth_poller,
+        spawn_page_visit_writer, spawn_pool_sampler, spawn_realtime_sampler,
+        spawn_server_metrics_writer, track_request_metrics,
+    },
+    rate_limit::{RateLimiter, enforce_rate_limits},
+    redis_pool::RedisPool,
+    reload::{ReloadableSettings, reload_on_hangup},
     routes::{
-        accept_invitation, chat_token, check_auth, create_user, delete_article, edit_article,
-        get_all_users, get_articles, get_messages, health_check, insert_article, login, logout,
-        patch_message, post_message, publish_article, reset_password, root, set_user_role,
-        totp_confirm, totp_disable, totp_setup, totp_status, verify_totp,
+        MediaMaxAge, accept_invitation, chat_token, check_auth, create_user,
+        dashboard_event_stream, dashboard_events, delete_album, delete_article, delete_experience,
+        delete_now_entry, delete_project, delete_short_link, delete_skill, delete_uses_item,
+        download_object, edit_album, edit_article, edit_experience, edit_now_entry, edit_profile,
+        edit_project, edit_short_link, edit_skill, edit_uses_item, export_analytics,
+        export_metrics, follow_short_link, get_album, get_all_albums, get_all_projects,
+        get_all_users, get_articles, get_availability, get_availability_history, get_campaigns,
+        get_error_breakdown, get_experience, get_gallery, get_github_activity, get_infrastructure,
+        get_media, get_messages, get_metrics_summary, get_now_entries, get_now_playing,
+        get_profile, get_project, get_projects, get_rate_limits, get_realtime_snapshot, get_resume,
+        get_resume_pdf, get_scheduler_status, get_session_report, get_sessions, get_short_links,
+        get_skills, get_slow_requests, get_uses, get_vitals, health_check, insert_album,
+        insert_article, insert_experience, insert_now_entry, insert_project, insert_short_link,
+        insert_skill, insert_uses_item, issue_upload, live, login, logout, patch_message,
+        post_message, post_revoke_session, previous_login, publish_article, ready, realtime_stats,
+        record_page_visit, record_page_visit_batch, record_performance_metric, reorder_albums,
+        reorder_projects, reset_password, reset_rate_limit, robots_txt, root, search,
+        set_availability, set_user_role, store_analytics_export, totp_confirm, totp_disable,
+        totp_setup, totp_status, update_resume, upload_object, verify_totp, version,
     },
+    scheduler::{Scheduler, SchedulerStatus},
+    self_test::run_startup_checks,
+    session_store::PooledSessionStore,
+    spotify::spawn_now_playing_poller,
+    storage::Storage,
+    telemetry::{QuietRootSpanBuilder, QuietRoutes},
+    types::api_response::ResponseEnvelope,
+    utils::QueryTimeout,
+    workers::WorkerRegistry,
 };
 
-#[derive(serde::Deserialize, Clone)]
+// the migrations this build expects, see `routes::ready` and `application.migrate_on_start`
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Clone)]
 struct UtilConfig {
-    rate: RateLimitSettings,
+    reloadable: ReloadableSettings,
     cors: CorsSettings,
     ttl: TtlSettings,
+    hashing: PasswordHashingSettings,
+    idempotency_keys: IdempotencyKeyPolicy,
+    idempotency: IdempotencySettings,
+    metrics: MetricsSettings,
+    page_visits: PageVisitRecorder,
+    digitalocean: DigitalOceanSettings,
+    spotify: SpotifySettings,
+    blog_cache: BlogCacheSettings,
+    contact: ContactSettings,
+    alerts: AlertSettings,
+    email: EmailSettings,
+    telemetry: TelemetrySettings,
+    trusted_proxies: TrustedProxies,
+    scheduler_status: SchedulerStatus,
+    storage: Storage,
+    storage_max_upload_bytes: usize,
+    media_max_age: MediaMaxAge,
+    scan_uploads: ScanUploads,
+    robots: RobotsSettings,
+    server: HttpServerSettings,
+    query_timeout: QueryTimeout,
+    response_envelope: ResponseEnvelope,
 }
 
 #[derive(Clone)]
 struct SecretsConfig {
     hmac: HmacSecret,
+    key_ring: KeyRing,
     totp: TotpEncryptionKey,
     jwt: JwtPrivateKey,
+    session_hash: SessionHashKey,
 }
 
 // wrapper type for SecretString
@@ -56,12 +131,22 @@ pub struct TotpEncryptionKey(pub [u8; 32]);
 #[derive(Clone)]
 pub struct JwtPrivateKey(pub SecretString);
 
+#[derive(Clone)]
+pub struct SessionHashKey(pub [u8; 16]);
+
 // wrapper for application url
 pub struct ApplicationBaseUrl(pub String);
 
 pub struct Application {
     port: u16,
+    admin_port: Option<u16>,
     server: Server,
+    metrics: AppMetrics,
+    page_visits: PageVisitRecorder,
+    pool: PgPool,
+    workers: WorkerRegistry,
+    scheduler: Scheduler,
+    reloadable: ReloadableSettings,
 }
 
 impl Application {
@@ -79,32 +164,150 @@ impl Application {
     /// # Panics
     /// probably not a bad idea to handle port binding issues gracefully
     pub async fn build(configuration: Settings) -> Result<Self, anyhow::Error> {
-        let connection_pool = get_connection_pool(&configuration.database);
-
-        tracing::info!("Database connection pool configured (lazy)");
-
-        connection_pool.acquire().await.map_err(|e| {
+        let build = build_info();
+        tracing::info!(
+            version = %build.version,
+            git_sha = %build.git_sha,
+            built_at = ?build.built_at,
+            "Starting portfolio-server"
+        );
+        let pools = DbPools::from_settings(&configuration.database).map_err(|e| {
             tracing::error!(
                 error.cause_chain = ?e,
                 error.message = %e,
-                "Failed to acquire initial database connection"
+                "Invalid read replica url"
             );
-            anyhow::anyhow!("Database connectivity check failed: {e}")
+            e
         })?;
-        tracing::info!("Database connectivity verified");
+        let pool = pools.writer.clone();
+        let workers = WorkerRegistry::new(configuration.workers.clone());
+        let reloadable = ReloadableSettings::new(&configuration).map_err(|e| {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to load the tls certificate"
+            );
+            e
+        })?;
+        let scheduler = Scheduler::new(&configuration.scheduler).map_err(|e| {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Invalid scheduler settings"
+            );
+            e
+        })?;
+
+        tracing::info!("Database connection pool configured (lazy)");
+
+        let redis = run_startup_checks(&configuration, &environment(), &pools).await?;
 
         let address = format!(
             "{}:{}",
             configuration.application.host, configuration.application.port,
         );
 
+        let idempotency_keys =
+            IdempotencyKeyPolicy::try_from(&configuration.idempotency).map_err(|e| {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    "Invalid idempotency key policy"
+                );
+                e
+            })?;
+
+        let hashing = configuration
+            .password_hashing
+            .with_dummy_hash()
+            .map_err(|e| {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    "Invalid password hashing settings"
+                );
+                e
+            })?;
+
+        let storage = Storage::from_settings(
+            &configuration.storage,
+            &configuration.application.base_url,
+            &configuration.application.hmac_secret,
+        )
+        .map_err(|e| {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Invalid storage settings"
+            );
+            e
+        })?;
+
+        let raw_session_hash_key = configuration
+            .application
+            .session_hash_key
+            .expose_secret()
+            .as_bytes();
+        let key: [u8; 16] = raw_session_hash_key.try_into().map_err(|_| {
+            tracing::error!(
+                key_len = raw_session_hash_key.len(),
+                "session_hash_key is not exactly 16 bytes"
+            );
+            anyhow::anyhow!("session_hash_key must be exactly 16 bytes")
+        })?;
+        let session_hash_key = SessionHashKey(key);
+
+        // built here rather than in `run` so tests can wait on the queued visits
+        let page_visits = spawn_page_visit_writer(
+            pools.writer.clone(),
+            configuration.metrics.page_visit_batch_size,
+            std::time::Duration::from_millis(configuration.metrics.page_visit_flush_interval_ms),
+            session_hash_key.clone(),
+        );
+
         // reduce run's argument count!
         let util_config = UtilConfig {
-            rate: configuration.rate_limit,
+            reloadable: reloadable.clone(),
             cors: configuration.cors,
             ttl: configuration.ttl,
+            hashing,
+            idempotency_keys,
+            idempotency: configuration.idempotency,
+            metrics: configuration.metrics,
+            page_visits: page_visits.clone(),
+            digitalocean: configuration.digitalocean,
+            spotify: configuration.spotify,
+            blog_cache: configuration.blog_cache,
+            contact: configuration.contact,
+            alerts: configuration.alerts,
+            email: configuration.email,
+            telemetry: configuration.telemetry,
+            trusted_proxies: TrustedProxies(configuration.application.trusted_proxies),
+            scheduler_status: scheduler.status(),
+            storage,
+            storage_max_upload_bytes: configuration.storage.max_upload_bytes,
+            media_max_age: MediaMaxAge(configuration.storage.media_max_age_secs),
+            scan_uploads: ScanUploads(configuration.storage.antivirus.clamd_address.is_some()),
+            robots: configuration.robots,
+            server: configuration.application.server,
+            query_timeout: QueryTimeout(std::time::Duration::from_millis(
+                configuration.database.query_timeout_ms,
+            )),
+            response_envelope: ResponseEnvelope(configuration.application.response_envelope),
         };
 
+        let key_ring = KeyRing::new(
+            &configuration.application.hmac_secret,
+            &configuration.application.previous_hmac_secrets,
+        )
+        .map_err(|e| {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to build the cookie signing key ring"
+            );
+            e
+        })?;
         let hmac_key = HmacSecret(configuration.application.hmac_secret);
         let raw_totp_key = configuration
             .application
@@ -124,28 +327,69 @@ impl Application {
 
         let secrets_config = SecretsConfig {
             hmac: hmac_key,
+            key_ring,
             totp: totp_key,
             jwt: jwt_private_key,
+            session_hash: session_hash_key,
         };
 
-        let listener = TcpListener::bind(&address).map_err(|e| {
+        // built here rather than in `run` so background workers can share the registry
+        let metrics = AppMetrics::new().map_err(|e| {
             tracing::error!(
-                address = %address,
                 error.cause_chain = ?e,
                 error.message = %e,
-                "Failed to bind TCP listener"
+                "Failed to register metrics"
             );
-            anyhow::Error::from(e)
+            anyhow::anyhow!("Metrics registration failed: {e}")
         })?;
-        tracing::info!(address = %address, "TCP listener bound");
+
+        let listener = match inherited_listener()? {
+            Some(listener) => listener,
+            None => {
+                let listener = TcpListener::bind(&address).map_err(|e| {
+                    tracing::error!(
+                        address = %address,
+                        error.cause_chain = ?e,
+                        error.message = %e,
+                        "Failed to bind TCP listener"
+                    );
+                    anyhow::Error::from(e)
+                })?;
+                tracing::info!(address = %address, "TCP listener bound");
+                listener
+            }
+        };
+        let admin_listener = configuration
+            .application
+            .admin_port
+            .map(|port| {
+                let address = format!("{}:{port}", configuration.application.admin_host);
+                let listener = TcpListener::bind(&address).map_err(|e| {
+                    tracing::error!(
+                        address = %address,
+                        error.cause_chain = ?e,
+                        error.message = %e,
+                        "Failed to bind admin TCP listener"
+                    );
+                    anyhow::Error::from(e)
+                })?;
+                tracing::info!(address = %address, "Admin TCP listener bound");
+                Ok::<_, anyhow::Error>(listener)
+            })
+            .transpose()?;
         let port = listener.local_addr().unwrap().port();
+        let admin_port = admin_listener
+            .as_ref()
+            .map(|listener| listener.local_addr().unwrap().port());
         let server = run(
             listener,
-            connection_pool,
+            admin_listener,
+            pools,
             configuration.application.base_url,
             secrets_config,
-            configuration.redis_uri,
+            redis,
             util_config,
+            metrics.clone(),
         )
         .await
         .map_err(|e| {
@@ -158,7 +402,17 @@ impl Application {
         })?;
         tracing::info!("Server components initialized successfully");
 
-        Ok(Self { port, server })
+        Ok(Self {
+            port,
+            admin_port,
+            server,
+            metrics,
+            page_visits,
+            pool,
+            workers,
+            scheduler,
+            reloadable,
+        })
     }
 
     #[must_use]
@@ -166,54 +420,218 @@ impl Application {
         self.port
     }
 
+    // the private listener's port, when `application.admin_port` is set
+    #[must_use]
+    pub const fn admin_port(&self) -> Option<u16> {
+        self.admin_port
+    }
+
+    // same instruments the server exports on /metrics
+    #[must_use]
+    pub fn metrics(&self) -> AppMetrics {
+        self.metrics.clone()
+    }
+
+    // the server's own writer pool, for jobs and workers to share rather than open their own
+    #[must_use]
+    pub fn db_pool(&self) -> PgPool {
+        self.pool.clone()
+    }
+
+    // where page visits are queued before they're written
+    #[must_use]
+    pub fn page_visit_recorder(&self) -> PageVisitRecorder {
+        self.page_visits.clone()
+    }
+
+    // what a SIGHUP swaps out, see `reload_on_hangup`
+    #[must_use]
+    pub fn reloadable_settings(&self) -> ReloadableSettings {
+        self.reloadable.clone()
+    }
+
+    // re-reads the configuration on SIGHUP for as long as the server runs
+    pub fn reload_on_hangup(&mut self) {
+        let reloadable = self.reloadable.clone();
+        self.workers.register("Configuration reload", move || {
+            reload_on_hangup(reloadable.clone())
+        });
+    }
+
+    // supervised next to the server once it runs, see `WorkerRegistry`
+    pub fn register_worker<F, Fut>(&mut self, name: &str, start: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.workers.register(name, start);
+    }
+
+    // run on its cron expression from `scheduler.schedules`, by the scheduler worker
+    pub fn schedule<F, Fut>(&mut self, name: &str, run: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.scheduler.register(name, run);
+    }
+
     #[allow(clippy::missing_errors_doc)]
-    // only return when the application is stopped
-    pub async fn run_until_stopped(self) -> Result<(), std::io::Error> {
+    // only return when the application is stopped, the workers go with it
+    pub async fn run_until_stopped(mut self) -> Result<(), std::io::Error> {
+        if !self.scheduler.is_empty() {
+            let scheduler = self.scheduler;
+            self.workers
+                .register("Scheduler", move || scheduler.clone().run_until_stopped());
+        }
+        let _workers = self.workers.spawn();
         self.server.await
     }
 }
 
+// a socket handed over by systemd socket activation or `systemfd`, through LISTEN_FDS,
+// so a restart never closes the port and connections queue up instead of being refused
+// `application.host`/`port` are ignored then, the socket is already bound
+fn inherited_listener() -> Result<Option<TcpListener>, anyhow::Error> {
+    let listener = ListenFd::from_env().take_tcp_listener(0).map_err(|e| {
+        tracing::error!(
+            error.cause_chain = ?e,
+            error.message = %e,
+            "LISTEN_FDS is set but the first descriptor isn't a TCP socket"
+        );
+        anyhow::Error::from(e)
+    })?;
+    if let Some(listener) = &listener {
+        tracing::info!(address = ?listener.local_addr().ok(), "Inherited TCP listener");
+    }
+    Ok(listener)
+}
+
 // run the actual server
 #[tracing::instrument(name = "Application::run", level = "info", skip_all)]
-#[allow(clippy::missing_errors_doc, clippy::too_many_lines)]
+#[allow(
+    clippy::missing_errors_doc,
+    clippy::too_many_lines,
+    clippy::too_many_arguments
+)]
 async fn run(
     listener: TcpListener,
-    db_pool: PgPool,
+    admin_listener: Option<TcpListener>,
+    db_pools: DbPools,
     base_url: String,
     secrets: SecretsConfig,
-    redis_uri: SecretString,
+    redis: RedisPool,
     util_config: UtilConfig,
+    app_metrics: AppMetrics,
 ) -> Result<Server, anyhow::Error> {
-    let db_pool = Data::new(db_pool);
+    let db_pool = Data::new(db_pools.writer.clone());
+    let db_pools = Data::new(db_pools);
     let base_url = Data::new(ApplicationBaseUrl(base_url));
-    let secret_key = Key::from(secrets.hmac.0.expose_secret().as_bytes());
+    let secret_key = secrets.key_ring.current().clone();
+    let key_ring = Data::new(secrets.key_ring.clone());
     let message_store = CookieMessageStore::builder(secret_key.clone())
         .same_site(SameSite::Strict)
         .build();
     let message_framework = FlashMessagesFramework::builder(message_store).build();
+    let app_metrics = Data::new(app_metrics);
+    let server_metrics_recorder = Data::new(spawn_server_metrics_writer(
+        db_pool.get_ref().clone(),
+        util_config.metrics.server_metrics_batch_size,
+        std::time::Duration::from_millis(util_config.metrics.server_metrics_flush_interval_ms),
+        &util_config.metrics.sampling,
+        util_config.metrics.slow_request_threshold_ms,
+    ));
+    let page_visit_recorder = Data::new(util_config.page_visits.clone());
+    let vitals_cache = Data::new(VitalsCache::new(std::time::Duration::from_secs(
+        util_config.metrics.vitals_cache_secs,
+    )));
+    let digitalocean_bandwidth =
+        Data::new(spawn_bandwidth_poller(util_config.digitalocean.clone()));
+    let now_playing = Data::new(spawn_now_playing_poller(util_config.spotify.clone()));
+    let blog_cache = Data::new(BlogCache::new(&util_config.blog_cache));
+    let event_bus = Data::new(EventBus::new());
+    let realtime_stats_feed = Data::new(spawn_realtime_sampler(
+        db_pool.get_ref().clone(),
+        app_metrics.get_ref().clone(),
+        std::time::Duration::from_secs(util_config.metrics.realtime_interval_secs),
+        event_bus.get_ref().clone(),
+        util_config.metrics.error_spike_threshold,
+    ));
+    // everything below reads what `track_request_metrics` records, so it goes quiet
+    // with the flag; alerts would only ever see zero traffic, they're skipped instead
+    if util_config.telemetry.metrics_enabled {
+        spawn_alert_evaluator(
+            db_pool.get_ref().clone(),
+            util_config.alerts.clone(),
+            event_bus.get_ref().clone(),
+            EmailClient::from_settings(&util_config.email),
+        );
+    } else {
+        tracing::warn!(
+            "Request metrics disabled: no prometheus request series, server_metrics rows, \
+            slow request capture, realtime request counts, error spikes or alerts"
+        );
+    }
 
-    tracing::info!("Connecting to Redis session store...");
-    let redis_store = RedisSessionStore::new(redis_uri.expose_secret())
-        .await
-        .map_err(|e| {
-            tracing::error!(
-                error.cause_chain = ?e,
-                error.message = %e,
-                "Failed to connect to Redis session store"
-            );
-            anyhow::anyhow!("Redis session store connection failed: {e}")
-        })?;
-    tracing::info!("Redis session store connected");
+    let redis = Data::new(redis);
+    spawn_pool_sampler(
+        db_pool.get_ref().clone(),
+        redis.get_ref().clone(),
+        app_metrics.get_ref().clone(),
+        std::time::Duration::from_secs(util_config.metrics.pool_sample_interval_secs),
+    );
+    spawn_active_sessions_sampler(
+        redis.get_ref().clone(),
+        app_metrics.get_ref().clone(),
+        std::time::Duration::from_secs(util_config.metrics.active_sessions_interval_secs),
+    );
+
+    let redis_store = PooledSessionStore::new(redis.get_ref().clone());
+    let rate_limiter = Data::new(RateLimiter::new(
+        redis.get_ref().clone(),
+        util_config.reloadable.rate_limit.clone(),
+    ));
+    let reloadable = Data::new(util_config.reloadable.clone());
+    let quiet_routes = Data::new(QuietRoutes(util_config.telemetry.quiet_routes.clone()));
+    let admin_address = admin_listener
+        .as_ref()
+        .map(TcpListener::local_addr)
+        .transpose()?;
+    let admin_listener_data = Data::new(AdminListener(admin_address));
 
+    let tls = util_config.reloadable.tls.clone();
+    let tuning = util_config.server.clone();
     let server = HttpServer::new(move || {
         App::new()
             .wrap(message_framework.clone())
-            .wrap(TracingLogger::default())
+            // must see the cookies before the flash and session middleware do
+            .wrap(from_fn(rotate_cookie_keys))
+            // inside `problem_details` so a panic still gets the request id
+            .wrap(from_fn(catch_panics))
+            // the public listener 404s the admin surface before any of it runs
+            .wrap(from_fn(restrict_admin_surface))
+            // inside the logger, which is where the request id comes from
+            .wrap(from_fn(problem_details))
+            .wrap(TracingLogger::<QuietRootSpanBuilder>::new())
+            .wrap(Condition::new(
+                util_config.telemetry.metrics_enabled,
+                from_fn(track_request_metrics),
+            ))
             .route("/", web::get().to(root))
             .route("/health_check", web::get().to(health_check))
+            .route("/live", web::get().to(live))
+            .route("/ready", web::get().to(ready))
+            .route("/metrics", web::get().to(export_metrics))
+            .route("/media/{id:.*}", web::get().to(get_media))
+            .route("/l/{code}", web::get().to(follow_short_link))
+            .route("/robots.txt", web::get().to(robots_txt))
+            .route("/version", web::get().to(version))
             .service(
                 web::scope("/v1")
                     .wrap(from_fn(cross_site_request_forgery_protection))
+                    // inside the session so budgets can be kept per user, and inside cors
+                    // so a 429 still carries the headers the frontend needs to read it
+                    .wrap(from_fn(enforce_rate_limits))
                     .wrap(
                         SessionMiddleware::builder(redis_store.clone(), secret_key.clone())
                             .cookie_same_site(SameSite::Strict)
@@ -230,37 +648,62 @@ async fn run(
                             )
                             .build(),
                     )
-                    .wrap({
-                        let mut cors = Cors::default();
-
-                        for origin in &util_config.cors.allowed_origins {
-                            cors = cors.allowed_origin(origin);
-                        }
-
-                        cors.allowed_methods(vec!["GET", "POST"])
-                            .allowed_headers(vec![
-                                http::header::AUTHORIZATION,
-                                http::header::ACCEPT,
-                                http::header::CONTENT_TYPE,
-                                http::header::HeaderName::from_static("idempotency-key"),
-                                http::header::HeaderName::from_static("x-xsrf-token"),
-                            ])
-                            .supports_credentials()
-                            .max_age(util_config.cors.max_age)
-                    })
+                    // PUT for uploads through the local storage backend's presigned urls
+                    .wrap(cors(
+                        &util_config.reloadable,
+                        vec!["GET", "POST", "PUT"],
+                        util_config.cors.max_age,
+                    ))
                     .route("/login", web::post().to(login))
                     .route("/verify_totp", web::post().to(verify_totp))
                     .route("/logout", web::post().to(logout))
                     .route("/check_auth", web::get().to(check_auth))
+                    .route("/check_auth/previous_login", web::get().to(previous_login))
                     .route("/contact", web::post().to(post_message))
+                    .service(
+                        web::scope("/metrics")
+                            .wrap(from_fn(require_ingest_signature))
+                            .route("/visit", web::post().to(record_page_visit))
+                            .route("/visits/batch", web::post().to(record_page_visit_batch))
+                            .route("/performance", web::post().to(record_performance_metric)),
+                    )
                     .route("/blog", web::get().to(get_articles))
+                    .route("/projects", web::get().to(get_projects))
+                    .route("/projects/{slug}", web::get().to(get_project))
+                    .route("/skills", web::get().to(get_skills))
+                    .route("/experience", web::get().to(get_experience))
+                    .route("/profile", web::get().to(get_profile))
+                    .route("/github/activity", web::get().to(get_github_activity))
+                    .route("/now-playing", web::get().to(get_now_playing))
+                    .route("/now", web::get().to(get_now_entries))
+                    .route("/availability", web::get().to(get_availability))
+                    .route("/gallery", web::get().to(get_gallery))
+                    .route("/gallery/{slug}", web::get().to(get_album))
+                    .route("/uses", web::get().to(get_uses))
+                    .route("/resume", web::get().to(get_resume))
+                    .route("/resume/pdf", web::get().to(get_resume_pdf))
+                    .route("/search", web::get().to(search))
                     .route("/accept", web::post().to(accept_invitation))
+                    .service(
+                        web::scope("/storage")
+                            .app_data(web::PayloadConfig::new(
+                                util_config.storage_max_upload_bytes,
+                            ))
+                            .route("/{key:.*}", web::get().to(download_object))
+                            .route("/{key:.*}", web::put().to(upload_object)),
+                    )
                     .service(
                         web::scope("/chat_token")
                             .wrap(from_fn(reject_anonymous_users))
                             // UserId needs to implement FromRequest?
                             .route("", web::get().to(chat_token)),
                     )
+                    .service(
+                        web::scope("/sessions")
+                            .wrap(from_fn(reject_anonymous_users))
+                            .route("", web::get().to(get_sessions))
+                            .route("/{session_id}/revoke", web::post().to(post_revoke_session)),
+                    )
                     .service(
                         web::scope("/change_password")
                             .wrap(from_fn(reject_anonymous_users))
@@ -272,29 +715,17 @@ async fn run(
                                 |err, _req| {
                                     actix_web::error::InternalError::from_response(
                                         err,
-                                        HttpResponse::PayloadTooLarge().finish(),
+                                        ApiProblem::new(http::StatusCode::PAYLOAD_TOO_LARGE)
+                                            .error_response(),
                                     )
                                     .into()
                                 },
                             ))
-                            .wrap({
-                                let mut cors = Cors::default();
-
-                                for origin in &util_config.cors.allowed_origins {
-                                    cors = cors.allowed_origin(origin);
-                                }
-
-                                cors.allowed_methods(vec!["GET", "POST", "PATCH", "DELETE"])
-                                    .allowed_headers(vec![
-                                        http::header::AUTHORIZATION,
-                                        http::header::ACCEPT,
-                                        http::header::CONTENT_TYPE,
-                                        http::header::HeaderName::from_static("idempotency-key"),
-                                        http::header::HeaderName::from_static("x-xsrf-token"),
-                                    ])
-                                    .supports_credentials()
-                                    .max_age(util_config.cors.max_age)
-                            })
+                            .wrap(cors(
+                                &util_config.reloadable,
+                                vec!["GET", "POST", "PUT", "PATCH", "DELETE"],
+                                util_config.cors.max_age,
+                            ))
                             .wrap(from_fn(reject_anonymous_users))
                             .wrap(from_fn(reject_non_admin))
                             .route("/create_user", web::post().to(create_user))
@@ -306,30 +737,164 @@ async fn run(
                             )
                             .route("/messages", web::get().to(get_messages))
                             .route("/messages", web::patch().to(patch_message))
+                            .route("/search", web::get().to(search))
                             .route("/blog/post", web::post().to(insert_article))
                             .route("/blog/publish", web::patch().to(publish_article))
                             .route("/blog/delete", web::delete().to(delete_article))
                             .route("/blog/edit", web::patch().to(edit_article))
+                            .route("/projects", web::get().to(get_all_projects))
+                            .route("/projects", web::post().to(insert_project))
+                            .route("/projects", web::patch().to(edit_project))
+                            .route("/projects", web::delete().to(delete_project))
+                            .route("/projects/order", web::patch().to(reorder_projects))
+                            .route("/albums", web::get().to(get_all_albums))
+                            .route("/albums", web::post().to(insert_album))
+                            .route("/albums", web::patch().to(edit_album))
+                            .route("/albums", web::delete().to(delete_album))
+                            .route("/albums/order", web::patch().to(reorder_albums))
+                            .route("/links", web::get().to(get_short_links))
+                            .route("/links", web::post().to(insert_short_link))
+                            .route("/links", web::patch().to(edit_short_link))
+                            .route("/links", web::delete().to(delete_short_link))
+                            .route("/now", web::post().to(insert_now_entry))
+                            .route("/now", web::patch().to(edit_now_entry))
+                            .route("/now", web::delete().to(delete_now_entry))
+                            .route("/skills", web::post().to(insert_skill))
+                            .route("/skills", web::patch().to(edit_skill))
+                            .route("/skills", web::delete().to(delete_skill))
+                            .route("/uses", web::post().to(insert_uses_item))
+                            .route("/uses", web::patch().to(edit_uses_item))
+                            .route("/uses", web::delete().to(delete_uses_item))
+                            .route("/uploads", web::post().to(issue_upload))
+                            .route("/resume", web::put().to(update_resume))
+                            .route("/profile", web::patch().to(edit_profile))
+                            .route("/availability", web::put().to(set_availability))
+                            .route(
+                                "/availability/history",
+                                web::get().to(get_availability_history),
+                            )
+                            .route("/experience", web::post().to(insert_experience))
+                            .route("/experience", web::patch().to(edit_experience))
+                            .route("/experience", web::delete().to(delete_experience))
                             .route("/totp/setup", web::get().to(totp_setup))
                             .route("/totp/confirm", web::post().to(totp_confirm))
                             .route("/totp/disable", web::post().to(totp_disable))
-                            .route("/totp/status", web::get().to(totp_status)),
+                            .route("/totp/status", web::get().to(totp_status))
+                            .route("/metrics/realtime", web::get().to(realtime_stats))
+                            .route(
+                                "/metrics/realtime-snapshot",
+                                web::get().to(get_realtime_snapshot),
+                            )
+                            .route("/metrics/campaigns", web::get().to(get_campaigns))
+                            .route("/metrics/errors", web::get().to(get_error_breakdown))
+                            .route("/metrics/vitals", web::get().to(get_vitals))
+                            .route("/metrics/summary", web::get().to(get_metrics_summary))
+                            .route("/metrics/sessions", web::get().to(get_session_report))
+                            .route("/metrics/slow-requests", web::get().to(get_slow_requests))
+                            .route("/metrics/export", web::get().to(export_analytics))
+                            .route("/metrics/export", web::post().to(store_analytics_export))
+                            .route("/metrics/infrastructure", web::get().to(get_infrastructure))
+                            .route("/rate_limits", web::get().to(get_rate_limits))
+                            .route("/rate_limits", web::delete().to(reset_rate_limit))
+                            .route("/scheduler", web::get().to(get_scheduler_status))
+                            // the socket for upgrades, server-sent events for everyone else
+                            .route(
+                                "/events",
+                                web::get()
+                                    .guard(guard::fn_guard(|ctx| ctx.head().upgrade()))
+                                    .to(dashboard_events),
+                            )
+                            .route("/events", web::get().to(dashboard_event_stream)),
                     ),
             )
             .app_data(db_pool.clone())
+            .app_data(db_pools.clone())
             .app_data(base_url.clone())
             .app_data(Data::new(secrets.hmac.clone()))
-            .app_data(Data::new(util_config.rate.message.clone()))
+            .app_data(key_ring.clone())
+            .app_data(Data::new(util_config.hashing.clone()))
+            .app_data(Data::new(util_config.idempotency_keys.clone()))
+            .app_data(Data::new(util_config.idempotency.clone()))
+            .app_data(Data::new(util_config.metrics.clone()))
+            .app_data(Data::new(util_config.trusted_proxies.clone()))
+            .app_data(Data::new(util_config.scheduler_status.clone()))
+            .app_data(Data::new(util_config.storage.clone()))
+            .app_data(Data::new(util_config.media_max_age))
+            .app_data(Data::new(util_config.scan_uploads))
+            .app_data(Data::new(util_config.robots.clone()))
+            .app_data(Data::new(util_config.contact.clone()))
+            .app_data(Data::new(util_config.query_timeout))
+            .app_data(Data::new(util_config.response_envelope))
+            .app_data(app_metrics.clone())
+            .app_data(realtime_stats_feed.clone())
+            .app_data(event_bus.clone())
+            .app_data(server_metrics_recorder.clone())
+            .app_data(page_visit_recorder.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(redis.clone())
+            .app_data(reloadable.clone())
+            .app_data(quiet_routes.clone())
+            .app_data(admin_listener_data.clone())
+            .app_data(vitals_cache.clone())
+            .app_data(digitalocean_bandwidth.clone())
+            .app_data(now_playing.clone())
+            .app_data(blog_cache.clone())
             .app_data(Data::new(secrets.totp.clone()))
             .app_data(Data::new(secrets.jwt.clone()))
-    })
-    .listen(listener)?
+            .app_data(Data::new(secrets.session_hash.clone()))
+    });
+    let server = server
+        .keep_alive(std::time::Duration::from_secs(tuning.keep_alive_secs))
+        .client_request_timeout(std::time::Duration::from_millis(
+            tuning.client_request_timeout_ms,
+        ))
+        .shutdown_timeout(tuning.shutdown_timeout_secs);
+    let server = match tuning.workers {
+        Some(workers) => server.workers(workers.max(1)),
+        None => server,
+    };
+    // with a certificate configured both listeners speak https instead
+    let server = match &tls {
+        Some(tls) => server.listen_rustls_0_23(listener, tls.server_config()?)?,
+        None => server.listen(listener)?,
+    };
+    let server = match (admin_listener, &tls) {
+        (Some(admin_listener), Some(tls)) => {
+            server.listen_rustls_0_23(admin_listener, tls.server_config()?)?
+        }
+        (Some(admin_listener), None) => server.listen(admin_listener)?,
+        (None, _) => server,
+    }
     .run();
 
     Ok(server)
 }
 
+// every write goes to `writer`, `reader` is the read replica when one is configured
+// and the primary otherwise, so a handler that only reads can always use it
+// (a replica lags a little, don't read back what the same request just wrote)
+#[derive(Clone, Debug)]
+pub struct DbPools {
+    pub writer: PgPool,
+    pub reader: PgPool,
+}
+
+impl DbPools {
+    /// # Errors
+    /// returns a `sqlx` error if the read replica url doesn't parse
+    pub fn from_settings(configuration: &DatabaseSettings) -> Result<Self, sqlx::Error> {
+        let writer = get_connection_pool(configuration);
+        let reader = match configuration.replica_connect_options() {
+            Some(options) => configuration.pool_options().connect_lazy_with(options?),
+            None => writer.clone(),
+        };
+        Ok(Self { writer, reader })
+    }
+}
+
 #[must_use]
 pub fn get_connection_pool(configuration: &DatabaseSettings) -> PgPool {
-    PgPoolOptions::new().connect_lazy_with(configuration.connect_options())
+    configuration
+        .pool_options()
+        .connect_lazy_with(configuration.connect_options())
 }
@@ -0,0 +1,413 @@
+use actix_web::web::Bytes;
+use hmac::{Hmac, KeyInit, Mac};
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::signer::Signer;
+use object_store::{ObjectStore, PutPayload, WriteMultipart};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::Sha256;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::configuration::{StorageBackend, StorageSettings};
+
+// the route the local backend serves its presigned urls from
+pub const LOCAL_STORAGE_ROUTE: &str = "/v1/storage";
+
+// objects under this prefix are public, served by `/media/{id}` without a signature
+pub const MEDIA_PREFIX: &str = "media";
+
+// analytics exports written for later download, only reachable through a presigned url
+pub const EXPORTS_PREFIX: &str = "exports";
+
+#[derive(thiserror::Error, Debug)]
+pub enum StorageError {
+    #[error("Invalid object key")]
+    InvalidKey(#[from] object_store::path::Error),
+    #[error("Invalid storage settings: {0}")]
+    InvalidSettings(String),
+    #[error(transparent)]
+    Backend(#[from] object_store::Error),
+    // whatever was producing the object's contents gave up partway
+    #[error("Failed to produce the object's contents")]
+    Source(#[source] anyhow::Error),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresignedMethod {
+    Get,
+    Put,
+}
+
+impl PresignedMethod {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Put => "PUT",
+        }
+    }
+}
+
+// media uploads, attachments and exports, in an S3-compatible bucket or a local directory
+#[derive(Clone)]
+pub struct Storage {
+    store: Arc<dyn ObjectStore>,
+    // the same store when it's the local backend, for serving its files directly
+    local: Option<Arc<LocalFileSystem>>,
+    signer: UrlSigner,
+    expires_in: Duration,
+}
+
+#[derive(Clone)]
+enum UrlSigner {
+    S3(Arc<AmazonS3>),
+    // the bucket can't sign for us, so urls point back at `LOCAL_STORAGE_ROUTE` with an hmac
+    Local {
+        base_url: String,
+        signing_key: SecretString,
+    },
+}
+
+impl Storage {
+    /// # Errors
+    /// returns an error if the local directory can't be created or the bucket settings are incomplete
+    pub fn from_settings(
+        settings: &StorageSettings,
+        base_url: &str,
+        signing_key: &SecretString,
+    ) -> Result<Self, StorageError> {
+        let expires_in = Duration::from_secs(settings.presigned_url_expiry_secs);
+        match settings.backend {
+            StorageBackend::Local => {
+                std::fs::create_dir_all(&settings.local_path).map_err(|e| {
+                    StorageError::InvalidSettings(format!(
+                        "can't create `{}`: {e}",
+                        settings.local_path
+                    ))
+                })?;
+                let local = Arc::new(LocalFileSystem::new_with_prefix(&settings.local_path)?);
+                Ok(Self {
+                    store: local.clone(),
+                    local: Some(local),
+                    signer: UrlSigner::Local {
+                        base_url: base_url.trim_end_matches('/').to_string(),
+                        signing_key: signing_key.clone(),
+                    },
+                    expires_in,
+                })
+            }
+            StorageBackend::S3 => {
+                let s3 = &settings.s3;
+                let Some(bucket) = &s3.bucket else {
+                    return Err(StorageError::InvalidSettings(
+                        "the s3 backend needs a bucket".to_string(),
+                    ));
+                };
+                let mut builder = AmazonS3Builder::from_env()
+                    .with_bucket_name(bucket)
+                    .with_region(&s3.region)
+                    .with_virtual_hosted_style_request(!s3.path_style);
+                if let Some(endpoint) = &s3.endpoint {
+                    builder = builder
+                        .with_endpoint(endpoint)
+                        .with_allow_http(endpoint.starts_with("http://"));
+                }
+                if let (Some(access_key_id), Some(secret_access_key)) =
+                    (&s3.access_key_id, &s3.secret_access_key)
+                {
+                    builder = builder
+                        .with_access_key_id(access_key_id)
+                        .with_secret_access_key(secret_access_key.expose_secret());
+                }
+                let store = Arc::new(builder.build()?);
+                Ok(Self {
+                    store: store.clone(),
+                    local: None,
+                    signer: UrlSigner::S3(store),
+                    expires_in,
+                })
+            }
+        }
+    }
+
+    /// # Errors
+    /// returns an error for an invalid key or if the backend rejects the write
+    pub async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), StorageError> {
+        self.store
+            .put(&Path::parse(key)?, PutPayload::from(bytes))
+            .await?;
+        Ok(())
+    }
+
+    // written in parts as the chunks arrive, so a large export never sits in memory whole
+    // nothing is left under the key if `chunks` fails partway
+    /// # Errors
+    /// returns an error for an invalid key, if `chunks` fails or the backend rejects a part
+    pub async fn put_stream(
+        &self,
+        key: &str,
+        mut chunks: impl Stream<Item = Result<Bytes, anyhow::Error>> + Unpin,
+    ) -> Result<(), StorageError> {
+        let upload = self.store.put_multipart(&Path::parse(key)?).await?;
+        let mut writer = WriteMultipart::new(upload);
+
+        while let Some(chunk) = chunks.next().await {
+            match chunk {
+                Ok(chunk) => writer.put(chunk),
+                Err(e) => {
+                    writer.abort().await?;
+                    return Err(StorageError::Source(e));
+                }
+            }
+        }
+
+        writer.finish().await?;
+        Ok(())
+    }
+
+    // `None` when there's nothing stored under the key
+    /// # Errors
+    /// returns an error for an invalid key or if the backend can't be read
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match self.store.get(&Path::parse(key)?).await {
+            Ok(result) => Ok(Some(result.bytes().await?.to_vec())),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// # Errors
+    /// returns an error for an invalid key or if the backend rejects the delete
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.store.delete(&Path::parse(key)?).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // where the local backend keeps `key` on disk, `None` for the s3 backend
+    /// # Errors
+    /// returns an error for an invalid key
+    pub fn local_file(&self, key: &str) -> Result<Option<PathBuf>, StorageError> {
+        let path = Path::parse(key)?;
+        self.local
+            .as_ref()
+            .map(|local| local.path_to_filesystem(&path))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    // a url anyone holding it can PUT the object to until it expires
+    /// # Errors
+    /// returns an error for an invalid key or if the backend can't sign
+    pub async fn presigned_upload_url(&self, key: &str) -> Result<String, StorageError> {
+        self.presigned_url(PresignedMethod::Put, key).await
+    }
+
+    /// # Errors
+    /// returns an error for an invalid key or if the backend can't sign
+    pub async fn presigned_download_url(&self, key: &str) -> Result<String, StorageError> {
+        self.presigned_url(PresignedMethod::Get, key).await
+    }
+
+    async fn presigned_url(
+        &self,
+        method: PresignedMethod,
+        key: &str,
+    ) -> Result<String, StorageError> {
+        let path = Path::parse(key)?;
+        match &self.signer {
+            UrlSigner::S3(s3) => {
+                let method = match method {
+                    PresignedMethod::Get => reqwest::Method::GET,
+                    PresignedMethod::Put => reqwest::Method::PUT,
+                };
+                Ok(s3
+                    .signed_url(method, &path, self.expires_in)
+                    .await?
+                    .to_string())
+            }
+            UrlSigner::Local {
+                base_url,
+                signing_key,
+            } => {
+                let expires = chrono::Utc::now()
+                    .timestamp()
+                    .saturating_add(i64::try_from(self.expires_in.as_secs()).unwrap_or(i64::MAX));
+                let signature = local_signature(signing_key, method, path.as_ref(), expires);
+                Ok(format!(
+                    "{base_url}{LOCAL_STORAGE_ROUTE}/{path}?expires={expires}&signature={signature}"
+                ))
+            }
+        }
+    }
+
+    // whether a presigned url handed out by the local backend is genuine and still valid,
+    // always false for the s3 backend since the bucket serves its own urls
+    #[must_use]
+    pub fn verify_local_signature(
+        &self,
+        method: PresignedMethod,
+        key: &str,
+        expires: i64,
+        signature: &str,
+    ) -> bool {
+        let UrlSigner::Local { signing_key, .. } = &self.signer else {
+            return false;
+        };
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        expires >= chrono::Utc::now().timestamp()
+            && signing_mac(signing_key, method, key, expires)
+                .verify_slice(&signature)
+                .is_ok()
+    }
+}
+
+fn signing_mac(
+    signing_key: &SecretString,
+    method: PresignedMethod,
+    key: &str,
+    expires: i64,
+) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_key.expose_secret().as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("storage\n{}\n{key}\n{expires}", method.as_str()).as_bytes());
+    mac
+}
+
+fn local_signature(
+    signing_key: &SecretString,
+    method: PresignedMethod,
+    key: &str,
+    expires: i64,
+) -> String {
+    hex::encode(
+        signing_mac(signing_key, method, key, expires)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn local_storage() -> Storage {
+        let settings = StorageSettings {
+            local_path: std::env::temp_dir()
+                .join(uuid::Uuid::new_v4().to_string())
+                .to_string_lossy()
+                .into_owned(),
+            ..StorageSettings::default()
+        };
+        Storage::from_settings(&settings, "http://127.0.0.1/", &"secret".into()).unwrap()
+    }
+
+    #[test]
+    fn signatures_are_bound_to_the_method_key_and_expiry() {
+        let storage = local_storage();
+        let key = SecretString::from("secret");
+        let expires = chrono::Utc::now().timestamp() + 60;
+        let signature = local_signature(&key, PresignedMethod::Get, "media/a.png", expires);
+
+        assert!(storage.verify_local_signature(
+            PresignedMethod::Get,
+            "media/a.png",
+            expires,
+            &signature
+        ));
+        assert!(!storage.verify_local_signature(
+            PresignedMethod::Put,
+            "media/a.png",
+            expires,
+            &signature
+        ));
+        assert!(!storage.verify_local_signature(
+            PresignedMethod::Get,
+            "media/b.png",
+            expires,
+            &signature
+        ));
+        assert!(!storage.verify_local_signature(
+            PresignedMethod::Get,
+            "media/a.png",
+            expires + 1,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn expired_signatures_are_rejected() {
+        let storage = local_storage();
+        let expires = chrono::Utc::now().timestamp() - 1;
+        let signature = local_signature(&"secret".into(), PresignedMethod::Get, "a.png", expires);
+
+        assert!(!storage.verify_local_signature(
+            PresignedMethod::Get,
+            "a.png",
+            expires,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn s3_needs_a_bucket() {
+        let settings = StorageSettings {
+            backend: StorageBackend::S3,
+            ..StorageSettings::default()
+        };
+
+        let e = Storage::from_settings(&settings, "", &"secret".into())
+            .err()
+            .unwrap();
+        assert!(matches!(e, StorageError::InvalidSettings(_)));
+    }
+
+    #[tokio::test]
+    async fn local_urls_point_back_at_the_api() {
+        let storage = local_storage();
+
+        let url = storage.presigned_download_url("media/a.png").await.unwrap();
+
+        assert!(url.starts_with("http://127.0.0.1/v1/storage/media/a.png?expires="));
+        assert!(storage.presigned_upload_url("../etc/passwd").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn streamed_objects_are_kept_whole_or_not_at_all() {
+        let storage = local_storage();
+        let chunks = |last: Result<Bytes, anyhow::Error>| {
+            tokio_stream::iter(vec![Ok(Bytes::from_static(b"a,b\n")), last])
+        };
+
+        storage
+            .put_stream("exports/ok.csv", chunks(Ok(Bytes::from_static(b"1,2\n"))))
+            .await
+            .unwrap();
+        let failed = storage
+            .put_stream("exports/failed.csv", chunks(Err(anyhow::anyhow!("gone"))))
+            .await;
+
+        assert_eq!(
+            storage.get("exports/ok.csv").await.unwrap().unwrap(),
+            b"a,b\n1,2\n"
+        );
+        assert!(matches!(failed, Err(StorageError::Source(_))));
+        assert!(storage.get("exports/failed.csv").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn local_files_stay_under_the_root() {
+        let storage = local_storage();
+
+        let file = storage.local_file("media/a.png").unwrap().unwrap();
+
+        assert!(file.ends_with("media/a.png"));
+        assert!(storage.local_file("media/../../etc/passwd").is_err());
+    }
+}
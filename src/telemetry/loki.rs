@@ -0,0 +1,264 @@
+use chrono::Utc;
+use secrecy::ExposeSecret;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::configuration::{LokiSettings, TelemetrySettings};
+
+// (unix timestamp in nanoseconds as a string, the line), the pair loki's push api takes
+type LokiEntry = (String, String);
+
+#[derive(serde::Serialize)]
+struct PushRequest<'a> {
+    streams: [Stream<'a>; 1],
+}
+
+#[derive(serde::Serialize)]
+struct Stream<'a> {
+    stream: &'a HashMap<String, String>,
+    values: &'a [LokiEntry],
+}
+
+// the sink half, teed next to stdout so loki gets every line after redaction
+// a disabled writer discards, so the subscriber has the same shape either way
+#[derive(Clone, Default)]
+pub struct LokiWriter {
+    sender: Option<mpsc::Sender<LokiEntry>>,
+}
+
+impl<'a> MakeWriter<'a> for LokiWriter {
+    type Writer = LokiLine<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LokiLine {
+            sender: self.sender.as_ref(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+// collects one bunyan record and queues it when dropped
+pub struct LokiLine<'a> {
+    sender: Option<&'a mpsc::Sender<LokiEntry>>,
+    buffer: Vec<u8>,
+}
+
+impl Write for LokiLine<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.sender.is_some() {
+            self.buffer.extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LokiLine<'_> {
+    fn drop(&mut self) {
+        let Some(sender) = self.sender else {
+            return;
+        };
+        let line = String::from_utf8_lossy(&self.buffer);
+        let line = line.trim_end_matches('\n');
+        if line.is_empty() {
+            return;
+        }
+        let timestamp = Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or_default()
+            .to_string();
+        // a full queue means loki is behind or down, the line is still on stdout
+        let _ = sender.try_send((timestamp, line.to_string()));
+    }
+}
+
+// the pushing half, stopped on shutdown so the last batch isn't lost
+pub struct LokiShipper {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl LokiShipper {
+    pub async fn shutdown(self) {
+        let _ = self.stop.send(());
+        let _ = self.task.await;
+    }
+}
+
+// a disabled writer and no shipper when `telemetry.loki.push_url` isn't set
+// has to be called from inside the runtime, the shipper is spawned onto it
+/// # Errors
+/// returns a `reqwest` error if the http client can't be built
+pub fn init_loki(
+    settings: &TelemetrySettings,
+) -> Result<(LokiWriter, Option<LokiShipper>), reqwest::Error> {
+    let loki = &settings.loki;
+    let Some(push_url) = loki.push_url.clone() else {
+        return Ok((LokiWriter::default(), None));
+    };
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(loki.timeout_secs))
+        .build()?;
+    let mut labels = loki.labels.clone();
+    labels
+        .entry("service_name".to_string())
+        .or_insert_with(|| settings.service_name.clone());
+
+    let (sender, receiver) = mpsc::channel(loki.queue_capacity.max(1));
+    let (stop, stopped) = oneshot::channel();
+    let pusher = Pusher {
+        client,
+        push_url,
+        labels,
+        settings: loki.clone(),
+    };
+    let task = tokio::spawn(ship(receiver, stopped, pusher));
+
+    Ok((
+        LokiWriter {
+            sender: Some(sender),
+        },
+        Some(LokiShipper { stop, task }),
+    ))
+}
+
+struct Pusher {
+    client: reqwest::Client,
+    push_url: String,
+    labels: HashMap<String, String>,
+    settings: LokiSettings,
+}
+
+impl Pusher {
+    // a batch that can't be delivered is dropped rather than retried, it's still on stdout
+    // reported on stderr, through tracing it would only queue another line for the same loki
+    async fn push(&self, batch: &mut Vec<LokiEntry>) {
+        let body = PushRequest {
+            streams: [Stream {
+                stream: &self.labels,
+                values: batch,
+            }],
+        };
+        let mut request = self.client.post(&self.push_url).json(&body);
+        if let Some(username) = &self.settings.username {
+            request = request.basic_auth(
+                username,
+                self.settings
+                    .password
+                    .as_ref()
+                    .map(|password| password.expose_secret()),
+            );
+        }
+        if let Some(tenant_id) = &self.settings.tenant_id {
+            request = request.header("X-Scope-OrgID", tenant_id);
+        }
+
+        if let Err(e) = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+        {
+            eprintln!("Failed to push {} log lines to loki: {e}", batch.len());
+        }
+        batch.clear();
+    }
+}
+
+// pushes whenever a batch fills up or the flush interval passes with lines waiting
+async fn ship(
+    mut lines: mpsc::Receiver<LokiEntry>,
+    mut stopped: oneshot::Receiver<()>,
+    pusher: Pusher,
+) {
+    let batch_size = pusher.settings.batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut ticker = tokio::time::interval(Duration::from_secs(
+        pusher.settings.flush_interval_secs.max(1),
+    ));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            line = lines.recv() => {
+                let Some(line) = line else { break };
+                batch.push(line);
+                if batch.len() >= batch_size {
+                    pusher.push(&mut batch).await;
+                }
+            }
+            _ = ticker.tick() => {
+                if !batch.is_empty() {
+                    pusher.push(&mut batch).await;
+                }
+            }
+            _ = &mut stopped => {
+                while let Ok(line) = lines.try_recv() {
+                    batch.push(line);
+                }
+                break;
+            }
+        }
+    }
+
+    for chunk in batch.chunks(batch_size) {
+        pusher.push(&mut chunk.to_vec()).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lines_are_queued_without_the_newline() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let writer = LokiWriter {
+            sender: Some(sender),
+        };
+
+        writer
+            .make_writer()
+            .write_all(b"{\"msg\":\"first\"}\n")
+            .unwrap();
+        // the queue is full, this one only went to stdout
+        writer
+            .make_writer()
+            .write_all(b"{\"msg\":\"second\"}\n")
+            .unwrap();
+
+        let (_, line) = receiver.try_recv().unwrap();
+        assert_eq!(line, "{\"msg\":\"first\"}");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn pushes_are_shaped_like_the_loki_api() {
+        let labels = HashMap::from([("service_name".to_string(), "test".to_string())]);
+        let values = [("1700000000000000000".to_string(), "line".to_string())];
+
+        let body = serde_json::to_value(PushRequest {
+            streams: [Stream {
+                stream: &labels,
+                values: &values,
+            }],
+        })
+        .unwrap();
+
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "streams": [{
+                    "stream": { "service_name": "test" },
+                    "values": [["1700000000000000000", "line"]],
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,214 @@
+// let's actually understand what we're doing here
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    web,
+};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{SdkTracer, SdkTracerProvider},
+};
+use tokio::task::JoinHandle;
+#[cfg(feature = "console")]
+use tracing::Level;
+use tracing::{Span, Subscriber, subscriber::set_global_default};
+use tracing_actix_web::{DefaultRootSpanBuilder, RootSpanBuilder};
+use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+use tracing_log::LogTracer;
+#[cfg(feature = "console")]
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan,
+};
+
+#[cfg(feature = "console")]
+use crate::configuration::ConsoleSettings;
+use crate::configuration::TelemetrySettings;
+use crate::rate_limit::route_matches;
+
+mod loki;
+mod redaction;
+mod slow_statements;
+
+pub use loki::{LokiLine, LokiShipper, LokiWriter, init_loki};
+pub use redaction::{RedactedLine, RedactingWriter, Redactor};
+pub use slow_statements::SlowStatementLayer;
+
+// compose multiple layers into a tracing subscriber
+// impl Sub to avoid specifying the return type (?)
+// explicitly call out Send + Sync so we can pass it to init_subscriber
+pub fn get_subscriber<Sink>(
+    name: String,
+    env_filter: String,
+    sink: Sink,
+) -> impl Subscriber + for<'a> LookupSpan<'a> + Send + Sync
+where
+    Sink: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    get_subscriber_with_tracer(name, env_filter, sink, None)
+}
+
+// same pipeline, plus span export when a tracer is passed in
+// the filter sits on the logging layers rather than the whole subscriber,
+// so a layer added on top (the tokio console) can see events they don't log
+pub fn get_subscriber_with_tracer<Sink>(
+    name: String,
+    env_filter: String,
+    sink: Sink,
+    tracer: Option<SdkTracer>,
+) -> impl Subscriber + for<'a> LookupSpan<'a> + Send + Sync
+// higher-ranked trait bound
+// aka: sink implements `MakeWriter` for all choices of the lifetime parameter
+// (how long the data Sink is writing lives), can be shared across threads safely
+where
+    Sink: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    // logging level filter (ie. info/debug) depending on the environment
+    let env_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(env_filter));
+    // bunyan formats log events into JSON
+    let formatting_layer = BunyanFormattingLayer::new(name, sink);
+
+    // assemble the subscriber pipeline starting from default
+    Registry::default().with(
+        // stores span contexts
+        JsonStorageLayer
+            // outputs the actual logs
+            .and_then(formatting_layer)
+            // counts the statements sqlx logged as slow
+            .and_then(SlowStatementLayer)
+            // ships spans to the OTLP collector, if configured
+            .and_then(tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)))
+            // determines what gets logged
+            .with_filter(env_filter),
+    )
+}
+
+// tokio-console's instrumentation, served on its own listener next to the logs rather
+// than instead of them; only there when built with the `console` feature, which
+// also needs `RUSTFLAGS="--cfg tokio_unstable"`
+#[cfg(feature = "console")]
+pub fn console_layer<S>(settings: &ConsoleSettings) -> Option<impl Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    settings.enabled.then(|| {
+        console_subscriber::ConsoleLayer::builder()
+            .server_addr(settings.bind_address)
+            .spawn()
+            // the runtime's own instrumentation, whatever the log level is
+            .with_filter(
+                Targets::new()
+                    .with_target("tokio", Level::TRACE)
+                    .with_target("runtime", Level::TRACE),
+            )
+    })
+}
+
+// `None` when trace export is switched off, the provider has to be kept around
+// and shut down on exit so buffered spans get flushed
+/// # Errors
+/// returns an `anyhow` error if export is enabled without an endpoint or the exporter can't be built
+pub fn init_tracer_provider(
+    settings: &TelemetrySettings,
+) -> Result<Option<SdkTracerProvider>, anyhow::Error> {
+    if !settings.traces_enabled {
+        return Ok(None);
+    }
+    let endpoint = settings
+        .otlp_endpoint
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("traces_enabled is set but otlp_endpoint is missing"))?;
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+
+    Ok(Some(
+        SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(settings.service_name.clone())
+                    .build(),
+            )
+            .build(),
+    ))
+}
+
+#[must_use]
+pub fn tracer(provider: &SdkTracerProvider, settings: &TelemetrySettings) -> SdkTracer {
+    provider.tracer(settings.service_name.clone())
+}
+
+// routes too frequent and too boring to log, load balancer probes and static media
+// same pattern syntax as the rate limit routes, a trailing `*` matches a whole prefix
+#[derive(Clone, Debug, Default)]
+pub struct QuietRoutes(pub Vec<String>);
+
+impl QuietRoutes {
+    // the pattern the request fell under, it's the label they're counted by
+    #[must_use]
+    pub fn matching(&self, request: &ServiceRequest) -> Option<&str> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let route = request
+            .match_pattern()
+            .unwrap_or_else(|| request.path().to_string());
+
+        self.0
+            .iter()
+            .find(|pattern| route_matches(pattern, &route))
+            .map(String::as_str)
+    }
+}
+
+// the default root span, except quiet routes get none so nothing about them reaches the logs
+// events raised while handling one still do, they just aren't attached to a request span
+pub struct QuietRootSpanBuilder;
+
+impl RootSpanBuilder for QuietRootSpanBuilder {
+    fn on_request_start(request: &ServiceRequest) -> Span {
+        let quiet = request
+            .app_data::<web::Data<QuietRoutes>>()
+            .is_some_and(|quiet| quiet.matching(request).is_some());
+        if quiet {
+            return Span::none();
+        }
+        DefaultRootSpanBuilder::on_request_start(request)
+    }
+
+    fn on_request_end<B: MessageBody>(
+        span: Span,
+        outcome: &Result<ServiceResponse<B>, actix_web::Error>,
+    ) {
+        DefaultRootSpanBuilder::on_request_end(span, outcome);
+    }
+}
+
+/// # Panics
+/// likewise should handle subscriber failures more gracefully
+pub fn init_subscriber(subscriber: impl Subscriber + Send + Sync) {
+    LogTracer::init().expect("Failed to set logger");
+    set_global_default(subscriber).expect("Failed to set subscriber");
+}
+
+// this function maintains logging context when offloading work to a background thread
+// by passing the context metadata of the current span to the new thread
+pub fn spawn_blocking_with_tracing<F, R>(f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    // get current span metadata
+    let current_span = tracing::Span::current();
+    // transfer ownership of the current span and the closure to the new thread
+    // inside said thread, .in_scope enters the captured span, runs the function/logic,
+    // then exits the span. Thus, for the duration of the operation, telemetry treats the
+    // logging context as active to keep consistency across thread boundaries
+    tokio::task::spawn_blocking(move || current_span.in_scope(f))
+}
@@ -0,0 +1,260 @@
+use regex::{Captures, Regex};
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use siphasher::sip::SipHasher13;
+use std::collections::HashSet;
+use std::hash::Hasher;
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock};
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::configuration::{RedactionMode, RedactionSettings};
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+        .expect("valid email pattern")
+});
+// loose on purpose, every candidate still has to parse as an address,
+// so timestamps and other colon-separated text are left alone
+static IP_ADDRESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b\d{1,3}(?:\.\d{1,3}){3}\b|[0-9A-Fa-f]*:[0-9A-Fa-f:.]*[0-9A-Fa-f]")
+        .expect("valid ip address pattern")
+});
+
+// bunyan's own fields and ones that only ever hold the server's side of the connection
+const UNSCANNED_FIELDS: [&str; 7] = ["v", "name", "hostname", "pid", "time", "level", "http.host"];
+
+// rewrites personal data in a bunyan record before it's written out
+pub struct Redactor {
+    mode: RedactionMode,
+    fields: HashSet<String>,
+    key: [u8; 16],
+}
+
+impl Redactor {
+    // the hash is keyed off the session hash secret, so the same address hashes the same way
+    // across restarts and instances but can't be matched against guesses without the key
+    #[must_use]
+    pub fn new(settings: &RedactionSettings, secret: &SecretString) -> Self {
+        let digest = Sha256::digest(secret.expose_secret().as_bytes());
+        let mut key = [0; 16];
+        key.copy_from_slice(&digest[..16]);
+
+        Self {
+            mode: settings.mode,
+            fields: settings.fields.iter().cloned().collect(),
+            key,
+        }
+    }
+
+    // one json line in, the same line with personal data replaced out, keys come back sorted
+    // anything that isn't a json object is only scanned as text
+    #[must_use]
+    pub fn redact_line(&self, line: &str) -> String {
+        let Ok(Value::Object(mut record)) = serde_json::from_str::<Value>(line) else {
+            return self.scrub(line);
+        };
+        for (name, value) in &mut record {
+            if self.fields.contains(name) {
+                let original = match value {
+                    Value::String(s) => std::mem::take(s),
+                    Value::Null => continue,
+                    ref other => other.to_string(),
+                };
+                *value = Value::String(self.replace("redacted", &original));
+            } else if let Value::String(s) = value
+                && !UNSCANNED_FIELDS.contains(&name.as_str())
+            {
+                *s = self.scrub(s);
+            }
+        }
+
+        serde_json::to_string(&record).unwrap_or_else(|_| self.scrub(line))
+    }
+
+    // emails and ip addresses inside free text, e.g. an error message that quotes its input
+    fn scrub(&self, text: &str) -> String {
+        let text = EMAIL.replace_all(text, |captures: &Captures| {
+            self.replace("email", &captures[0])
+        });
+        IP_ADDRESS
+            .replace_all(&text, |captures: &Captures| {
+                let candidate = &captures[0];
+                if candidate.parse::<IpAddr>().is_ok() {
+                    self.replace("ip", candidate)
+                } else {
+                    candidate.to_string()
+                }
+            })
+            .into_owned()
+    }
+
+    fn replace(&self, kind: &str, original: &str) -> String {
+        match self.mode {
+            RedactionMode::Off => original.to_string(),
+            RedactionMode::Redact => format!("[{kind}]"),
+            RedactionMode::Hash => {
+                let mut hasher = SipHasher13::new_with_key(&self.key);
+                hasher.write(original.to_lowercase().as_bytes());
+                format!("[{kind}:{:016x}]", hasher.finish())
+            }
+        }
+    }
+}
+
+// wraps the sink bunyan writes to, each record is written in one go so a writer
+// holds exactly one line and rewrites it when dropped
+pub struct RedactingWriter<M> {
+    inner: M,
+    redactor: Option<Arc<Redactor>>,
+}
+
+impl<M> RedactingWriter<M> {
+    #[must_use]
+    pub fn new(inner: M, redactor: Redactor) -> Self {
+        let redactor = (redactor.mode != RedactionMode::Off).then(|| Arc::new(redactor));
+        Self { inner, redactor }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingWriter<M> {
+    type Writer = RedactedLine<'a, M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedLine {
+            inner: self.inner.make_writer(),
+            redactor: self.redactor.as_deref(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+pub struct RedactedLine<'a, W: Write> {
+    inner: W,
+    redactor: Option<&'a Redactor>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Write for RedactedLine<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.redactor.is_none() {
+            return self.inner.write(buf);
+        }
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactedLine<'_, W> {
+    fn drop(&mut self) {
+        let Some(redactor) = self.redactor else {
+            return;
+        };
+        if self.buffer.is_empty() {
+            return;
+        }
+        let line = String::from_utf8_lossy(&self.buffer);
+        let mut redacted = redactor.redact_line(line.trim_end_matches('\n'));
+        redacted.push('\n');
+        // nowhere left to report a failed log write
+        let _ = self.inner.write_all(redacted.as_bytes());
+        let _ = self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor(mode: RedactionMode) -> Redactor {
+        Redactor::new(
+            &RedactionSettings {
+                mode,
+                ..RedactionSettings::default()
+            },
+            &SecretString::from("test-key"),
+        )
+    }
+
+    fn redact(redactor: &Redactor, record: &Value) -> Value {
+        serde_json::from_str(&redactor.redact_line(&record.to_string())).unwrap()
+    }
+
+    #[test]
+    fn listed_fields_are_replaced_whatever_they_hold() {
+        let record = json!({
+            "msg": "Email validation failed",
+            "email": "not an email",
+            "message_text": "Hello there, call me back",
+            "level": 40,
+        });
+
+        let redacted = redact(&redactor(RedactionMode::Redact), &record);
+
+        assert_eq!(redacted["email"], "[redacted]");
+        assert_eq!(redacted["message_text"], "[redacted]");
+        assert_eq!(redacted["msg"], "Email validation failed");
+        assert_eq!(redacted["level"], 40);
+    }
+
+    #[test]
+    fn addresses_inside_text_are_scrubbed() {
+        let record = json!({
+            "msg": "Rejected jane.doe@example.com from 203.0.113.7 and 2001:db8::1",
+            "time": "2026-03-01T12:00:00.000Z",
+        });
+
+        let redacted = redact(&redactor(RedactionMode::Redact), &record);
+
+        assert_eq!(redacted["msg"], "Rejected [email] from [ip] and [ip]");
+        assert_eq!(redacted["time"], "2026-03-01T12:00:00.000Z");
+    }
+
+    #[test]
+    fn hashes_are_stable_and_case_insensitive() {
+        let redactor = redactor(RedactionMode::Hash);
+
+        let first = redact(&redactor, &json!({ "email": "Jane@Example.com" }));
+        let second = redact(&redactor, &json!({ "msg": "from jane@example.com" }));
+
+        let hash = first["email"].as_str().unwrap();
+        assert!(hash.starts_with("[redacted:"));
+        assert!(!hash.contains("example"));
+        assert_eq!(
+            second["msg"].as_str().unwrap(),
+            format!("from {}", hash.replace("redacted", "email"))
+        );
+    }
+
+    #[test]
+    fn colons_that_are_not_addresses_are_kept() {
+        let redacted = redactor(RedactionMode::Redact).scrub("retry at 12:00:30, key dead:beef");
+
+        assert_eq!(redacted, "retry at 12:00:30, key dead:beef");
+    }
+
+    #[test]
+    fn the_writer_rewrites_each_line() {
+        let redactor = redactor(RedactionMode::Redact);
+        let mut output = Vec::new();
+
+        {
+            let mut line = RedactedLine {
+                inner: &mut output,
+                redactor: Some(&redactor),
+                buffer: Vec::new(),
+            };
+            line.write_all(b"{\"email\":\"jane@example.com\"}\n")
+                .unwrap();
+        }
+
+        assert_eq!(output, b"{\"email\":\"[redacted]\"}\n");
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+use crate::metrics::DB_SLOW_STATEMENTS_TOTAL;
+
+// sqlx logs a statement over `database.slow_statement_ms` itself, this only counts them
+// so a query that starts tanking shows up on the dashboard and not just in the logs
+pub struct SlowStatementLayer;
+
+impl<S: Subscriber> Layer<S> for SlowStatementLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // only sqlx's slow statement event carries the threshold it went over
+        if metadata.target() != "sqlx::query" || metadata.fields().field("slow_threshold").is_none()
+        {
+            return;
+        }
+        let mut summary = Summary(None);
+        event.record(&mut summary);
+        let statement = summary.0.as_deref().unwrap_or("unknown");
+
+        DB_SLOW_STATEMENTS_TOTAL
+            .with_label_values(&[statement.trim_end_matches('…').trim_end()])
+            .inc();
+    }
+}
+
+// sqlx's summary is the statement's first few words, with `…` when it cut the rest off
+struct Summary(Option<String>);
+
+impl Visit for Summary {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "summary" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "summary" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+    use tracing_subscriber::{Registry, layer::SubscriberExt};
+
+    #[test]
+    fn slow_statements_are_counted_by_summary() {
+        let subscriber = Registry::default().with(SlowStatementLayer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(
+                target: "sqlx::query",
+                summary = "select count(*) as …",
+                slow_threshold = ?Duration::from_millis(500),
+                "slow statement: execution time exceeded alert threshold"
+            );
+            tracing::debug!(target: "sqlx::query", summary = "select count(*) as …", "fast");
+            tracing::warn!(slow_threshold = 1, "not from sqlx");
+        });
+
+        assert_eq!(
+            DB_SLOW_STATEMENTS_TOTAL
+                .with_label_values(&["select count(*) as"])
+                .get(),
+            1
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use rustls::ServerConfig;
+use rustls::crypto::{CryptoProvider, aws_lc_rs};
+use rustls::pki_types::pem::{self, PemObject};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use std::sync::Arc;
+
+use crate::reload::Reloadable;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TlsError {
+    #[error("Failed to read `{path}`")]
+    Pem {
+        path: String,
+        #[source]
+        source: pem::Error,
+    },
+    #[error("`{0}` has no certificate in it")]
+    NoCertificate(String),
+    #[error("The certificate and key don't make a usable pair")]
+    InvalidPair(#[from] rustls::Error),
+}
+
+// the certificate the https listener presents, handed out per handshake
+// so a reload applies to the next connection without dropping the open ones
+#[derive(Clone, Debug)]
+pub struct TlsCertificate {
+    key: Reloadable<CertifiedKey>,
+}
+
+impl TlsCertificate {
+    /// # Errors
+    /// returns a `TlsError` if either file can't be read or they don't belong together
+    pub fn load(cert_path: &str, key_path: &str) -> Result<Self, TlsError> {
+        Ok(Self {
+            key: Reloadable::new(certified_key(cert_path, key_path)?),
+        })
+    }
+
+    // on failure the current certificate stays in use
+    /// # Errors
+    /// see `load`
+    pub fn reload(&self, cert_path: &str, key_path: &str) -> Result<(), TlsError> {
+        self.key.replace(certified_key(cert_path, key_path)?);
+        Ok(())
+    }
+
+    /// # Errors
+    /// returns a `rustls` error if the provider supports no safe protocol version
+    pub fn server_config(&self) -> Result<ServerConfig, rustls::Error> {
+        Ok(
+            ServerConfig::builder_with_provider(Arc::new(aws_lc_rs::default_provider()))
+                .with_safe_default_protocol_versions()?
+                .with_no_client_auth()
+                .with_cert_resolver(Arc::new(self.clone())),
+        )
+    }
+}
+
+impl ResolvesServerCert for TlsCertificate {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.key.get())
+    }
+}
+
+fn certified_key(cert_path: &str, key_path: &str) -> Result<CertifiedKey, TlsError> {
+    let pem_error = |path: &str| {
+        let path = path.to_string();
+        move |source| TlsError::Pem { path, source }
+    };
+
+    let chain = CertificateDer::pem_file_iter(cert_path)
+        .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+        .map_err(pem_error(cert_path))?;
+    if chain.is_empty() {
+        return Err(TlsError::NoCertificate(cert_path.to_string()));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).map_err(pem_error(key_path))?;
+
+    let provider: CryptoProvider = aws_lc_rs::default_provider();
+    let certified_key = CertifiedKey::new(chain, provider.key_provider.load_private_key(key)?);
+    certified_key.keys_match()?;
+    Ok(certified_key)
+}
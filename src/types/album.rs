@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::AlbumError;
+use crate::types::article::CarouselImage;
+
+const MAX_PHOTOS: usize = 200;
+
+#[derive(serde::Serialize)]
+pub struct AlbumRecord {
+    pub album_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    // in the order they're shown, each with its own caption
+    pub photos: Vec<CarouselImage>,
+    pub published: bool,
+    pub display_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct AlbumRecordRaw {
+    pub album_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub photos: serde_json::Value,
+    pub published: bool,
+    pub display_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<AlbumRecordRaw> for AlbumRecord {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: AlbumRecordRaw) -> Result<Self, Self::Error> {
+        Ok(Self {
+            album_id: raw.album_id,
+            title: raw.title,
+            slug: raw.slug,
+            description: raw.description,
+            photos: serde_json::from_value(raw.photos)?,
+            published: raw.published,
+            display_order: raw.display_order,
+            created_at: raw.created_at,
+            updated_at: raw.updated_at,
+        })
+    }
+}
+
+// a gallery tile, the photos themselves are loaded with the album
+#[derive(serde::Serialize)]
+pub struct AlbumSummary {
+    pub album_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub cover: Option<CarouselImage>,
+    pub photo_count: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct AlbumSummaryRaw {
+    pub album_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub cover: Option<serde_json::Value>,
+    pub photo_count: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<AlbumSummaryRaw> for AlbumSummary {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: AlbumSummaryRaw) -> Result<Self, Self::Error> {
+        Ok(Self {
+            album_id: raw.album_id,
+            title: raw.title,
+            slug: raw.slug,
+            description: raw.description,
+            cover: raw.cover.map(serde_json::from_value).transpose()?,
+            photo_count: raw.photo_count,
+            updated_at: raw.updated_at,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct AlbumId(pub Uuid);
+
+impl std::fmt::Display for AlbumId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for AlbumId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct AlbumResponse {
+    pub message: &'static str,
+    pub album_id: AlbumId,
+}
+
+impl AlbumResponse {
+    pub const fn new(message: &'static str, album_id: AlbumId) -> Self {
+        Self { message, album_id }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AlbumForm {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub photos: Vec<CarouselImage>,
+    #[serde(default)]
+    pub published: bool,
+}
+
+impl AlbumForm {
+    pub fn validate(&self) -> Result<(), AlbumError> {
+        validate_title(&self.title)?;
+        validate_description(&self.description)?;
+        validate_photos(&self.photos)
+    }
+}
+
+// every album's id, in the order the gallery should show them
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AlbumOrderRequest {
+    pub album_ids: Vec<Uuid>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AlbumDeleteRequest {
+    pub album_id: Uuid,
+}
+
+// only the fields that are present change, `photos` replaces the whole list, so it's also
+// how photos are reordered, recaptioned or removed
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AlbumEditRequest {
+    pub album_id: Uuid,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub photos: Option<Vec<CarouselImage>>,
+    pub published: Option<bool>,
+}
+
+impl AlbumEditRequest {
+    pub fn validate(&self) -> Result<(), AlbumError> {
+        if let Some(title) = &self.title {
+            validate_title(title)?;
+        }
+        if let Some(description) = &self.description {
+            validate_description(description)?;
+        }
+        if let Some(photos) = &self.photos {
+            validate_photos(photos)?;
+        }
+        Ok(())
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.photos.is_none()
+            && self.published.is_none()
+    }
+}
+
+fn validate_title(title: &str) -> Result<(), AlbumError> {
+    if title.trim().is_empty() || title.len() > 200 {
+        return Err(AlbumError::ValidationError("Invalid title".into()));
+    }
+    Ok(())
+}
+
+fn validate_description(description: &str) -> Result<(), AlbumError> {
+    if description.len() > 2000 {
+        return Err(AlbumError::ValidationError("Invalid description".into()));
+    }
+    Ok(())
+}
+
+// only uploads from the media library, so the gallery never hotlinks someone else's photos
+fn validate_photos(photos: &[CarouselImage]) -> Result<(), AlbumError> {
+    if photos.len() > MAX_PHOTOS
+        || photos.iter().any(|photo| {
+            photo.src.len() <= "/media/".len()
+                || !photo.src.starts_with("/media/")
+                || photo.caption.as_ref().is_some_and(|c| c.len() > 1000)
+                || photo.alt.as_ref().is_some_and(|a| a.len() > 500)
+        })
+    {
+        return Err(AlbumError::ValidationError("Invalid photos".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn album(photos: serde_json::Value) -> AlbumForm {
+        serde_json::from_value(serde_json::json!({
+            "title": "Iceland, 2025",
+            "photos": photos,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn photos_come_from_the_media_library() {
+        let form = album(serde_json::json!([
+            { "src": "/media/iceland/skogafoss.jpg", "caption": "Skógafoss" }
+        ]));
+
+        assert!(form.validate().is_ok());
+    }
+
+    #[test]
+    fn other_sites_photos_are_rejected() {
+        let form = album(serde_json::json!([
+            { "src": "https://example.com/photo.jpg" }
+        ]));
+
+        assert!(form.validate().is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, Responder,
+    body::BoxBody,
+    http::{
+        Method, StatusCode,
+        header::{HeaderName, HeaderValue, IfModifiedSince, LastModified, VARY},
+    },
+    web,
+};
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use tracing_actix_web::RequestId;
+
+use crate::types::pagination::PaginationMeta;
+
+// `true` asks for the envelope, `false` for the old bare body, whatever the server default is
+pub const RESPONSE_ENVELOPE_HEADER: HeaderName = HeaderName::from_static("x-response-envelope");
+
+// `application.response_envelope`, the shape a request that doesn't ask gets
+#[derive(Copy, Clone, Debug)]
+pub struct ResponseEnvelope(pub bool);
+
+// what a list or a page says about itself, next to the data rather than mixed into it
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ResponseMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<PaginationMeta>,
+}
+
+// every successful api response, `{ data, meta, request_id }` once the client asks for it
+// until the frontend has moved over, a request that doesn't gets the body it always got:
+// `data` on its own, or the handler's `legacy` shape where that differed
+// an idempotent replay is the stored body, in whichever shape the first request asked for
+pub struct ApiResponse<T = ()> {
+    status: StatusCode,
+    data: Option<T>,
+    meta: Option<ResponseMeta>,
+    legacy: Option<serde_json::Result<serde_json::Value>>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    data: Option<&'a T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meta: Option<&'a ResponseMeta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+impl<T: Serialize> ApiResponse<T> {
+    #[must_use]
+    pub const fn ok(data: T) -> Self {
+        Self::with_status(StatusCode::OK, data)
+    }
+
+    #[must_use]
+    pub const fn accepted(data: T) -> Self {
+        Self::with_status(StatusCode::ACCEPTED, data)
+    }
+
+    #[must_use]
+    pub const fn with_status(status: StatusCode, data: T) -> Self {
+        Self {
+            status,
+            data: Some(data),
+            meta: None,
+            legacy: None,
+            last_modified: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_pagination(mut self, pagination: PaginationMeta) -> Self {
+        self.meta
+            .get_or_insert_with(ResponseMeta::default)
+            .pagination = Some(pagination);
+        self
+    }
+
+    // the pre-envelope body, for endpoints whose old shape was more than their data
+    // serialized inside the closure, so the old shape can borrow from `data`
+    #[must_use]
+    pub fn with_legacy_body(
+        mut self,
+        body: impl FnOnce(&T) -> serde_json::Result<serde_json::Value>,
+    ) -> Self {
+        self.legacy = self.data.as_ref().map(body);
+        self
+    }
+
+    // sent as `Last-Modified`, and a GET whose `If-Modified-Since` isn't older gets a bare 304
+    // http dates stop at seconds, so the rest is dropped or every check would look modified
+    #[must_use]
+    pub fn with_last_modified(mut self, last_modified: DateTime<Utc>) -> Self {
+        self.last_modified = Some(last_modified.trunc_subsecs(0));
+        self
+    }
+
+    // no body in the old shape, `data: null` in the envelope
+    #[must_use]
+    pub const fn empty(status: StatusCode) -> Self {
+        Self {
+            status,
+            data: None,
+            meta: None,
+            legacy: None,
+            last_modified: None,
+        }
+    }
+}
+
+impl<T: Serialize> Responder for ApiResponse<T> {
+    type Body = BoxBody;
+
+    fn respond_to(self, request: &HttpRequest) -> HttpResponse<Self::Body> {
+        let mut builder = HttpResponse::build(self.status);
+        builder.insert_header((VARY, HeaderValue::from_static("x-response-envelope")));
+        if let Some(last_modified) = self.last_modified {
+            let last_modified = SystemTime::from(last_modified);
+            builder.insert_header(LastModified(last_modified.into()));
+            if not_modified_since(request, last_modified) {
+                return builder.status(StatusCode::NOT_MODIFIED).finish();
+            }
+        }
+
+        if wants_envelope(request) {
+            let request_id = request
+                .extensions()
+                .get::<RequestId>()
+                .map(ToString::to_string);
+            return builder.json(Envelope {
+                data: self.data.as_ref(),
+                meta: self.meta.as_ref(),
+                request_id,
+            });
+        }
+
+        match (self.legacy, self.data) {
+            (Some(Ok(legacy)), _) => builder.json(legacy),
+            (Some(Err(e)), _) => {
+                tracing::error!(error.message = %e, "Failed to serialize the legacy response body");
+                HttpResponse::InternalServerError().finish()
+            }
+            (None, Some(data)) => builder.json(data),
+            (None, None) => builder.finish(),
+        }
+    }
+}
+
+fn not_modified_since(request: &HttpRequest, last_modified: SystemTime) -> bool {
+    matches!(request.method(), &Method::GET | &Method::HEAD)
+        && request
+            .get_header::<IfModifiedSince>()
+            .is_some_and(|IfModifiedSince(since)| last_modified <= SystemTime::from(since))
+}
+
+fn wants_envelope(request: &HttpRequest) -> bool {
+    request
+        .headers()
+        .get(RESPONSE_ENVELOPE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<bool>().ok())
+        .unwrap_or_else(|| {
+            request
+                .app_data::<web::Data<ResponseEnvelope>>()
+                .is_some_and(|envelope| envelope.0)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{body::to_bytes, test::TestRequest};
+
+    async fn body_of(response: impl Responder, request: &HttpRequest) -> serde_json::Value {
+        let response = response.respond_to(request).map_into_boxed_body();
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn bare_data_without_the_flag() {
+        let request = TestRequest::default().to_http_request();
+
+        let body = body_of(ApiResponse::ok(vec![1, 2]), &request).await;
+
+        assert_eq!(body, serde_json::json!([1, 2]));
+    }
+
+    #[actix_web::test]
+    async fn the_header_asks_for_the_envelope() {
+        let request = TestRequest::default()
+            .insert_header((RESPONSE_ENVELOPE_HEADER, "true"))
+            .to_http_request();
+
+        let body = body_of(ApiResponse::ok(vec![1, 2]), &request).await;
+
+        assert_eq!(body, serde_json::json!({ "data": [1, 2] }));
+    }
+
+    #[actix_web::test]
+    async fn the_header_overrides_the_default() {
+        let request = TestRequest::default()
+            .app_data(web::Data::new(ResponseEnvelope(true)))
+            .insert_header((RESPONSE_ENVELOPE_HEADER, "false"))
+            .to_http_request();
+        let response = ApiResponse::ok(vec![1])
+            .with_legacy_body(|items| Ok(serde_json::json!({ "items": items })));
+
+        let body = body_of(response, &request).await;
+
+        assert_eq!(body, serde_json::json!({ "items": [1] }));
+    }
+
+    #[actix_web::test]
+    async fn empty_responses_have_no_legacy_body() {
+        let request = TestRequest::default().to_http_request();
+
+        let response = ApiResponse::<()>::empty(StatusCode::ACCEPTED).respond_to(&request);
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert!(to_bytes(response.into_body()).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn unchanged_resources_are_not_modified() {
+        let updated_at = "2026-03-01T12:00:00.750Z".parse::<DateTime<Utc>>().unwrap();
+        let request = TestRequest::get()
+            .insert_header(("If-Modified-Since", "Sun, 01 Mar 2026 12:00:00 GMT"))
+            .to_http_request();
+
+        let unchanged = ApiResponse::ok(1)
+            .with_last_modified(updated_at)
+            .respond_to(&request);
+        let changed = ApiResponse::ok(1)
+            .with_last_modified(updated_at + chrono::Duration::seconds(1))
+            .respond_to(&request);
+
+        assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            unchanged.headers().get("last-modified").unwrap(),
+            "Sun, 01 Mar 2026 12:00:00 GMT"
+        );
+        assert_eq!(changed.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn pagination_goes_in_meta() {
+        let request = TestRequest::default()
+            .app_data(web::Data::new(ResponseEnvelope(true)))
+            .to_http_request();
+        let pagination = PaginationMeta {
+            page: 1,
+            page_size: 20,
+            total_items: 0,
+            total_pages: 1,
+        };
+
+        let body = body_of(
+            ApiResponse::ok(Vec::<u8>::new()).with_pagination(pagination),
+            &request,
+        )
+        .await;
+
+        assert_eq!(body["data"], serde_json::json!([]));
+        assert_eq!(body["meta"]["pagination"]["total_pages"], 1);
+    }
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use crate::errors::BlogError;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct CarouselImage {
     pub src: String,
     pub alt: Option<String>,
@@ -14,7 +14,7 @@ pub struct CarouselImage {
 // need to parse something like `[order: 1]/[order: ...]`
 // at the start of each section (this is a client-side thing)
 // sike no you dont, JSON is ordered lol
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ArticleSection {
     Markdown {
@@ -40,7 +40,7 @@ impl ArticleSection {
     }
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 pub struct ArticleRecord {
     pub post_id: Uuid,
     pub title: String,
@@ -140,7 +140,7 @@ impl ArticleResponse {
     }
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct ArticleForm {
     pub title: String,
     pub excerpt: String,
@@ -186,18 +186,18 @@ impl ArticleForm {
     }
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct ArticleDeleteRequest {
     pub post_id: Uuid,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct ArticlePublishRequest {
     pub post_id: Uuid,
     pub published: bool,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct ArticleEditRequest {
     pub post_id: Uuid,
     pub title: Option<String>,
@@ -0,0 +1,76 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::errors::AvailabilityError;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[sqlx(type_name = "availability_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AvailabilityStatus {
+    OpenToWork,
+    Booked,
+    Unavailable,
+}
+
+#[derive(serde::Serialize)]
+pub struct AvailabilityRecord {
+    pub availability_id: Uuid,
+    pub status: AvailabilityStatus,
+    pub booked_until: Option<NaiveDate>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// replaces the current availability, the old one stays in the history
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AvailabilityForm {
+    pub status: AvailabilityStatus,
+    pub booked_until: Option<NaiveDate>,
+    pub note: Option<String>,
+}
+
+impl AvailabilityForm {
+    pub fn validate(&self) -> Result<(), AvailabilityError> {
+        if self.booked_until.is_some() && self.status != AvailabilityStatus::Booked {
+            return Err(AvailabilityError::ValidationError(
+                "Only a booked status has an end date".into(),
+            ));
+        }
+        if self
+            .note
+            .as_ref()
+            .is_some_and(|note| note.trim().is_empty() || note.len() > 280)
+        {
+            return Err(AvailabilityError::ValidationError("Invalid note".into()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_bookings_have_an_end_date() {
+        let form: AvailabilityForm = serde_json::from_value(serde_json::json!({
+            "status": "open_to_work",
+            "booked_until": "2027-03-01",
+        }))
+        .unwrap();
+
+        assert!(form.validate().is_err());
+    }
+
+    #[test]
+    fn notes_cannot_be_blank() {
+        let form: AvailabilityForm = serde_json::from_value(serde_json::json!({
+            "status": "booked",
+            "booked_until": "2027-03-01",
+            "note": "  ",
+        }))
+        .unwrap();
+
+        assert!(form.validate().is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use email_address::EmailAddress;
+use std::str::FromStr;
+
+// an address the way it's stored and compared, so "Foo@Example.COM " and "foo@example.com"
+// are one sender to the contact form, its dedup check and its rate limit
+// the local part is lowercased too, no mail provider worth worrying about treats it as
+// case-sensitive even though the spec allows it
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ValidatedEmail(String);
+
+impl ValidatedEmail {
+    // `strip_plus_addresses` drops a `+tag` from the local part, see `contact.strip_plus_addresses`
+    /// # Errors
+    /// returns an `email_address` error if `raw` isn't a valid address once trimmed
+    pub fn parse(raw: &str, strip_plus_addresses: bool) -> Result<Self, email_address::Error> {
+        let address = EmailAddress::from_str(raw.trim())?;
+        let local = address.local_part().to_lowercase();
+        // a quoted local part can hold a literal `+`, it's left alone
+        let local = match local.split_once('+') {
+            Some((base, _tag))
+                if strip_plus_addresses && !base.is_empty() && !base.starts_with('"') =>
+            {
+                base.to_string()
+            }
+            _ => local,
+        };
+
+        Ok(Self(format!("{local}@{}", address.domain().to_lowercase())))
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ValidatedEmail {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ValidatedEmail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<ValidatedEmail> for String {
+    fn from(email: ValidatedEmail) -> Self {
+        email.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ValidatedEmail;
+
+    #[test]
+    fn addresses_are_trimmed_and_lowercased() {
+        let email = ValidatedEmail::parse("  Foo@Example.COM ", false).unwrap();
+
+        assert_eq!(email.as_str(), "foo@example.com");
+        assert_eq!(
+            email,
+            ValidatedEmail::parse("foo@example.com", false).unwrap()
+        );
+    }
+
+    #[test]
+    fn plus_tags_are_only_stripped_when_asked() {
+        let kept = ValidatedEmail::parse("Foo+News@example.com", false).unwrap();
+        let stripped = ValidatedEmail::parse("Foo+News@example.com", true).unwrap();
+
+        assert_eq!(kept.as_str(), "foo+news@example.com");
+        assert_eq!(stripped.as_str(), "foo@example.com");
+    }
+
+    #[test]
+    fn a_bare_plus_local_part_is_kept() {
+        let email = ValidatedEmail::parse("+tag@example.com", true).unwrap();
+
+        assert_eq!(email.as_str(), "+tag@example.com");
+    }
+
+    #[test]
+    fn invalid_addresses_are_rejected() {
+        for raw in ["", "   ", "bademail", "foo@", "@example.com"] {
+            assert!(
+                ValidatedEmail::parse(raw, true).is_err(),
+                "{raw:?} was accepted"
+            );
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::ExperienceError;
+
+const MAX_HIGHLIGHTS: usize = 20;
+const MAX_TECH: usize = 30;
+
+#[derive(serde::Serialize)]
+pub struct ExperienceRecord {
+    pub experience_id: Uuid,
+    pub company: String,
+    pub role: String,
+    pub start_date: NaiveDate,
+    // `None` while it's the current job
+    pub end_date: Option<NaiveDate>,
+    pub highlights: Vec<String>,
+    pub tech: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct ExperienceId(pub Uuid);
+
+impl std::fmt::Display for ExperienceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for ExperienceId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct ExperienceResponse {
+    pub message: &'static str,
+    pub experience_id: ExperienceId,
+}
+
+impl ExperienceResponse {
+    pub const fn new(message: &'static str, experience_id: ExperienceId) -> Self {
+        Self {
+            message,
+            experience_id,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ExperienceForm {
+    pub company: String,
+    pub role: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+    #[serde(default)]
+    pub tech: Vec<String>,
+}
+
+impl ExperienceForm {
+    pub fn validate(&self) -> Result<(), ExperienceError> {
+        validate_text("company", &self.company)?;
+        validate_text("role", &self.role)?;
+        validate_dates(self.start_date, self.end_date)?;
+        validate_highlights(&self.highlights)?;
+        validate_tech(&self.tech)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ExperienceDeleteRequest {
+    pub experience_id: Uuid,
+}
+
+// only the fields that are present change, `"end_date": null` marks the job as current
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ExperienceEditRequest {
+    pub experience_id: Uuid,
+    pub company: Option<String>,
+    pub role: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end_date: Option<Option<NaiveDate>>,
+    pub highlights: Option<Vec<String>>,
+    pub tech: Option<Vec<String>>,
+}
+
+// tells a field that was sent as null apart from one that wasn't sent
+pub(crate) fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl ExperienceEditRequest {
+    // both dates are checked against each other once the update has been applied
+    pub fn validate(&self) -> Result<(), ExperienceError> {
+        if let Some(company) = &self.company {
+            validate_text("company", company)?;
+        }
+        if let Some(role) = &self.role {
+            validate_text("role", role)?;
+        }
+        if let (Some(start_date), Some(end_date)) = (self.start_date, self.end_date) {
+            validate_dates(start_date, end_date)?;
+        }
+        if let Some(highlights) = &self.highlights {
+            validate_highlights(highlights)?;
+        }
+        if let Some(tech) = &self.tech {
+            validate_tech(tech)?;
+        }
+        Ok(())
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.company.is_none()
+            && self.role.is_none()
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+            && self.highlights.is_none()
+            && self.tech.is_none()
+    }
+}
+
+fn validate_text(name: &str, value: &str) -> Result<(), ExperienceError> {
+    if value.trim().is_empty() || value.len() > 200 {
+        return Err(ExperienceError::ValidationError(format!("Invalid {name}")));
+    }
+    Ok(())
+}
+
+fn validate_dates(start: NaiveDate, end: Option<NaiveDate>) -> Result<(), ExperienceError> {
+    if end.is_some_and(|end| end < start) {
+        return Err(ExperienceError::EndsBeforeStart);
+    }
+    Ok(())
+}
+
+fn validate_highlights(highlights: &[String]) -> Result<(), ExperienceError> {
+    if highlights.len() > MAX_HIGHLIGHTS
+        || highlights
+            .iter()
+            .any(|highlight| highlight.trim().is_empty() || highlight.len() > 500)
+    {
+        return Err(ExperienceError::ValidationError(
+            "Invalid highlights".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_tech(tech: &[String]) -> Result<(), ExperienceError> {
+    if tech.len() > MAX_TECH || tech.iter().any(|t| t.trim().is_empty() || t.len() > 50) {
+        return Err(ExperienceError::ValidationError("Invalid tech".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn entries_cannot_end_before_they_start() {
+        let form: ExperienceForm = serde_json::from_value(serde_json::json!({
+            "company": "Acme",
+            "role": "Engineer",
+            "start_date": "2022-01-01",
+            "end_date": "2021-01-01",
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            form.validate(),
+            Err(ExperienceError::EndsBeforeStart)
+        ));
+    }
+
+    #[test]
+    fn a_null_end_date_is_an_edit() {
+        let current: ExperienceEditRequest = serde_json::from_value(serde_json::json!({
+            "experience_id": Uuid::new_v4(),
+            "end_date": null,
+        }))
+        .unwrap();
+        let untouched: ExperienceEditRequest = serde_json::from_value(serde_json::json!({
+            "experience_id": Uuid::new_v4(),
+            "role": "Staff engineer",
+        }))
+        .unwrap();
+
+        assert_eq!(current.end_date, Some(None));
+        assert!(!current.is_empty());
+        assert_eq!(untouched.end_date, None);
+    }
+}
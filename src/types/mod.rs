@@ -1,3 +1,16 @@
+pub mod album;
+pub mod api_response;
 pub mod article;
+pub mod availability;
+pub mod email;
+pub mod experience;
+pub mod now_entry;
 pub mod pagination;
+pub mod profile;
+pub mod project;
+pub mod resume;
+pub mod search;
+pub mod short_link;
+pub mod skill;
 pub mod user;
+pub mod uses;
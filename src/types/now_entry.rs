@@ -0,0 +1,142 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::NowEntryError;
+
+const MAX_BODY_LENGTH: usize = 5000;
+
+// `now` is what's being worked on, `changelog` is what changed on the site
+#[derive(PartialEq, Eq, Debug, Clone, Copy, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[sqlx(type_name = "now_entry_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NowEntryKind {
+    Now,
+    Changelog,
+}
+
+#[derive(serde::Serialize)]
+pub struct NowEntryRecord {
+    pub entry_id: Uuid,
+    pub kind: NowEntryKind,
+    pub entry_date: NaiveDate,
+    pub title: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// without a kind both are listed together
+#[derive(Debug, serde::Deserialize)]
+pub struct NowEntryQuery {
+    pub kind: Option<NowEntryKind>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct NowEntryId(pub Uuid);
+
+impl std::fmt::Display for NowEntryId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for NowEntryId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct NowEntryResponse {
+    pub message: &'static str,
+    pub entry_id: NowEntryId,
+}
+
+impl NowEntryResponse {
+    pub const fn new(message: &'static str, entry_id: NowEntryId) -> Self {
+        Self { message, entry_id }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NowEntryForm {
+    pub kind: NowEntryKind,
+    pub entry_date: NaiveDate,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl NowEntryForm {
+    pub fn validate(&self) -> Result<(), NowEntryError> {
+        validate_title(&self.title)?;
+        validate_body(&self.body)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NowEntryDeleteRequest {
+    pub entry_id: Uuid,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NowEntryEditRequest {
+    pub entry_id: Uuid,
+    pub kind: Option<NowEntryKind>,
+    pub entry_date: Option<NaiveDate>,
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+impl NowEntryEditRequest {
+    pub fn validate(&self) -> Result<(), NowEntryError> {
+        if let Some(title) = &self.title {
+            validate_title(title)?;
+        }
+        if let Some(body) = &self.body {
+            validate_body(body)?;
+        }
+        Ok(())
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.kind.is_none()
+            && self.entry_date.is_none()
+            && self.title.is_none()
+            && self.body.is_none()
+    }
+}
+
+fn validate_title(title: &str) -> Result<(), NowEntryError> {
+    if title.trim().is_empty() || title.len() > 200 {
+        return Err(NowEntryError::ValidationError("Invalid title".into()));
+    }
+    Ok(())
+}
+
+fn validate_body(body: &str) -> Result<(), NowEntryError> {
+    if body.len() > MAX_BODY_LENGTH {
+        return Err(NowEntryError::ValidationError("Invalid body".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn entries_need_a_title() {
+        let form: NowEntryForm = serde_json::from_value(serde_json::json!({
+            "kind": "changelog",
+            "entry_date": "2026-04-27",
+            "title": "  ",
+        }))
+        .unwrap();
+
+        assert!(form.validate().is_err());
+    }
+}
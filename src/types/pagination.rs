@@ -60,10 +60,11 @@ impl PaginationMeta {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct PaginatedResponse<T> {
-    pub data: Vec<T>,
-    pub pagination: PaginationMeta,
+// the shape pages had before `ApiResponse`, still sent to clients that haven't asked for it
+#[derive(Debug, Serialize)]
+pub struct PaginatedResponse<'a, T> {
+    pub data: &'a [T],
+    pub pagination: &'a PaginationMeta,
 }
 
 #[cfg(test)]
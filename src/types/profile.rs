@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+
+use crate::errors::ProfileError;
+
+const MAX_SOCIAL_LINKS: usize = 20;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SocialLink {
+    // e.g. `github`, the frontend picks the icon from it
+    pub platform: String,
+    pub url: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct Profile {
+    pub display_name: String,
+    pub bio: String,
+    pub avatar_url: Option<String>,
+    pub social_links: Vec<SocialLink>,
+    pub contact_available: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct ProfileRaw {
+    pub display_name: String,
+    pub bio: String,
+    pub avatar_url: Option<String>,
+    pub social_links: serde_json::Value,
+    pub contact_available: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ProfileRaw> for Profile {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: ProfileRaw) -> Result<Self, Self::Error> {
+        Ok(Self {
+            display_name: raw.display_name,
+            bio: raw.bio,
+            avatar_url: raw.avatar_url,
+            social_links: serde_json::from_value(raw.social_links)?,
+            contact_available: raw.contact_available,
+            updated_at: raw.updated_at,
+        })
+    }
+}
+
+// only the fields that are present change, an empty avatar_url removes the avatar
+// and `social_links` replaces the whole list
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ProfileEditRequest {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub social_links: Option<Vec<SocialLink>>,
+    pub contact_available: Option<bool>,
+}
+
+impl ProfileEditRequest {
+    pub fn validate(&self) -> Result<(), ProfileError> {
+        if let Some(display_name) = &self.display_name
+            && (display_name.trim().is_empty() || display_name.len() > 100)
+        {
+            return Err(ProfileError::ValidationError("Invalid display name".into()));
+        }
+        if let Some(bio) = &self.bio
+            && bio.len() > 2000
+        {
+            return Err(ProfileError::ValidationError("Invalid bio".into()));
+        }
+        if let Some(avatar_url) = self.avatar_url.as_deref().filter(|url| !url.is_empty())
+            && !is_link(avatar_url)
+            && !avatar_url.starts_with("/media/")
+        {
+            return Err(ProfileError::ValidationError("Invalid avatar_url".into()));
+        }
+        if let Some(social_links) = &self.social_links
+            && (social_links.len() > MAX_SOCIAL_LINKS
+                || social_links.iter().any(|link| {
+                    link.platform.trim().is_empty()
+                        || link.platform.len() > 50
+                        || !is_link(&link.url)
+                }))
+        {
+            return Err(ProfileError::ValidationError("Invalid social links".into()));
+        }
+        Ok(())
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.display_name.is_none()
+            && self.bio.is_none()
+            && self.avatar_url.is_none()
+            && self.social_links.is_none()
+            && self.contact_available.is_none()
+    }
+}
+
+fn is_link(url: &str) -> bool {
+    url.len() <= 2048 && (url.starts_with("https://") || url.starts_with("http://"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn edit(body: serde_json::Value) -> ProfileEditRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn avatars_can_be_uploaded_media_or_links() {
+        assert!(
+            edit(serde_json::json!({"avatar_url": "/media/me.jpg"}))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            edit(serde_json::json!({"avatar_url": "https://example.com/me.jpg"}))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            edit(serde_json::json!({"avatar_url": ""}))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            edit(serde_json::json!({"avatar_url": "javascript:alert(1)"}))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn social_links_must_be_http() {
+        let request = edit(serde_json::json!({
+            "social_links": [{"platform": "github", "url": "github.com/calvin-devogel"}]
+        }));
+
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn empty_edits_are_empty() {
+        assert!(edit(serde_json::json!({})).is_empty());
+        assert!(!edit(serde_json::json!({"contact_available": false})).is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+use chrono::{DateTime, Utc};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::{BlogError, ProjectError};
+use crate::types::article::{ArticleSection, CarouselImage};
+
+const MAX_TECH_TAGS: usize = 30;
+const MAX_IMAGES: usize = 20;
+const MAX_SECTIONS: usize = 50;
+
+#[derive(serde::Serialize)]
+pub struct ProjectRecord {
+    pub project_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    // same sections as a blog post, empty for projects without a write-up
+    pub content: Vec<ArticleSection>,
+    pub tech_tags: Vec<String>,
+    pub repo_url: Option<String>,
+    pub demo_url: Option<String>,
+    pub images: Vec<CarouselImage>,
+    pub published: bool,
+    // lowest first on the homepage grid, set through the reorder endpoint
+    pub display_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct ProjectRecordRaw {
+    pub project_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub content: serde_json::Value,
+    pub tech_tags: Vec<String>,
+    pub repo_url: Option<String>,
+    pub demo_url: Option<String>,
+    pub images: serde_json::Value,
+    pub published: bool,
+    pub display_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ProjectRecordRaw> for ProjectRecord {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: ProjectRecordRaw) -> Result<Self, Self::Error> {
+        Ok(Self {
+            project_id: raw.project_id,
+            title: raw.title,
+            slug: raw.slug,
+            description: raw.description,
+            content: serde_json::from_value(raw.content)?,
+            tech_tags: raw.tech_tags,
+            repo_url: raw.repo_url,
+            demo_url: raw.demo_url,
+            images: serde_json::from_value(raw.images)?,
+            published: raw.published,
+            display_order: raw.display_order,
+            created_at: raw.created_at,
+            updated_at: raw.updated_at,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct ProjectId(pub Uuid);
+
+impl std::fmt::Display for ProjectId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for ProjectId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct ProjectResponse {
+    pub message: &'static str,
+    pub project_id: ProjectId,
+}
+
+impl ProjectResponse {
+    pub const fn new(message: &'static str, project_id: ProjectId) -> Self {
+        Self {
+            message,
+            project_id,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ProjectForm {
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub content: Vec<ArticleSection>,
+    #[serde(default)]
+    pub tech_tags: Vec<String>,
+    pub repo_url: Option<String>,
+    pub demo_url: Option<String>,
+    #[serde(default)]
+    pub images: Vec<CarouselImage>,
+    #[serde(default)]
+    pub published: bool,
+}
+
+impl ProjectForm {
+    pub fn validate(&self) -> Result<(), ProjectError> {
+        validate_title(&self.title)?;
+        validate_description(&self.description)?;
+        validate_content(&self.content)?;
+        validate_tech_tags(&self.tech_tags)?;
+        validate_link("repo_url", self.repo_url.as_deref())?;
+        validate_link("demo_url", self.demo_url.as_deref())?;
+        validate_images(&self.images)
+    }
+}
+
+// every project's id, in the order the grid should show them
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ProjectOrderRequest {
+    pub project_ids: Vec<Uuid>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ProjectDeleteRequest {
+    pub project_id: Uuid,
+}
+
+// only the fields that are present change, an empty repo_url or demo_url removes the link
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ProjectEditRequest {
+    pub project_id: Uuid,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content: Option<Vec<ArticleSection>>,
+    pub tech_tags: Option<Vec<String>>,
+    pub repo_url: Option<String>,
+    pub demo_url: Option<String>,
+    pub images: Option<Vec<CarouselImage>>,
+    pub published: Option<bool>,
+}
+
+impl ProjectEditRequest {
+    pub fn validate(&self) -> Result<(), ProjectError> {
+        if let Some(title) = &self.title {
+            validate_title(title)?;
+        }
+        if let Some(description) = &self.description {
+            validate_description(description)?;
+        }
+        if let Some(content) = &self.content {
+            validate_content(content)?;
+        }
+        if let Some(tech_tags) = &self.tech_tags {
+            validate_tech_tags(tech_tags)?;
+        }
+        validate_link(
+            "repo_url",
+            self.repo_url.as_deref().filter(|url| !url.is_empty()),
+        )?;
+        validate_link(
+            "demo_url",
+            self.demo_url.as_deref().filter(|url| !url.is_empty()),
+        )?;
+        if let Some(images) = &self.images {
+            validate_images(images)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_title(title: &str) -> Result<(), ProjectError> {
+    if title.trim().is_empty() || title.len() > 200 {
+        return Err(ProjectError::ValidationError("Invalid title".into()));
+    }
+    Ok(())
+}
+
+fn validate_description(description: &str) -> Result<(), ProjectError> {
+    if description.len() > 2000 {
+        return Err(ProjectError::ValidationError("Invalid description".into()));
+    }
+    Ok(())
+}
+
+fn validate_content(content: &[ArticleSection]) -> Result<(), ProjectError> {
+    if content.len() > MAX_SECTIONS {
+        return Err(ProjectError::ValidationError(
+            "Invalid section count".into(),
+        ));
+    }
+    for section in content {
+        section.validate().map_err(|e| match e {
+            BlogError::ValidationError(reason) => ProjectError::ValidationError(reason),
+            other => ProjectError::UnexpectedError(other.into()),
+        })?;
+    }
+    Ok(())
+}
+
+fn validate_tech_tags(tech_tags: &[String]) -> Result<(), ProjectError> {
+    if tech_tags.len() > MAX_TECH_TAGS
+        || tech_tags
+            .iter()
+            .any(|tag| tag.trim().is_empty() || tag.len() > 50)
+    {
+        return Err(ProjectError::ValidationError("Invalid tech tags".into()));
+    }
+    Ok(())
+}
+
+fn validate_link(name: &str, url: Option<&str>) -> Result<(), ProjectError> {
+    let Some(url) = url else {
+        return Ok(());
+    };
+    if url.len() > 2048 || !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(ProjectError::ValidationError(format!("Invalid {name}")));
+    }
+    Ok(())
+}
+
+fn validate_images(images: &[CarouselImage]) -> Result<(), ProjectError> {
+    if images.len() > MAX_IMAGES || images.iter().any(|image| image.src.is_empty()) {
+        return Err(ProjectError::ValidationError("Invalid images".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn project() -> ProjectForm {
+        serde_json::from_value(serde_json::json!({
+            "title": "Portfolio server",
+            "description": "The api behind this site",
+            "tech_tags": ["rust", "actix-web"],
+            "repo_url": "https://github.com/calvin-devogel/portfolio-server",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn minimal_projects_are_valid() {
+        assert!(project().validate().is_ok());
+    }
+
+    #[test]
+    fn links_must_be_http() {
+        let mut form = project();
+        form.demo_url = Some("javascript:alert(1)".to_string());
+
+        assert!(form.validate().is_err());
+    }
+
+    #[test]
+    fn empty_links_clear_on_edit() {
+        let edit: ProjectEditRequest = serde_json::from_value(serde_json::json!({
+            "project_id": Uuid::new_v4(),
+            "repo_url": "",
+        }))
+        .unwrap();
+
+        assert!(edit.validate().is_ok());
+    }
+
+    #[test]
+    fn blank_tags_are_rejected() {
+        let mut form = project();
+        form.tech_tags.push("  ".to_string());
+
+        assert!(form.validate().is_err());
+    }
+}
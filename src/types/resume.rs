@@ -0,0 +1,309 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::errors::ResumeError;
+use crate::pdf::{Font, PdfDocument};
+use crate::types::experience::ExperienceRecord;
+
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct EducationEntry {
+    pub institution: String,
+    pub credential: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+}
+
+// skills come from the skills table, grouped the way the skills endpoint orders them
+#[derive(serde::Serialize)]
+pub struct SkillGroup {
+    pub category: String,
+    pub skills: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Resume {
+    pub name: String,
+    pub headline: String,
+    pub summary: String,
+    pub experience: Vec<ExperienceRecord>,
+    pub education: Vec<EducationEntry>,
+    pub skills: Vec<SkillGroup>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct ResumeRecordRaw {
+    pub name: String,
+    pub headline: String,
+    pub summary: String,
+    pub education: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct ResumeSkillRaw {
+    pub name: String,
+    pub category: String,
+}
+
+impl Resume {
+    // experience comes already ordered, education is put in the same order,
+    // newest first with a current course ahead of anything that has ended
+    /// # Errors
+    /// returns a `serde_json` error if the stored entries don't match their types
+    pub fn from_parts(
+        raw: ResumeRecordRaw,
+        experience: Vec<ExperienceRecord>,
+        skills: Vec<ResumeSkillRaw>,
+    ) -> Result<Self, serde_json::Error> {
+        let mut education: Vec<EducationEntry> = serde_json::from_value(raw.education)?;
+        education.sort_by(|a, b| {
+            (b.end_date.is_none(), b.start_date).cmp(&(a.end_date.is_none(), a.start_date))
+        });
+
+        let mut groups: Vec<SkillGroup> = Vec::new();
+        for skill in skills {
+            match groups.last_mut() {
+                Some(group) if group.category == skill.category => group.skills.push(skill.name),
+                _ => groups.push(SkillGroup {
+                    category: skill.category,
+                    skills: vec![skill.name],
+                }),
+            }
+        }
+
+        Ok(Self {
+            name: raw.name,
+            headline: raw.headline,
+            summary: raw.summary,
+            experience,
+            education,
+            skills: groups,
+            updated_at: raw.updated_at,
+        })
+    }
+
+    // laid out from the same struct the json endpoint serves, so the two can't disagree
+    #[must_use]
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut pdf = PdfDocument::new(&format!("{} - Resume", self.name));
+        pdf.text(&self.name, Font::Bold, 22.0);
+        pdf.text(&self.headline, Font::Regular, 12.0);
+        if !self.summary.is_empty() {
+            pdf.space(8.0);
+            pdf.text(&self.summary, Font::Regular, 10.0);
+        }
+
+        if !self.experience.is_empty() {
+            section(&mut pdf, "Experience");
+            for entry in &self.experience {
+                pdf.row(
+                    &format!("{}, {}", entry.role, entry.company),
+                    &date_range(entry.start_date, entry.end_date),
+                    Font::Bold,
+                    11.0,
+                );
+                for highlight in &entry.highlights {
+                    pdf.bullet(highlight, 10.0);
+                }
+                if !entry.tech.is_empty() {
+                    pdf.text(&entry.tech.join(", "), Font::Oblique, 9.0);
+                }
+                pdf.space(6.0);
+            }
+        }
+
+        if !self.education.is_empty() {
+            section(&mut pdf, "Education");
+            for entry in &self.education {
+                pdf.row(
+                    &entry.credential,
+                    &date_range(entry.start_date, entry.end_date),
+                    Font::Bold,
+                    11.0,
+                );
+                pdf.text(&entry.institution, Font::Oblique, 10.0);
+                pdf.space(6.0);
+            }
+        }
+
+        if !self.skills.is_empty() {
+            section(&mut pdf, "Skills");
+            for group in &self.skills {
+                pdf.text(
+                    &format!("{}: {}", group.category, group.skills.join(", ")),
+                    Font::Regular,
+                    10.0,
+                );
+            }
+        }
+
+        pdf.finish()
+    }
+}
+
+fn section(pdf: &mut PdfDocument, heading: &str) {
+    pdf.space(12.0);
+    pdf.text(heading, Font::Bold, 13.0);
+    pdf.rule();
+    pdf.space(4.0);
+}
+
+fn date_range(start: NaiveDate, end: Option<NaiveDate>) -> String {
+    let end = end.map_or_else(
+        || "Present".to_string(),
+        |end| end.format("%b %Y").to_string(),
+    );
+    format!("{} – {end}", start.format("%b %Y"))
+}
+
+// replaces the whole resume, experience and skills are edited through their own endpoints
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ResumeForm {
+    pub name: String,
+    pub headline: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub education: Vec<EducationEntry>,
+}
+
+impl ResumeForm {
+    pub fn validate(&self) -> Result<(), ResumeError> {
+        validate_text("name", &self.name, 200)?;
+        validate_text("headline", &self.headline, 200)?;
+        if self.summary.len() > 5000 {
+            return Err(ResumeError::ValidationError("Invalid summary".into()));
+        }
+        if self.education.len() > MAX_ENTRIES {
+            return Err(ResumeError::ValidationError("Too many entries".into()));
+        }
+        for entry in &self.education {
+            validate_text("institution", &entry.institution, 200)?;
+            validate_text("credential", &entry.credential, 200)?;
+            validate_dates(entry.start_date, entry.end_date)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_text(name: &str, value: &str, max_len: usize) -> Result<(), ResumeError> {
+    if value.trim().is_empty() || value.len() > max_len {
+        return Err(ResumeError::ValidationError(format!("Invalid {name}")));
+    }
+    Ok(())
+}
+
+fn validate_dates(start: NaiveDate, end: Option<NaiveDate>) -> Result<(), ResumeError> {
+    if end.is_some_and(|end| end < start) {
+        return Err(ResumeError::ValidationError(
+            "An entry can't end before it starts".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use uuid::Uuid;
+
+    fn raw(education: serde_json::Value) -> ResumeRecordRaw {
+        ResumeRecordRaw {
+            name: "Calvin DeVogel".to_string(),
+            headline: "Software engineer".to_string(),
+            summary: String::new(),
+            education,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn experience(company: &str, role: &str, highlights: &[&str]) -> ExperienceRecord {
+        ExperienceRecord {
+            experience_id: Uuid::new_v4(),
+            company: company.to_string(),
+            role: role.to_string(),
+            start_date: NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+            end_date: None,
+            highlights: highlights.iter().map(ToString::to_string).collect(),
+            tech: vec!["Rust".to_string()],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn skill(name: &str, category: &str) -> ResumeSkillRaw {
+        ResumeSkillRaw {
+            name: name.to_string(),
+            category: category.to_string(),
+        }
+    }
+
+    #[test]
+    fn current_courses_come_first() {
+        let resume = Resume::from_parts(
+            raw(serde_json::json!([
+                {"institution": "Old", "credential": "Certificate", "start_date": "2012-09-01", "end_date": "2013-06-01"},
+                {"institution": "Now", "credential": "MSc", "start_date": "2024-09-01"},
+                {"institution": "Last", "credential": "BSc", "start_date": "2015-09-01", "end_date": "2019-06-01"},
+            ])),
+            Vec::new(),
+            Vec::new(),
+        )
+        .unwrap();
+
+        let institutions: Vec<_> = resume
+            .education
+            .iter()
+            .map(|e| e.institution.as_str())
+            .collect();
+        assert_eq!(institutions, ["Now", "Last", "Old"]);
+    }
+
+    #[test]
+    fn skills_are_grouped_by_category() {
+        let resume = Resume::from_parts(
+            raw(serde_json::json!([])),
+            Vec::new(),
+            vec![
+                skill("Rust", "Languages"),
+                skill("Go", "Languages"),
+                skill("Postgres", "Databases"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(resume.skills.len(), 2);
+        assert_eq!(resume.skills[0].skills, ["Rust", "Go"]);
+    }
+
+    #[test]
+    fn entries_cannot_end_before_they_start() {
+        let form: ResumeForm = serde_json::from_value(serde_json::json!({
+            "name": "Calvin DeVogel",
+            "headline": "Software engineer",
+            "education": [
+                {"institution": "State", "credential": "BSc", "start_date": "2019-01-01", "end_date": "2018-01-01"}
+            ],
+        }))
+        .unwrap();
+
+        assert!(form.validate().is_err());
+    }
+
+    #[test]
+    fn the_pdf_carries_the_resume() {
+        let resume = Resume::from_parts(
+            raw(serde_json::json!([])),
+            vec![experience("Acme", "Engineer", &["Shipped it"])],
+            vec![skill("Rust", "Languages")],
+        )
+        .unwrap();
+
+        let pdf = String::from_utf8_lossy(&resume.to_pdf()).into_owned();
+
+        assert!(pdf.starts_with("%PDF-"));
+        assert!(pdf.contains("(Engineer, Acme) Tj"));
+        assert!(pdf.contains("(Shipped it) Tj"));
+        assert!(pdf.contains("(Languages: Rust) Tj"));
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::errors::SearchError;
+
+const MAX_QUERY_LEN: usize = 200;
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 50;
+
+// ts_headline wraps matches in these, they're swapped for `<mark>` once the snippet is escaped
+pub const MATCH_START: char = '\u{2}';
+pub const MATCH_STOP: char = '\u{3}';
+
+#[derive(serde::Deserialize, Debug)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+impl SearchQuery {
+    /// # Errors
+    /// returns `InvalidQuery` for a blank or overlong query
+    pub fn terms(&self) -> Result<&str, SearchError> {
+        let terms = self.q.trim();
+        if terms.is_empty() {
+            return Err(SearchError::InvalidQuery("Query is empty".into()));
+        }
+        if terms.len() > MAX_QUERY_LEN {
+            return Err(SearchError::InvalidQuery("Query is too long".into()));
+        }
+        Ok(terms)
+    }
+
+    #[must_use]
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Post,
+    Project,
+    // only searched from the admin scope
+    Message,
+}
+
+impl SearchKind {
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "post" => Some(Self::Post),
+            "project" => Some(Self::Project),
+            "message" => Some(Self::Message),
+            _ => None,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub id: Uuid,
+    // a post or project's title, a message's sender
+    pub title: String,
+    // `None` for messages, which have no public page
+    pub slug: Option<String>,
+    // html-escaped, matched terms wrapped in `<mark>`
+    pub snippet: String,
+    pub rank: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct SearchResultRaw {
+    pub kind: String,
+    pub id: Uuid,
+    pub title: String,
+    pub slug: Option<String>,
+    pub snippet: String,
+    pub rank: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<SearchResultRaw> for SearchResult {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: SearchResultRaw) -> Result<Self, Self::Error> {
+        Ok(Self {
+            kind: SearchKind::parse(&raw.kind)
+                .ok_or_else(|| anyhow::anyhow!("Unknown search result kind: {}", raw.kind))?,
+            id: raw.id,
+            title: raw.title,
+            slug: raw.slug,
+            snippet: highlight(&raw.snippet),
+            rank: raw.rank,
+            created_at: raw.created_at,
+        })
+    }
+}
+
+// the snippet is user content, so it's escaped before the markers become tags
+fn highlight(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len());
+    for c in snippet.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            MATCH_START => html.push_str("<mark>"),
+            MATCH_STOP => html.push_str("</mark>"),
+            _ => html.push(c),
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snippets_are_escaped_around_the_marks() {
+        let snippet = format!("<script> and {MATCH_START}rust{MATCH_STOP} & more");
+
+        assert_eq!(
+            highlight(&snippet),
+            "&lt;script&gt; and <mark>rust</mark> &amp; more"
+        );
+    }
+
+    #[test]
+    fn blank_queries_are_rejected() {
+        let query = SearchQuery {
+            q: "   ".to_string(),
+            limit: None,
+        };
+
+        assert!(query.terms().is_err());
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            limit: Some(1000),
+        };
+
+        assert_eq!(query.limit(), MAX_LIMIT);
+    }
+}
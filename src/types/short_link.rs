@@ -0,0 +1,227 @@
+use chrono::{DateTime, Utc};
+use rand::{RngExt, distr::Alphanumeric};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::ShortLinkError;
+
+const GENERATED_CODE_LENGTH: usize = 7;
+const MAX_CODE_LENGTH: usize = 64;
+const MAX_URL_LENGTH: usize = 2048;
+const MAX_UTM_LENGTH: usize = 200;
+
+#[derive(serde::Serialize)]
+pub struct ShortLinkRecord {
+    pub link_id: Uuid,
+    pub code: String,
+    pub target_url: String,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub click_count: i64,
+    pub last_clicked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct ShortLinkId(pub Uuid);
+
+impl std::fmt::Display for ShortLinkId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for ShortLinkId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// the code is echoed back since it may have been generated
+#[derive(serde::Serialize)]
+pub struct ShortLinkResponse {
+    pub message: &'static str,
+    pub link_id: ShortLinkId,
+    pub code: String,
+}
+
+impl ShortLinkResponse {
+    pub const fn new(message: &'static str, link_id: ShortLinkId, code: String) -> Self {
+        Self {
+            message,
+            link_id,
+            code,
+        }
+    }
+}
+
+// without a code one is generated, the utm tags are attached to every click's page visit
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ShortLinkForm {
+    pub code: Option<String>,
+    pub target_url: String,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+}
+
+impl ShortLinkForm {
+    pub fn validate(&self) -> Result<(), ShortLinkError> {
+        if let Some(code) = &self.code {
+            validate_code(code)?;
+        }
+        validate_target_url(&self.target_url)?;
+        validate_utm(&self.utm_source)?;
+        validate_utm(&self.utm_medium)?;
+        validate_utm(&self.utm_campaign)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ShortLinkDeleteRequest {
+    pub link_id: Uuid,
+}
+
+// only the fields that are present change, an empty utm tag clears it
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ShortLinkEditRequest {
+    pub link_id: Uuid,
+    pub code: Option<String>,
+    pub target_url: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+}
+
+impl ShortLinkEditRequest {
+    pub fn validate(&self) -> Result<(), ShortLinkError> {
+        if let Some(code) = &self.code {
+            validate_code(code)?;
+        }
+        if let Some(target_url) = &self.target_url {
+            validate_target_url(target_url)?;
+        }
+        validate_utm(&self.utm_source)?;
+        validate_utm(&self.utm_medium)?;
+        validate_utm(&self.utm_campaign)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.code.is_none()
+            && self.target_url.is_none()
+            && self.utm_source.is_none()
+            && self.utm_medium.is_none()
+            && self.utm_campaign.is_none()
+    }
+}
+
+// short enough to read out loud, collisions come back as a 409 to retry
+#[must_use]
+pub fn generate_code() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(GENERATED_CODE_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+// stored the way page_visits stores them, so clicks group with the rest of a campaign
+#[must_use]
+pub fn normalize_utm(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+fn validate_code(code: &str) -> Result<(), ShortLinkError> {
+    if code.is_empty()
+        || code.len() > MAX_CODE_LENGTH
+        || !code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ShortLinkError::ValidationError("Invalid code".into()));
+    }
+    Ok(())
+}
+
+// only absolute http(s) urls, anything else could turn the redirect into a script
+// whitespace is refused too, the url ends up in a Location header
+fn validate_target_url(target_url: &str) -> Result<(), ShortLinkError> {
+    let scheme_ok = target_url
+        .strip_prefix("https://")
+        .or_else(|| target_url.strip_prefix("http://"))
+        .is_some_and(|rest| !rest.is_empty() && !rest.starts_with('/'));
+    if !scheme_ok
+        || target_url.len() > MAX_URL_LENGTH
+        || target_url
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(ShortLinkError::ValidationError("Invalid target url".into()));
+    }
+    Ok(())
+}
+
+fn validate_utm(value: &Option<String>) -> Result<(), ShortLinkError> {
+    if let Some(value) = value
+        && (value.len() > MAX_UTM_LENGTH || value.chars().any(char::is_control))
+    {
+        return Err(ShortLinkError::ValidationError("Invalid utm tag".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn form(code: Option<&str>, target_url: &str) -> ShortLinkForm {
+        ShortLinkForm {
+            code: code.map(ToString::to_string),
+            target_url: target_url.to_string(),
+            utm_source: None,
+            utm_medium: None,
+            utm_campaign: None,
+        }
+    }
+
+    #[test]
+    fn only_web_urls_are_targets() {
+        assert!(form(None, "https://example.com/talk").validate().is_ok());
+        assert!(form(None, "javascript:alert(1)").validate().is_err());
+        assert!(form(None, "/blog/some-post").validate().is_err());
+    }
+
+    #[test]
+    fn codes_are_url_safe() {
+        assert!(
+            form(Some("rustconf-24"), "https://example.com")
+                .validate()
+                .is_ok()
+        );
+        assert!(form(Some("a/b"), "https://example.com").validate().is_err());
+        assert!(form(Some(""), "https://example.com").validate().is_err());
+    }
+
+    #[test]
+    fn generated_codes_pass_validation() {
+        let code = generate_code();
+
+        assert_eq!(code.len(), GENERATED_CODE_LENGTH);
+        assert!(validate_code(&code).is_ok());
+    }
+
+    #[test]
+    fn blank_utm_tags_are_dropped() {
+        assert_eq!(normalize_utm(Some("  ".to_string())), None);
+        assert_eq!(
+            normalize_utm(Some(" Mastodon ".to_string())).as_deref(),
+            Some("mastodon")
+        );
+    }
+}
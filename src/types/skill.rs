@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::SkillError;
+
+// more links than any one skill plausibly has, keeps a single request bounded
+const MAX_LINKED_PROJECTS: usize = 100;
+
+#[derive(serde::Serialize)]
+pub struct SkillRecord {
+    pub skill_id: Uuid,
+    pub name: String,
+    pub category: String,
+    pub proficiency: i16,
+    pub years: i16,
+    // published projects using the skill, in grid order
+    pub project_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct SkillId(pub Uuid);
+
+impl std::fmt::Display for SkillId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for SkillId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SkillResponse {
+    pub message: &'static str,
+    pub skill_id: SkillId,
+}
+
+impl SkillResponse {
+    pub const fn new(message: &'static str, skill_id: SkillId) -> Self {
+        Self { message, skill_id }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SkillForm {
+    pub name: String,
+    pub category: String,
+    pub proficiency: i16,
+    #[serde(default)]
+    pub years: i16,
+    #[serde(default)]
+    pub project_ids: Vec<Uuid>,
+}
+
+impl SkillForm {
+    pub fn validate(&self) -> Result<(), SkillError> {
+        validate_name(&self.name)?;
+        validate_category(&self.category)?;
+        validate_proficiency(self.proficiency)?;
+        validate_years(self.years)?;
+        validate_project_ids(&self.project_ids)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SkillDeleteRequest {
+    pub skill_id: Uuid,
+}
+
+// only the fields that are present change, `project_ids` replaces every link
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SkillEditRequest {
+    pub skill_id: Uuid,
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub proficiency: Option<i16>,
+    pub years: Option<i16>,
+    pub project_ids: Option<Vec<Uuid>>,
+}
+
+impl SkillEditRequest {
+    pub fn validate(&self) -> Result<(), SkillError> {
+        if let Some(name) = &self.name {
+            validate_name(name)?;
+        }
+        if let Some(category) = &self.category {
+            validate_category(category)?;
+        }
+        if let Some(proficiency) = self.proficiency {
+            validate_proficiency(proficiency)?;
+        }
+        if let Some(years) = self.years {
+            validate_years(years)?;
+        }
+        if let Some(project_ids) = &self.project_ids {
+            validate_project_ids(project_ids)?;
+        }
+        Ok(())
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.category.is_none()
+            && self.proficiency.is_none()
+            && self.years.is_none()
+            && self.project_ids.is_none()
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), SkillError> {
+    if name.trim().is_empty() || name.len() > 100 {
+        return Err(SkillError::ValidationError("Invalid name".into()));
+    }
+    Ok(())
+}
+
+fn validate_category(category: &str) -> Result<(), SkillError> {
+    if category.trim().is_empty() || category.len() > 100 {
+        return Err(SkillError::ValidationError("Invalid category".into()));
+    }
+    Ok(())
+}
+
+fn validate_proficiency(proficiency: i16) -> Result<(), SkillError> {
+    if !(1..=5).contains(&proficiency) {
+        return Err(SkillError::ValidationError(
+            "Proficiency must be between 1 and 5".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_years(years: i16) -> Result<(), SkillError> {
+    if !(0..=80).contains(&years) {
+        return Err(SkillError::ValidationError(
+            "Years must be between 0 and 80".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_project_ids(project_ids: &[Uuid]) -> Result<(), SkillError> {
+    if project_ids.len() > MAX_LINKED_PROJECTS {
+        return Err(SkillError::ValidationError(
+            "Too many linked projects".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn skill() -> SkillForm {
+        serde_json::from_value(serde_json::json!({
+            "name": "Rust",
+            "category": "Languages",
+            "proficiency": 4,
+            "years": 3,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn proficiency_is_one_to_five() {
+        let mut form = skill();
+        assert!(form.validate().is_ok());
+
+        form.proficiency = 0;
+        assert!(form.validate().is_err());
+        form.proficiency = 6;
+        assert!(form.validate().is_err());
+    }
+
+    #[test]
+    fn negative_years_are_rejected() {
+        let mut form = skill();
+        form.years = -1;
+
+        assert!(form.validate().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use email_address::EmailAddress;
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -68,4 +69,6 @@ pub struct User {
     pub username: String,
     pub role: String,
     pub must_change_password: bool,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub last_login_ip: Option<String>,
 }
@@ -0,0 +1,199 @@
+use chrono::{DateTime, Utc};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use crate::errors::UsesError;
+use crate::types::experience::present;
+
+#[derive(serde::Serialize)]
+pub struct UsesItemRecord {
+    pub item_id: Uuid,
+    pub category: String,
+    pub name: String,
+    pub description: String,
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize)]
+pub struct UsesGroup {
+    pub category: String,
+    pub items: Vec<UsesItemRecord>,
+}
+
+impl UsesGroup {
+    // items have to come sorted by category, each run of one category becomes a group
+    #[must_use]
+    pub fn group(items: Vec<UsesItemRecord>) -> Vec<Self> {
+        let mut groups: Vec<Self> = Vec::new();
+        for item in items {
+            match groups.last_mut() {
+                Some(group) if group.category == item.category => group.items.push(item),
+                _ => groups.push(Self {
+                    category: item.category.clone(),
+                    items: vec![item],
+                }),
+            }
+        }
+        groups
+    }
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct UsesItemId(pub Uuid);
+
+impl std::fmt::Display for UsesItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for UsesItemId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct UsesItemResponse {
+    pub message: &'static str,
+    pub item_id: UsesItemId,
+}
+
+impl UsesItemResponse {
+    pub const fn new(message: &'static str, item_id: UsesItemId) -> Self {
+        Self { message, item_id }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct UsesItemForm {
+    pub category: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub url: Option<String>,
+}
+
+impl UsesItemForm {
+    pub fn validate(&self) -> Result<(), UsesError> {
+        validate_text("category", &self.category)?;
+        validate_text("name", &self.name)?;
+        validate_description(&self.description)?;
+        validate_url(self.url.as_deref())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct UsesItemDeleteRequest {
+    pub item_id: Uuid,
+}
+
+// only the fields that are present change, `"url": null` removes the link
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct UsesItemEditRequest {
+    pub item_id: Uuid,
+    pub category: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub url: Option<Option<String>>,
+}
+
+impl UsesItemEditRequest {
+    pub fn validate(&self) -> Result<(), UsesError> {
+        if let Some(category) = &self.category {
+            validate_text("category", category)?;
+        }
+        if let Some(name) = &self.name {
+            validate_text("name", name)?;
+        }
+        if let Some(description) = &self.description {
+            validate_description(description)?;
+        }
+        if let Some(url) = &self.url {
+            validate_url(url.as_deref())?;
+        }
+        Ok(())
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.category.is_none()
+            && self.name.is_none()
+            && self.description.is_none()
+            && self.url.is_none()
+    }
+}
+
+fn validate_text(name: &str, value: &str) -> Result<(), UsesError> {
+    if value.trim().is_empty() || value.len() > 100 {
+        return Err(UsesError::ValidationError(format!("Invalid {name}")));
+    }
+    Ok(())
+}
+
+fn validate_description(description: &str) -> Result<(), UsesError> {
+    if description.len() > 1000 {
+        return Err(UsesError::ValidationError("Invalid description".into()));
+    }
+    Ok(())
+}
+
+fn validate_url(url: Option<&str>) -> Result<(), UsesError> {
+    let Some(url) = url else {
+        return Ok(());
+    };
+    if url.len() > 2048 || !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(UsesError::ValidationError("Invalid url".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn item(category: &str, name: &str) -> UsesItemRecord {
+        UsesItemRecord {
+            item_id: Uuid::new_v4(),
+            category: category.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            url: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn items_are_grouped_by_category() {
+        let groups = UsesGroup::group(vec![
+            item("Hardware", "Framework 13"),
+            item("Hardware", "Keychron Q1"),
+            item("Software", "Helix"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].items.len(), 2);
+        assert_eq!(groups[1].category, "Software");
+    }
+
+    #[test]
+    fn a_null_url_is_an_edit() {
+        let edit: UsesItemEditRequest = serde_json::from_value(serde_json::json!({
+            "item_id": Uuid::new_v4(),
+            "url": null,
+        }))
+        .unwrap();
+
+        assert_eq!(edit.url, Some(None));
+        assert!(!edit.is_empty());
+    }
+}
@@ -1,11 +1,20 @@
-use actix_web::{HttpResponse, http::header::LOCATION};
+use actix_web::{
+    HttpResponse, ResponseError,
+    error::InternalError,
+    http::{StatusCode, header::LOCATION},
+};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::errors::ApiProblem;
 
 // http 400 aka client-side error
 pub fn e400<T>(e: T) -> actix_web::Error
 where
     T: std::fmt::Debug + std::fmt::Display + 'static,
 {
-    actix_web::error::ErrorBadRequest(e)
+    let problem = ApiProblem::new(StatusCode::BAD_REQUEST).with_detail(e.to_string());
+    InternalError::from_response(e, problem.error_response()).into()
 }
 
 // http 500 aka server-side error
@@ -13,7 +22,55 @@ pub fn e500<T>(e: T) -> actix_web::Error
 where
     T: std::fmt::Debug + std::fmt::Display + 'static,
 {
-    actix_web::error::ErrorInternalServerError(e)
+    let problem = ApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR);
+    InternalError::from_response(e, problem.error_response()).into()
+}
+
+// http 504, the database didn't answer in time
+pub fn e504<T>(e: T) -> actix_web::Error
+where
+    T: std::fmt::Debug + std::fmt::Display + 'static,
+{
+    let problem = ApiProblem::new(StatusCode::GATEWAY_TIMEOUT);
+    InternalError::from_response(e, problem.error_response()).into()
+}
+
+// how long a handler waits on its queries, from `database.query_timeout_ms`
+#[derive(Clone, Copy, Debug)]
+pub struct QueryTimeout(pub Duration);
+
+// a handler's queries get `timeout` between them, after that the handler answers 504
+// instead of holding the worker; postgres cancelling one on statement_timeout is a 504 too
+/// # Errors
+/// returns a 504 when the queries time out and a 500 when they fail otherwise
+pub async fn with_query_timeout<T>(
+    timeout: QueryTimeout,
+    queries: impl Future<Output = Result<T, sqlx::Error>>,
+) -> Result<T, actix_web::Error> {
+    match tokio::time::timeout(timeout.0, queries).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) if is_statement_timeout(&e) => Err(e504(e)),
+        Ok(Err(e)) => Err(e500(e)),
+        Err(elapsed) => Err(e504(elapsed)),
+    }
+}
+
+// 57014 is query_canceled, what postgres raises once statement_timeout passes
+fn is_statement_timeout(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|e| e.code())
+        .is_some_and(|code| code == "57014")
+}
+
+// url-safe version of a title, shared by blog posts and projects
+#[must_use]
+pub fn slugify(title: &str) -> String {
+    title
+        .replace(' ', "-")
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic() || *c == '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
 }
 
 // redirect (don't think I need this on the server side, probably have to send a signal?)
@@ -26,7 +83,7 @@ pub fn see_other(location: &str) -> HttpResponse {
 
 #[must_use]
 pub fn unauthorized() -> HttpResponse {
-    HttpResponse::Unauthorized().finish()
+    ApiProblem::new(StatusCode::UNAUTHORIZED).error_response()
 }
 
 // format the error chain
@@ -47,7 +104,6 @@ pub fn error_chain_fmt(
 #[cfg(test)]
 mod test {
     use super::*;
-    use actix_web::http::StatusCode;
     use std::fmt;
 
     #[test]
@@ -68,6 +124,30 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn slow_queries_time_out_with_a_504() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok::<_, sqlx::Error>(())
+        };
+
+        let err = with_query_timeout(QueryTimeout(Duration::from_millis(10)), slow)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn article_slug() {
+        let title = "New Blog Title".to_string();
+        let slug = slugify(&title);
+        assert_eq!(slug, "new-blog-title".to_string())
+    }
+
     #[test]
     fn see_other_returns_303_with_location_header() {
         let response = see_other("/new-location");
@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use crate::configuration::WorkerSettings;
+
+type WorkerFuture = Pin<Box<dyn Future<Output = Result<(), anyhow::Error>> + Send>>;
+type WorkerFactory = Box<dyn Fn() -> WorkerFuture + Send + Sync>;
+
+struct Worker {
+    name: String,
+    // builds a fresh run each time the worker is (re)started
+    start: WorkerFactory,
+}
+
+// long-running jobs (cleanup, schedulers, delivery) that run next to the API
+// a worker that returns, fails or panics is started again after a backoff
+pub struct WorkerRegistry {
+    settings: WorkerSettings,
+    workers: Vec<Worker>,
+}
+
+impl WorkerRegistry {
+    #[must_use]
+    pub const fn new(settings: WorkerSettings) -> Self {
+        Self {
+            settings,
+            workers: Vec::new(),
+        }
+    }
+
+    pub fn register<F, Fut>(&mut self, name: &str, start: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.workers.push(Worker {
+            name: name.to_string(),
+            start: Box::new(move || Box::pin(start())),
+        });
+    }
+
+    // the workers stop when the returned set is dropped
+    #[must_use]
+    pub fn spawn(self) -> JoinSet<()> {
+        let mut tasks = JoinSet::new();
+        for worker in self.workers {
+            tasks.spawn(supervise(worker, self.settings.clone()));
+        }
+        tasks
+    }
+}
+
+async fn supervise(worker: Worker, settings: WorkerSettings) {
+    let initial_backoff = Duration::from_millis(settings.restart_initial_backoff_ms);
+    let max_backoff = Duration::from_secs(settings.restart_max_backoff_secs).max(initial_backoff);
+    let healthy_after = Duration::from_secs(settings.healthy_after_secs);
+    let mut backoff = initial_backoff;
+
+    loop {
+        let started = Instant::now();
+        // its own task, so a panic is reported here instead of taking the supervisor down
+        match tokio::spawn((worker.start)()).await {
+            Ok(Ok(())) => tracing::warn!(worker = %worker.name, "Worker exited"),
+            Ok(Err(e)) => tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                worker = %worker.name,
+                "Worker failed"
+            ),
+            Err(e) => tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                worker = %worker.name,
+                "Worker panicked"
+            ),
+        }
+
+        if started.elapsed() >= healthy_after {
+            backoff = initial_backoff;
+        }
+        tracing::info!(worker = %worker.name, ?backoff, "Restarting worker");
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn failed_workers_are_restarted() {
+        let mut registry = WorkerRegistry::new(WorkerSettings {
+            restart_initial_backoff_ms: 1,
+            restart_max_backoff_secs: 1,
+            healthy_after_secs: 60,
+        });
+        let runs = Arc::new(AtomicUsize::new(0));
+        registry.register("flaky", {
+            let runs = runs.clone();
+            move || {
+                let runs = runs.clone();
+                async move {
+                    match runs.fetch_add(1, Ordering::SeqCst) {
+                        0 => anyhow::bail!("first run fails"),
+                        1 => panic!("second run panics"),
+                        _ => std::future::pending().await,
+                    }
+                }
+            }
+        });
+
+        let _tasks = registry.spawn();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while runs.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("The worker was not restarted");
+    }
+}
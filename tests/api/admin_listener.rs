@@ -0,0 +1,99 @@
+use crate::helpers::{TestApp, spawn_app, spawn_app_with};
+
+async fn spawn_app_with_admin_listener() -> TestApp {
+    spawn_app_with(|c| c.application.admin_port = Some(0)).await
+}
+
+#[tokio::test]
+async fn admin_routes_are_hidden_on_the_public_listener() {
+    // arrange
+    let app = spawn_app_with_admin_listener().await;
+
+    for path in ["/metrics", "/v1/admin/users"] {
+        // act
+        let response = app
+            .api_client
+            .get(format!("{}{path}", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        // assert
+        assert_eq!(response.status().as_u16(), 404, "{path}");
+    }
+}
+
+#[tokio::test]
+async fn percent_encoded_admin_routes_are_hidden_on_the_public_listener() {
+    // arrange
+    let app = spawn_app_with_admin_listener().await;
+
+    for path in ["/%6detrics", "/v1/%61dmin/users"] {
+        // act
+        let response = app
+            .api_client
+            .get(format!("{}{path}", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        // assert
+        assert_eq!(response.status().as_u16(), 404, "{path}");
+    }
+}
+
+#[tokio::test]
+async fn admin_routes_are_served_on_the_admin_listener() {
+    // arrange
+    let app = spawn_app_with_admin_listener().await;
+    let admin_address = app.admin_address.clone().unwrap();
+
+    // act
+    let metrics = app
+        .api_client
+        .get(format!("{admin_address}/metrics"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let users = app
+        .api_client
+        .get(format!("{admin_address}/v1/admin/users"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(metrics.status().as_u16(), 200);
+    // reached the admin scope, which wants a logged in admin
+    assert_eq!(users.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn public_routes_stay_on_the_public_listener() {
+    // arrange
+    let app = spawn_app_with_admin_listener().await;
+
+    // act
+    let response = app.get_home().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn everything_is_served_on_one_listener_by_default() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert!(app.admin_address.is_none());
+    assert_eq!(response.status().as_u16(), 200);
+}
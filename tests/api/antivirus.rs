@@ -0,0 +1,186 @@
+use portfolio_server::antivirus::{Clamd, SCAN_UPLOAD_JOB, scan_upload};
+use portfolio_server::configuration::{AntivirusSettings, JobSettings};
+use portfolio_server::jobs::{ExecutionOutcome, JobHandlers, try_execute_job};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::helpers::{TestApp, spawn_app_with};
+
+// answers INSTREAM like clamd would, anything containing `EICAR` is infected
+async fn spawn_fake_clamd() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut command = [0u8; 10];
+            socket.read_exact(&mut command).await.unwrap();
+            let mut upload = Vec::new();
+            loop {
+                let length = socket.read_u32().await.unwrap();
+                if length == 0 {
+                    break;
+                }
+                let mut chunk = vec![0u8; length as usize];
+                socket.read_exact(&mut chunk).await.unwrap();
+                upload.extend(chunk);
+            }
+            let reply: &[u8] = if upload.windows(5).any(|window| window == b"EICAR") {
+                b"stream: Eicar-Test-Signature FOUND\0"
+            } else {
+                b"stream: OK\0"
+            };
+            socket.write_all(reply).await.unwrap();
+        }
+    });
+    address
+}
+
+async fn spawn_scanning_app() -> (TestApp, AntivirusSettings) {
+    let address = spawn_fake_clamd().await;
+    let app = spawn_app_with(|c| c.storage.antivirus.clamd_address = Some(address.clone())).await;
+    let settings = AntivirusSettings {
+        clamd_address: Some(address),
+        ..AntivirusSettings::default()
+    };
+    (app, settings)
+}
+
+async fn upload(app: &TestApp, key: &str, body: &'static str) {
+    let url = app.storage.presigned_upload_url(key).await.unwrap();
+    let response = app
+        .api_client
+        .put(app.local_storage_url(&url))
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 204);
+}
+
+async fn run_scan(app: &TestApp, settings: &AntivirusSettings) {
+    let clamd = Clamd::from_settings(settings).unwrap();
+    let (storage, pool) = (app.storage.clone(), app.db_pool.clone());
+    let handlers = JobHandlers::default().on(SCAN_UPLOAD_JOB, move |payload| {
+        let (clamd, storage, pool) = (clamd.clone(), storage.clone(), pool.clone());
+        async move { scan_upload(payload, &clamd, &storage, &pool).await }
+    });
+    let outcome = try_execute_job(&app.db_pool, &handlers, &JobSettings::default())
+        .await
+        .unwrap();
+    assert!(matches!(outcome, ExecutionOutcome::TaskCompleted));
+}
+
+#[tokio::test]
+async fn uploads_are_held_back_until_scanned() {
+    // arrange
+    let (app, _) = spawn_scanning_app().await;
+
+    // act
+    upload(&app, "media/notes.txt", "hello there").await;
+    let response = app
+        .api_client
+        .get(format!("{}/media/notes.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn media_that_was_never_scanned_is_not_found() {
+    // arrange
+    let (app, _) = spawn_scanning_app().await;
+    app.storage
+        .put("media/sideloaded.txt", b"hello there".to_vec())
+        .await
+        .unwrap();
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/media/sideloaded.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn keys_that_were_never_uploaded_are_not_found() {
+    // arrange
+    let (app, _) = spawn_scanning_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/media/{}.txt",
+            &app.address,
+            uuid::Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn clean_uploads_are_served_once_scanned() {
+    // arrange
+    let (app, settings) = spawn_scanning_app().await;
+    upload(&app, "media/notes.txt", "hello there").await;
+
+    // act
+    run_scan(&app, &settings).await;
+    let response = app
+        .api_client
+        .get(format!("{}/media/notes.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["x-scan-status"], "clean");
+    assert_eq!(response.text().await.unwrap(), "hello there");
+}
+
+#[tokio::test]
+async fn infected_uploads_are_quarantined() {
+    // arrange
+    let (app, settings) = spawn_scanning_app().await;
+    upload(&app, "media/payload.txt", "X5O!P%@AP EICAR test").await;
+
+    // act
+    run_scan(&app, &settings).await;
+    let response = app
+        .api_client
+        .get(format!("{}/media/payload.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(app.storage.get("media/payload.txt").await.unwrap(), None);
+    assert!(
+        app.storage
+            .get("quarantine/media/payload.txt")
+            .await
+            .unwrap()
+            .is_some()
+    );
+    let signature = sqlx::query_scalar!(
+        r#"SELECT signature FROM media_scans WHERE object_key = 'media/payload.txt'"#
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(signature.as_deref(), Some("Eicar-Test-Signature"));
+}
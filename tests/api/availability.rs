@@ -0,0 +1,85 @@
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn unauthorized_users_cannot_set_availability() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .put_availability(&serde_json::json!({ "status": "unavailable" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn availability_starts_open_to_work() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_availability().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().get("Last-Modified").is_some());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "open_to_work");
+    assert_eq!(body["booked_until"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn the_newest_availability_is_public_and_the_rest_is_history() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .put_availability(&serde_json::json!({
+            "status": "booked",
+            "booked_until": "2027-03-01",
+            "note": "Booked until March, happy to chat about later projects"
+        }))
+        .await;
+    let current: serde_json::Value = app.get_availability().await.json().await.unwrap();
+    let history: serde_json::Value = app.get_availability_history().await.json().await.unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(current["status"], "booked");
+    assert_eq!(current["booked_until"], "2027-03-01");
+    assert_eq!(
+        current["note"],
+        "Booked until March, happy to chat about later projects"
+    );
+    let statuses: Vec<_> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["booked", "open_to_work"]);
+}
+
+#[tokio::test]
+async fn only_a_booking_can_have_an_end_date() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .put_availability(&serde_json::json!({
+            "status": "open_to_work",
+            "booked_until": "2027-03-01"
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+    let current: serde_json::Value = app.get_availability().await.json().await.unwrap();
+    assert_eq!(current["status"], "open_to_work");
+}
@@ -44,7 +44,7 @@ async fn authorized_user_can_delete_articles() {
     let response = app.get_article("false", None).await;
     let blogs_response: ArticleResponse = response.json().await.expect("Failed to parse blogs");
 
-    assert!(blogs_response.data.len() == 0);
+    assert!(blogs_response.data.is_empty());
 }
 
 #[tokio::test]
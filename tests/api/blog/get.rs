@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::helpers::{GetResponse, PublishRequest, spawn_app};
 
 // how to destructure pagination query:
@@ -114,3 +116,136 @@ async fn blogs_can_be_filtered_on_slug() {
     let article = &get_response.data[0];
     assert_eq!(article.excerpt, "unpublished...");
 }
+
+#[tokio::test]
+async fn unchanged_articles_are_not_modified() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let article = serde_json::json!({
+        "title": "Title",
+        "sections": [{"type": "markdown", "content": "fake post content..."}],
+        "excerpt": "fake post...",
+        "author": "Andy Admin"
+    });
+    assert_eq!(app.post_article(&article).await.status().as_u16(), 202);
+
+    let response = app.get_article("false", None).await;
+    let last_modified = response.headers()["last-modified"].clone();
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/blog", &app.address))
+        .header("If-Modified-Since", last_modified)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 304);
+    assert!(response.text().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn deleting_an_article_modifies_the_list() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    for title in ["Kept", "Deleted"] {
+        let article = serde_json::json!({
+            "title": title,
+            "sections": [{"type": "markdown", "content": "fake post content..."}],
+            "excerpt": "fake post...",
+            "author": "Andy Admin"
+        });
+        assert_eq!(app.post_article(&article).await.status().as_u16(), 202);
+    }
+    let response = app.get_article("false", None).await;
+    let last_modified = response.headers()["last-modified"].clone();
+    let articles: GetResponse = response.json().await.unwrap();
+    // Last-Modified only has whole seconds
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let deleted = app
+        .delete_article(&serde_json::json!({ "post_id": articles.data[0].post_id }))
+        .await;
+    assert_eq!(deleted.status().as_u16(), 200);
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/blog", &app.address))
+        .header("If-Modified-Since", last_modified)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn an_older_if_modified_since_gets_the_articles() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let article = serde_json::json!({
+        "title": "Title",
+        "sections": [{"type": "markdown", "content": "fake post content..."}],
+        "excerpt": "fake post...",
+        "author": "Andy Admin"
+    });
+    assert_eq!(app.post_article(&article).await.status().as_u16(), 202);
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/blog", &app.address))
+        .header("If-Modified-Since", "Thu, 01 Jan 1970 00:00:00 GMT")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().contains_key("last-modified"));
+}
+
+#[tokio::test]
+async fn published_pages_are_served_from_memory_until_an_edit() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let article = serde_json::json!({
+        "title": "Title",
+        "sections": [{"type": "markdown", "content": "fake post content..."}],
+        "excerpt": "fake post...",
+        "author": "Andy Admin"
+    });
+    assert_eq!(app.post_article(&article).await.status().as_u16(), 202);
+    let drafts: GetResponse = app.get_article("false", None).await.json().await.unwrap();
+    let post_id = drafts.data[0].post_id;
+    let publish_body = PublishRequest {
+        post_id,
+        published: true,
+    };
+    assert_eq!(
+        app.publish_article(&publish_body).await.status().as_u16(),
+        202
+    );
+    app.get_article("true", None).await;
+
+    // act
+    sqlx::query!("UPDATE blog_posts SET title = 'Changed behind the cache'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to update the post.");
+    let cached: GetResponse = app.get_article("true", None).await.json().await.unwrap();
+    let edit = serde_json::json!({ "post_id": post_id, "excerpt": "edited..." });
+    assert_eq!(app.edit_article(&edit).await.status().as_u16(), 202);
+    let fresh: GetResponse = app.get_article("true", None).await.json().await.unwrap();
+
+    // assert
+    assert_eq!(cached.data[0].title, "Title");
+    assert_eq!(fresh.data[0].title, "Changed behind the cache");
+}
@@ -43,7 +43,7 @@ async fn authorized_user_can_publish_articles() {
     let blog_is_published = blogs_response.data[0].clone();
 
     assert_eq!(blog_is_published.post_id, publish_body.post_id);
-    assert_eq!(blog_is_published.published, true);
+    assert!(blog_is_published.published);
 }
 
 #[tokio::test]
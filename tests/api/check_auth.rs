@@ -1,6 +1,7 @@
 use crate::helpers::spawn_app;
 use portfolio_server::{
     authentication::{Credentials, change_password, validate_credentials_with_verifier},
+    configuration::PasswordHashingSettings,
     errors::AuthError,
 };
 use secrecy::ExposeSecret;
@@ -36,6 +37,40 @@ async fn authorized_users_are_authorized() {
     assert_eq!(response.status().as_u16(), 200);
 }
 
+#[tokio::test]
+async fn check_auth_returns_the_role() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app.check_auth().await;
+
+    // assert
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, "admin");
+}
+
+#[tokio::test]
+async fn previous_login_shows_the_login_before_the_current_one() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let first: serde_json::Value = app.get_previous_login().await.json().await.unwrap();
+    app.post_logout().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app.get_previous_login().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(first["login_at"].is_null());
+    assert!(body["login_at"].is_string());
+    assert_eq!(body["ip_address"], "127.0.0.1");
+}
+
 // we need to use a dummy verify_password_hash to simulate a hash collision
 // or the error state on `user_id.ok_or_else(|| anyhow::anyhow!("Unknown username")))
 // will be practically unreachable
@@ -48,8 +83,13 @@ async fn unknown_users_are_rejected_on_hash_collisions() {
         password: secrecy::SecretString::new("fake_password".into()),
     };
 
-    let result =
-        validate_credentials_with_verifier(fake_credentials, &app.db_pool, |_, _| Ok(())).await;
+    let result = validate_credentials_with_verifier(
+        fake_credentials,
+        &PasswordHashingSettings::default(),
+        &app.db_pool,
+        |_, _| Ok(()),
+    )
+    .await;
 
     assert!(matches!(result, Err(AuthError::InvalidCredentials(_))));
 }
@@ -61,7 +101,13 @@ async fn change_password_works() {
     let user_id = app.test_user.user_id;
     let new_password = secrecy::SecretString::new("new_password".into());
 
-    let _ = change_password(user_id, new_password.clone(), &app.db_pool).await;
+    let _ = change_password(
+        user_id,
+        new_password.clone(),
+        &PasswordHashingSettings::default(),
+        &app.db_pool,
+    )
+    .await;
 
     let login_body = serde_json::json!({
         "username": app.test_user.username,
@@ -0,0 +1,56 @@
+use crate::helpers::{TestApp, spawn_app_with};
+
+async fn preflight(app: &TestApp, path: &str, origin: &str) -> reqwest::Response {
+    app.api_client
+        .request(
+            reqwest::Method::OPTIONS,
+            format!("{}{}", &app.address, path),
+        )
+        .header("Origin", origin)
+        .header("Access-Control-Request-Method", "GET")
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn origins_matching_a_pattern_are_allowed_on_both_scopes() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.cors.allowed_origin_patterns = vec!["https://*.preview.example.com".parse().unwrap()];
+    })
+    .await;
+    let origin = "https://pr-123.preview.example.com";
+
+    for path in ["/v1/blog", "/v1/admin/messages"] {
+        // act
+        let response = preflight(&app, path, origin).await;
+
+        // assert
+        assert_eq!(response.headers()["access-control-allow-origin"], origin);
+    }
+}
+
+#[tokio::test]
+async fn origins_outside_every_pattern_are_not_allowed() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.cors.allowed_origin_patterns = vec!["https://*.preview.example.com".parse().unwrap()];
+    })
+    .await;
+
+    for origin in [
+        "https://a.b.preview.example.com",
+        "https://pr-123.preview.example.com.evil.com",
+    ] {
+        // act
+        let response = preflight(&app, "/v1/blog", origin).await;
+
+        // assert
+        assert!(
+            !response
+                .headers()
+                .contains_key("access-control-allow-origin")
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use futures_util::StreamExt;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn dashboard_events_require_an_admin() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let result = app.connect_dashboard_events(None).await;
+
+    // assert
+    match result {
+        Err(Error::Http(response)) => assert_eq!(response.status().as_u16(), 401),
+        _ => panic!("Anonymous upgrade should have been rejected"),
+    }
+}
+
+#[tokio::test]
+async fn new_contact_messages_are_pushed_to_the_dashboard() {
+    // arrange
+    let app = spawn_app().await;
+    let login_response = app
+        .post_login(&serde_json::json!({
+            "username": &app.test_user.username,
+            "password": &app.test_user.password
+        }))
+        .await;
+    let session_cookie = login_response
+        .cookies()
+        .find(|c| c.name() == "id")
+        .map(|c| c.value().to_string())
+        .expect("Session cookie not set on login");
+    let mut socket = app
+        .connect_dashboard_events(Some(&session_cookie))
+        .await
+        .expect("Failed to open the dashboard socket");
+
+    // act
+    app.post_message(&serde_json::json!({
+        "email": "sender@example.com",
+        "sender_name": "Jane Doe",
+        "message_text": "Hello from the contact form"
+    }))
+    .await;
+
+    // assert
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+        .await
+        .expect("No event within the timeout")
+        .unwrap()
+        .unwrap();
+    let Message::Text(text) = frame else {
+        panic!("Expected a text frame, got {frame:?}");
+    };
+    let event: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(event["type"], "contact_message");
+    assert_eq!(event["sender_name"], "Jane Doe");
+}
+
+#[tokio::test]
+async fn replayed_contact_messages_are_pushed_once() {
+    // arrange
+    let app = spawn_app().await;
+    let login_response = app
+        .post_login(&serde_json::json!({
+            "username": &app.test_user.username,
+            "password": &app.test_user.password
+        }))
+        .await;
+    let session_cookie = login_response
+        .cookies()
+        .find(|c| c.name() == "id")
+        .map(|c| c.value().to_string())
+        .expect("Session cookie not set on login");
+    let mut socket = app
+        .connect_dashboard_events(Some(&session_cookie))
+        .await
+        .expect("Failed to open the dashboard socket");
+    let key = uuid::Uuid::new_v4().to_string();
+    let message = serde_json::json!({
+        "email": "sender@example.com",
+        "sender_name": "Jane Doe",
+        "message_text": "Hello from the contact form"
+    });
+
+    // act
+    for _ in 0..2 {
+        let response = app
+            .api_client
+            .post(format!("{}/v1/contact", &app.address))
+            .header("Idempotency-Key", &key)
+            .header("X-XSRF-TOKEN", &app.xsrf_token)
+            .form(&message)
+            .send()
+            .await
+            .expect("Failed to send message.");
+        assert_eq!(response.status().as_u16(), 202);
+    }
+
+    // assert
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+        .await
+        .expect("No event within the timeout")
+        .unwrap()
+        .unwrap();
+    let Message::Text(text) = frame else {
+        panic!("Expected a text frame, got {frame:?}");
+    };
+    let event: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(event["type"], "contact_message");
+    let replayed = tokio::time::timeout(std::time::Duration::from_secs(1), socket.next()).await;
+    assert!(
+        replayed.is_err(),
+        "The replay was pushed again: {replayed:?}"
+    );
+}
+
+#[tokio::test]
+async fn failed_logins_are_pushed_to_the_dashboard() {
+    // arrange
+    let app = spawn_app().await;
+    let login_response = app
+        .post_login(&serde_json::json!({
+            "username": &app.test_user.username,
+            "password": &app.test_user.password
+        }))
+        .await;
+    let session_cookie = login_response
+        .cookies()
+        .find(|c| c.name() == "id")
+        .map(|c| c.value().to_string())
+        .expect("Session cookie not set on login");
+    let mut socket = app
+        .connect_dashboard_events(Some(&session_cookie))
+        .await
+        .expect("Failed to open the dashboard socket");
+
+    // act
+    app.post_login(&serde_json::json!({
+        "username": "intruder",
+        "password": "wrong-password"
+    }))
+    .await;
+
+    // assert
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+        .await
+        .expect("No event within the timeout")
+        .unwrap()
+        .unwrap();
+    let Message::Text(text) = frame else {
+        panic!("Expected a text frame, got {frame:?}");
+    };
+    let event: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(event["type"], "login_attempt");
+    assert_eq!(event["username"], "intruder");
+    assert_eq!(event["success"], false);
+}
+
+#[tokio::test]
+async fn dashboard_event_stream_requires_an_admin() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/admin/events", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn new_contact_messages_are_streamed_as_server_sent_events() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let mut response = app
+        .api_client
+        .get(format!("{}/v1/admin/events", &app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    // act
+    app.post_message(&serde_json::json!({
+        "email": "sender@example.com",
+        "sender_name": "Jane Doe",
+        "message_text": "Hello from the contact form"
+    }))
+    .await;
+
+    // assert
+    let chunk = tokio::time::timeout(std::time::Duration::from_secs(10), response.chunk())
+        .await
+        .expect("No event within the timeout")
+        .unwrap()
+        .unwrap();
+    let event = String::from_utf8(chunk.to_vec()).unwrap();
+    let json = event
+        .strip_prefix("data: ")
+        .and_then(|data| data.strip_suffix("\n\n"))
+        .expect("Not a server-sent event");
+    let event: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(event["type"], "contact_message");
+    assert_eq!(event["sender_name"], "Jane Doe");
+}
@@ -0,0 +1,164 @@
+use portfolio_server::configuration::{EmailSettings, JobSettings};
+use portfolio_server::email::{Email, EmailClient, queue_email, try_deliver_email};
+use portfolio_server::jobs::ExecutionOutcome;
+use sqlx::PgPool;
+use tokio::sync::mpsc::UnboundedReceiver;
+use uuid::Uuid;
+
+use crate::helpers::spawn_app;
+
+struct OutboxRow {
+    status: String,
+    attempts: i32,
+    last_error: Option<String>,
+    due: Option<bool>,
+}
+
+async fn outbox_row(pool: &PgPool, email_id: Uuid) -> OutboxRow {
+    sqlx::query_as!(
+        OutboxRow,
+        r#"
+        SELECT status, attempts, last_error, next_attempt_at <= NOW() AS due
+        FROM email_outbox
+        WHERE email_id = $1
+        "#,
+        email_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+// stands in for the postmark api, answering with `status` and handing each message to the test
+fn spawn_email_api(status: u16) -> (EmailClient, UnboundedReceiver<serde_json::Value>) {
+    let (sender, emails) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let email_api = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let server = actix_web::HttpServer::new(move || {
+        let sender = sender.clone();
+        actix_web::App::new().route(
+            "/email",
+            actix_web::web::post().to(move |body: actix_web::web::Json<serde_json::Value>| {
+                let _ = sender.send(body.into_inner());
+                async move {
+                    actix_web::HttpResponse::build(
+                        actix_web::http::StatusCode::from_u16(status).unwrap(),
+                    )
+                    .finish()
+                }
+            }),
+        )
+    })
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(server);
+
+    let client = EmailClient::from_settings(&EmailSettings {
+        api_token: Some(secrecy::SecretString::from("postmark-token")),
+        sender: Some("hello@example.com".to_string()),
+        api_base_url: email_api,
+        ..EmailSettings::default()
+    })
+    .unwrap();
+    (client, emails)
+}
+
+fn welcome() -> Email {
+    Email {
+        subject: "Welcome".to_string(),
+        text_body: "Hi there".to_string(),
+        html_body: Some("<p>Hi there</p>".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn queued_emails_are_delivered() {
+    // arrange
+    let app = spawn_app().await;
+    let (client, mut emails) = spawn_email_api(200);
+    let email_id = queue_email(
+        &app.db_pool,
+        &["reader@example.com".to_string()],
+        &welcome(),
+    )
+    .await
+    .unwrap();
+
+    // act
+    let outcome = try_deliver_email(&app.db_pool, &client, &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    assert!(matches!(outcome, ExecutionOutcome::TaskCompleted));
+    let email = emails.try_recv().unwrap();
+    assert_eq!(email["From"], "hello@example.com");
+    assert_eq!(email["To"], "reader@example.com");
+    assert_eq!(email["Subject"], "Welcome");
+    assert_eq!(email["HtmlBody"], "<p>Hi there</p>");
+    let row = outbox_row(&app.db_pool, email_id).await;
+    assert_eq!(row.status, "sent");
+    assert_eq!(row.attempts, 1);
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried_later() {
+    // arrange
+    let app = spawn_app().await;
+    let (client, _emails) = spawn_email_api(500);
+    let email_id = queue_email(
+        &app.db_pool,
+        &["reader@example.com".to_string()],
+        &welcome(),
+    )
+    .await
+    .unwrap();
+
+    // act
+    try_deliver_email(&app.db_pool, &client, &JobSettings::default())
+        .await
+        .unwrap();
+    let next = try_deliver_email(&app.db_pool, &client, &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    let row = outbox_row(&app.db_pool, email_id).await;
+    assert_eq!(row.status, "queued");
+    assert_eq!(row.attempts, 1);
+    assert!(row.last_error.unwrap().contains("500"));
+    assert_eq!(row.due, Some(false));
+    assert!(matches!(next, ExecutionOutcome::EmptyQueue));
+}
+
+#[tokio::test]
+async fn emails_out_of_attempts_are_marked_failed() {
+    // arrange
+    let app = spawn_app().await;
+    let (client, _emails) = spawn_email_api(500);
+    let email_id = queue_email(
+        &app.db_pool,
+        &["reader@example.com".to_string()],
+        &welcome(),
+    )
+    .await
+    .unwrap();
+    sqlx::query!(
+        "UPDATE email_outbox SET max_attempts = 1 WHERE email_id = $1",
+        email_id
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    // act
+    try_deliver_email(&app.db_pool, &client, &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    let row = outbox_row(&app.db_pool, email_id).await;
+    assert_eq!(row.status, "failed");
+    assert_eq!(row.attempts, 1);
+}
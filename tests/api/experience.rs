@@ -0,0 +1,149 @@
+use uuid::Uuid;
+
+use crate::helpers::{TestApp, spawn_app};
+
+fn experience(company: &str, start_date: &str, end_date: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "company": company,
+        "role": "Engineer",
+        "start_date": start_date,
+        "end_date": end_date,
+        "highlights": ["Shipped the thing"],
+        "tech": ["Rust", "Postgres"]
+    })
+}
+
+async fn create_experience(app: &TestApp, body: &serde_json::Value) -> String {
+    let response = app.post_experience(body).await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["experience_id"].as_str().unwrap().to_string()
+}
+
+async fn companies(app: &TestApp) -> Vec<String> {
+    let response = app.get_experience().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["company"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_experience() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .post_experience(&experience("Acme", "2020-01-01", None))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn experience_is_listed_newest_first_with_the_current_job_on_top() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_experience(&app, &experience("First", "2015-01-01", Some("2017-01-01"))).await;
+    create_experience(&app, &experience("Current", "2018-01-01", None)).await;
+    create_experience(
+        &app,
+        &experience("Second", "2019-01-01", Some("2021-01-01")),
+    )
+    .await;
+
+    // act
+    let companies = companies(&app).await;
+
+    // assert
+    assert_eq!(companies, ["Current", "Second", "First"]);
+}
+
+#[tokio::test]
+async fn experience_that_ends_before_it_starts_is_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let experience_id =
+        create_experience(&app, &experience("Acme", "2020-01-01", Some("2021-01-01"))).await;
+
+    // act
+    let created = app
+        .post_experience(&experience("Acme", "2020-01-01", Some("2019-01-01")))
+        .await;
+    // only one date, checked against the stored start
+    let edited = app
+        .edit_experience(&serde_json::json!({
+            "experience_id": experience_id,
+            "end_date": "2019-06-01"
+        }))
+        .await;
+
+    // assert
+    assert_eq!(created.status().as_u16(), 400);
+    assert_eq!(edited.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn a_null_end_date_makes_the_job_current() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let experience_id =
+        create_experience(&app, &experience("Acme", "2020-01-01", Some("2021-01-01"))).await;
+
+    // act
+    let response = app
+        .edit_experience(&serde_json::json!({
+            "experience_id": experience_id,
+            "end_date": null
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = app.get_experience().await.json().await.unwrap();
+    assert!(body[0]["end_date"].is_null());
+    assert_eq!(body[0]["tech"], serde_json::json!(["Rust", "Postgres"]));
+}
+
+#[tokio::test]
+async fn editing_missing_experience_returns_not_found() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .edit_experience(&serde_json::json!({
+            "experience_id": Uuid::new_v4(),
+            "role": "Staff engineer"
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn deleted_experience_leaves_the_timeline() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let experience_id = create_experience(&app, &experience("Acme", "2020-01-01", None)).await;
+
+    // act
+    let response = app
+        .delete_experience(&serde_json::json!({ "experience_id": experience_id }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(companies(&app).await.is_empty());
+}
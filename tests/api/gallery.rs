@@ -0,0 +1,139 @@
+use crate::helpers::{TestApp, spawn_app};
+
+fn album(title: &str, published: bool) -> serde_json::Value {
+    serde_json::json!({
+        "title": title,
+        "description": "A week on the ring road",
+        "photos": [
+            { "src": "/media/iceland/skogafoss.jpg", "alt": "A waterfall", "caption": "Skógafoss" },
+            { "src": "/media/iceland/vik.jpg", "caption": "Black sand at Vík" }
+        ],
+        "published": published
+    })
+}
+
+async fn post_album(app: &TestApp, title: &str, published: bool) -> String {
+    let response = app.post_album(&album(title, published)).await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["album_id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_albums() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.post_album(&album("Iceland", true)).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn published_albums_are_in_the_gallery_with_their_cover() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_album(&app, "Iceland", true).await;
+    post_album(&app, "Unsorted", false).await;
+
+    // act
+    let gallery: serde_json::Value = app.get_gallery().await.json().await.unwrap();
+    let album = app.get_album("iceland").await;
+    let draft = app.get_album("unsorted").await;
+
+    // assert
+    assert_eq!(gallery.as_array().unwrap().len(), 1);
+    assert_eq!(gallery[0]["slug"], "iceland");
+    assert_eq!(gallery[0]["photo_count"], 2);
+    assert_eq!(gallery[0]["cover"]["src"], "/media/iceland/skogafoss.jpg");
+    assert_eq!(album.status().as_u16(), 200);
+    let album: serde_json::Value = album.json().await.unwrap();
+    assert_eq!(album["photos"][1]["caption"], "Black sand at Vík");
+    assert_eq!(draft.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn replacing_the_photos_reorders_and_recaptions_them() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let album_id = post_album(&app, "Iceland", true).await;
+
+    // act
+    let response = app
+        .edit_album(&serde_json::json!({
+            "album_id": album_id,
+            "photos": [
+                { "src": "/media/iceland/vik.jpg", "caption": "Reynisfjara" },
+                { "src": "/media/iceland/skogafoss.jpg" }
+            ]
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let album: serde_json::Value = app.get_album("iceland").await.json().await.unwrap();
+    assert_eq!(album["photos"][0]["src"], "/media/iceland/vik.jpg");
+    assert_eq!(album["photos"][0]["caption"], "Reynisfjara");
+    assert_eq!(album["photos"][1]["caption"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn albums_follow_the_admin_order() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let iceland = post_album(&app, "Iceland", true).await;
+    let lisbon = post_album(&app, "Lisbon", true).await;
+
+    // act
+    let response = app
+        .reorder_albums(&serde_json::json!({ "album_ids": [iceland, lisbon] }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let gallery: serde_json::Value = app.get_gallery().await.json().await.unwrap();
+    assert_eq!(gallery[0]["slug"], "iceland");
+    assert_eq!(gallery[1]["slug"], "lisbon");
+}
+
+#[tokio::test]
+async fn photos_must_come_from_the_media_library() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .post_album(&serde_json::json!({
+            "title": "Hotlinked",
+            "photos": [{ "src": "https://example.com/photo.jpg" }]
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn deleted_albums_leave_the_gallery() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let album_id = post_album(&app, "Iceland", true).await;
+
+    // act
+    let response = app
+        .delete_album(&serde_json::json!({ "album_id": album_id }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(app.get_album("iceland").await.status().as_u16(), 404);
+    let albums: serde_json::Value = app.get_all_albums().await.json().await.unwrap();
+    assert!(albums.as_array().unwrap().is_empty());
+}
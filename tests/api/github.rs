@@ -0,0 +1,138 @@
+use portfolio_server::configuration::GithubSettings;
+use portfolio_server::github::{GithubClient, refresh_github_activity};
+
+use crate::helpers::spawn_app;
+
+// stands in for github's graphql api, answering every query with `body` and handing the
+// request's authorization header to the test
+fn spawn_github_api(
+    body: serde_json::Value,
+) -> (
+    GithubClient,
+    tokio::sync::mpsc::UnboundedReceiver<Option<String>>,
+) {
+    let (sender, tokens) = tokio::sync::mpsc::unbounded_channel::<Option<String>>();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let github_api = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let server = actix_web::HttpServer::new(move || {
+        let (sender, body) = (sender.clone(), body.clone());
+        actix_web::App::new().route(
+            "/graphql",
+            actix_web::web::post().to(move |request: actix_web::HttpRequest| {
+                let token = request
+                    .headers()
+                    .get("Authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .map(ToString::to_string);
+                let _ = sender.send(token);
+                let body = body.clone();
+                async move { actix_web::HttpResponse::Ok().json(body) }
+            }),
+        )
+    })
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(server);
+
+    let client = GithubClient::from_settings(&GithubSettings {
+        username: Some("calvin-devogel".to_string()),
+        api_token: Some(secrecy::SecretString::from("github-token")),
+        api_base_url: github_api,
+        ..GithubSettings::default()
+    })
+    .unwrap();
+    (client, tokens)
+}
+
+fn activity(total_contributions: i64) -> serde_json::Value {
+    serde_json::json!({
+        "data": { "user": {
+            "login": "calvin-devogel",
+            "contributionsCollection": {
+                "totalCommitContributions": total_contributions,
+                "totalPullRequestContributions": 0,
+                "totalIssueContributions": 0,
+                "totalPullRequestReviewContributions": 0,
+                "contributionCalendar": {
+                    "totalContributions": total_contributions,
+                    "weeks": [{ "contributionDays": [
+                        { "date": "2026-04-20", "contributionCount": total_contributions }
+                    ] }]
+                }
+            },
+            "pinnedItems": { "nodes": [{
+                "name": "portfolio-server",
+                "description": "The API behind the site",
+                "url": "https://github.com/calvin-devogel/portfolio-server",
+                "stargazerCount": 4,
+                "forkCount": 1,
+                "primaryLanguage": { "name": "Rust" }
+            }] }
+        } }
+    })
+}
+
+#[tokio::test]
+async fn activity_is_a_404_until_it_has_been_fetched() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_github_activity().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn fetched_activity_is_served_from_the_cache() {
+    // arrange
+    let app = spawn_app().await;
+    let (client, mut tokens) = spawn_github_api(activity(42));
+
+    // act
+    refresh_github_activity(&app.db_pool, &client)
+        .await
+        .unwrap();
+    let response = app.get_github_activity().await;
+
+    // assert
+    assert_eq!(
+        tokens.try_recv().unwrap().as_deref(),
+        Some("Bearer github-token")
+    );
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().get("Last-Modified").is_some());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["username"], "calvin-devogel");
+    assert_eq!(body["total_contributions"], 42);
+    assert_eq!(body["calendar"][0]["date"], "2026-04-20");
+    assert_eq!(body["pinned_repos"][0]["name"], "portfolio-server");
+    assert_eq!(body["pinned_repos"][0]["language"], "Rust");
+    assert!(body["fetched_at"].is_string());
+    // serving it didn't go back to github
+    assert!(tokens.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn a_failed_fetch_keeps_the_last_activity() {
+    // arrange
+    let app = spawn_app().await;
+    let (client, _tokens) = spawn_github_api(activity(42));
+    refresh_github_activity(&app.db_pool, &client)
+        .await
+        .unwrap();
+    let (failing, _tokens) = spawn_github_api(serde_json::json!({
+        "data": null,
+        "errors": [{ "message": "Bad credentials" }]
+    }));
+
+    // act
+    let outcome = refresh_github_activity(&app.db_pool, &failing).await;
+
+    // assert
+    assert!(outcome.is_err());
+    let body: serde_json::Value = app.get_github_activity().await.json().await.unwrap();
+    assert_eq!(body["total_contributions"], 42);
+}
@@ -1,4 +1,4 @@
-use crate::helpers::spawn_app;
+use crate::helpers::{TestApp, spawn_app, spawn_app_with};
 
 #[tokio::test]
 async fn health_check_reports_correctly() {
@@ -11,3 +11,81 @@ async fn health_check_reports_correctly() {
     // assert
     assert_eq!(response.status().as_u16(), 200);
 }
+
+async fn get_probe(app: &TestApp, probe: &str) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/{probe}", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn live_and_ready_report_a_healthy_app() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let live = get_probe(&app, "live").await;
+    let ready = get_probe(&app, "ready").await;
+
+    // assert
+    assert_eq!(live.status().as_u16(), 200);
+    assert_eq!(ready.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn ready_fails_in_maintenance_mode_while_live_does_not() {
+    // arrange
+    let app = spawn_app().await;
+    app.reloadable.maintenance_mode.replace(true);
+
+    // act
+    let ready = get_probe(&app, "ready").await;
+
+    // assert
+    assert_eq!(ready.status().as_u16(), 503);
+    let problem: serde_json::Value = ready.json().await.unwrap();
+    assert_eq!(problem["detail"], "Not ready: maintenance mode");
+    assert_eq!(get_probe(&app, "live").await.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn ready_fails_until_every_migration_is_applied() {
+    // arrange
+    let app = spawn_app().await;
+    sqlx::query(
+        "DELETE FROM _sqlx_migrations WHERE version = (SELECT MAX(version) FROM _sqlx_migrations)",
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    // act
+    let ready = get_probe(&app, "ready").await;
+
+    // assert
+    assert_eq!(ready.status().as_u16(), 503);
+    let problem: serde_json::Value = ready.json().await.unwrap();
+    assert_eq!(problem["detail"], "Not ready: migrations");
+}
+
+#[tokio::test]
+async fn the_server_runs_with_tuned_workers_and_timeouts() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.application.server.workers = Some(1);
+        c.application.server.keep_alive_secs = 0;
+        c.application.server.client_request_timeout_ms = 1000;
+        c.application.server.shutdown_timeout_secs = 1;
+    })
+    .await;
+
+    // act
+    let first = app.generic_request().await;
+    let second = app.generic_request().await;
+
+    // assert
+    assert_eq!(first.status().as_u16(), 200);
+    assert_eq!(second.status().as_u16(), 200);
+}
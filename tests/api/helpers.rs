@@ -7,12 +7,16 @@ use reqwest::header::HeaderMap;
 use secrecy::{ExposeSecret, SecretString};
 use sqlx::{Connection, Executor, PgConnection, PgPool};
 use std::sync::LazyLock;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::client::IntoClientRequest};
 use totp_rs::{Secret, TOTP};
 use uuid::Uuid;
 
 use portfolio_server::{
-    configuration::{DatabaseSettings, get_configuration},
+    configuration::{DatabaseSettings, Settings, get_configuration},
+    metrics::PageVisitRecorder,
+    reload::ReloadableSettings,
     startup::{Application, get_connection_pool},
+    storage::Storage,
     telemetry::{get_subscriber, init_subscriber},
     types::user::UserRole,
 };
@@ -166,17 +170,22 @@ impl TestUser {
 
 pub struct TestApp {
     pub address: String,
+    // set when the app was spawned with `application.admin_port`
+    pub admin_address: Option<String>,
     pub db_pool: PgPool,
     pub _port: u16,
     pub test_user: TestUser,
     pub api_client: reqwest::Client,
     pub xsrf_token: String,
+    pub storage: Storage,
+    pub reloadable: ReloadableSettings,
+    pub page_visits: PageVisitRecorder,
 }
 
 impl TestApp {
     pub async fn get_home(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/", &self.address))
+            .get(format!("{}/", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -186,7 +195,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .post(&format!("{}/v1/login", &self.address))
+            .post(format!("{}/v1/login", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .form(&body)
             .send()
@@ -196,7 +205,7 @@ impl TestApp {
 
     pub async fn post_logout(&self) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/v1/logout", &self.address))
+            .post(format!("{}/v1/logout", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .send()
             .await
@@ -205,15 +214,65 @@ impl TestApp {
 
     pub async fn check_auth(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/v1/check_auth", &self.address))
+            .get(format!("{}/v1/check_auth", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
     }
 
+    pub async fn get_previous_login(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/check_auth/previous_login", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_sessions(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/sessions", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_revoke_session(&self, session_id: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/v1/sessions/{}/revoke",
+                &self.address, session_id
+            ))
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    // tungstenite doesn't share reqwest's cookie store, so the session cookie is passed in
+    pub async fn connect_dashboard_events(
+        &self,
+        session_cookie: Option<&str>,
+    ) -> Result<
+        WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+        tokio_tungstenite::tungstenite::Error,
+    > {
+        let mut request = format!("{}/v1/admin/events", self.address.replacen("http", "ws", 1))
+            .into_client_request()
+            .unwrap();
+        if let Some(cookie) = session_cookie {
+            request
+                .headers_mut()
+                .insert("Cookie", format!("id={cookie}").parse().unwrap());
+        }
+
+        tokio_tungstenite::connect_async(request)
+            .await
+            .map(|(stream, _)| stream)
+    }
+
     pub async fn generic_request(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/health_check", &self.address))
+            .get(format!("{}/health_check", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -224,7 +283,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .post(&format!("{}/v1/contact", &self.address))
+            .post(format!("{}/v1/contact", &self.address))
             .header("Idempotency-Key", Uuid::new_v4().to_string())
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .form(&body)
@@ -233,9 +292,53 @@ impl TestApp {
             .expect("Failed to send message.")
     }
 
+    pub async fn post_page_visit<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/metrics/visit", &self.address))
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn flush_page_visits(&self) {
+        self.page_visits.flush().await;
+    }
+
+    pub async fn post_page_visit_batch<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/metrics/visits/batch", &self.address))
+            // what `navigator.sendBeacon` sends: no custom headers, text/plain
+            .header(reqwest::header::CONTENT_TYPE, "text/plain;charset=UTF-8")
+            .body(serde_json::to_string(body).unwrap())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_performance_metric<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/metrics/performance", &self.address))
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn get_messages(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/v1/admin/messages", &self.address))
+            .get(format!("{}/v1/admin/messages", &self.address))
             .send()
             .await
             .expect("Failed to get messages.")
@@ -246,7 +349,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .patch(&format!("{}/v1/admin/messages", &self.address))
+            .patch(format!("{}/v1/admin/messages", &self.address))
             .header("Idempotency-Key", Uuid::new_v4().to_string())
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&body)
@@ -264,7 +367,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .patch(&format!("{}/v1/admin/messages", &self.address))
+            .patch(format!("{}/v1/admin/messages", &self.address))
             .header("Idempotency-Key", idempotency_key.to_string())
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&body)
@@ -282,11 +385,11 @@ impl TestApp {
             "BlogPost-OnPublished",
             on_published.parse().unwrap_or("false".parse().unwrap()),
         );
-        if slug.is_some() {
-            header_map.insert("BlogPost-Slug", slug.unwrap().parse().unwrap());
+        if let Some(slug) = slug {
+            header_map.insert("BlogPost-Slug", slug.parse().unwrap());
         }
         self.api_client
-            .get(&format!("{}/v1/blog", &self.address))
+            .get(format!("{}/v1/blog", &self.address))
             .headers(header_map)
             .send()
             .await
@@ -349,9 +452,592 @@ impl TestApp {
             .expect("Failed to delete article")
     }
 
+    pub async fn get_projects(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/projects", &self.address))
+            .send()
+            .await
+            .expect("Failed to get projects")
+    }
+
+    pub async fn get_project(&self, slug: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/projects/{slug}", &self.address))
+            .send()
+            .await
+            .expect("Failed to get project")
+    }
+
+    pub async fn get_all_projects(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/projects", &self.address))
+            .send()
+            .await
+            .expect("Failed to get all projects")
+    }
+
+    pub async fn post_project<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/projects", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post project")
+    }
+
+    pub async fn edit_project<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/projects", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit project")
+    }
+
+    pub async fn reorder_projects<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/projects/order", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to reorder projects")
+    }
+
+    pub async fn delete_project<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/projects", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete project")
+    }
+
+    pub async fn get_gallery(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/gallery", &self.address))
+            .send()
+            .await
+            .expect("Failed to get gallery")
+    }
+
+    pub async fn get_album(&self, slug: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/gallery/{slug}", &self.address))
+            .send()
+            .await
+            .expect("Failed to get album")
+    }
+
+    pub async fn get_all_albums(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/albums", &self.address))
+            .send()
+            .await
+            .expect("Failed to get all albums")
+    }
+
+    pub async fn post_album<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/albums", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post album")
+    }
+
+    pub async fn edit_album<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/albums", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit album")
+    }
+
+    pub async fn reorder_albums<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/albums/order", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to reorder albums")
+    }
+
+    pub async fn delete_album<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/albums", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete album")
+    }
+
+    pub async fn follow_short_link(&self, code: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/l/{code}", &self.address))
+            .send()
+            .await
+            .expect("Failed to follow short link")
+    }
+
+    pub async fn get_short_links(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/links", &self.address))
+            .send()
+            .await
+            .expect("Failed to get short links")
+    }
+
+    pub async fn post_short_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/links", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post short link")
+    }
+
+    pub async fn edit_short_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/links", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit short link")
+    }
+
+    pub async fn delete_short_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/links", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete short link")
+    }
+
+    pub async fn get_now_entries(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/now{query}", &self.address))
+            .send()
+            .await
+            .expect("Failed to get now entries")
+    }
+
+    pub async fn post_now_entry<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/now", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post now entry")
+    }
+
+    pub async fn edit_now_entry<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/now", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit now entry")
+    }
+
+    pub async fn delete_now_entry<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/now", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete now entry")
+    }
+
+    pub async fn get_uses(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/uses", &self.address))
+            .send()
+            .await
+            .expect("Failed to get uses")
+    }
+
+    pub async fn post_uses_item<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/uses", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post uses item")
+    }
+
+    pub async fn edit_uses_item<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/uses", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit uses item")
+    }
+
+    pub async fn delete_uses_item<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/uses", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete uses item")
+    }
+
+    pub async fn get_projects_with_skill(&self, skill: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/projects", &self.address))
+            .query(&[("skill", skill)])
+            .send()
+            .await
+            .expect("Failed to get projects")
+    }
+
+    pub async fn get_skills(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/skills", &self.address))
+            .send()
+            .await
+            .expect("Failed to get skills")
+    }
+
+    pub async fn post_skill<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/skills", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post skill")
+    }
+
+    pub async fn edit_skill<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/skills", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit skill")
+    }
+
+    pub async fn delete_skill<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/skills", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete skill")
+    }
+
+    pub async fn get_experience(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/experience", &self.address))
+            .send()
+            .await
+            .expect("Failed to get experience")
+    }
+
+    pub async fn post_experience<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .post(format!("{}/v1/admin/experience", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to post experience")
+    }
+
+    pub async fn edit_experience<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/experience", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit experience")
+    }
+
+    pub async fn delete_experience<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/experience", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to delete experience")
+    }
+
+    pub async fn search(&self, terms: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/search", &self.address))
+            .query(&[("q", terms)])
+            .send()
+            .await
+            .expect("Failed to search")
+    }
+
+    pub async fn admin_search(&self, terms: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/search", &self.address))
+            .query(&[("q", terms)])
+            .send()
+            .await
+            .expect("Failed to search as admin")
+    }
+
+    pub async fn get_profile(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/profile", &self.address))
+            .send()
+            .await
+            .expect("Failed to get profile")
+    }
+
+    pub async fn get_github_activity(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/github/activity", &self.address))
+            .send()
+            .await
+            .expect("Failed to get GitHub activity")
+    }
+
+    pub async fn get_now_playing(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/now-playing", &self.address))
+            .send()
+            .await
+            .expect("Failed to get now playing")
+    }
+
+    pub async fn edit_profile<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .patch(format!("{}/v1/admin/profile", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to edit profile")
+    }
+
+    pub async fn get_resume(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/resume", &self.address))
+            .send()
+            .await
+            .expect("Failed to get resume")
+    }
+
+    pub async fn get_resume_pdf(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/resume/pdf", &self.address))
+            .send()
+            .await
+            .expect("Failed to get resume pdf")
+    }
+
+    pub async fn put_resume<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .put(format!("{}/v1/admin/resume", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to put resume")
+    }
+
+    pub async fn get_availability(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/availability", &self.address))
+            .send()
+            .await
+            .expect("Failed to get availability")
+    }
+
+    pub async fn put_availability<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .put(format!("{}/v1/admin/availability", &self.address))
+            .header("Idempotency-Key", Uuid::new_v4().to_string())
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to put availability")
+    }
+
+    pub async fn get_availability_history(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/availability/history", &self.address))
+            .send()
+            .await
+            .expect("Failed to get availability history")
+    }
+
+    // presigned urls carry the configured base url, this sends them to the test app instead
+    pub fn local_storage_url(&self, presigned: &str) -> String {
+        let url = reqwest::Url::parse(presigned).expect("Invalid presigned url");
+        format!(
+            "{}{}?{}",
+            &self.address,
+            url.path(),
+            url.query().unwrap_or_default()
+        )
+    }
+
+    pub async fn get_scheduler_status(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/scheduler", &self.address))
+            .send()
+            .await
+            .expect("Failed to get scheduler status")
+    }
+
+    pub async fn get_rate_limits(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/v1/admin/rate_limits", &self.address))
+            .send()
+            .await
+            .expect("Failed to get rate limits")
+    }
+
+    pub async fn reset_rate_limit<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.api_client
+            .delete(format!("{}/v1/admin/rate_limits", &self.address))
+            .header("X-XSRF-TOKEN", &self.xsrf_token)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to reset rate limit")
+    }
+
     pub async fn post_verify_totp(&self, code: &str) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/v1/verify_totp", &self.address))
+            .post(format!("{}/v1/verify_totp", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&serde_json::json!({ "code": code }))
             .send()
@@ -361,7 +1047,7 @@ impl TestApp {
 
     pub async fn get_totp_setup(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/v1/admin/totp/setup", &self.address))
+            .get(format!("{}/v1/admin/totp/setup", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -369,7 +1055,7 @@ impl TestApp {
 
     pub async fn post_totp_confirm(&self, code: &str) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/v1/admin/totp/confirm", &self.address))
+            .post(format!("{}/v1/admin/totp/confirm", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&serde_json::json!({ "code": code }))
             .send()
@@ -379,7 +1065,7 @@ impl TestApp {
 
     pub async fn post_totp_disable(&self, password: &str) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/v1/admin/totp/disable", &self.address))
+            .post(format!("{}/v1/admin/totp/disable", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&serde_json::json!({ "password": password }))
             .send()
@@ -389,7 +1075,7 @@ impl TestApp {
 
     pub async fn get_totp_status(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/v1/admin/totp/status", &self.address))
+            .get(format!("{}/v1/admin/totp/status", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
@@ -400,7 +1086,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .post(&format!("{}/v1/admin/create_user", &self.address))
+            .post(format!("{}/v1/admin/create_user", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .header("Idempotency-Key", Uuid::new_v4().to_string())
             .json(&body)
@@ -414,7 +1100,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .post(&format!("{}/v1/change_password", &self.address))
+            .post(format!("{}/v1/change_password", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&body)
             .send()
@@ -424,12 +1110,12 @@ impl TestApp {
 
     pub async fn get_user_names(&self, username: Option<String>) -> reqwest::Response {
         let mut header_map = HeaderMap::new();
-        if username.is_some() {
-            header_map.insert("UserName", username.unwrap().parse().unwrap());
+        if let Some(username) = username {
+            header_map.insert("UserName", username.parse().unwrap());
         }
 
         self.api_client
-            .get(&format!("{}/v1/admin/users", &self.address))
+            .get(format!("{}/v1/admin/users", &self.address))
             .headers(header_map)
             .send()
             .await
@@ -438,7 +1124,7 @@ impl TestApp {
 
     pub async fn get_chat_token(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/v1/chat_token", &self.address))
+            .get(format!("{}/v1/chat_token", &self.address))
             .send()
             .await
             .expect("Failed to get chat token")
@@ -449,10 +1135,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .patch(&format!(
-                "{}/v1/admin/users/{}/role",
-                &self.address, user_id
-            ))
+            .patch(format!("{}/v1/admin/users/{}/role", &self.address, user_id))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&body)
             .send()
@@ -465,7 +1148,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.api_client
-            .post(&format!("{}/v1/accept", &self.address))
+            .post(format!("{}/v1/accept", &self.address))
             .header("X-XSRF-TOKEN", &self.xsrf_token)
             .json(&body)
             .send()
@@ -496,17 +1179,35 @@ impl TestApp {
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+// same as `spawn_app`, with a chance to tweak the configuration first
+pub async fn spawn_app_with(customize: impl FnOnce(&mut Settings)) -> TestApp {
     LazyLock::force(&TRACING);
 
     let configuration = {
         let mut c = get_configuration().expect("Failed to read configuration.");
         c.database.database_name = Uuid::new_v4().to_string();
+        // hundreds of apps run at once against one postgres, each only needs a few
+        c.database.max_connections = 4;
         c.application.port = 0;
+        // every test app shares 127.0.0.1 and one redis, so each counts in its own namespace
+        c.rate_limit.namespace = format!("rate_limit:{}", Uuid::new_v4());
+        c.storage.local_path = std::env::temp_dir()
+            .join(format!("storage-{}", Uuid::new_v4()))
+            .to_string_lossy()
+            .into_owned();
+        customize(&mut c);
         c
     };
 
-    //create and migrate the database
-    configure_database(&configuration.database).await;
+    //create and migrate the database, unless the app is meant to migrate it
+    if configuration.application.migrate_on_start {
+        create_database(&configuration.database).await;
+    } else {
+        configure_database(&configuration.database).await;
+    }
 
     // launch as background task
     let application = Application::build(configuration.clone())
@@ -514,16 +1215,25 @@ pub async fn spawn_app() -> TestApp {
         .expect("Failed to build configuration.");
 
     let application_port = application.port();
-    let _ = tokio::spawn(application.run_until_stopped());
+    let admin_port = application.admin_port();
+    let reloadable = application.reloadable_settings();
+    let page_visits = application.page_visit_recorder();
+    drop(tokio::spawn(application.run_until_stopped()));
 
+    // a test certificate is self-signed, nothing would trust it otherwise
+    let scheme = match configuration.application.tls_paths() {
+        Some(_) => "https",
+        None => "http",
+    };
     let client = reqwest::Client::builder()
         .redirect(reqwest::redirect::Policy::none())
         .cookie_store(true)
+        .tls_danger_accept_invalid_certs(true)
         .build()
         .unwrap();
 
     let seed = client
-        .get(format!("http://localhost:{}/v1/blog", application_port))
+        .get(format!("{scheme}://localhost:{application_port}/v1/blog"))
         .send()
         .await
         .expect("Failed to seed CSRF token");
@@ -534,18 +1244,45 @@ pub async fn spawn_app() -> TestApp {
         .expect("XSRF-TOKEN not found in seed response");
 
     let test_app = TestApp {
-        address: format!("http://localhost:{}", application_port),
+        address: format!("{scheme}://localhost:{application_port}"),
+        admin_address: admin_port.map(|port| format!("{scheme}://localhost:{port}")),
         _port: application_port,
         db_pool: get_connection_pool(&configuration.database),
         test_user: TestUser::generate(),
         api_client: client,
         xsrf_token,
+        // the same directory and signing key as the app, for handing out presigned urls
+        storage: Storage::from_settings(
+            &configuration.storage,
+            &configuration.application.base_url,
+            &configuration.application.hmac_secret,
+        )
+        .expect("Failed to build test storage"),
+        // stands in for a SIGHUP, tests don't install the signal handler
+        reloadable,
+        // visits are written in the background, tests flush before reading page_visits
+        page_visits,
     };
     test_app.test_user.store(&test_app.db_pool).await;
     test_app
 }
 
 async fn configure_database(config: &DatabaseSettings) -> PgPool {
+    create_database(config).await;
+
+    let connection_pool = PgPool::connect_with(config.connect_options())
+        .await
+        .expect("Failed to connect to Postgres.");
+    sqlx::migrate!("./migrations")
+        .run(&connection_pool)
+        .await
+        .expect("Failed to migrate the database.");
+
+    connection_pool
+}
+
+// an empty database, nothing migrated
+pub async fn create_database(config: &DatabaseSettings) {
     let maintenance_settings = DatabaseSettings {
         database_name: "postgres".to_string(),
         username: "postgres".to_string(),
@@ -560,16 +1297,6 @@ async fn configure_database(config: &DatabaseSettings) -> PgPool {
         .execute(format!(r#"CREATE DATABASE "{}";"#, config.database_name).as_str())
         .await
         .expect("Failed to create database.");
-
-    let connection_pool = PgPool::connect_with(config.connect_options())
-        .await
-        .expect("Failed to connect to Postgres.");
-    sqlx::migrate!("./migrations")
-        .run(&connection_pool)
-        .await
-        .expect("Failed to migrate the database.");
-
-    connection_pool
 }
 
 // i need a way to seed a user into the database without exposing the hash explicitly
@@ -3,23 +3,35 @@ use actix_web::HttpResponse;
 use portfolio_server::{
     errors::IdempotencyError::{self, RequestInFlight},
     idempotency::{
-        IdempotencyKey, NextAction, execute_idempotent_with, get_saved_response, save_response,
-        try_processing,
+        IdempotencyKey, NextAction, RequestFingerprint, execute_idempotent_with,
+        get_saved_response, save_response, try_processing,
     },
+    metrics::AppMetrics,
 };
 use uuid::Uuid;
 
 const ANONYMOUS_OPERATION: &str = "POST:/v1/contact";
 const AUTHORIZED_OPERATION: &str = "PATCH:/v1/admin/messages";
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+fn fingerprint() -> RequestFingerprint {
+    RequestFingerprint::of(&"test-payload").unwrap()
+}
 
 #[tokio::test]
 async fn try_processing_returns_start_processing_for_new_key() {
     let app = spawn_app().await;
     let key = IdempotencyKey::try_from("test-key-123".to_string()).unwrap();
 
-    let (action, transaction) = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
-        .await
-        .expect("Failed to process");
+    let (action, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to process");
 
     assert!(matches!(action, NextAction::StartProcessing));
     assert!(transaction.is_some());
@@ -31,9 +43,15 @@ async fn try_processing_returns_saved_response_for_duplicate_key() {
     let key = IdempotencyKey::try_from("duplicate-key".to_string()).unwrap();
 
     // act 1: process and save
-    let (action, transaction) = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
-        .await
-        .expect("Failed to process first request");
+    let (action, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to process first request");
 
     assert!(matches!(action, NextAction::StartProcessing));
     let transaction = transaction.unwrap();
@@ -42,14 +60,28 @@ async fn try_processing_returns_saved_response_for_duplicate_key() {
         .insert_header(("X-Test-Header", "test-value"))
         .body("Test response body");
 
-    save_response(transaction, &key, None, ANONYMOUS_OPERATION, response)
-        .await
-        .expect("Failed to save response");
+    save_response(
+        transaction,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        response,
+        MAX_BODY_BYTES,
+        None,
+    )
+    .await
+    .expect("Failed to save response");
 
     // act 2: try processing, should return saved response
-    let (action, transaction) = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
-        .await
-        .expect("Failed to process second request");
+    let (action, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to process second request");
 
     assert!(transaction.is_none());
 
@@ -66,9 +98,15 @@ async fn save_response_persists_status_code_and_body() {
     let app = spawn_app().await;
     let key = IdempotencyKey::try_from("persist-test".to_string()).unwrap();
 
-    let (_, transaction) = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
-        .await
-        .expect("Failed to start processing");
+    let (_, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to start processing");
 
     let response = HttpResponse::Accepted().body("Message received");
 
@@ -78,6 +116,8 @@ async fn save_response_persists_status_code_and_body() {
         None,
         ANONYMOUS_OPERATION,
         response,
+        MAX_BODY_BYTES,
+        None,
     )
     .await
     .expect("Failed to save");
@@ -95,9 +135,15 @@ async fn save_response_persists_headers() {
     let app = spawn_app().await;
     let key = IdempotencyKey::try_from("header-test".to_string()).unwrap();
 
-    let (_, transaction) = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
-        .await
-        .expect("Failed to start processing");
+    let (_, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to start processing");
 
     let response = HttpResponse::Ok()
         .insert_header(("Content-Type", "application/json"))
@@ -110,6 +156,8 @@ async fn save_response_persists_headers() {
         None,
         ANONYMOUS_OPERATION,
         response,
+        MAX_BODY_BYTES,
+        None,
     )
     .await
     .expect("Failed to save");
@@ -150,9 +198,15 @@ async fn idempotency_works_with_user_scoped_keys() {
     let user_id = Uuid::new_v4();
 
     // save response for specific user
-    let (_, transaction) = try_processing(&app.db_pool, &key, Some(user_id), AUTHORIZED_OPERATION)
-        .await
-        .expect("Failed to process");
+    let (_, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        Some(user_id),
+        AUTHORIZED_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to process");
 
     let response = HttpResponse::Ok().body("User-specific response");
     save_response(
@@ -161,6 +215,8 @@ async fn idempotency_works_with_user_scoped_keys() {
         Some(user_id),
         AUTHORIZED_OPERATION,
         response,
+        MAX_BODY_BYTES,
+        None,
     )
     .await
     .expect("Failed to save");
@@ -190,12 +246,24 @@ async fn different_keys_dont_interfere() {
     let key2 = IdempotencyKey::try_from("key-two".to_string()).unwrap();
 
     // Process both keys
-    let (action1, tx1) = try_processing(&app.db_pool, &key1, None, ANONYMOUS_OPERATION)
-        .await
-        .unwrap();
-    let (action2, tx2) = try_processing(&app.db_pool, &key2, None, ANONYMOUS_OPERATION)
-        .await
-        .unwrap();
+    let (action1, tx1) = try_processing(
+        &app.db_pool,
+        &key1,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .unwrap();
+    let (action2, tx2) = try_processing(
+        &app.db_pool,
+        &key2,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .unwrap();
 
     // Both should be new
     assert!(matches!(action1, NextAction::StartProcessing));
@@ -210,19 +278,39 @@ async fn same_key_different_operations_dont_interfere() {
     let key = IdempotencyKey::try_from("shared-key".to_string()).unwrap();
 
     // anonymous op first
-    let (action1, tx1) = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
-        .await
-        .unwrap();
+    let (action1, tx1) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .unwrap();
     assert!(matches!(action1, NextAction::StartProcessing));
     let response1 = HttpResponse::Accepted().body("contact ok");
-    save_response(tx1.unwrap(), &key, None, ANONYMOUS_OPERATION, response1)
-        .await
-        .expect("Failed to save first response");
+    save_response(
+        tx1.unwrap(),
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        response1,
+        MAX_BODY_BYTES,
+        None,
+    )
+    .await
+    .expect("Failed to save first response");
 
     // same key different op, shouldn't conflict
-    let (action2, tx2) = try_processing(&app.db_pool, &key, None, AUTHORIZED_OPERATION)
-        .await
-        .unwrap();
+    let (action2, tx2) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        AUTHORIZED_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .unwrap();
     assert!(
         matches!(action2, NextAction::StartProcessing),
         "Same key under a different operation should start fresh"
@@ -246,7 +334,14 @@ async fn try_processing_returns_request_in_flight_when_response_not_yet_saved()
     .await
     .expect("Failed to seed in-flight request");
 
-    let result = try_processing(&app.db_pool, &key, None, ANONYMOUS_OPERATION).await;
+    let result = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await;
     assert!(matches!(result, Err(RequestInFlight)));
 }
 
@@ -263,8 +358,9 @@ async fn missing_transaction_operation_is_handled() {
         &request,
         &app.db_pool,
         None,
+        &fingerprint(),
         |_tx| Box::pin(async { Ok(HttpResponse::Ok().finish()) }),
-        |_, _, _, _| Box::pin(async { Ok((NextAction::StartProcessing, None)) }),
+        |_, _, _, _, _| Box::pin(async { Ok((NextAction::StartProcessing, None)) }),
     )
     .await;
 
@@ -284,8 +380,9 @@ async fn process_fn_error_is_handled() {
         &request,
         &app.db_pool,
         None,
+        &fingerprint(),
         |_tx| Box::pin(async { Ok(HttpResponse::Ok().finish()) }),
-        |_, _, _, _| Box::pin(async { Err(IdempotencyError::RequestInFlight) }),
+        |_, _, _, _, _| Box::pin(async { Err(IdempotencyError::RequestInFlight) }),
     )
     .await;
 
@@ -295,3 +392,185 @@ async fn process_fn_error_is_handled() {
         IdempotencyError::RequestInFlight
     ));
 }
+
+#[tokio::test]
+async fn try_processing_rejects_reused_key_with_different_payload() {
+    let app = spawn_app().await;
+    let key = IdempotencyKey::try_from("reused-key".to_string()).unwrap();
+
+    let (_, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to process first request");
+    save_response(
+        transaction.unwrap(),
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        HttpResponse::Accepted().finish(),
+        MAX_BODY_BYTES,
+        None,
+    )
+    .await
+    .expect("Failed to save response");
+
+    let other_payload = RequestFingerprint::of(&"another-payload").unwrap();
+    let result = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &other_payload,
+    )
+    .await;
+
+    assert!(matches!(result, Err(IdempotencyError::PayloadMismatch)));
+}
+
+#[tokio::test]
+async fn concurrent_duplicates_wait_for_the_first_and_replay_its_response() {
+    let app = spawn_app().await;
+    let key = IdempotencyKey::try_from("racing-key".to_string()).unwrap();
+
+    // the first request has claimed the key and is still processing
+    let (_, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to start processing");
+
+    // the duplicate's insert blocks on the uncommitted row rather than seeing it
+    let duplicate = tokio::spawn({
+        let pool = app.db_pool.clone();
+        let key = IdempotencyKey::try_from("racing-key".to_string()).unwrap();
+        async move {
+            try_processing(&pool, &key, None, ANONYMOUS_OPERATION, &fingerprint())
+                .await
+                .map(|(action, _)| matches!(action, NextAction::ReturnSavedResponse(_)))
+        }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(!duplicate.is_finished());
+
+    save_response(
+        transaction.unwrap(),
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        HttpResponse::Ok().body("first"),
+        MAX_BODY_BYTES,
+        None,
+    )
+    .await
+    .expect("Failed to save response");
+
+    let replayed = duplicate
+        .await
+        .unwrap()
+        .expect("Failed to process duplicate");
+    assert!(replayed);
+}
+
+// concurrent duplicates never see this state (see above), a committed row without a
+// response is only possible if one was written outside `try_processing`
+// it's still refused rather than treated as a new request
+#[tokio::test]
+async fn keys_without_a_saved_response_get_conflict() {
+    let app = spawn_app().await;
+    let key = "racing-key";
+
+    sqlx::query!(
+        "INSERT INTO idempotency (user_id, idempotency_key, operation, created_at)
+        VALUES (NULL, $1, $2, now())",
+        key,
+        ANONYMOUS_OPERATION
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to seed a row without a response");
+
+    let response = app
+        .api_client
+        .post(format!("{}/v1/contact", &app.address))
+        .header("Idempotency-Key", key)
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .form(&serde_json::json!({
+            "email": "racer@email.com",
+            "sender_name": "Racer",
+            "message_text": "Second in line."
+        }))
+        .send()
+        .await
+        .expect("Failed to send message.");
+
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn oversized_responses_are_not_stored_and_release_the_key() {
+    let app = spawn_app().await;
+    let key = IdempotencyKey::try_from("oversized-key".to_string()).unwrap();
+
+    let (_, transaction) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to start processing");
+
+    let metrics = AppMetrics::new().unwrap();
+
+    let body = "a".repeat(32);
+    let response = save_response(
+        transaction.unwrap(),
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        HttpResponse::Ok().body(body.clone()),
+        16,
+        Some(&metrics),
+    )
+    .await
+    .expect("Failed to save");
+
+    // the caller still gets the full response
+    let returned = actix_web::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    assert_eq!(returned, body.as_bytes());
+    assert_eq!(
+        metrics
+            .idempotency_responses_not_stored_total
+            .with_label_values(&["oversized"])
+            .get(),
+        1
+    );
+
+    // nothing was stored, so a retry processes again
+    let saved = get_saved_response(&app.db_pool, &key, None, ANONYMOUS_OPERATION)
+        .await
+        .expect("Query failed");
+    assert!(saved.is_none());
+    let (action, _) = try_processing(
+        &app.db_pool,
+        &key,
+        None,
+        ANONYMOUS_OPERATION,
+        &fingerprint(),
+    )
+    .await
+    .expect("Failed to process retry");
+    assert!(matches!(action, NextAction::StartProcessing));
+}
@@ -0,0 +1,194 @@
+use portfolio_server::configuration::JobSettings;
+use portfolio_server::jobs::{ExecutionOutcome, JobHandlers, enqueue_job, try_execute_job};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::helpers::spawn_app;
+
+struct JobRow {
+    status: String,
+    attempts: i32,
+    max_attempts: i32,
+    last_error: Option<String>,
+    due: Option<bool>,
+}
+
+async fn job_row(pool: &PgPool, job_id: Uuid) -> JobRow {
+    sqlx::query_as!(
+        JobRow,
+        r#"
+        SELECT status, attempts, max_attempts, last_error, run_at <= NOW() AS due
+        FROM jobs
+        WHERE job_id = $1
+        "#,
+        job_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+fn failing_handlers() -> JobHandlers {
+    JobHandlers::default().on("fail", |_| async { anyhow::bail!("upstream is down") })
+}
+
+#[tokio::test]
+async fn jobs_that_succeed_are_marked_done() {
+    // arrange
+    let app = spawn_app().await;
+    let handlers = JobHandlers::default().on("greet", |payload| async move {
+        anyhow::ensure!(payload["name"] == "calvin", "unexpected payload");
+        Ok(())
+    });
+    let job_id = enqueue_job(
+        &app.db_pool,
+        "greet",
+        &serde_json::json!({ "name": "calvin" }),
+    )
+    .await
+    .unwrap();
+
+    // act
+    let outcome = try_execute_job(&app.db_pool, &handlers, &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    assert!(matches!(outcome, ExecutionOutcome::TaskCompleted));
+    let job = job_row(&app.db_pool, job_id).await;
+    assert_eq!(job.status, "succeeded");
+    assert_eq!(job.attempts, 1);
+}
+
+#[tokio::test]
+async fn failed_jobs_are_retried_later() {
+    // arrange
+    let app = spawn_app().await;
+    let job_id = enqueue_job(&app.db_pool, "fail", &serde_json::json!({}))
+        .await
+        .unwrap();
+
+    // act
+    try_execute_job(&app.db_pool, &failing_handlers(), &JobSettings::default())
+        .await
+        .unwrap();
+    let next = try_execute_job(&app.db_pool, &failing_handlers(), &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    let job = job_row(&app.db_pool, job_id).await;
+    assert_eq!(job.status, "queued");
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.last_error.as_deref(), Some("upstream is down"));
+    assert_eq!(job.due, Some(false));
+    // backing off, so nothing is due yet
+    assert!(matches!(next, ExecutionOutcome::EmptyQueue));
+}
+
+#[tokio::test]
+async fn jobs_out_of_attempts_are_dead_lettered() {
+    // arrange
+    let app = spawn_app().await;
+    let job_id = enqueue_job(&app.db_pool, "fail", &serde_json::json!({}))
+        .await
+        .unwrap();
+    sqlx::query!("UPDATE jobs SET max_attempts = 1 WHERE job_id = $1", job_id)
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    // act
+    try_execute_job(&app.db_pool, &failing_handlers(), &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    let job = job_row(&app.db_pool, job_id).await;
+    assert_eq!(job.status, "dead");
+    assert_eq!(job.attempts, 1);
+}
+
+#[tokio::test]
+async fn jobs_with_no_handler_fail() {
+    // arrange
+    let app = spawn_app().await;
+    let job_id = enqueue_job(&app.db_pool, "unknown", &serde_json::json!({}))
+        .await
+        .unwrap();
+
+    // act
+    try_execute_job(
+        &app.db_pool,
+        &JobHandlers::default(),
+        &JobSettings::default(),
+    )
+    .await
+    .unwrap();
+
+    // assert
+    let job = job_row(&app.db_pool, job_id).await;
+    assert_eq!(job.status, "queued");
+    assert!(job.last_error.unwrap().contains("No handler"));
+}
+
+#[tokio::test]
+async fn jobs_claimed_by_another_worker_are_skipped() {
+    // arrange
+    let app = spawn_app().await;
+    let job_id = enqueue_job(&app.db_pool, "fail", &serde_json::json!({}))
+        .await
+        .unwrap();
+    let mut other_worker = app.db_pool.begin().await.unwrap();
+    sqlx::query!(
+        "SELECT job_id FROM jobs WHERE job_id = $1 FOR UPDATE",
+        job_id
+    )
+    .fetch_one(other_worker.as_mut())
+    .await
+    .unwrap();
+
+    // act
+    let outcome = try_execute_job(&app.db_pool, &failing_handlers(), &JobSettings::default())
+        .await
+        .unwrap();
+
+    // assert
+    assert!(matches!(outcome, ExecutionOutcome::EmptyQueue));
+    other_worker.rollback().await.unwrap();
+    assert_eq!(job_row(&app.db_pool, job_id).await.attempts, 0);
+}
+
+#[tokio::test]
+async fn jobs_that_keep_panicking_are_dead_lettered() {
+    // arrange
+    let app = spawn_app().await;
+    let job_id = enqueue_job(&app.db_pool, "panic", &serde_json::json!({}))
+        .await
+        .unwrap();
+    let handlers = JobHandlers::default().on("panic", |_| async { panic!("poison job") });
+    // no lease, so the job is due again as soon as its worker is gone
+    let settings = JobSettings {
+        lease_secs: 0,
+        ..JobSettings::default()
+    };
+    let max_attempts = job_row(&app.db_pool, job_id).await.max_attempts;
+
+    // act
+    for _ in 0..max_attempts {
+        let (pool, handlers, settings) = (app.db_pool.clone(), handlers.clone(), settings.clone());
+        let worker =
+            tokio::spawn(async move { try_execute_job(&pool, &handlers, &settings).await });
+        assert!(worker.await.is_err_and(|e| e.is_panic()));
+    }
+    let outcome = try_execute_job(&app.db_pool, &handlers, &settings)
+        .await
+        .unwrap();
+
+    // assert
+    assert!(matches!(outcome, ExecutionOutcome::TaskCompleted));
+    let job = job_row(&app.db_pool, job_id).await;
+    assert_eq!(job.status, "dead");
+    assert_eq!(job.attempts, max_attempts);
+    assert!(job.last_error.unwrap().contains("worker stopped"));
+}
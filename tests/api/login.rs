@@ -52,3 +52,79 @@ async fn authorized_users_can_access_restricted_routes() {
     // assert
     assert_eq!(response.status().as_u16(), 200);
 }
+
+#[tokio::test]
+async fn login_upgrades_outdated_password_hashes() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.post_login(&app.test_user).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // assert
+    // the rehash runs in the background, give it a moment to land
+    let mut password_hash = String::new();
+    for _ in 0..50 {
+        password_hash = sqlx::query_scalar!(
+            "SELECT password_hash FROM users WHERE user_id = $1",
+            app.test_user.user_id
+        )
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+        if password_hash.contains("m=19456") {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(password_hash.contains("m=19456,t=2,p=1"));
+
+    // the upgraded hash still verifies
+    app.post_logout().await;
+    let response = app.post_login(&app.test_user).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn login_attempts_are_limited_per_ip_across_usernames() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    // the default IP budget is 20 attempts, each with a fresh username
+    for i in 0..20 {
+        let body = serde_json::json!({
+            "username": format!("sprayed-user-{i}"),
+            "password": "wrong-password",
+        });
+        let response = app.post_login(&body).await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+    let response = app.post_login(&app.test_user).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 429);
+}
+
+#[tokio::test]
+async fn login_attempts_are_limited_per_username() {
+    // arrange
+    let app = spawn_app().await;
+    let body = serde_json::json!({
+        "username": app.test_user.username,
+        "password": "wrong-password",
+    });
+
+    // act
+    // the default username budget is 10 attempts
+    for _ in 0..10 {
+        let response = app.post_login(&body).await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+    // even the right password is rejected once the budget is spent
+    let response = app.post_login(&app.test_user).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 429);
+}
@@ -1,16 +1,50 @@
 mod accept_invitation;
+mod admin_listener;
+mod antivirus;
+mod availability;
 mod blog;
 mod change_password;
 mod chat_token;
 mod check_auth;
+mod cors;
 mod create_user;
 mod csrf;
+mod dashboard_events;
+mod email;
+mod experience;
+mod gallery;
+mod github;
 mod health_check;
 mod helpers;
 mod home;
 mod idempotency;
+mod jobs;
 mod login;
 mod logout;
+mod media;
 mod messages;
+mod metrics;
+mod migrations;
+mod now;
+mod now_playing;
+mod problem_details;
+mod profile;
+mod projects;
+mod rate_limit;
+mod read_replica;
+mod reload;
+mod response_envelope;
+mod resume;
+mod robots;
+mod scheduler;
+mod search;
+mod sessions;
+mod short_links;
+mod skills;
+mod startup_checks;
+mod storage;
+mod tls;
 mod totp;
 mod totp_admin;
+mod uses;
+mod version;
@@ -0,0 +1,120 @@
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn media_is_served_with_its_type_and_cache_headers() {
+    // arrange
+    let app = spawn_app().await;
+    app.storage
+        .put("media/notes.txt", b"hello there".to_vec())
+        .await
+        .unwrap();
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/media/notes.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let headers = response.headers();
+    assert!(
+        headers["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    assert_eq!(headers["cache-control"], "public, max-age=86400");
+    assert_eq!(headers["x-content-type-options"], "nosniff");
+    assert!(headers.contains_key("etag"));
+    assert_eq!(response.text().await.unwrap(), "hello there");
+}
+
+#[tokio::test]
+async fn media_supports_range_requests() {
+    // arrange
+    let app = spawn_app().await;
+    app.storage
+        .put("media/notes.txt", b"hello there".to_vec())
+        .await
+        .unwrap();
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/media/notes.txt", &app.address))
+        .header("Range", "bytes=0-4")
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 206);
+    assert_eq!(response.headers()["content-range"], "bytes 0-4/11");
+    assert_eq!(response.text().await.unwrap(), "hello");
+}
+
+#[tokio::test]
+async fn unchanged_media_is_not_sent_again() {
+    // arrange
+    let app = spawn_app().await;
+    app.storage
+        .put("media/notes.txt", b"hello there".to_vec())
+        .await
+        .unwrap();
+    let url = format!("{}/media/notes.txt", &app.address);
+    let first = app.api_client.get(&url).send().await.unwrap();
+    let etag = first.headers()["etag"].clone();
+
+    // act
+    let response = app
+        .api_client
+        .get(&url)
+        .header("If-None-Match", etag)
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 304);
+}
+
+#[tokio::test]
+async fn missing_media_is_a_404() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/media/nothing-here.png", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn media_cannot_escape_the_media_prefix() {
+    // arrange
+    let app = spawn_app().await;
+    app.storage
+        .put("private/secret.txt", b"top secret".to_vec())
+        .await
+        .unwrap();
+
+    // act, encoded so the client can't resolve the `..` before sending it
+    let response = app
+        .api_client
+        .get(format!("{}/media/..%2Fprivate%2Fsecret.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
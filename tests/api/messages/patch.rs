@@ -156,6 +156,25 @@ async fn try_to_patch_with_reused_idempotency_key() {
         first_response.status().as_u16(),
         second_response.status().as_u16()
     );
+    assert!(
+        first_response
+            .headers()
+            .get("Idempotent-Replayed")
+            .is_none()
+    );
+    assert_eq!(
+        second_response
+            .headers()
+            .get("Idempotent-Replayed")
+            .unwrap(),
+        "true"
+    );
+    assert!(
+        second_response
+            .headers()
+            .get("Idempotent-Processed-At")
+            .is_some()
+    );
 }
 
 #[tokio::test]
@@ -171,3 +190,42 @@ async fn patching_nonexistent_message_returns_not_found() {
     let response = app.patch_message(&patch_body).await;
     assert_eq!(response.status().as_u16(), 404);
 }
+
+#[tokio::test]
+async fn reused_idempotency_key_with_different_body_is_rejected() {
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    let message = serde_json::json!({
+        "email": "fake@email.com",
+        "sender_name": "John Doe",
+        "message_text": "Message text."
+    });
+    app.post_message(&message).await;
+    let messages_response: MessagesResponse = app
+        .get_messages()
+        .await
+        .json()
+        .await
+        .expect("Failed to parse messages response");
+
+    let idempotency_key = Uuid::new_v4();
+    let first_body = MessageToPatch {
+        message_id: messages_response.messages[0].message_id,
+        read: true,
+    };
+    let second_body = MessageToPatch {
+        message_id: first_body.message_id,
+        read: false,
+    };
+
+    let response = app
+        .patch_message_with_reused_key(&first_body, &idempotency_key)
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    let response = app
+        .patch_message_with_reused_key(&second_body, &idempotency_key)
+        .await;
+
+    assert_eq!(response.status().as_u16(), 422);
+}
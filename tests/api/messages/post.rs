@@ -1,4 +1,4 @@
-use crate::helpers::spawn_app;
+use crate::helpers::{spawn_app, spawn_app_with};
 
 #[derive(serde::Deserialize, Debug)]
 struct MessageResponse {
@@ -53,6 +53,57 @@ async fn duplicate_messages_are_not_accepted() {
     assert_eq!(response.status().as_u16(), 409);
 }
 
+#[tokio::test]
+async fn duplicates_are_caught_however_the_email_is_written() {
+    let app = spawn_app().await;
+    let message = |email: &str| {
+        serde_json::json!({
+            "email": email,
+            "sender_name": "John Doe",
+            "message_text": "Message text.",
+        })
+    };
+
+    let first = app.post_message(&message(" Fake@Email.COM ")).await;
+    let second = app.post_message(&message("fake@email.com")).await;
+
+    assert_eq!(first.status().as_u16(), 202);
+    assert_eq!(second.status().as_u16(), 409);
+    let saved = sqlx::query_scalar!("SELECT email FROM messages")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved, vec!["fake@email.com".to_string()]);
+}
+
+#[tokio::test]
+async fn plus_addresses_share_a_rate_limit_when_stripped() {
+    let app = spawn_app_with(|c| c.contact.strip_plus_addresses = true).await;
+
+    for i in 0..3 {
+        let message = serde_json::json!({
+            "email": format!("tester+{i}@example.com"),
+            "sender_name": "Rate Tester",
+            "message_text": format!("Message number: {i}")
+        });
+        let response = app.post_message(&message).await;
+        assert_eq!(response.status().as_u16(), 202);
+    }
+    let message = serde_json::json!({
+        "email": "Tester@Example.com",
+        "sender_name": "Rate Tester",
+        "message_text": "Fourth message should fail",
+    });
+    let response = app.post_message(&message).await;
+
+    assert_eq!(response.status().as_u16(), 429);
+    let saved = sqlx::query_scalar!("SELECT email FROM messages")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved, vec!["tester@example.com".to_string(); 3]);
+}
+
 #[tokio::test]
 async fn invalid_emails_are_rejected() {
     let app = spawn_app().await;
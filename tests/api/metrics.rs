@@ -0,0 +1,1433 @@
+use portfolio_server::metrics::{cleanup_old_metrics, days_pending_rollup, rollup_daily_metrics};
+
+use crate::helpers::{spawn_app, spawn_app_with};
+
+#[tokio::test]
+async fn metrics_are_exposed_in_prometheus_format() {
+    // arrange
+    let app = spawn_app().await;
+    app.generic_request().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.text().await.unwrap();
+    assert!(body.contains(
+        r#"portfolio_http_requests_total{method="GET",path="/health_check",status="200"} 1"#
+    ));
+    assert!(body.contains("portfolio_http_request_duration_seconds_bucket"));
+}
+
+#[tokio::test]
+async fn quiet_routes_are_only_counted_by_their_pattern() {
+    // arrange
+    let app =
+        spawn_app_with(|c| c.telemetry.quiet_routes = vec!["/health_check".to_string()]).await;
+    app.generic_request().await;
+    app.generic_request().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    let body = response.text().await.unwrap();
+    assert!(
+        body.contains(
+            r#"portfolio_http_quiet_requests_total{route="/health_check",status="200"} 2"#
+        )
+    );
+    assert!(!body.contains(r#"path="/health_check""#));
+}
+
+#[tokio::test]
+async fn requests_are_not_tracked_when_metrics_are_disabled() {
+    // arrange
+    let app = spawn_app_with(|c| c.telemetry.metrics_enabled = false).await;
+    app.generic_request().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.text().await.unwrap();
+    assert!(!body.contains(r#"path="/health_check""#));
+}
+
+#[tokio::test]
+async fn active_sessions_gauge_counts_sessions_in_the_store() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.active_sessions_interval_secs = 1).await;
+    app.test_user.login(&app).await;
+
+    // act
+    // the sampler runs on its own schedule, give it a few ticks
+    let mut active_sessions = 0;
+    for _ in 0..10 {
+        let body = app
+            .api_client
+            .get(format!("{}/metrics", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap();
+        active_sessions = body
+            .lines()
+            .find_map(|line| line.strip_prefix("portfolio_active_sessions "))
+            .and_then(|value| value.parse::<i64>().ok())
+            .unwrap_or(0);
+        if active_sessions > 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    // assert
+    // other tests share the store, so only a lower bound is meaningful
+    assert!(active_sessions >= 1);
+}
+
+#[tokio::test]
+async fn database_pool_gauges_are_exported() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.pool_sample_interval_secs = 1).await;
+    app.generic_request().await;
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    let body = response.text().await.unwrap();
+    let idle = body
+        .lines()
+        .find_map(|line| line.strip_prefix("portfolio_db_connections_idle "))
+        .and_then(|value| value.parse::<i64>().ok())
+        .expect("db_connections_idle not exported");
+    // the requests above opened at least one connection and returned it to the pool
+    assert!(idle >= 1);
+    assert!(body.contains("portfolio_db_connections_active"));
+}
+
+#[tokio::test]
+async fn redis_pool_gauges_are_exported() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.pool_sample_interval_secs = 1).await;
+    app.generic_request().await;
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    let body = response.text().await.unwrap();
+    let idle = body
+        .lines()
+        .find_map(|line| line.strip_prefix("portfolio_redis_connections_idle "))
+        .and_then(|value| value.parse::<i64>().ok())
+        .expect("redis_connections_idle not exported");
+    // startup checks out one connection to prove the server is reachable, then returns it
+    assert!(idle >= 1);
+    assert!(body.contains("portfolio_redis_connections_active"));
+    assert!(body.contains("portfolio_redis_connections_waiting"));
+}
+
+#[tokio::test]
+async fn requests_rejected_by_middleware_are_counted() {
+    // arrange
+    let app = spawn_app().await;
+    app.get_messages().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    let body = response.text().await.unwrap();
+    assert!(body.contains(r#"path="/v1/admin/messages",status="401""#));
+}
+
+#[tokio::test]
+async fn realtime_stats_require_an_admin() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/realtime", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn realtime_stats_are_streamed_as_server_sent_events() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.metrics.realtime_interval_secs = 1;
+        c.metrics.active_sessions_interval_secs = 1;
+    })
+    .await;
+    app.post_page_visit_batch(&serde_json::json!({
+        "visits": [{ "path": "/blog", "session_id": uuid::Uuid::new_v4() }]
+    }))
+    .await;
+    app.flush_page_visits().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let mut response = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/realtime", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+    // the first frames may predate the login, the visit or the sessions sampler's first count
+    let mut stats = serde_json::Value::Null;
+    for _ in 0..10 {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(10), response.chunk())
+            .await
+            .expect("No event within the timeout")
+            .unwrap()
+            .unwrap();
+        let event = String::from_utf8(chunk.to_vec()).unwrap();
+        let json = event
+            .strip_prefix("data: ")
+            .and_then(|data| data.strip_suffix("\n\n"))
+            .expect("Not a server-sent event");
+        stats = serde_json::from_str(json).unwrap();
+        // active_users can be non-zero from other tests' sessions before this test's visit lands
+        if stats["active_users"].as_i64() > Some(0)
+            && stats["current_page_views"]
+                .as_array()
+                .is_some_and(|views| !views.is_empty())
+        {
+            break;
+        }
+    }
+    // other tests share the session store, so only a lower bound is meaningful
+    assert!(stats["active_users"].as_i64() >= Some(1));
+    assert_eq!(
+        stats["current_page_views"],
+        serde_json::json!([{ "path": "/blog", "viewers": 1 }])
+    );
+}
+
+#[tokio::test]
+async fn realtime_snapshot_shows_current_pages_and_recent_errors() {
+    // arrange
+    let app = spawn_app().await;
+    let reader = uuid::Uuid::new_v4();
+    // a written batch shares one timestamp, the earlier page goes in on its own
+    app.post_page_visit_batch(&serde_json::json!({
+        "visits": [{ "path": "/", "session_id": reader }]
+    }))
+    .await;
+    app.flush_page_visits().await;
+    app.post_page_visit_batch(&serde_json::json!({
+        "visits": [
+            { "path": "/blog", "session_id": reader },
+            { "path": "/blog", "session_id": uuid::Uuid::new_v4() },
+            { "path": "/about" }
+        ]
+    }))
+    .await;
+    app.flush_page_visits().await;
+    sqlx::query!(
+        r#"
+        INSERT INTO server_metrics (endpoint, method, status_code, response_time_ms)
+        VALUES ('/v1/contact', 'POST', 500, 12.5), ('/v1/blog', 'GET', 200, 3.0)
+        "#
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/realtime-snapshot",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["active_visitors"], 3);
+    assert_eq!(body["current_pages"][0]["path"], "/blog");
+    assert_eq!(body["current_pages"][0]["viewers"], 2);
+    assert_eq!(body["current_pages"][1]["path"], "/about");
+    let errors = body["recent_errors"].as_array().unwrap();
+    assert!(
+        errors
+            .iter()
+            .any(|error| error["endpoint"] == "/v1/contact" && error["status_code"] == 500)
+    );
+    assert!(errors.iter().all(|error| error["status_code"] != 200));
+}
+
+#[tokio::test]
+async fn page_visits_are_recorded_with_a_hashed_session() {
+    // arrange
+    let app = spawn_app().await;
+    let session_id = uuid::Uuid::new_v4();
+
+    // act
+    let response = app
+        .post_page_visit(&serde_json::json!({
+            "path": "/blog/hello-world",
+            "referrer": "https://news.ycombinator.com/",
+            "session_id": session_id,
+            "duration_ms": 4200
+        }))
+        .await;
+    app.flush_page_visits().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let saved = sqlx::query!("SELECT path, session_hash, duration_ms FROM page_visits")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved visit.");
+    assert_eq!(saved.path, "/blog/hello-world");
+    assert_eq!(saved.duration_ms, Some(4200));
+    let session_hash = saved.session_hash.expect("Session hash not stored");
+    assert_ne!(session_hash, session_id.to_string());
+}
+
+#[tokio::test]
+async fn queued_page_visits_are_written_on_the_flush_interval() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.page_visit_flush_interval_ms = 100).await;
+
+    // act
+    let response = app
+        .post_page_visit(&serde_json::json!({ "path": "/queued" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let mut count = 0;
+    for _ in 0..20 {
+        count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM page_visits"#)
+            .fetch_one(&app.db_pool)
+            .await
+            .unwrap();
+        if count > 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn invalid_page_visits_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    let test_cases = vec![
+        (
+            serde_json::json!({ "path": "https://example.com/" }),
+            "absolute url",
+        ),
+        (serde_json::json!({ "path": "" }), "empty path"),
+        (
+            serde_json::json!({ "path": "/", "duration_ms": -5 }),
+            "negative duration",
+        ),
+        (serde_json::json!({ "referrer": "/" }), "missing path"),
+    ];
+
+    for (body, description) in test_cases {
+        // act
+        let response = app.post_page_visit(&body).await;
+
+        // assert
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "The API did not reject a page visit with {description}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn page_visit_batches_store_valid_visits_and_report_invalid_ones() {
+    // arrange
+    let app = spawn_app().await;
+    let session_id = uuid::Uuid::new_v4();
+
+    // act
+    let response = app
+        .post_page_visit_batch(&serde_json::json!({
+            "visits": [
+                { "path": "/", "session_id": session_id, "utm_source": " Newsletter " },
+                { "path": "https://example.com/" },
+                { "path": "/blog", "session_id": session_id, "duration_ms": 1500 }
+            ]
+        }))
+        .await;
+    app.flush_page_visits().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["accepted"], 2);
+    assert_eq!(body["rejected"], 1);
+    assert_eq!(body["results"][1]["index"], 1);
+    assert_eq!(body["results"][1]["accepted"], false);
+    assert!(body["results"][1]["error"].is_string());
+
+    let saved =
+        sqlx::query!("SELECT path, utm_source, session_hash FROM page_visits ORDER BY path")
+            .fetch_all(&app.db_pool)
+            .await
+            .expect("Failed to fetch saved visits.");
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[0].utm_source.as_deref(), Some("newsletter"));
+    assert_eq!(saved[0].session_hash, saved[1].session_hash);
+}
+
+#[tokio::test]
+async fn empty_or_oversized_visit_batches_are_rejected() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.max_visit_batch_size = 2).await;
+    let visit = serde_json::json!({ "path": "/" });
+    let test_cases = vec![
+        (serde_json::json!({ "visits": [] }), "an empty batch"),
+        (
+            serde_json::json!({ "visits": [visit, visit, visit] }),
+            "too many visits",
+        ),
+        (serde_json::json!({ "pages": ["/"] }), "no list of visits"),
+    ];
+
+    for (body, description) in test_cases {
+        // act
+        let response = app.post_page_visit_batch(&body).await;
+
+        // assert
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "The API did not reject a batch with {description}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn performance_metrics_are_recorded() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .post_performance_metric(&serde_json::json!({
+            "path": "/",
+            "metric_name": "LCP",
+            "value": 1830.4
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let saved = sqlx::query!("SELECT metric_name, value FROM performance_metrics")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved metric.");
+    assert_eq!(saved.metric_name, "LCP");
+    assert!((saved.value - 1830.4).abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+async fn unknown_or_invalid_performance_metrics_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    let test_cases = vec![
+        (
+            serde_json::json!({ "path": "/", "metric_name": "FOO", "value": 1.0 }),
+            "unknown metric",
+        ),
+        (
+            serde_json::json!({ "path": "/", "metric_name": "CLS", "value": -0.1 }),
+            "negative value",
+        ),
+        (
+            serde_json::json!({ "path": "no-slash", "metric_name": "CLS", "value": 0.1 }),
+            "relative path",
+        ),
+    ];
+
+    for (body, description) in test_cases {
+        // act
+        let response = app.post_performance_metric(&body).await;
+
+        // assert
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "The API did not reject a metric with {description}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn metrics_ingestion_is_rate_limited_per_ip() {
+    // arrange
+    let app = spawn_app().await;
+    let visit = serde_json::json!({ "path": "/" });
+    let metric = serde_json::json!({ "path": "/", "metric_name": "TTFB", "value": 80 });
+
+    // act
+    // the default ingestion budget is 120 requests, shared by both routes
+    for _ in 0..60 {
+        assert_eq!(app.post_page_visit(&visit).await.status().as_u16(), 202);
+        assert_eq!(
+            app.post_performance_metric(&metric).await.status().as_u16(),
+            202
+        );
+    }
+    let response = app.post_page_visit(&visit).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 429);
+}
+
+#[tokio::test]
+async fn metrics_ingestion_is_rate_limited_per_session() {
+    // arrange
+    let app = spawn_app().await;
+    let session_id = uuid::Uuid::new_v4();
+    let visit = serde_json::json!({ "path": "/", "session_id": session_id });
+    let batch = serde_json::json!({ "visits": [{ "path": "/", "session_id": session_id }] });
+
+    // act
+    // the default per-session budget is 30 requests per route
+    for _ in 0..30 {
+        assert_eq!(app.post_page_visit(&visit).await.status().as_u16(), 202);
+        app.post_page_visit_batch(&batch).await;
+    }
+    let same_session = app.post_page_visit(&visit).await;
+    let same_session_batch = app.post_page_visit_batch(&batch).await;
+    let other_session = app
+        .post_page_visit(&serde_json::json!({ "path": "/", "session_id": uuid::Uuid::new_v4() }))
+        .await;
+
+    // assert
+    assert_eq!(same_session.status().as_u16(), 429);
+    assert_eq!(same_session_batch.status().as_u16(), 429);
+    assert_eq!(other_session.status().as_u16(), 202);
+}
+
+#[tokio::test]
+async fn cleanup_purges_metrics_past_retention() {
+    // arrange
+    let app = spawn_app().await;
+    app.post_page_visit(&serde_json::json!({ "path": "/recent" }))
+        .await;
+    app.flush_page_visits().await;
+    app.post_performance_metric(
+        &serde_json::json!({ "path": "/recent", "metric_name": "CLS", "value": 0.02 }),
+    )
+    .await;
+    sqlx::query!(
+        r#"
+        INSERT INTO page_visits (visit_id, path, created_at)
+        VALUES ($1, '/stale', NOW() - INTERVAL '91 days')
+        "#,
+        uuid::Uuid::new_v4()
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    // act
+    let report = cleanup_old_metrics(90, &app.db_pool)
+        .await
+        .expect("Cleanup failed");
+
+    // assert
+    assert_eq!(report.page_visits, 1);
+    assert_eq!(report.performance_metrics, 0);
+    let remaining = sqlx::query_scalar!(r#"SELECT path AS "path!" FROM page_visits"#)
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec!["/recent".to_string()]);
+}
+
+#[tokio::test]
+async fn summaries_read_rolled_up_days_once_raw_rows_are_gone() {
+    // arrange
+    let app = spawn_app().await;
+    app.post_page_visit(&serde_json::json!({ "path": "/" }))
+        .await;
+    app.flush_page_visits().await;
+    for path in ["/", "/blog", "/blog"] {
+        sqlx::query!(
+            r#"
+            INSERT INTO page_visits (visit_id, path, created_at)
+            VALUES ($1, $2, NOW() - INTERVAL '5 days')
+            "#,
+            uuid::Uuid::new_v4(),
+            path
+        )
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    }
+    for value in [100.0, 300.0] {
+        sqlx::query!(
+            r#"
+            INSERT INTO performance_metrics (metric_id, path, metric_name, value, created_at)
+            VALUES ($1, '/', 'LCP', $2, NOW() - INTERVAL '5 days')
+            "#,
+            uuid::Uuid::new_v4(),
+            value
+        )
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    }
+
+    // act
+    let pending = days_pending_rollup(&app.db_pool).await.unwrap();
+    assert_eq!(pending.len(), 5);
+    for day in pending {
+        rollup_daily_metrics(day, &app.db_pool).await.unwrap();
+    }
+    assert!(days_pending_rollup(&app.db_pool).await.unwrap().is_empty());
+    // only the rollups can answer for those days now
+    sqlx::query!("DELETE FROM page_visits WHERE created_at < NOW() - INTERVAL '1 day'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    sqlx::query!("DELETE FROM performance_metrics")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    app.test_user.login(&app).await;
+
+    // assert
+    let summary: serde_json::Value = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/summary?window=7d",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(summary["visits"], 4);
+    assert_eq!(summary["top_paths"][0]["path"], "/");
+    assert_eq!(summary["top_paths"][0]["visits"], 2);
+    assert_eq!(summary["top_paths"][1]["path"], "/blog");
+    assert_eq!(summary["top_paths"][1]["visits"], 2);
+
+    let vitals: serde_json::Value = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/vitals?window=7d",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(vitals["vitals"][0]["samples"], 2);
+    assert_eq!(vitals["vitals"][0]["p50"], 200.0);
+
+    let short_window: serde_json::Value = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/summary?window=24h",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(short_window["visits"], 1);
+}
+
+#[tokio::test]
+async fn handled_requests_are_written_to_server_metrics() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    app.generic_request().await;
+
+    // assert
+    // rows are flushed in the background, on an interval when the batch isn't full
+    let mut rows = Vec::new();
+    for _ in 0..20 {
+        rows = sqlx::query!(
+            "SELECT method, status_code, response_time_ms FROM server_metrics WHERE endpoint = '/health_check'"
+        )
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+        if !rows.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].method, "GET");
+    assert_eq!(rows[0].status_code, 200);
+    assert!(rows[0].response_time_ms >= 0.0);
+}
+
+#[tokio::test]
+async fn errors_are_broken_down_by_route_and_status_class() {
+    // arrange
+    let app = spawn_app().await;
+    sqlx::query!(
+        r#"
+        INSERT INTO server_metrics (endpoint, method, status_code, response_time_ms)
+        VALUES
+            ('/v1/contact', 'POST', 409, 4.0),
+            ('/v1/contact', 'POST', 409, 4.0),
+            ('/v1/contact', 'POST', 422, 4.0),
+            ('/v1/contact', 'POST', 500, 4.0),
+            ('/v1/contact', 'POST', 200, 4.0),
+            ('/v1/blog', 'GET', 200, 4.0)
+        "#
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/errors?window=24h",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["window_hours"], 24);
+    let errors = body["errors"].as_array().unwrap();
+    let contact: Vec<_> = errors
+        .iter()
+        .filter(|row| row["endpoint"] == "/v1/contact")
+        .collect();
+    assert_eq!(contact.len(), 2);
+    assert_eq!(contact[0]["status_class"], "4xx");
+    assert_eq!(contact[0]["status_codes"], serde_json::json!([409, 422]));
+    assert_eq!(contact[0]["errors"], 3);
+    assert_eq!(contact[0]["requests"], 5);
+    assert_eq!(contact[1]["status_class"], "5xx");
+    assert_eq!(contact[1]["errors"], 1);
+    assert!(errors.iter().all(|row| row["endpoint"] != "/v1/blog"));
+}
+
+#[tokio::test]
+async fn requests_over_the_threshold_are_listed_as_slow() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.metrics.slow_request_threshold_ms = 1;
+        c.metrics.server_metrics_flush_interval_ms = 100;
+    })
+    .await;
+
+    // act
+    // password hashing keeps a login well over a millisecond
+    app.test_user.login(&app).await;
+
+    // assert
+    let mut body = serde_json::Value::Null;
+    for _ in 0..20 {
+        let response = app
+            .api_client
+            .get(format!("{}/v1/admin/metrics/slow-requests", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_eq!(response.status().as_u16(), 200);
+        body = response.json().await.unwrap();
+        if body["requests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|request| request["path"] == "/v1/login")
+        {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+    assert_eq!(body["threshold_ms"], 1);
+    let login = body["requests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|request| request["path"] == "/v1/login")
+        .expect("login was not recorded as slow");
+    assert_eq!(login["method"], "POST");
+    assert!(login["duration_ms"].as_f64().unwrap() >= 1.0);
+}
+
+#[tokio::test]
+async fn slow_requests_are_not_captured_when_the_threshold_is_zero() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.metrics.slow_request_threshold_ms = 0;
+        c.metrics.server_metrics_flush_interval_ms = 100;
+    })
+    .await;
+
+    // act
+    app.test_user.login(&app).await;
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    // assert
+    let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM slow_requests"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn campaign_breakdown_groups_visits_by_utm_parameters() {
+    // arrange
+    let app = spawn_app().await;
+    let newsletter = serde_json::json!({
+        "path": "/blog/hello-world",
+        "utm_source": "Newsletter",
+        "utm_medium": "email",
+        "utm_campaign": "launch"
+    });
+    app.post_page_visit(&newsletter).await;
+    app.post_page_visit(&newsletter).await;
+    app.post_page_visit(&serde_json::json!({ "path": "/", "utm_source": "mastodon" }))
+        .await;
+    app.post_page_visit(&serde_json::json!({ "path": "/" }))
+        .await;
+    app.flush_page_visits().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/campaigns?window=7d",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["window_hours"], 168);
+    let campaigns = body["campaigns"].as_array().unwrap();
+    assert_eq!(campaigns.len(), 2);
+    assert_eq!(campaigns[0]["utm_source"], "newsletter");
+    assert_eq!(campaigns[0]["utm_campaign"], "launch");
+    assert_eq!(campaigns[0]["visits"], 2);
+    assert_eq!(campaigns[1]["utm_source"], "mastodon");
+    assert_eq!(campaigns[1]["utm_medium"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn campaign_breakdown_rejects_invalid_windows() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/campaigns?window=forever",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn vitals_are_reported_as_percentiles_per_path() {
+    // arrange
+    let app = spawn_app().await;
+    for value in [100, 200, 300, 400, 500] {
+        app.post_performance_metric(&serde_json::json!({
+            "path": "/",
+            "metric_name": "LCP",
+            "value": value
+        }))
+        .await;
+    }
+    app.post_performance_metric(
+        &serde_json::json!({ "path": "/blog", "metric_name": "CLS", "value": 0.1 }),
+    )
+    .await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/vitals?window=24h",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["window_hours"], 24);
+    let vitals = body["vitals"].as_array().unwrap();
+    assert_eq!(vitals.len(), 2);
+    let lcp = vitals
+        .iter()
+        .find(|vital| vital["metric_name"] == "LCP")
+        .unwrap();
+    assert_eq!(lcp["path"], "/");
+    assert_eq!(lcp["samples"], 5);
+    assert_eq!(lcp["p50"], 300.0);
+    assert_eq!(lcp["p75"], 400.0);
+    assert_eq!(lcp["p95"], 480.0);
+}
+
+#[tokio::test]
+async fn sessions_report_depth_duration_and_bounce_rate() {
+    // arrange
+    let app = spawn_app().await;
+    let reader = uuid::Uuid::new_v4();
+    let bouncer = uuid::Uuid::new_v4();
+    app.post_page_visit_batch(&serde_json::json!({
+        "visits": [
+            { "path": "/", "session_id": reader, "duration_ms": 1000 },
+            { "path": "/blog", "session_id": reader, "duration_ms": 3000 },
+            { "path": "/about", "session_id": bouncer, "duration_ms": 500 },
+            { "path": "/" }
+        ]
+    }))
+    .await;
+    app.flush_page_visits().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/sessions?window=24h",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["stats"]["sessions"], 2);
+    assert_eq!(body["stats"]["avg_page_depth"], 1.5);
+    assert_eq!(body["stats"]["avg_duration_ms"], 2250.0);
+    assert_eq!(body["stats"]["bounce_rate"], 0.5);
+    let sessions = body["sessions"].as_array().unwrap();
+    let deepest = sessions
+        .iter()
+        .find(|session| session["page_depth"] == 2)
+        .unwrap();
+    assert_eq!(deepest["total_duration_ms"], 4000);
+}
+
+#[tokio::test]
+async fn metrics_summary_includes_visits_top_paths_and_sessions() {
+    // arrange
+    let app = spawn_app().await;
+    let session_id = uuid::Uuid::new_v4();
+    app.post_page_visit_batch(&serde_json::json!({
+        "visits": [
+            { "path": "/blog", "session_id": session_id },
+            { "path": "/blog" },
+            { "path": "/" }
+        ]
+    }))
+    .await;
+    app.flush_page_visits().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/summary", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["visits"], 3);
+    assert_eq!(body["top_paths"][0]["path"], "/blog");
+    assert_eq!(body["top_paths"][0]["visits"], 2);
+    assert_eq!(body["sessions"]["sessions"], 1);
+    assert_eq!(body["sessions"]["bounce_rate"], 1.0);
+}
+
+#[tokio::test]
+async fn sampled_rows_are_scaled_back_up_in_reports() {
+    // arrange
+    let app = spawn_app().await;
+    for (path, campaign, sample_rate) in [
+        ("/", Some("launch"), 0.1),
+        ("/", None, 0.1),
+        ("/blog", None, 1.0),
+    ] {
+        sqlx::query!(
+            r#"
+            INSERT INTO page_visits (visit_id, path, utm_campaign, sample_rate)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            uuid::Uuid::new_v4(),
+            path,
+            campaign,
+            sample_rate
+        )
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    }
+    app.test_user.login(&app).await;
+
+    // act
+    let summary: serde_json::Value = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/summary", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap();
+    let campaigns: serde_json::Value = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/campaigns", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(summary["visits"], 21);
+    assert_eq!(summary["top_paths"][0]["path"], "/");
+    assert_eq!(summary["top_paths"][0]["visits"], 20);
+    assert_eq!(campaigns["campaigns"][0]["visits"], 10);
+}
+
+#[tokio::test]
+async fn analytics_exports_stream_csv_for_the_requested_days() {
+    // arrange
+    let app = spawn_app().await;
+    app.post_page_visit(&serde_json::json!({ "path": "/today", "utm_source": "rss" }))
+        .await;
+    app.flush_page_visits().await;
+    sqlx::query!(
+        r#"
+        INSERT INTO page_visits (visit_id, path, created_at)
+        VALUES ($1, '/last-month', NOW() - INTERVAL '30 days')
+        "#,
+        uuid::Uuid::new_v4()
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    app.test_user.login(&app).await;
+    let today = chrono::Utc::now().date_naive();
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/export?dataset=visits&from={}&to={today}",
+            &app.address,
+            today - chrono::Days::new(1)
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+    let body = response.text().await.unwrap();
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("visit_id,path,referrer"));
+    assert!(lines[1].contains(",/today,"));
+    assert!(lines[1].contains(",rss,"));
+}
+
+#[tokio::test]
+async fn analytics_exports_can_be_newline_delimited_json() {
+    // arrange
+    let app = spawn_app().await;
+    for value in [120.0, 80.0] {
+        app.post_performance_metric(
+            &serde_json::json!({ "path": "/", "metric_name": "TTFB", "value": value }),
+        )
+        .await;
+    }
+    app.test_user.login(&app).await;
+    let today = chrono::Utc::now().date_naive();
+
+    // act
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/v1/admin/metrics/export?dataset=performance&from={today}&to={today}&format=json",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.text().await.unwrap();
+    let rows: Vec<serde_json::Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["metric_name"], "TTFB");
+    assert_eq!(rows[0]["value"], 120.0);
+}
+
+#[tokio::test]
+async fn analytics_exports_can_be_stored_for_later_download() {
+    // arrange
+    let app = spawn_app().await;
+    app.post_page_visit(&serde_json::json!({ "path": "/today" }))
+        .await;
+    app.flush_page_visits().await;
+    app.test_user.login(&app).await;
+    let today = chrono::Utc::now().date_naive();
+
+    // act
+    let response = app
+        .api_client
+        .post(format!("{}/v1/admin/metrics/export", &app.address))
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .json(&serde_json::json!({ "dataset": "visits", "from": today, "to": today }))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 201);
+    let stored: serde_json::Value = response.json().await.unwrap();
+    assert!(stored["key"].as_str().unwrap().starts_with("exports/"));
+    let download = app
+        .api_client
+        .get(app.local_storage_url(stored["download_url"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(download.status().as_u16(), 200);
+    let body = download.text().await.unwrap();
+    assert_eq!(body.lines().count(), 2);
+    assert!(body.contains(",/today,"));
+}
+
+#[tokio::test]
+async fn analytics_exports_reject_bad_parameters() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let test_cases = vec![
+        (
+            "dataset=visits&from=2026-04-02&to=2026-04-01",
+            "a backwards range",
+        ),
+        (
+            "dataset=visits&from=2024-01-01&to=2026-04-01",
+            "a range over a year",
+        ),
+        (
+            "dataset=users&from=2026-04-01&to=2026-04-01",
+            "an unknown dataset",
+        ),
+        (
+            "dataset=visits&from=yesterday&to=2026-04-01",
+            "an invalid date",
+        ),
+    ];
+
+    for (params, description) in test_cases {
+        // act
+        let response = app
+            .api_client
+            .get(format!("{}/v1/admin/metrics/export?{params}", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        // assert
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "The API did not reject an export with {description}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn infrastructure_metrics_are_empty_without_digitalocean_credentials() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/infrastructure", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["digitalocean_bandwidth_24h"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn alerts_are_mailed_to_the_configured_recipients() {
+    // arrange
+    // stands in for the email api, handing each message to the test
+    let (sender, mut emails) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let email_api = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let server = actix_web::HttpServer::new(move || {
+        let sender = sender.clone();
+        actix_web::App::new().route(
+            "/email",
+            actix_web::web::post().to(move |body: actix_web::web::Json<serde_json::Value>| {
+                let _ = sender.send(body.into_inner());
+                async { actix_web::HttpResponse::Ok().finish() }
+            }),
+        )
+    })
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(server);
+
+    let app = spawn_app_with(|c| {
+        c.alerts.error_rate_percent = Some(5.0);
+        c.alerts.evaluation_interval_secs = 1;
+        c.alerts.email_recipients = vec!["ops@example.com".to_string()];
+        c.email.api_token = Some(secrecy::SecretString::from("postmark-token"));
+        c.email.sender = Some("alerts@example.com".to_string());
+        c.email.api_base_url = email_api;
+    })
+    .await;
+
+    // act
+    sqlx::query!(
+        r#"
+        INSERT INTO server_metrics (endpoint, method, status_code, response_time_ms)
+        VALUES ('/v1/contact', 'POST', 500, 12.5), ('/v1/blog', 'GET', 200, 3.0)
+        "#
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    // assert
+    let email = tokio::time::timeout(std::time::Duration::from_secs(10), emails.recv())
+        .await
+        .expect("No email within the timeout")
+        .unwrap();
+    assert_eq!(email["From"], "alerts@example.com");
+    assert_eq!(email["To"], "ops@example.com");
+    assert_eq!(email["Subject"], "Alert: error rate");
+}
+
+#[tokio::test]
+async fn reports_that_outlast_the_query_timeout_are_a_504() {
+    // arrange
+    let app = spawn_app_with(|c| c.database.query_timeout_ms = 0).await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/admin/metrics/summary", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 504);
+    let problem: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(problem["title"], "Gateway Timeout");
+}
+
+fn sign_visit(key: &str, body: &str) -> String {
+    use hmac::{KeyInit, Mac};
+
+    let timestamp = chrono::Utc::now().timestamp();
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key.as_bytes()).unwrap();
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+#[tokio::test]
+async fn unsigned_visits_are_rejected_once_a_signing_key_is_set() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.ingest_signing_key = Some("frontend-key".into())).await;
+    let body = serde_json::json!({ "path": "/forged" }).to_string();
+
+    // act
+    let unsigned = app
+        .post_page_visit(&serde_json::json!({ "path": "/forged" }))
+        .await;
+    let wrong_key = app
+        .api_client
+        .post(format!("{}/v1/metrics/visit", &app.address))
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .header("Content-Type", "application/json")
+        .header("X-Metrics-Signature", sign_visit("guessed-key", &body))
+        .body(body)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    app.flush_page_visits().await;
+
+    // assert
+    assert_eq!(unsigned.status().as_u16(), 401);
+    assert_eq!(wrong_key.status().as_u16(), 401);
+    let saved = sqlx::query_scalar!(r#"SELECT path AS "path!" FROM page_visits"#)
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert!(saved.is_empty());
+}
+
+#[tokio::test]
+async fn signed_visits_are_recorded() {
+    // arrange
+    let app = spawn_app_with(|c| c.metrics.ingest_signing_key = Some("frontend-key".into())).await;
+    let visit = serde_json::json!({ "path": "/signed" }).to_string();
+    let batch = serde_json::json!({ "visits": [{ "path": "/beacon" }] }).to_string();
+
+    // act
+    let signed = app
+        .api_client
+        .post(format!("{}/v1/metrics/visit", &app.address))
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .header("Content-Type", "application/json")
+        .header("X-Metrics-Signature", sign_visit("frontend-key", &visit))
+        .body(visit)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    // a beacon can't set headers, so its signature rides in the query string
+    let beacon = app
+        .api_client
+        .post(format!("{}/v1/metrics/visits/batch", &app.address))
+        .query(&[("signature", sign_visit("frontend-key", &batch))])
+        .header("Content-Type", "text/plain;charset=UTF-8")
+        .body(batch)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    app.flush_page_visits().await;
+
+    // assert
+    assert_eq!(signed.status().as_u16(), 202);
+    assert_eq!(beacon.status().as_u16(), 202);
+    let mut saved = sqlx::query_scalar!(r#"SELECT path AS "path!" FROM page_visits"#)
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    saved.sort();
+    assert_eq!(saved, ["/beacon", "/signed"]);
+}
@@ -0,0 +1,51 @@
+use portfolio_server::{
+    configuration::get_configuration,
+    startup::{Application, MIGRATOR},
+};
+use uuid::Uuid;
+
+use crate::helpers::{create_database, spawn_app_with};
+
+#[tokio::test]
+async fn the_app_migrates_its_database_on_start_when_asked() {
+    // arrange
+    let app = spawn_app_with(|c| c.application.migrate_on_start = true).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/ready", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn instances_starting_together_migrate_once() {
+    // arrange
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.database.database_name = Uuid::new_v4().to_string();
+    configuration.application.port = 0;
+    configuration.application.migrate_on_start = true;
+    create_database(&configuration.database).await;
+
+    // act
+    let (first, second) = tokio::join!(
+        Application::build(configuration.clone()),
+        Application::build(configuration.clone())
+    );
+
+    // assert
+    assert!(first.is_ok() && second.is_ok());
+    let pool = sqlx::PgPool::connect_with(configuration.database.connect_options())
+        .await
+        .unwrap();
+    let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(applied, i64::try_from(MIGRATOR.iter().count()).unwrap());
+}
@@ -0,0 +1,104 @@
+use crate::helpers::{TestApp, spawn_app};
+
+async fn post_entry(app: &TestApp, kind: &str, entry_date: &str, title: &str) -> String {
+    let response = app
+        .post_now_entry(&serde_json::json!({
+            "kind": kind,
+            "entry_date": entry_date,
+            "title": title,
+            "body": "Details to follow"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["entry_id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_entries() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .post_now_entry(&serde_json::json!({
+            "kind": "now",
+            "entry_date": "2026-04-27",
+            "title": "Learning Zig"
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn entries_are_listed_by_kind_newest_first() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_entry(
+        &app,
+        "now",
+        "2026-03-01",
+        "Reading Designing Data-Intensive Applications",
+    )
+    .await;
+    post_entry(&app, "now", "2026-04-01", "Learning Zig").await;
+    post_entry(&app, "changelog", "2026-04-15", "Added a dark theme").await;
+
+    // act
+    let now: serde_json::Value = app.get_now_entries("?kind=now").await.json().await.unwrap();
+    let everything: serde_json::Value = app.get_now_entries("").await.json().await.unwrap();
+
+    // assert
+    assert_eq!(now.as_array().unwrap().len(), 2);
+    assert_eq!(now[0]["title"], "Learning Zig");
+    assert_eq!(everything.as_array().unwrap().len(), 3);
+    assert_eq!(everything[0]["kind"], "changelog");
+}
+
+#[tokio::test]
+async fn edited_entries_can_move_to_the_changelog() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let entry_id = post_entry(&app, "now", "2026-04-01", "Rewriting the site in Rust").await;
+
+    // act
+    let response = app
+        .edit_now_entry(&serde_json::json!({ "entry_id": entry_id, "kind": "changelog" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let changelog: serde_json::Value = app
+        .get_now_entries("?kind=changelog")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(changelog[0]["title"], "Rewriting the site in Rust");
+}
+
+#[tokio::test]
+async fn deleted_entries_are_gone() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let entry_id = post_entry(&app, "now", "2026-04-01", "Learning Zig").await;
+
+    // act
+    let response = app
+        .delete_now_entry(&serde_json::json!({ "entry_id": entry_id }))
+        .await;
+    let again = app
+        .delete_now_entry(&serde_json::json!({ "entry_id": entry_id }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(again.status().as_u16(), 404);
+    let entries: serde_json::Value = app.get_now_entries("").await.json().await.unwrap();
+    assert!(entries.as_array().unwrap().is_empty());
+}
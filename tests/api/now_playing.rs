@@ -0,0 +1,118 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use std::collections::HashMap;
+
+use crate::helpers::{TestApp, spawn_app, spawn_app_with};
+
+async fn token(form: web::Form<HashMap<String, String>>) -> HttpResponse {
+    if form.get("refresh_token").map(String::as_str) != Some("refresh-token") {
+        return HttpResponse::BadRequest().finish();
+    }
+    HttpResponse::Ok().json(serde_json::json!({
+        "access_token": "access-token",
+        "token_type": "Bearer",
+        "expires_in": 3600
+    }))
+}
+
+async fn recently_played(request: HttpRequest) -> HttpResponse {
+    let authorized = request
+        .headers()
+        .get("Authorization")
+        .is_some_and(|value| value == "Bearer access-token");
+    if !authorized {
+        return HttpResponse::Unauthorized().finish();
+    }
+    HttpResponse::Ok().json(serde_json::json!({
+        "items": [{
+            "played_at": "2026-04-20T21:15:00.000Z",
+            "track": {
+                "name": "Teardrop",
+                "artists": [{ "name": "Massive Attack" }],
+                "album": {
+                    "name": "Mezzanine",
+                    "images": [{ "url": "https://i.scdn.co/image/mezzanine" }]
+                },
+                "external_urls": { "spotify": "https://open.spotify.com/track/teardrop" }
+            }
+        }]
+    }))
+}
+
+// stands in for spotify's accounts and web apis, nothing is playing and the last track is
+// only handed out to the access token the refresh token was exchanged for
+fn spawn_spotify_api() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let spotify_api = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let server = actix_web::HttpServer::new(|| {
+        actix_web::App::new()
+            .route("/api/token", web::post().to(token))
+            .route(
+                "/v1/me/player/currently-playing",
+                web::get().to(|| async { HttpResponse::NoContent().finish() }),
+            )
+            .route(
+                "/v1/me/player/recently-played",
+                web::get().to(recently_played),
+            )
+    })
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(server);
+    spotify_api
+}
+
+async fn wait_for_track(app: &TestApp) -> reqwest::Response {
+    for _ in 0..50 {
+        let response = app.get_now_playing().await;
+        if response.status().as_u16() == 200 {
+            return response;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("No track within the timeout");
+}
+
+#[tokio::test]
+async fn now_playing_is_empty_without_spotify_credentials() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_now_playing().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 204);
+}
+
+#[tokio::test]
+async fn the_last_played_track_is_served_when_nothing_is_playing() {
+    // arrange
+    let spotify_api = spawn_spotify_api();
+    let app = spawn_app_with(|c| {
+        c.spotify.client_id = Some("client-id".to_string());
+        c.spotify.client_secret = Some(secrecy::SecretString::from("client-secret"));
+        c.spotify.refresh_token = Some(secrecy::SecretString::from("refresh-token"));
+        c.spotify.accounts_base_url.clone_from(&spotify_api);
+        c.spotify.api_base_url = spotify_api;
+    })
+    .await;
+
+    // act
+    let response = wait_for_track(&app).await;
+
+    // assert
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["is_playing"], false);
+    assert_eq!(body["track"]["title"], "Teardrop");
+    assert_eq!(body["track"]["artists"][0], "Massive Attack");
+    assert_eq!(
+        body["track"]["album_art_url"],
+        "https://i.scdn.co/image/mezzanine"
+    );
+    assert_eq!(body["played_at"], "2026-04-20T21:15:00Z");
+    // neither token is ever handed to the client
+    let body = body.to_string();
+    assert!(!body.contains("access-token"));
+    assert!(!body.contains("refresh-token"));
+}
@@ -0,0 +1,80 @@
+use crate::helpers::spawn_app;
+
+async fn problem(response: reqwest::Response) -> serde_json::Value {
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn validation_errors_are_problems_with_a_detail() {
+    // arrange
+    let app = spawn_app().await;
+    let message = serde_json::json!({
+        "email": "fake",
+        "sender_name": "John Doe",
+        "message_text": "Message text."
+    });
+
+    // act
+    let response = app.post_message(&message).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+    let body = problem(response).await;
+    assert_eq!(body["type"], "about:blank");
+    assert_eq!(body["title"], "Bad Request");
+    assert_eq!(body["status"], 400);
+    assert_eq!(body["detail"], "Invalid email");
+    assert!(body["request_id"].is_string());
+}
+
+#[tokio::test]
+async fn bare_error_responses_become_problems() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_scheduler_status().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+    let body = problem(response).await;
+    assert_eq!(body["title"], "Unauthorized");
+    assert!(body["request_id"].is_string());
+}
+
+#[tokio::test]
+async fn unknown_routes_are_problems() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/nothing-here", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+    let body = problem(response).await;
+    assert_eq!(body["status"], 404);
+    assert_eq!(body.get("detail"), None);
+}
+
+#[tokio::test]
+async fn request_ids_differ_between_requests() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let first = problem(app.get_scheduler_status().await).await;
+    let second = problem(app.get_scheduler_status().await).await;
+
+    // assert
+    assert_ne!(first["request_id"], second["request_id"]);
+}
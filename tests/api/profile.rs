@@ -0,0 +1,77 @@
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn unauthorized_users_cannot_edit_the_profile() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .edit_profile(&serde_json::json!({ "display_name": "Someone else" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn profile_edits_are_public() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .edit_profile(&serde_json::json!({
+            "display_name": "Calvin DeVogel",
+            "bio": "I build backends.",
+            "avatar_url": "/media/avatar.jpg",
+            "social_links": [
+                { "platform": "github", "url": "https://github.com/calvin-devogel" }
+            ],
+            "contact_available": false
+        }))
+        .await;
+    let profile = app.get_profile().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    assert_eq!(profile.status().as_u16(), 200);
+    assert!(profile.headers().contains_key("last-modified"));
+    let profile: serde_json::Value = profile.json().await.unwrap();
+    assert_eq!(profile["display_name"], "Calvin DeVogel");
+    assert_eq!(profile["social_links"][0]["platform"], "github");
+    assert_eq!(profile["contact_available"], false);
+}
+
+#[tokio::test]
+async fn an_empty_avatar_url_removes_the_avatar() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.edit_profile(&serde_json::json!({ "avatar_url": "/media/avatar.jpg" }))
+        .await;
+
+    // act
+    let response = app
+        .edit_profile(&serde_json::json!({ "avatar_url": "" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let profile: serde_json::Value = app.get_profile().await.json().await.unwrap();
+    assert!(profile["avatar_url"].is_null());
+}
+
+#[tokio::test]
+async fn empty_profile_edits_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app.edit_profile(&serde_json::json!({})).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
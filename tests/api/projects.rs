@@ -0,0 +1,270 @@
+use crate::helpers::{TestApp, spawn_app};
+
+fn project(title: &str, published: bool) -> serde_json::Value {
+    serde_json::json!({
+        "title": title,
+        "description": "The api behind this site",
+        "content": [{"type": "markdown", "content": "How it was built..."}],
+        "tech_tags": ["rust", "postgres"],
+        "repo_url": "https://github.com/calvin-devogel/portfolio-server",
+        "images": [{"src": "/media/screenshot.png", "alt": "The dashboard"}],
+        "published": published
+    })
+}
+
+async fn create_project(app: &TestApp, title: &str, published: bool) -> String {
+    let response = app.post_project(&project(title, published)).await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["project_id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_projects() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.post_project(&project("Portfolio server", true)).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn only_published_projects_are_public() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_project(&app, "Portfolio server", true).await;
+    create_project(&app, "Secret side project", false).await;
+
+    // act
+    let public = app.get_projects().await;
+    let admin = app.get_all_projects().await;
+
+    // assert
+    assert_eq!(public.status().as_u16(), 200);
+    let public: serde_json::Value = public.json().await.unwrap();
+    assert_eq!(public.as_array().unwrap().len(), 1);
+    assert_eq!(public[0]["slug"], "portfolio-server");
+    assert_eq!(
+        public[0]["tech_tags"],
+        serde_json::json!(["rust", "postgres"])
+    );
+    assert_eq!(public[0]["content"][0]["type"], "markdown");
+    assert_eq!(public[0]["images"][0]["alt"], "The dashboard");
+    let admin: serde_json::Value = admin.json().await.unwrap();
+    assert_eq!(admin.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn projects_with_bad_data_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let mut bad_link = project("Portfolio server", true);
+    bad_link["demo_url"] = "javascript:alert(1)".into();
+
+    // act
+    let response = app.post_project(&bad_link).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn posting_a_duplicate_project_returns_conflict() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_project(&app, "Portfolio server", true).await;
+
+    // act
+    let response = app.post_project(&project("Portfolio server", false)).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn projects_can_be_edited() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let project_id = create_project(&app, "Portfolio server", false).await;
+
+    // act
+    let response = app
+        .edit_project(&serde_json::json!({
+            "project_id": project_id,
+            "description": "Now with projects",
+            "repo_url": "",
+            "published": true
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let public: serde_json::Value = app.get_projects().await.json().await.unwrap();
+    assert_eq!(public[0]["description"], "Now with projects");
+    assert_eq!(public[0]["repo_url"], serde_json::Value::Null);
+    assert_eq!(public[0]["title"], "Portfolio server");
+}
+
+#[tokio::test]
+async fn empty_edits_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let project_id = create_project(&app, "Portfolio server", false).await;
+
+    // act
+    let response = app
+        .edit_project(&serde_json::json!({ "project_id": project_id }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn projects_can_be_deleted() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let project_id = create_project(&app, "Portfolio server", true).await;
+
+    // act
+    let deleted = app
+        .delete_project(&serde_json::json!({ "project_id": project_id }))
+        .await;
+    let again = app
+        .delete_project(&serde_json::json!({ "project_id": project_id }))
+        .await;
+
+    // assert
+    assert_eq!(deleted.status().as_u16(), 200);
+    assert_eq!(again.status().as_u16(), 404);
+    let public: serde_json::Value = app.get_projects().await.json().await.unwrap();
+    assert!(public.as_array().unwrap().is_empty());
+}
+
+async fn public_titles(app: &TestApp) -> Vec<String> {
+    let projects: serde_json::Value = app.get_projects().await.json().await.unwrap();
+    projects
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|project| project["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn new_projects_lead_the_grid() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_project(&app, "First", true).await;
+    create_project(&app, "Second", true).await;
+
+    // act
+    let titles = public_titles(&app).await;
+
+    // assert
+    assert_eq!(titles, ["Second", "First"]);
+}
+
+#[tokio::test]
+async fn projects_can_be_reordered() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let first = create_project(&app, "First", true).await;
+    let second = create_project(&app, "Second", true).await;
+    let third = create_project(&app, "Third", true).await;
+
+    // act
+    let response = app
+        .reorder_projects(&serde_json::json!({ "project_ids": [first, third, second] }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    assert_eq!(public_titles(&app).await, ["First", "Third", "Second"]);
+}
+
+#[tokio::test]
+async fn partial_or_repeated_orders_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let first = create_project(&app, "First", true).await;
+    let second = create_project(&app, "Second", true).await;
+    let test_cases = [
+        (serde_json::json!([first]), "a missing project"),
+        (serde_json::json!([first, first]), "a repeated project"),
+        (
+            serde_json::json!([first, second, uuid::Uuid::new_v4()]),
+            "an unknown project",
+        ),
+    ];
+
+    for (project_ids, description) in test_cases {
+        // act
+        let response = app
+            .reorder_projects(&serde_json::json!({ "project_ids": project_ids }))
+            .await;
+
+        // assert
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "The API did not reject an order with {description}"
+        );
+    }
+    assert_eq!(public_titles(&app).await, ["Second", "First"]);
+}
+
+#[tokio::test]
+async fn a_case_study_is_served_by_slug_with_its_blocks_in_order() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let mut body = project("Portfolio server", true);
+    body["content"] = serde_json::json!([
+        {"type": "markdown", "content": "## The problem"},
+        {"type": "carousel", "label": "Before and after", "slides": [{"src": "/media/before.png"}]},
+        {"type": "markdown", "content": "## What shipped"}
+    ]);
+    let response = app.post_project(&body).await;
+    assert_eq!(response.status().as_u16(), 202);
+
+    // act
+    let response = app.get_project("portfolio-server").await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().contains_key("Last-Modified"));
+    let project: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(project["content"][0]["content"], "## The problem");
+    assert_eq!(project["content"][1]["type"], "carousel");
+    assert_eq!(project["content"][2]["content"], "## What shipped");
+}
+
+#[tokio::test]
+async fn drafts_are_only_previewed_by_signed_in_users() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_project(&app, "Secret side project", false).await;
+
+    // act
+    let preview = app.get_project("secret-side-project").await;
+    app.post_logout().await;
+    let public = app.get_project("secret-side-project").await;
+
+    // assert
+    assert_eq!(preview.status().as_u16(), 200);
+    assert_eq!(public.status().as_u16(), 404);
+}
@@ -0,0 +1,403 @@
+use portfolio_server::configuration::{RateLimitAlgorithm, RateLimitKey, RateLimitPolicy};
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::helpers::{TestApp, spawn_app_with};
+
+// a long window so the budget can't reset halfway through a test
+fn blog_policy(max_requests: u64) -> RateLimitPolicy {
+    RateLimitPolicy {
+        name: "blog".to_string(),
+        routes: vec!["/v1/blog".to_string()],
+        methods: vec!["GET".to_string()],
+        max_requests,
+        window_secs: 3600,
+        key: RateLimitKey::Ip,
+        fail_closed: false,
+        algorithm: RateLimitAlgorithm::FixedWindow,
+        burst: None,
+    }
+}
+
+// connects from `ip`, any 127.x.y.z address works as a distinct peer on loopback
+async fn get_as(app: &TestApp, ip: &str, path: &str) -> reqwest::Response {
+    reqwest::Client::builder()
+        .local_address(ip.parse::<std::net::IpAddr>().unwrap())
+        .build()
+        .unwrap()
+        .get(format!("http://127.0.0.1:{}{path}", app._port))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn get_blog_as(app: &TestApp, ip: &str) -> reqwest::Response {
+    get_as(app, ip, "/v1/blog").await
+}
+
+// a login from `ip`, with that client's own csrf token
+async fn post_login_as(app: &TestApp, ip: &str, body: &serde_json::Value) -> reqwest::Response {
+    let client = reqwest::Client::builder()
+        .local_address(ip.parse::<IpAddr>().unwrap())
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let seed = client
+        .get(format!("http://127.0.0.1:{}/v1/blog", app._port))
+        .send()
+        .await
+        .expect("Failed to seed CSRF token");
+    let xsrf_token = seed
+        .cookies()
+        .find(|c| c.name() == "XSRF-TOKEN")
+        .map(|c| c.value().to_string())
+        .expect("XSRF-TOKEN not found in seed response");
+
+    client
+        .post(format!("http://127.0.0.1:{}/v1/login", app._port))
+        .header("X-XSRF-TOKEN", xsrf_token)
+        .form(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn requests_over_a_policy_budget_are_rejected() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![blog_policy(3)]).await;
+    for _ in 0..3 {
+        assert_eq!(get_blog_as(&app, "127.0.0.2").await.status().as_u16(), 200);
+    }
+
+    // act
+    let response = get_blog_as(&app, "127.0.0.2").await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 429);
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=3600).contains(&retry_after));
+}
+
+#[tokio::test]
+async fn each_client_ip_has_its_own_budget() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![blog_policy(1)]).await;
+    get_blog_as(&app, "127.0.0.2").await;
+    assert_eq!(get_blog_as(&app, "127.0.0.2").await.status().as_u16(), 429);
+
+    // act
+    let response = get_blog_as(&app, "127.0.0.3").await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn forwarded_headers_do_not_buy_a_fresh_budget() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![blog_policy(1)]).await;
+    get_blog_as(&app, "127.0.0.2").await;
+
+    // act
+    let response = reqwest::Client::builder()
+        .local_address("127.0.0.2".parse::<std::net::IpAddr>().unwrap())
+        .build()
+        .unwrap()
+        .get(format!("http://127.0.0.1:{}/v1/blog", app._port))
+        .header("X-Forwarded-For", "203.0.113.7")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 429);
+}
+
+#[tokio::test]
+async fn trusted_proxies_forward_each_client_its_own_budget() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.rate_limit.policies = vec![blog_policy(1)];
+        c.application.trusted_proxies = vec!["127.0.0.2".parse::<IpAddr>().unwrap().into()];
+    })
+    .await;
+    let get_blog_for = |client: &'static str| {
+        reqwest::Client::builder()
+            .local_address("127.0.0.2".parse::<IpAddr>().unwrap())
+            .build()
+            .unwrap()
+            .get(format!("http://127.0.0.1:{}/v1/blog", app._port))
+            .header("X-Forwarded-For", client)
+            .send()
+    };
+    get_blog_for("203.0.113.7").await.unwrap();
+
+    // act
+    let same_client = get_blog_for("203.0.113.7").await.unwrap();
+    let other_client = get_blog_for("203.0.113.8").await.unwrap();
+
+    // assert
+    assert_eq!(same_client.status().as_u16(), 429);
+    assert_eq!(other_client.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn allowlisted_ips_skip_every_policy() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.rate_limit.policies = vec![blog_policy(1)];
+        c.rate_limit.allowlist.ips = vec!["127.0.0.2".parse::<IpAddr>().unwrap().into()];
+    })
+    .await;
+
+    // act
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        statuses.push(get_blog_as(&app, "127.0.0.2").await.status().as_u16());
+    }
+    get_blog_as(&app, "127.0.0.3").await;
+    let not_allowlisted = get_blog_as(&app, "127.0.0.3").await;
+
+    // assert
+    assert_eq!(statuses, vec![200, 200, 200]);
+    assert_eq!(not_allowlisted.status().as_u16(), 429);
+}
+
+#[tokio::test]
+async fn allowlisted_emails_do_not_skip_the_login_budgets() {
+    // arrange
+    let app =
+        spawn_app_with(|c| c.rate_limit.allowlist.emails = vec!["Probe@Example.com".to_string()])
+            .await;
+    let login_body = serde_json::json!({
+        "username": "probe@example.com",
+        "password": "wrong-password"
+    });
+
+    // act
+    let mut statuses = Vec::new();
+    // one more than the login_username budget
+    for _ in 0..11 {
+        statuses.push(app.post_login(&login_body).await.status().as_u16());
+    }
+
+    // assert
+    assert!(statuses[..10].iter().all(|&status| status != 429));
+    assert_eq!(statuses[10], 429);
+}
+
+#[tokio::test]
+async fn routes_outside_every_policy_are_not_limited() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![blog_policy(1)]).await;
+
+    // act
+    for _ in 0..3 {
+        let response = app
+            .api_client
+            .get(format!("{}/v1/check_auth", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        // assert
+        assert_ne!(response.status().as_u16(), 429);
+    }
+}
+
+#[tokio::test]
+async fn token_bucket_policies_allow_a_burst_before_limiting() {
+    // arrange
+    let policy = RateLimitPolicy {
+        algorithm: RateLimitAlgorithm::TokenBucket,
+        burst: Some(3),
+        ..blog_policy(1)
+    };
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![policy]).await;
+
+    // act
+    let mut statuses = Vec::new();
+    for _ in 0..4 {
+        statuses.push(get_blog_as(&app, "127.0.0.2").await.status().as_u16());
+    }
+
+    // assert
+    // a refill of one token an hour can't land mid-test
+    assert_eq!(statuses, vec![200, 200, 200, 429]);
+}
+
+#[tokio::test]
+async fn exempt_routes_skip_every_policy() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.rate_limit.policies = vec![blog_policy(1)];
+        c.rate_limit.exempt_routes = vec!["/v1/blog".to_string()];
+    })
+    .await;
+
+    // act
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        statuses.push(get_blog_as(&app, "127.0.0.2").await.status().as_u16());
+    }
+
+    // assert
+    assert_eq!(statuses, vec![200, 200, 200]);
+}
+
+#[tokio::test]
+async fn admins_can_see_and_reset_throttled_subjects() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![blog_policy(1)]).await;
+    app.test_user.login(&app).await;
+    get_blog_as(&app, "127.0.0.2").await;
+    get_blog_as(&app, "127.0.0.2").await;
+    get_blog_as(&app, "127.0.0.3").await;
+
+    // act
+    let listed: serde_json::Value = app.get_rate_limits().await.json().await.unwrap();
+    let reset = app
+        .reset_rate_limit(&serde_json::json!({ "policy": "blog", "subject": "ip:127.0.0.2" }))
+        .await;
+    let unknown = app
+        .reset_rate_limit(&serde_json::json!({ "policy": "nope", "subject": "ip:127.0.0.2" }))
+        .await;
+    let after_reset = get_blog_as(&app, "127.0.0.2").await;
+
+    // assert
+    // 127.0.0.3 is within its budget, so only 127.0.0.2 is throttled
+    let throttled = listed["throttled"].as_array().unwrap();
+    assert_eq!(throttled.len(), 1);
+    assert_eq!(throttled[0]["policy"], "blog");
+    assert_eq!(throttled[0]["subject"], "ip:127.0.0.2");
+    assert!(throttled[0]["retry_after_secs"].as_u64() > Some(0));
+    assert_eq!(reset.status().as_u16(), 204);
+    assert_eq!(unknown.status().as_u16(), 404);
+    assert_eq!(after_reset.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn rate_limit_admin_endpoints_reject_anonymous_users() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = vec![blog_policy(1)]).await;
+
+    // act
+    let response = app.get_rate_limits().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn repeat_offenders_are_banned_from_every_route() {
+    // arrange
+    let everything = RateLimitPolicy {
+        name: "everything".to_string(),
+        routes: vec!["/v1/*".to_string()],
+        ..blog_policy(100)
+    };
+    let app = spawn_app_with(|c| {
+        c.rate_limit.policies = vec![blog_policy(1), everything];
+        c.rate_limit.penalties.strikes_before_ban = 2;
+        c.rate_limit.penalties.ban_secs = 7200;
+    })
+    .await;
+    app.test_user.login(&app).await;
+    for _ in 0..3 {
+        get_blog_as(&app, "127.0.0.2").await;
+    }
+
+    // act
+    let elsewhere = get_as(&app, "127.0.0.2", "/v1/check_auth").await;
+    let listed: serde_json::Value = app.get_rate_limits().await.json().await.unwrap();
+
+    // assert
+    // check_auth is well within its budget, only the ban stops it
+    assert_eq!(elsewhere.status().as_u16(), 429);
+    let retry_after: u64 = elsewhere.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 3600, "the ban outlasts the blog window");
+    let banned = listed["banned"].as_array().unwrap();
+    assert_eq!(banned.len(), 1);
+    assert_eq!(banned[0]["subject"], "ip:127.0.0.2");
+}
+
+#[tokio::test]
+async fn resetting_a_subject_lifts_its_ban() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.rate_limit.policies = vec![blog_policy(1)];
+        c.rate_limit.penalties.strikes_before_ban = 1;
+    })
+    .await;
+    app.test_user.login(&app).await;
+    get_blog_as(&app, "127.0.0.2").await;
+    get_blog_as(&app, "127.0.0.2").await;
+
+    // act
+    app.reset_rate_limit(&serde_json::json!({ "policy": "blog", "subject": "ip:127.0.0.2" }))
+        .await;
+    let response = get_blog_as(&app, "127.0.0.2").await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn a_hammered_username_is_never_banned() {
+    // arrange
+    let login_username = RateLimitPolicy {
+        name: "login_username".to_string(),
+        routes: vec!["/v1/login".to_string()],
+        methods: vec!["POST".to_string()],
+        key: RateLimitKey::FormField("username".to_string()),
+        fail_closed: true,
+        window_secs: 2,
+        ..blog_policy(1)
+    };
+    let app = spawn_app_with(|c| {
+        c.rate_limit.policies = vec![login_username];
+        c.rate_limit.penalties.strikes_before_ban = 1;
+        c.rate_limit.penalties.ban_secs = 7200;
+    })
+    .await;
+    let wrong_password = serde_json::json!({
+        "username": &app.test_user.username,
+        "password": "wrong-password"
+    });
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        statuses.push(
+            post_login_as(&app, "127.0.0.2", &wrong_password)
+                .await
+                .status()
+                .as_u16(),
+        );
+    }
+    assert!(statuses.contains(&429));
+    // past the username window, only a ban would still hold
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    // act
+    let response = post_login_as(
+        &app,
+        "127.0.0.3",
+        &serde_json::json!({
+            "username": &app.test_user.username,
+            "password": &app.test_user.password
+        }),
+    )
+    .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -0,0 +1,73 @@
+use portfolio_server::configuration::{RateLimitAlgorithm, RateLimitKey, RateLimitPolicy};
+
+use crate::helpers::{TestApp, spawn_app, spawn_app_with};
+
+async fn get_blog(app: &TestApp) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/v1/blog", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn reloaded_rate_limits_apply_to_the_next_request() {
+    // arrange
+    let app = spawn_app_with(|c| c.rate_limit.policies = Vec::new()).await;
+    for _ in 0..2 {
+        assert_eq!(get_blog(&app).await.status().as_u16(), 200);
+    }
+    let mut settings = (*app.reloadable.rate_limit.get()).clone();
+    settings.policies = vec![RateLimitPolicy {
+        name: "blog".to_string(),
+        routes: vec!["/v1/blog".to_string()],
+        methods: vec!["GET".to_string()],
+        max_requests: 1,
+        window_secs: 3600,
+        key: RateLimitKey::Ip,
+        fail_closed: false,
+        algorithm: RateLimitAlgorithm::FixedWindow,
+        burst: None,
+    }];
+
+    // act
+    app.reloadable.rate_limit.replace(settings);
+
+    // assert
+    assert_eq!(get_blog(&app).await.status().as_u16(), 200);
+    assert_eq!(get_blog(&app).await.status().as_u16(), 429);
+}
+
+#[tokio::test]
+async fn reloaded_cors_origins_are_allowed() {
+    // arrange
+    let app = spawn_app().await;
+    let preflight = || {
+        app.api_client
+            .request(
+                reqwest::Method::OPTIONS,
+                format!("{}/v1/blog", &app.address),
+            )
+            .header("Origin", "https://new.example")
+            .header("Access-Control-Request-Method", "GET")
+            .send()
+    };
+    let response = preflight().await.expect("Failed to execute request.");
+    assert!(
+        !response
+            .headers()
+            .contains_key("access-control-allow-origin")
+    );
+    let mut origins = (*app.reloadable.cors_allowed_origins.get()).clone();
+    origins.push("https://new.example".to_string());
+
+    // act
+    app.reloadable.cors_allowed_origins.replace(origins);
+
+    // assert
+    let response = preflight().await.expect("Failed to execute request.");
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://new.example"
+    );
+}
@@ -0,0 +1,79 @@
+use crate::helpers::{spawn_app, spawn_app_with};
+
+#[tokio::test]
+async fn responses_keep_their_old_shape_by_default() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_article("true", None).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["data"].is_array());
+    assert_eq!(body["pagination"]["page"], 1);
+    assert!(body.get("request_id").is_none());
+}
+
+#[tokio::test]
+async fn the_envelope_is_sent_when_asked_for() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/blog", &app.address))
+        .header("X-Response-Envelope", "true")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["data"].is_array());
+    assert_eq!(body["meta"]["pagination"]["page"], 1);
+    assert!(body["request_id"].is_string());
+    assert!(body.get("pagination").is_none());
+}
+
+#[tokio::test]
+async fn empty_responses_carry_a_null_data_in_the_envelope() {
+    // arrange
+    let app = spawn_app_with(|c| c.application.response_envelope = true).await;
+    let login_body = serde_json::json!({
+        "username": &app.test_user.username,
+        "password": &app.test_user.password,
+    });
+
+    // act
+    let response = app.post_login(&login_body).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["data"].is_null());
+    assert!(body["request_id"].is_string());
+}
+
+#[tokio::test]
+async fn a_request_can_opt_out_of_the_configured_envelope() {
+    // arrange
+    let app = spawn_app_with(|c| c.application.response_envelope = true).await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/v1/blog", &app.address))
+        .header("X-Response-Envelope", "false")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // assert
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["pagination"]["page"], 1);
+    assert!(body.get("meta").is_none());
+}
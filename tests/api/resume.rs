@@ -0,0 +1,121 @@
+use crate::helpers::{TestApp, spawn_app};
+
+fn resume() -> serde_json::Value {
+    serde_json::json!({
+        "name": "Calvin DeVogel",
+        "headline": "Software engineer",
+        "summary": "I build backends.",
+        "education": [
+            {
+                "institution": "State University",
+                "credential": "BSc Computer Science",
+                "start_date": "2015-09-01",
+                "end_date": "2019-05-01"
+            }
+        ]
+    })
+}
+
+async fn add_experience(app: &TestApp) {
+    for experience in [
+        serde_json::json!({
+            "company": "Acme",
+            "role": "Intern",
+            "start_date": "2019-06-01",
+            "end_date": "2019-09-01"
+        }),
+        serde_json::json!({
+            "company": "Initech",
+            "role": "Engineer",
+            "start_date": "2021-01-01",
+            "highlights": ["Rewrote the billing service"],
+            "tech": ["Rust"]
+        }),
+    ] {
+        let response = app.post_experience(&experience).await;
+        assert_eq!(response.status().as_u16(), 202);
+    }
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_update_the_resume() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.put_resume(&resume()).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn the_resume_includes_experience_education_and_skills() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.post_skill(&serde_json::json!({
+        "name": "Rust",
+        "category": "Languages",
+        "proficiency": 5
+    }))
+    .await;
+    add_experience(&app).await;
+
+    // act
+    let response = app.put_resume(&resume()).await;
+    let resume = app.get_resume().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(resume.status().as_u16(), 200);
+    let resume: serde_json::Value = resume.json().await.unwrap();
+    assert_eq!(resume["name"], "Calvin DeVogel");
+    assert_eq!(resume["experience"][0]["company"], "Initech");
+    assert_eq!(resume["experience"][1]["company"], "Acme");
+    assert_eq!(resume["education"][0]["credential"], "BSc Computer Science");
+    assert_eq!(resume["skills"][0]["category"], "Languages");
+    assert_eq!(resume["skills"][0]["skills"], serde_json::json!(["Rust"]));
+}
+
+#[tokio::test]
+async fn the_pdf_is_rendered_from_the_same_data() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.put_resume(&resume()).await;
+    add_experience(&app).await;
+
+    // act
+    let response = app.get_resume_pdf().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["content-type"], "application/pdf");
+    assert!(
+        response.headers()["content-disposition"]
+            .to_str()
+            .unwrap()
+            .starts_with("attachment")
+    );
+    let pdf = response.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    let pdf = String::from_utf8_lossy(&pdf);
+    assert!(pdf.contains("(Engineer, Initech) Tj"));
+    assert!(pdf.contains("(Rewrote the billing service) Tj"));
+}
+
+#[tokio::test]
+async fn entries_that_end_before_they_start_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let mut body = resume();
+    body["education"][0]["end_date"] = "2014-01-01".into();
+
+    // act
+    let response = app.put_resume(&body).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,53 @@
+use crate::helpers::{spawn_app, spawn_app_with};
+
+#[tokio::test]
+async fn robots_txt_disallows_everything_outside_production() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/robots.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    assert_eq!(
+        response.text().await.unwrap(),
+        "User-agent: *\nDisallow: /\n"
+    );
+}
+
+#[tokio::test]
+async fn robots_txt_follows_the_configured_rules() {
+    // arrange
+    let app = spawn_app_with(|c| {
+        c.robots.allow_indexing = true;
+        c.robots.disallow = vec!["/v1/".to_string()];
+        c.robots.sitemap_url = Some("https://devogel.dev/sitemap.xml".to_string());
+    })
+    .await;
+
+    // act
+    let response = app
+        .api_client
+        .get(format!("{}/robots.txt", &app.address))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    let body = response.text().await.unwrap();
+    assert!(body.contains("Disallow: /v1/\n"));
+    assert!(!body.contains("Disallow: /\n"));
+    assert!(body.contains("Sitemap: https://devogel.dev/sitemap.xml\n"));
+}
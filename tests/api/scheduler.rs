@@ -0,0 +1,29 @@
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn scheduler_status_requires_login() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_scheduler_status().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn scheduler_status_lists_the_registered_jobs() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app.get_scheduler_status().await;
+
+    // assert
+    // the test app registers no jobs, main is what schedules them
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, serde_json::json!({ "jobs": [] }));
+}
@@ -0,0 +1,118 @@
+use crate::helpers::{TestApp, spawn_app};
+
+async fn create_project(app: &TestApp, title: &str, description: &str, published: bool) {
+    let response = app
+        .post_project(&serde_json::json!({
+            "title": title,
+            "description": description,
+            "tech_tags": ["rust"],
+            "published": published
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+}
+
+async fn results(response: reqwest::Response) -> Vec<serde_json::Value> {
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body.as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn published_content_is_found_with_highlighted_snippets() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_project(
+        &app,
+        "Portfolio server",
+        "An api for serving <b>portfolios</b>",
+        true,
+    )
+    .await;
+    create_project(&app, "Weather station", "Sensors on the roof", true).await;
+
+    // act
+    let results = results(app.search("portfolio").await).await;
+
+    // assert
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["kind"], "project");
+    assert_eq!(results[0]["slug"], "portfolio-server");
+    let snippet = results[0]["snippet"].as_str().unwrap();
+    assert!(snippet.contains("<mark>"));
+    assert!(snippet.contains("&lt;b&gt;"));
+}
+
+#[tokio::test]
+async fn titles_outrank_descriptions() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_project(
+        &app,
+        "Weather station",
+        "Logs readings from a garden sensor",
+        true,
+    )
+    .await;
+    create_project(&app, "Garden planner", "Plans beds", true).await;
+
+    // act
+    let results = results(app.search("garden").await).await;
+
+    // assert
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["title"], "Garden planner");
+}
+
+#[tokio::test]
+async fn drafts_and_messages_are_only_searched_by_admins() {
+    // arrange
+    let app = spawn_app().await;
+    app.post_message(&serde_json::json!({
+        "email": "jane@example.com",
+        "sender_name": "Jane Doe",
+        "message_text": "Would you like to collaborate on a telescope?"
+    }))
+    .await;
+    app.test_user.login(&app).await;
+    create_project(&app, "Telescope mount", "Unfinished", false).await;
+
+    // act
+    let public = results(app.search("telescope").await).await;
+    let admin = results(app.admin_search("telescope").await).await;
+
+    // assert
+    assert!(public.is_empty());
+    let mut kinds: Vec<_> = admin
+        .iter()
+        .map(|result| result["kind"].as_str().unwrap())
+        .collect();
+    kinds.sort_unstable();
+    assert_eq!(kinds, ["message", "project"]);
+}
+
+#[tokio::test]
+async fn the_admin_search_requires_a_login() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.admin_search("telescope").await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn blank_queries_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.search("   ").await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,119 @@
+use std::net::IpAddr;
+use uuid::Uuid;
+
+use crate::helpers::{TestApp, spawn_app, spawn_app_with};
+
+async fn login_from_nz(app: &TestApp) {
+    let response = app
+        .api_client
+        .post(format!("{}/v1/login", &app.address))
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .header("User-Agent", "test-device/1.0")
+        .header("CF-IPCountry", "NZ")
+        .form(&serde_json::json!({
+            "username": app.test_user.username,
+            "password": app.test_user.password,
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn anonymous_users_cannot_list_sessions() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.get_sessions().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn sessions_include_device_metadata() {
+    // arrange
+    // the cdn's country header only counts from a proxy
+    let app = spawn_app_with(|c| {
+        c.application.trusted_proxies = vec!["127.0.0.1".parse::<IpAddr>().unwrap().into()];
+    })
+    .await;
+    login_from_nz(&app).await;
+
+    // act
+    let response = app.get_sessions().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let sessions: serde_json::Value = response.json().await.unwrap();
+    let sessions = sessions.as_array().unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["user_agent"], "test-device/1.0");
+    assert_eq!(sessions[0]["ip_address"], "127.0.0.1");
+    assert_eq!(sessions[0]["geo"], "NZ");
+    assert_eq!(sessions[0]["current"], true);
+    assert!(sessions[0]["created_at"].is_string());
+}
+
+#[tokio::test]
+async fn clients_cannot_pick_their_own_location() {
+    // arrange
+    let app = spawn_app().await;
+    login_from_nz(&app).await;
+
+    // act
+    let response = app.get_sessions().await;
+
+    // assert
+    let sessions: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(sessions[0]["geo"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn revoked_sessions_are_logged_out() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sessions: serde_json::Value = app.get_sessions().await.json().await.unwrap();
+    let session_id = sessions[0]["session_id"].as_str().unwrap().to_string();
+
+    // act
+    let response = app.post_revoke_session(&session_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // assert
+    let response = app.check_auth().await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn revoking_an_unknown_session_returns_not_found() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app.post_revoke_session(&Uuid::new_v4().to_string()).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn logging_out_removes_the_session_from_the_list() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.post_logout().await;
+
+    // act
+    app.test_user.login(&app).await;
+    let sessions: serde_json::Value = app.get_sessions().await.json().await.unwrap();
+
+    // assert
+    assert_eq!(sessions.as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,177 @@
+use crate::helpers::{TestApp, spawn_app};
+
+async fn post_link(app: &TestApp, body: &serde_json::Value) -> serde_json::Value {
+    let response = app.post_short_link(body).await;
+    assert_eq!(response.status().as_u16(), 202);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_links() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .post_short_link(&serde_json::json!({ "target_url": "https://example.com" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn links_redirect_and_count_their_clicks() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_link(
+        &app,
+        &serde_json::json!({ "code": "talk", "target_url": "https://example.com/slides" }),
+    )
+    .await;
+
+    // act
+    let first = app.follow_short_link("talk").await;
+    app.follow_short_link("talk").await;
+
+    // assert
+    assert_eq!(first.status().as_u16(), 302);
+    assert_eq!(
+        first.headers()["Location"].to_str().unwrap(),
+        "https://example.com/slides"
+    );
+    assert_eq!(
+        first.headers()["Cache-Control"].to_str().unwrap(),
+        "no-store"
+    );
+    let links: serde_json::Value = app.get_short_links().await.json().await.unwrap();
+    assert_eq!(links[0]["click_count"], 2);
+    assert!(links[0]["last_clicked_at"].is_string());
+}
+
+#[tokio::test]
+async fn clicks_are_recorded_as_campaign_visits() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_link(
+        &app,
+        &serde_json::json!({
+            "code": "conf",
+            "target_url": "https://example.com",
+            "utm_source": "Mastodon",
+            "utm_campaign": "rustconf"
+        }),
+    )
+    .await;
+
+    // act
+    app.follow_short_link("conf").await;
+    app.flush_page_visits().await;
+
+    // assert
+    let saved = sqlx::query!("SELECT path, utm_source, utm_campaign FROM page_visits")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved visit.");
+    assert_eq!(saved.path, "/l/conf");
+    assert_eq!(saved.utm_source.as_deref(), Some("mastodon"));
+    assert_eq!(saved.utm_campaign.as_deref(), Some("rustconf"));
+}
+
+#[tokio::test]
+async fn a_code_is_generated_when_none_is_given() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let body = post_link(
+        &app,
+        &serde_json::json!({ "target_url": "https://example.com" }),
+    )
+    .await;
+
+    // assert
+    let code = body["code"].as_str().unwrap();
+    assert_eq!(code.len(), 7);
+    let response = app.follow_short_link(code).await;
+    assert_eq!(response.status().as_u16(), 302);
+}
+
+#[tokio::test]
+async fn codes_are_unique() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let body = serde_json::json!({ "code": "talk", "target_url": "https://example.com" });
+    post_link(&app, &body).await;
+
+    // act
+    let response = app.post_short_link(&body).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn only_web_urls_can_be_targets() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .post_short_link(&serde_json::json!({ "target_url": "javascript:alert(1)" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn renamed_links_keep_their_clicks() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let body = post_link(
+        &app,
+        &serde_json::json!({ "code": "old", "target_url": "https://example.com" }),
+    )
+    .await;
+    app.follow_short_link("old").await;
+
+    // act
+    let response = app
+        .edit_short_link(&serde_json::json!({ "link_id": body["link_id"], "code": "new" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    assert_eq!(app.follow_short_link("old").await.status().as_u16(), 404);
+    assert_eq!(app.follow_short_link("new").await.status().as_u16(), 302);
+    let links: serde_json::Value = app.get_short_links().await.json().await.unwrap();
+    assert_eq!(links[0]["click_count"], 2);
+}
+
+#[tokio::test]
+async fn deleted_links_stop_redirecting() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let body = post_link(
+        &app,
+        &serde_json::json!({ "code": "gone", "target_url": "https://example.com" }),
+    )
+    .await;
+
+    // act
+    let response = app
+        .delete_short_link(&serde_json::json!({ "link_id": body["link_id"] }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(app.follow_short_link("gone").await.status().as_u16(), 404);
+}
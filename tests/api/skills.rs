@@ -0,0 +1,179 @@
+use uuid::Uuid;
+
+use crate::helpers::{TestApp, spawn_app};
+
+fn skill(name: &str, project_ids: &[&str]) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "category": "Languages",
+        "proficiency": 4,
+        "years": 3,
+        "project_ids": project_ids
+    })
+}
+
+async fn create_project(app: &TestApp, title: &str) -> String {
+    let response = app
+        .post_project(&serde_json::json!({
+            "title": title,
+            "description": "Something I built",
+            "published": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["project_id"].as_str().unwrap().to_string()
+}
+
+async fn create_skill(app: &TestApp, name: &str, project_ids: &[&str]) -> String {
+    let response = app.post_skill(&skill(name, project_ids)).await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["skill_id"].as_str().unwrap().to_string()
+}
+
+async fn project_titles_using(app: &TestApp, skill: &str) -> Vec<String> {
+    let response = app.get_projects_with_skill(skill).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|project| project["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_skills() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app.post_skill(&skill("Rust", &[])).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn projects_can_be_filtered_by_skill() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let server = create_project(&app, "Portfolio server").await;
+    create_project(&app, "Frontend").await;
+    create_skill(&app, "Rust", &[&server]).await;
+
+    // act
+    let titles = project_titles_using(&app, "rust").await;
+    let skills = app.get_skills().await;
+
+    // assert
+    assert_eq!(titles, ["Portfolio server"]);
+    assert_eq!(skills.status().as_u16(), 200);
+    let skills: serde_json::Value = skills.json().await.unwrap();
+    assert_eq!(skills[0]["name"], "Rust");
+    assert_eq!(skills[0]["proficiency"], 4);
+    assert_eq!(skills[0]["project_ids"], serde_json::json!([server]));
+}
+
+#[tokio::test]
+async fn skill_names_are_unique_whatever_their_case() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_skill(&app, "Rust", &[]).await;
+
+    // act
+    let response = app.post_skill(&skill("rust", &[])).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn linking_an_unknown_project_is_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let unknown = Uuid::new_v4().to_string();
+
+    // act
+    let response = app.post_skill(&skill("Rust", &[&unknown])).await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(
+        app.get_skills()
+            .await
+            .json::<serde_json::Value>()
+            .await
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn editing_project_ids_replaces_the_links() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let server = create_project(&app, "Portfolio server").await;
+    let frontend = create_project(&app, "Frontend").await;
+    let skill_id = create_skill(&app, "Rust", &[&server]).await;
+
+    // act
+    let response = app
+        .edit_skill(&serde_json::json!({
+            "skill_id": skill_id,
+            "proficiency": 5,
+            "project_ids": [frontend]
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    assert_eq!(project_titles_using(&app, "Rust").await, ["Frontend"]);
+}
+
+#[tokio::test]
+async fn editing_a_missing_skill_returns_not_found() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let response = app
+        .edit_skill(&serde_json::json!({
+            "skill_id": Uuid::new_v4(),
+            "years": 2
+        }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn deleted_skills_no_longer_filter_projects() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let server = create_project(&app, "Portfolio server").await;
+    let skill_id = create_skill(&app, "Rust", &[&server]).await;
+
+    // act
+    let response = app
+        .delete_skill(&serde_json::json!({ "skill_id": skill_id }))
+        .await;
+    let missing = app
+        .delete_skill(&serde_json::json!({ "skill_id": skill_id }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(missing.status().as_u16(), 404);
+    assert!(project_titles_using(&app, "rust").await.is_empty());
+}
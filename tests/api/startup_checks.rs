@@ -0,0 +1,45 @@
+use portfolio_server::{configuration::get_configuration, startup::Application};
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::helpers::create_database;
+
+#[tokio::test]
+async fn startup_fails_listing_every_failed_check() {
+    // arrange
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.database.database_name = Uuid::new_v4().to_string();
+    configuration.application.port = 0;
+    // nothing listens on port 1
+    configuration.redis_uri = SecretString::from("redis://127.0.0.1:1");
+    configuration.redis.acquire_timeout_secs = 1;
+    // created but never migrated
+    create_database(&configuration.database).await;
+
+    // act
+    let error = Application::build(configuration)
+        .await
+        .err()
+        .expect("the app started against a broken environment")
+        .to_string();
+
+    // assert
+    assert!(error.contains("migrations:"), "{error}");
+    assert!(error.contains("redis:"), "{error}");
+}
+
+#[tokio::test]
+async fn pending_migrations_can_be_left_to_the_readiness_probe() {
+    // arrange
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.database.database_name = Uuid::new_v4().to_string();
+    configuration.application.port = 0;
+    configuration.application.allow_pending_migrations = true;
+    create_database(&configuration.database).await;
+
+    // act
+    let application = Application::build(configuration).await;
+
+    // assert
+    assert!(application.is_ok());
+}
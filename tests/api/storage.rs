@@ -0,0 +1,171 @@
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn presigned_uploads_can_be_downloaded() {
+    // arrange
+    let app = spawn_app().await;
+    let upload = app
+        .storage
+        .presigned_upload_url("media/hello.txt")
+        .await
+        .unwrap();
+    let download = app
+        .storage
+        .presigned_download_url("media/hello.txt")
+        .await
+        .unwrap();
+
+    // act
+    let uploaded = app
+        .api_client
+        .put(app.local_storage_url(&upload))
+        .body("hello there")
+        .send()
+        .await
+        .unwrap();
+    let downloaded = app
+        .api_client
+        .get(app.local_storage_url(&download))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(uploaded.status().as_u16(), 204);
+    assert_eq!(downloaded.status().as_u16(), 200);
+    assert_eq!(downloaded.text().await.unwrap(), "hello there");
+}
+
+#[tokio::test]
+async fn download_urls_cannot_upload() {
+    // arrange
+    let app = spawn_app().await;
+    let download = app
+        .storage
+        .presigned_download_url("media/hello.txt")
+        .await
+        .unwrap();
+
+    // act
+    let response = app
+        .api_client
+        .put(app.local_storage_url(&download))
+        .body("not yours")
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(app.storage.get("media/hello.txt").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn urls_for_another_key_are_rejected() {
+    // arrange
+    let app = spawn_app().await;
+    app.storage
+        .put("media/private.txt", b"secret".to_vec())
+        .await
+        .unwrap();
+    let download = app
+        .storage
+        .presigned_download_url("media/public.txt")
+        .await
+        .unwrap();
+
+    // act
+    let response = app
+        .api_client
+        .get(
+            app.local_storage_url(&download)
+                .replace("public", "private"),
+        )
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn missing_objects_are_not_found() {
+    // arrange
+    let app = spawn_app().await;
+    let download = app
+        .storage
+        .presigned_download_url("media/missing.txt")
+        .await
+        .unwrap();
+
+    // act
+    let response = app
+        .api_client
+        .get(app.local_storage_url(&download))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn issued_upload_urls_are_served_as_media() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    // act
+    let issued: serde_json::Value = app
+        .api_client
+        .post(format!("{}/v1/admin/uploads", &app.address))
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .json(&serde_json::json!({ "filename": "Hello.TXT" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let uploaded = app
+        .api_client
+        .put(app.local_storage_url(issued["upload_url"].as_str().unwrap()))
+        .body("hello there")
+        .send()
+        .await
+        .unwrap();
+    let served = app
+        .api_client
+        .get(app.local_storage_url(issued["media_url"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert!(issued["key"].as_str().unwrap().starts_with("media/"));
+    assert!(issued["key"].as_str().unwrap().ends_with(".txt"));
+    assert_eq!(uploaded.status().as_u16(), 204);
+    assert_eq!(served.status().as_u16(), 200);
+    assert_eq!(served.text().await.unwrap(), "hello there");
+}
+
+#[tokio::test]
+async fn upload_urls_are_only_issued_to_admins() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .api_client
+        .post(format!("{}/v1/admin/uploads", &app.address))
+        .header("X-XSRF-TOKEN", &app.xsrf_token)
+        .json(&serde_json::json!({ "filename": "hello.txt" }))
+        .send()
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
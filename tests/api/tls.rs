@@ -0,0 +1,92 @@
+use crate::helpers::{TestApp, spawn_app_with};
+
+struct TestCertificate {
+    cert_path: String,
+    key_path: String,
+    // der of the certificate first written
+    der: Vec<u8>,
+}
+
+impl TestCertificate {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut certificate = Self {
+            cert_path: dir.join("cert.pem").to_string_lossy().into_owned(),
+            key_path: dir.join("key.pem").to_string_lossy().into_owned(),
+            der: Vec::new(),
+        };
+        certificate.der = certificate.renew();
+        certificate
+    }
+
+    // a fresh self-signed pair in the same files, returns the new certificate
+    fn renew(&self) -> Vec<u8> {
+        let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        std::fs::write(&self.cert_path, generated.cert.pem()).unwrap();
+        std::fs::write(&self.key_path, generated.signing_key.serialize_pem()).unwrap();
+        generated.cert.der().to_vec()
+    }
+}
+
+// a new client for each call so every request makes its own handshake
+async fn served_certificate(app: &TestApp) -> Vec<u8> {
+    let response = reqwest::Client::builder()
+        .tls_danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .build()
+        .unwrap()
+        .get(format!("{}/health_check", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .expect("No peer certificate")
+        .to_vec()
+}
+
+#[tokio::test]
+async fn the_server_speaks_https_with_a_configured_certificate() {
+    // arrange
+    let certificate = TestCertificate::new();
+    let app = spawn_app_with(|c| {
+        c.application.tls_cert_path = Some(certificate.cert_path.clone());
+        c.application.tls_key_path = Some(certificate.key_path.clone());
+    })
+    .await;
+
+    // act
+    let served = served_certificate(&app).await;
+
+    // assert
+    assert!(app.address.starts_with("https://"));
+    assert_eq!(served, certificate.der);
+}
+
+#[tokio::test]
+async fn a_reload_swaps_the_certificate_for_new_connections() {
+    // arrange
+    let certificate = TestCertificate::new();
+    let app = spawn_app_with(|c| {
+        c.application.tls_cert_path = Some(certificate.cert_path.clone());
+        c.application.tls_key_path = Some(certificate.key_path.clone());
+    })
+    .await;
+    assert_eq!(served_certificate(&app).await, certificate.der);
+    let renewed = certificate.renew();
+
+    // act
+    app.reloadable
+        .tls
+        .as_ref()
+        .unwrap()
+        .reload(&certificate.cert_path, &certificate.key_path)
+        .unwrap();
+
+    // assert
+    assert_eq!(served_certificate(&app).await, renewed);
+}
@@ -0,0 +1,104 @@
+use crate::helpers::{TestApp, spawn_app};
+
+async fn post_item(app: &TestApp, category: &str, name: &str) -> String {
+    let response = app
+        .post_uses_item(&serde_json::json!({
+            "category": category,
+            "name": name,
+            "description": "Daily driver",
+            "url": "https://example.com"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["item_id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn unauthorized_users_cannot_post_items() {
+    // arrange
+    let app = spawn_app().await;
+
+    // act
+    let response = app
+        .post_uses_item(&serde_json::json!({ "category": "Hardware", "name": "Framework 13" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn items_are_grouped_by_category() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_item(&app, "Software", "Helix").await;
+    post_item(&app, "Hardware", "Keychron Q1").await;
+    post_item(&app, "Hardware", "Framework 13").await;
+
+    // act
+    let response = app.get_uses().await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let groups: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(groups.as_array().unwrap().len(), 2);
+    assert_eq!(groups[0]["category"], "Hardware");
+    assert_eq!(groups[0]["items"][0]["name"], "Framework 13");
+    assert_eq!(groups[0]["items"][1]["name"], "Keychron Q1");
+    assert_eq!(groups[1]["items"][0]["url"], "https://example.com");
+}
+
+#[tokio::test]
+async fn an_item_is_listed_once_per_category() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_item(&app, "Software", "Helix").await;
+
+    // act
+    let response = app
+        .post_uses_item(&serde_json::json!({ "category": "software", "name": "helix" }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn edits_can_remove_the_link() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let item_id = post_item(&app, "Software", "Helix").await;
+
+    // act
+    let response = app
+        .edit_uses_item(&serde_json::json!({ "item_id": item_id, "url": null }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 202);
+    let groups: serde_json::Value = app.get_uses().await.json().await.unwrap();
+    assert_eq!(groups[0]["items"][0]["url"], serde_json::Value::Null);
+    assert_eq!(groups[0]["items"][0]["description"], "Daily driver");
+}
+
+#[tokio::test]
+async fn deleted_items_are_gone() {
+    // arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let item_id = post_item(&app, "Software", "Helix").await;
+
+    // act
+    let response = app
+        .delete_uses_item(&serde_json::json!({ "item_id": item_id }))
+        .await;
+
+    // assert
+    assert_eq!(response.status().as_u16(), 200);
+    let groups: serde_json::Value = app.get_uses().await.json().await.unwrap();
+    assert!(groups.as_array().unwrap().is_empty());
+}